@@ -3,10 +3,11 @@
 layout(location=0) in vec3 inPos;
 layout(location=1) in vec3 inNormal;
 
+layout(push_constant) uniform MeshPC { mat4 mvp; mat4 model; } PC;
+
 layout(location=0) out vec3 vNormal;
 
 void main() {
-    gl_Position = vec4(inPos, 1.0);
-    vNormal = normalize(inNormal);
+    gl_Position = PC.mvp * vec4(inPos, 1.0);
+    vNormal = normalize(mat3(PC.model) * inNormal);
 }
-
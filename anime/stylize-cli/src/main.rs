@@ -1,6 +1,8 @@
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 use stylize_core::{asset_dna, VERSION};
+#[cfg(feature = "vulkan")]
+use stylize_core::render;
 
 #[derive(Parser, Debug)]
 #[command(name = "stylize", version = VERSION, about = "Procedural anime asset tools")] 
@@ -61,6 +63,15 @@ enum Command {
         height: u32,
         #[arg(long, default_value = "mesh")] 
         out_prefix: String,
+        /// Model rotation about X in degrees
+        #[arg(long, default_value_t = 0.0)]
+        rot_x: f32,
+        /// Model rotation about Y in degrees
+        #[arg(long, default_value_t = 0.0)]
+        rot_y: f32,
+        /// Use a perspective camera with this vertical FOV (degrees) instead of the identity camera
+        #[arg(long)]
+        fov: Option<f32>,
     },
     /// Render toon shading from mesh G-buffer and save PNG
     #[cfg(feature = "vulkan")]
@@ -111,7 +122,7 @@ fn main() -> Result<()> {
         }
         #[cfg(feature = "vulkan")]
         Command::VkInfo => {
-            let list = stylize_core::render::vk::enumerate_devices()?;
+            let list = render::list_devices()?;
             if list.is_empty() { println!("No Vulkan devices found"); }
             for (i, d) in list.iter().enumerate() { println!("[{}] {}", i, d); }
         }
@@ -130,9 +141,7 @@ fn main() -> Result<()> {
         }
         #[cfg(feature = "vulkan")]
         Command::VkRenderTest { width, height, out } => {
-            use stylize_core::render::vk;
-            let ctx = vk::VkContext::new("stylize-render-test")?;
-            let pixels = vk::render_offscreen_rgba(&ctx, width, height)?;
+            let pixels = render::render_test_rgba(width, height)?;
             let img = image::RgbaImage::from_raw(width, height, pixels)
                 .ok_or_else(|| anyhow::anyhow!("Failed to create image from raw"))?;
             img.save(&out)?;
@@ -140,9 +149,7 @@ fn main() -> Result<()> {
         }
         #[cfg(feature = "vulkan")]
         Command::VkGbufferTest { width, height, out_prefix } => {
-            use stylize_core::render::vk;
-            let ctx = vk::VkContext::new("stylize-gbuffer-test")?;
-            let (albedo, normal) = vk::render_gbuffer_offscreen(&ctx, width, height)?;
+            let (albedo, normal) = render::render_gbuffer_rgba(width, height)?;
             let img_a = image::RgbaImage::from_raw(width, height, albedo)
                 .ok_or_else(|| anyhow::anyhow!("Failed to create albedo image"))?;
             let img_n = image::RgbaImage::from_raw(width, height, normal)
@@ -155,25 +162,22 @@ fn main() -> Result<()> {
         }
         #[cfg(feature = "vulkan")]
         Command::VkToonFromGbuf { width, height, out, dna } => {
-            use stylize_core::render::vk;
-            let ctx = vk::VkContext::new("stylize-toon-from-gbuf")?;
-            let style = if let Some(path) = dna {
-                let dna = asset_dna::load_from_path(&path)?;
-                vk::toon_style_from_dna(&dna.shading)
-            } else {
-                vk::ToonStyle::default()
-            };
-            let pixels = vk::render_toon_from_gbuffer(&ctx, width, height, &style)?;
+            let dna = dna.map(asset_dna::load_from_path).transpose()?;
+            let pixels = render::render_toon_rgba(dna.as_ref(), width, height)?;
             let img = image::RgbaImage::from_raw(width, height, pixels)
                 .ok_or_else(|| anyhow::anyhow!("Failed to create image from raw"))?;
             img.save(&out)?;
             println!("Wrote {}x{} image to {}", width, height, out);
         }
         #[cfg(feature = "vulkan")]
-        Command::VkGbufferMesh { width, height, out_prefix } => {
-            use stylize_core::render::vk;
-            let ctx = vk::VkContext::new("stylize-gbuffer-mesh")?;
-            let (albedo, normal) = vk::render_mesh_gbuffer_offscreen(&ctx, width, height)?;
+        Command::VkGbufferMesh { width, height, out_prefix, rot_x, rot_y, fov } => {
+            use stylize_core::render::{math, mesh::Camera};
+            let camera = match fov {
+                Some(fov) => Camera::look_at([0.0, 0.0, 3.0], [0.0, 0.0, 0.0], fov, width as f32 / height as f32),
+                None => Camera::default(),
+            };
+            let model = math::rotation_xy_deg(rot_x, rot_y);
+            let (albedo, normal) = render::render_mesh_gbuffer_rgba(width, height, &camera, &model)?;
             let img_a = image::RgbaImage::from_raw(width, height, albedo)
                 .ok_or_else(|| anyhow::anyhow!("Failed to create albedo image"))?;
             let img_n = image::RgbaImage::from_raw(width, height, normal)
@@ -186,15 +190,8 @@ fn main() -> Result<()> {
         }
         #[cfg(feature = "vulkan")]
         Command::VkToonMesh { width, height, out, dna, outline_width } => {
-            use stylize_core::render::vk;
-            let ctx = vk::VkContext::new("stylize-toon-mesh")?;
-            let (style, ow_px) = if let Some(path) = dna {
-                let dna = asset_dna::load_from_path(&path)?;
-                (vk::toon_style_from_dna(&dna.shading), outline_width.or(Some(dna.lines.width_px)))
-            } else {
-                (vk::ToonStyle::default(), outline_width)
-            };
-            let pixels = vk::render_toon_from_mesh(&ctx, width, height, &style, ow_px)?;
+            let dna = dna.map(asset_dna::load_from_path).transpose()?;
+            let pixels = render::render_toon_mesh_rgba(dna.as_ref(), width, height, outline_width)?;
             let img = image::RgbaImage::from_raw(width, height, pixels)
                 .ok_or_else(|| anyhow::anyhow!("Failed to create image from raw"))?;
             img.save(&out)?;
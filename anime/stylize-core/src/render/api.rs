@@ -0,0 +1,106 @@
+//! One-call rendering entry points mirroring the CLI `vk-*` commands.
+//! Each function creates its own Vulkan context, renders offscreen and returns tightly
+//! packed RGBA8 pixels (`width * height * 4` bytes), so callers never touch `vk` directly.
+//! Without the `vulkan` feature the same signatures exist but return [`VulkanDisabled`].
+
+use anyhow::Result;
+
+use crate::asset_dna::schema::AssetDNA;
+use crate::render::{math::Mat4, mesh::Camera};
+
+#[cfg(feature = "vulkan")]
+use crate::render::vk;
+
+/// Returned by every render function when stylize-core was built without Vulkan support.
+#[derive(Debug, thiserror::Error)]
+#[error("{0} requires stylize-core to be built with the `vulkan` feature (e.g. `--features vulkan`)")]
+pub struct VulkanDisabled(pub &'static str);
+
+#[cfg(feature = "vulkan")]
+fn toon_style(dna: Option<&AssetDNA>) -> vk::ToonStyle {
+    dna.map(|d| vk::toon_style_from_dna(&d.shading)).unwrap_or_default()
+}
+
+/// Names of the available Vulkan devices.
+pub fn list_devices() -> Result<Vec<String>> {
+    #[cfg(feature = "vulkan")]
+    {
+        vk::enumerate_devices()
+    }
+    #[cfg(not(feature = "vulkan"))]
+    {
+        Err(VulkanDisabled("list_devices").into())
+    }
+}
+
+/// Simple offscreen test image.
+pub fn render_test_rgba(width: u32, height: u32) -> Result<Vec<u8>> {
+    #[cfg(feature = "vulkan")]
+    {
+        let ctx = vk::VkContext::new("stylize-render-test")?;
+        vk::render_offscreen_rgba(&ctx, width, height)
+    }
+    #[cfg(not(feature = "vulkan"))]
+    {
+        let _ = (width, height);
+        Err(VulkanDisabled("render_test_rgba").into())
+    }
+}
+
+/// Procedural G-buffer as `(albedo, normal)`.
+pub fn render_gbuffer_rgba(width: u32, height: u32) -> Result<(Vec<u8>, Vec<u8>)> {
+    #[cfg(feature = "vulkan")]
+    {
+        let ctx = vk::VkContext::new("stylize-gbuffer-test")?;
+        vk::render_gbuffer_offscreen(&ctx, width, height)
+    }
+    #[cfg(not(feature = "vulkan"))]
+    {
+        let _ = (width, height);
+        Err(VulkanDisabled("render_gbuffer_rgba").into())
+    }
+}
+
+/// Toon shading over the procedural G-buffer, styled by `dna` (default style when `None`).
+pub fn render_toon_rgba(dna: Option<&AssetDNA>, width: u32, height: u32) -> Result<Vec<u8>> {
+    #[cfg(feature = "vulkan")]
+    {
+        let ctx = vk::VkContext::new("stylize-toon-from-gbuf")?;
+        vk::render_toon_from_gbuffer(&ctx, width, height, &toon_style(dna))
+    }
+    #[cfg(not(feature = "vulkan"))]
+    {
+        let _ = (dna, width, height);
+        Err(VulkanDisabled("render_toon_rgba").into())
+    }
+}
+
+/// UV-sphere mesh rendered into a G-buffer as `(albedo, normal)`.
+pub fn render_mesh_gbuffer_rgba(width: u32, height: u32, camera: &Camera, model: &Mat4) -> Result<(Vec<u8>, Vec<u8>)> {
+    #[cfg(feature = "vulkan")]
+    {
+        let ctx = vk::VkContext::new("stylize-gbuffer-mesh")?;
+        vk::render_mesh_gbuffer_offscreen(&ctx, width, height, camera, model)
+    }
+    #[cfg(not(feature = "vulkan"))]
+    {
+        let _ = (width, height, camera, model);
+        Err(VulkanDisabled("render_mesh_gbuffer_rgba").into())
+    }
+}
+
+/// Toon-shaded mesh with an optional screen-space outline. The outline width falls back to
+/// `dna.lines.width_px` when `outline_width_px` is `None`.
+pub fn render_toon_mesh_rgba(dna: Option<&AssetDNA>, width: u32, height: u32, outline_width_px: Option<f32>) -> Result<Vec<u8>> {
+    #[cfg(feature = "vulkan")]
+    {
+        let ctx = vk::VkContext::new("stylize-toon-mesh")?;
+        let outline = outline_width_px.or(dna.map(|d| d.lines.width_px));
+        vk::render_toon_from_mesh(&ctx, width, height, &toon_style(dna), outline)
+    }
+    #[cfg(not(feature = "vulkan"))]
+    {
+        let _ = (dna, width, height, outline_width_px);
+        Err(VulkanDisabled("render_toon_mesh_rgba").into())
+    }
+}
@@ -1,3 +1,5 @@
+use crate::render::math::{self, Mat4};
+
 #[derive(Debug, Clone, Copy)]
 pub struct Vertex {
     pub pos: [f32; 3],
@@ -47,3 +49,42 @@ pub fn generate_uv_sphere(radius: f32, stacks: u32, slices: u32) -> (Vec<Vertex>
     (vertices, indices)
 }
 
+
+/// View and projection used when rendering a mesh. The default is identity for both,
+/// which draws object space straight into clip space.
+#[derive(Debug, Clone, Copy)]
+pub struct Camera {
+    pub view: Mat4,
+    pub proj: Mat4,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self { view: math::IDENTITY, proj: math::IDENTITY }
+    }
+}
+
+impl Camera {
+    /// Perspective camera at `eye` looking at `target` (Y up); `fovy_deg` in degrees.
+    pub fn look_at(eye: [f32; 3], target: [f32; 3], fovy_deg: f32, aspect: f32) -> Self {
+        Self {
+            view: math::look_at(eye, target, [0.0, 1.0, 0.0]),
+            proj: math::perspective(fovy_deg.to_radians(), aspect, 0.1, 100.0),
+        }
+    }
+}
+
+/// Push-constant block for `mesh_gbuffer.vert` (128 bytes, the guaranteed minimum).
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct MeshPushConstants {
+    pub mvp: Mat4,
+    pub model: Mat4,
+}
+
+impl MeshPushConstants {
+    pub fn new(camera: &Camera, model: &Mat4) -> Self {
+        let view_model = math::mul(&camera.view, model);
+        Self { mvp: math::mul(&camera.proj, &view_model), model: *model }
+    }
+}
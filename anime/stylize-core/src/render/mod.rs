@@ -8,3 +8,10 @@ pub mod sprite_pack;
 pub mod vk;
 
 pub mod mesh;
+pub mod math;
+
+pub mod api;
+pub use api::{
+    list_devices, render_gbuffer_rgba, render_mesh_gbuffer_rgba, render_test_rgba, render_toon_mesh_rgba,
+    render_toon_rgba, VulkanDisabled,
+};
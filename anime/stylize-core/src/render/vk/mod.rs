@@ -5,6 +5,8 @@
 use anyhow::{anyhow, Result};
 use std::ffi::CString;
 
+use crate::render::{math::Mat4, mesh::Camera};
+
 pub struct VkContext {
     pub entry: ash::Entry,
     pub instance: ash::Instance,
@@ -1136,7 +1138,7 @@ pub fn render_toon_from_gbuffer(ctx: &VkContext, width: u32, height: u32, style:
 
 pub fn render_toon_from_mesh(ctx: &VkContext, width: u32, height: u32, style: &ToonStyle, outline_width_px: Option<f32>) -> Result<Vec<u8>> {
     use ash::vk as vk;
-    use crate::render::mesh::{generate_uv_sphere, Vertex};
+    use crate::render::mesh::{generate_uv_sphere, MeshPushConstants, Vertex};
 
     // Generate a UV-sphere mesh
     let (verts, inds) = generate_uv_sphere(0.8, 32, 64);
@@ -1207,7 +1209,8 @@ pub fn render_toon_from_mesh(ctx: &VkContext, width: u32, height: u32, style: &T
     let dyn_state = vk::PipelineDynamicStateCreateInfo::builder().dynamic_states(&dyn_states);
     let color_formats = [albedo_format, normal_format, material_format];
     let mut rendering_info = vk::PipelineRenderingCreateInfo::builder().color_attachment_formats(&color_formats).depth_attachment_format(depth_format);
-    let layout_ci = vk::PipelineLayoutCreateInfo::builder();
+    let pc_range = vk::PushConstantRange::builder().stage_flags(vk::ShaderStageFlags::VERTEX).offset(0).size(std::mem::size_of::<MeshPushConstants>() as u32).build();
+    let layout_ci = vk::PipelineLayoutCreateInfo::builder().push_constant_ranges(std::slice::from_ref(&pc_range));
     let pipeline_layout = unsafe { ctx.device.create_pipeline_layout(&layout_ci, None)? };
     let gp_ci = vk::GraphicsPipelineCreateInfo::builder()
         .stages(&stages)
@@ -1283,6 +1286,10 @@ pub fn render_toon_from_mesh(ctx: &VkContext, width: u32, height: u32, style: &T
         let vb_offsets = [0u64];
         ctx.device.cmd_bind_vertex_buffers(cmd_buf, 0, &vb_buffers, &vb_offsets);
         ctx.device.cmd_bind_index_buffer(cmd_buf, ib, 0, vk::IndexType::UINT32);
+        // Identity transform keeps the G-buffer aligned with the untransformed outline pass
+        let pc = MeshPushConstants::new(&Camera::default(), &crate::render::math::IDENTITY);
+        let pc_bytes = std::slice::from_raw_parts((&pc as *const MeshPushConstants) as *const u8, std::mem::size_of::<MeshPushConstants>());
+        ctx.device.cmd_push_constants(cmd_buf, pipeline_layout, vk::ShaderStageFlags::VERTEX, 0, pc_bytes);
         ctx.device.cmd_draw_indexed(cmd_buf, inds.len() as u32, 1, 0, 0, 0);
         ctx.device.cmd_end_rendering(cmd_buf);
     }
@@ -1601,11 +1608,11 @@ pub fn render_toon_from_mesh(ctx: &VkContext, width: u32, height: u32, style: &T
     Ok(pixels)
 }
 
-pub fn render_mesh_gbuffer_offscreen(ctx: &VkContext, width: u32, height: u32) -> Result<(Vec<u8>, Vec<u8>)> {
+pub fn render_mesh_gbuffer_offscreen(ctx: &VkContext, width: u32, height: u32, camera: &Camera, model: &Mat4) -> Result<(Vec<u8>, Vec<u8>)> {
     use ash::vk as vk;
-    use crate::render::mesh::{generate_uv_sphere, Vertex};
+    use crate::render::mesh::{generate_uv_sphere, MeshPushConstants, Vertex};
 
-    // Generate a sphere that fits in clip space without projection
+    // Generate a sphere that fits in clip space under the default (identity) camera
     let (verts, inds) = generate_uv_sphere(0.8, 32, 64);
 
     // Create HOST_VISIBLE vertex and index buffers
@@ -1674,7 +1681,8 @@ pub fn render_mesh_gbuffer_offscreen(ctx: &VkContext, width: u32, height: u32) -
     let dyn_state = vk::PipelineDynamicStateCreateInfo::builder().dynamic_states(&dyn_states);
     let color_formats = [albedo_format, normal_format, material_format];
     let mut rendering_info = vk::PipelineRenderingCreateInfo::builder().color_attachment_formats(&color_formats);
-    let layout_ci = vk::PipelineLayoutCreateInfo::builder();
+    let pc_range = vk::PushConstantRange::builder().stage_flags(vk::ShaderStageFlags::VERTEX).offset(0).size(std::mem::size_of::<MeshPushConstants>() as u32).build();
+    let layout_ci = vk::PipelineLayoutCreateInfo::builder().push_constant_ranges(std::slice::from_ref(&pc_range));
     let pipeline_layout = unsafe { ctx.device.create_pipeline_layout(&layout_ci, None)? };
     let gp_ci = vk::GraphicsPipelineCreateInfo::builder()
         .stages(&stages)
@@ -1744,6 +1752,9 @@ pub fn render_mesh_gbuffer_offscreen(ctx: &VkContext, width: u32, height: u32) -
         let vb_offsets = [0u64];
         ctx.device.cmd_bind_vertex_buffers(cmd_buf, 0, &vb_buffers, &vb_offsets);
         ctx.device.cmd_bind_index_buffer(cmd_buf, ib, 0, vk::IndexType::UINT32);
+        let pc = MeshPushConstants::new(camera, model);
+        let pc_bytes = std::slice::from_raw_parts((&pc as *const MeshPushConstants) as *const u8, std::mem::size_of::<MeshPushConstants>());
+        ctx.device.cmd_push_constants(cmd_buf, pipeline_layout, vk::ShaderStageFlags::VERTEX, 0, pc_bytes);
         ctx.device.cmd_draw_indexed(cmd_buf, inds.len() as u32, 1, 0, 0, 0);
         ctx.device.cmd_end_rendering(cmd_buf);
     }
@@ -0,0 +1,21 @@
+//! Library render API smoke test. Needs a Vulkan device; run with `cargo test -p stylize-core --features vulkan`.
+#![cfg(feature = "vulkan")]
+
+use stylize_core::{asset_dna, render};
+
+#[test]
+fn toon_render_returns_rgba_pixels() {
+    if render::list_devices().map(|d| d.is_empty()).unwrap_or(true) {
+        eprintln!("skipping: no Vulkan device available");
+        return;
+    }
+    let yaml = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/../examples/char_01.yml")).unwrap();
+    let dna = asset_dna::load_from_yaml_str(&yaml).unwrap();
+    let (w, h) = (64, 32);
+    let pixels = render::render_toon_rgba(Some(&dna), w, h).unwrap();
+    assert_eq!(pixels.len(), (w * h * 4) as usize);
+    assert!(pixels.chunks_exact(4).any(|p| p[..3] != [0, 0, 0]), "image should not be blank");
+
+    let mesh = render::render_toon_mesh_rgba(None, w, h, Some(2.0)).unwrap();
+    assert_eq!(mesh.len(), pixels.len());
+}
@@ -1,10 +1,17 @@
-use macrokid::{make_enum, trace};
-use custom_derive::{Display, DebugVerbose, Display2, FirstExposed, AssocImpl, DisplayDSL};
+use macrokid::{make_enum, memoize, state_machine, trace};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use custom_derive::{Builder, Display, DebugVerbose, Display2, FirstExposed, AssocImpl, DisplayDSL};
 use custom_derive_support::AssocDemo;
 
 // Function-like macro: generates an enum with Display + FromStr
 make_enum!(Color: Red, Green, Blue);
 
+// Function-like macro: state + event enums and a checked `step` from a transition table
+state_machine!(Door {
+    states: [Closed, Open, Locked],
+    transitions: [Closed -> Open on open, Open -> Closed on close, Closed -> Locked on lock, Locked -> Closed on unlock],
+});
+
 // Derive macro: implement Display for this enum (prints variant names)
 #[derive(Debug, Display, Clone, Copy)]
 enum Mode {
@@ -17,16 +24,50 @@ enum Mode {
 #[display("Point2D")]
 struct Point(i32, i32);
 
-// Demonstrate the more advanced DebugVerbose derive
+// Display with field values: prints `Size { width: 3, height: 4 }`
+#[derive(Display)]
+#[display(fields)]
+struct Size {
+    width: u32,
+    height: u32,
+    #[display(skip)]
+    #[allow(dead_code)]
+    cache: Vec<u8>,
+}
+
+#[derive(Display)]
+#[display(fields)]
+struct Rgb(u8, u8, u8);
+
+// Lifetimes and const generics carry through to the generated impl
+#[derive(Display)]
+#[display("Buffer")]
+#[allow(dead_code)]
+struct Buf<'a, 'b: 'a, const N: usize, T> {
+    head: &'a [T; N],
+    tail: &'b [T],
+}
+
+// Demonstrate the more advanced DebugVerbose derive. A plain #[skip] warns at the field;
+// this one is skipped on purpose, so #[skip(quiet)] says so and keeps the build quiet.
 #[derive(DebugVerbose)]
 #[debug_verbose("CustomConfig")]
 struct Config {
     name: String,
-    #[skip]
+    #[skip(quiet)]
     secret_key: String,
     port: u16,
 }
 
+// Builder with `from_value`: an existing config can be turned back into a builder and tweaked
+#[derive(Builder, Clone, Debug, PartialEq)]
+#[builder(from_value)]
+struct ServerConfig {
+    host: String,
+    port: u16,
+    workers: usize,
+}
+
 // Attribute macro: times the function and logs duration
 #[trace]
 fn work(mode: Mode) -> usize {
@@ -43,11 +84,32 @@ fn work(mode: Mode) -> usize {
     }
 }
 
+// Attribute macro: caches results keyed on the arguments; FIB_CALLS counts real evaluations
+static FIB_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+#[memoize]
+fn fib(n: u64) -> u64 {
+    FIB_CALLS.fetch_add(1, Ordering::Relaxed);
+    if n < 2 { n } else { fib(n - 1) + fib(n - 2) }
+}
+
+static SCALE_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+#[memoize(capacity = 2)]
+fn scale(value: u32, factor: u32) -> u32 {
+    SCALE_CALLS.fetch_add(1, Ordering::Relaxed);
+    value * factor
+}
+
 fn main() {
     // Function-like macro: Display on generated enum
     let c: Color = "Green".parse().expect("valid variant");
     println!("Color from str: {}", c);
 
+    // Function-like macro: walk the door through its transition table
+    let door = Door::Closed.step(DoorEvent::Lock).and_then(|d| d.step(DoorEvent::Unlock)).expect("valid transitions");
+    println!("Door: {:?} ({} transitions); opening a locked door -> {}", door, Door::TRANSITIONS.len(), Door::Locked.step(DoorEvent::Open).unwrap_err());
+
     // Custom derive macro: Display on our hand-written enum and struct
     println!("Mode: {}", Mode::Fast);
     println!("Mode (custom): {}", Mode::Slow);
@@ -56,6 +118,7 @@ fn main() {
     let p = Point(3, 4);
     let Point(a, b) = p;
     let _sum = a + b;
+    println!("Struct fields: {} / {}", Size { width: 3, height: 4, cache: Vec::new() }, Rgb(255, 128, 0));
 
     // Advanced custom derive: DebugVerbose 
     let config = Config {
@@ -67,10 +130,19 @@ fn main() {
     // Ensure skipped field participates in runtime to avoid dead code
     let _sk_len = config.secret_key.len();
 
+    // Builder: start from scratch, then derive a variant from the finished value
+    let base = ServerConfig::builder().host("localhost".into()).port(8080).workers(4).build().expect("all fields set");
+    let alt = ServerConfigBuilder::from(base.clone()).port(9090).build().expect("pre-filled");
+    println!("Builder: {:?} -> port {}", base, alt.port);
+
     // Attribute macro: trace function execution
     let n = work(Mode::Slow);
     println!("work returned {}", n);
 
+    // Attribute macro: memoized recursion evaluates each n once
+    println!("fib(50) = {} after {} evaluations", fib(50), FIB_CALLS.load(Ordering::Relaxed));
+    println!("scale(3, 4) = {}", scale(3, 4));
+
     // Demonstrate Display2 using semantic helper
     #[derive(Display2)]
     enum State { One, Two }
@@ -105,3 +177,215 @@ fn main() {
     let out: <AssocHolder as AssocDemo>::Output = AssocHolder.get();
     println!("AssocDemo get() -> {}", out);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+    use std::time::Duration;
+
+    /// Re-run `test` in a child test process (with `MK_TRACE_CHILD` set) and return its stderr,
+    /// where `#[trace]` writes.
+    fn traced_stderr(test: &str) -> String {
+        let out = std::process::Command::new(std::env::current_exe().unwrap())
+            .args([test, "--exact", "--nocapture", "--test-threads=1"])
+            .env("MK_TRACE_CHILD", "1")
+            .output()
+            .unwrap();
+        assert!(out.status.success(), "child test failed: {}", String::from_utf8_lossy(&out.stderr));
+        String::from_utf8_lossy(&out.stderr).into_owned()
+    }
+
+    fn in_trace_child() -> bool { std::env::var_os("MK_TRACE_CHILD").is_some() }
+
+    /// Durations logged as `<prefix> <name> took <Duration:?>`.
+    fn logged_durations(stderr: &str, name: &str) -> Vec<Duration> {
+        let marker = format!(" {} took ", name);
+        stderr.lines().filter_map(|l| l.split_once(&marker)).map(|(_, d)| {
+            let split = d.find(|c: char| c.is_ascii_alphabetic() || c == 'µ').unwrap();
+            let (value, unit) = d.split_at(split);
+            let value: f64 = value.parse().unwrap();
+            let scale = match unit { "s" => 1.0, "ms" => 1e-3, "µs" => 1e-6, "ns" => 1e-9, other => panic!("unit {}", other) };
+            Duration::from_secs_f64(value * scale)
+        }).collect()
+    }
+
+    /// Pending until a helper thread has slept for the given duration.
+    struct Sleep { done: Arc<std::sync::atomic::AtomicBool>, dur: Duration, started: bool }
+
+    impl Future for Sleep {
+        type Output = ();
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+            if self.done.load(Ordering::Acquire) { return Poll::Ready(()); }
+            if !self.started {
+                self.started = true;
+                let (done, dur, waker) = (self.done.clone(), self.dur, cx.waker().clone());
+                std::thread::spawn(move || { std::thread::sleep(dur); done.store(true, Ordering::Release); waker.wake(); });
+            }
+            Poll::Pending
+        }
+    }
+
+    struct ThreadWaker(std::thread::Thread);
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) { self.0.unpark(); }
+    }
+
+    fn block_on<F: Future>(fut: F) -> F::Output {
+        let mut fut = Box::pin(fut);
+        let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            if let Poll::Ready(v) = fut.as_mut().poll(&mut cx) { return v; }
+            std::thread::park();
+        }
+    }
+
+    #[trace]
+    async fn fetch_after(delay_ms: u64) -> Result<u64, String> {
+        Sleep { done: Default::default(), dur: Duration::from_millis(delay_ms), started: false }.await;
+        let n: u64 = "7".parse().map_err(|e: std::num::ParseIntError| e.to_string())?;
+        Ok(n + delay_ms)
+    }
+
+    #[trace(min_ms = 1000)]
+    fn quick() -> u32 { 1 }
+
+    #[trace(min_ms = 5)]
+    fn sluggish() -> u32 { std::thread::sleep(Duration::from_millis(10)); 2 }
+
+    #[trace(sample = 3)]
+    fn hot(i: u32) -> u32 { i }
+
+    #[trace(sample = 2, min_ms = 1000)]
+    fn hot_and_quick() {}
+
+    #[test]
+    fn trace_threshold_and_sampling() {
+        if in_trace_child() {
+            assert_eq!(quick(), 1);
+            assert_eq!(sluggish(), 2);
+            assert_eq!((0..7).map(hot).sum::<u32>(), 21);
+            (0..4).for_each(|_| hot_and_quick());
+            return;
+        }
+        let stderr = traced_stderr("tests::trace_threshold_and_sampling");
+        assert!(logged_durations(&stderr, "quick").is_empty(), "fast call under min_ms must not log:\n{}", stderr);
+        assert_eq!(logged_durations(&stderr, "sluggish").len(), 1);
+        // Calls 1, 4 and 7 of 7
+        assert_eq!(logged_durations(&stderr, "hot").len(), 3);
+        assert!(logged_durations(&stderr, "hot_and_quick").is_empty());
+    }
+
+    #[test]
+    fn trace_async_covers_await() {
+        if in_trace_child() {
+            assert_eq!(block_on(fetch_after(30)), Ok(37));
+            return;
+        }
+        let logged = logged_durations(&traced_stderr("tests::trace_async_covers_await"), "fetch_after");
+        assert_eq!(logged.len(), 1);
+        assert!(logged[0] >= Duration::from_millis(30), "logged {:?}", logged[0]);
+    }
+
+    #[test]
+    fn debug_verbose_skips_quiet_field() {
+        let config = Config { name: "svc".into(), secret_key: "hunter2".into(), port: 8 };
+        assert_eq!(format!("{:?}", config), r#"CustomConfig { name: "svc", port: 8 }"#);
+    }
+
+    #[test]
+    fn lazy_field_types_slice_is_stable() {
+        #[derive(custom_derive::FieldTypes)]
+        #[allow(dead_code)]
+        struct Row { id: u32, tags: Vec<String> }
+        let first = Row::field_types();
+        assert_eq!(first[0], custom_derive_support::FieldType { name: "id".into(), ty: "u32".into() });
+        assert_eq!(first[1].name, "tags");
+        assert!(std::ptr::eq(first, Row::field_types()));
+    }
+
+    #[test]
+    fn make_enum_lists_all_variants() {
+        assert_eq!(Color::ALL.len(), 3);
+        assert_eq!(Color::ALL[1], Color::Green);
+        let names: Vec<String> = Color::iter().map(|c| c.to_string()).collect();
+        assert_eq!(names, ["Red", "Green", "Blue"]);
+    }
+
+    #[test]
+    fn display_fields_named_struct() {
+        let s = Size { width: 3, height: 4, cache: vec![1] };
+        assert_eq!(s.to_string(), "Size { width: 3, height: 4 }");
+    }
+
+    #[test]
+    fn door_state_machine_steps() {
+        let open = Door::Closed.step(DoorEvent::Open).unwrap();
+        assert_eq!(open, Door::Open);
+        assert_eq!(open.step(DoorEvent::Close), Ok(Door::Closed));
+        assert_eq!(Door::Closed.step(DoorEvent::Lock).and_then(|d| d.step(DoorEvent::Unlock)), Ok(Door::Closed));
+        assert_eq!(Door::Locked.step(DoorEvent::Open), Err(DoorInvalidTransition { from: Door::Locked, event: DoorEvent::Open }));
+        assert!(Door::Open.step(DoorEvent::Lock).is_err());
+        assert_eq!(Door::TRANSITIONS[0], (Door::Closed, DoorEvent::Open, Door::Open));
+    }
+
+    #[test]
+    fn memoize_runs_body_once_per_args() {
+        let before = FIB_CALLS.load(Ordering::Relaxed);
+        assert_eq!(fib(30), 832_040);
+        let first = FIB_CALLS.load(Ordering::Relaxed) - before;
+        assert!(first <= 31, "each n should be evaluated at most once, got {}", first);
+        assert_eq!(fib(30), 832_040);
+        assert_eq!(FIB_CALLS.load(Ordering::Relaxed) - before, first);
+    }
+
+    #[test]
+    fn memoize_capacity_evicts_least_recently_used() {
+        assert_eq!(scale(1, 2), 2);
+        assert_eq!(scale(2, 2), 4);
+        assert_eq!(scale(1, 2), 2); // hit; (2, 2) is now the oldest
+        assert_eq!(SCALE_CALLS.load(Ordering::Relaxed), 2);
+        assert_eq!(scale(3, 2), 6); // evicts (2, 2)
+        assert_eq!(scale(1, 2), 2);
+        assert_eq!(SCALE_CALLS.load(Ordering::Relaxed), 3);
+        assert_eq!(scale(2, 2), 4);
+        assert_eq!(SCALE_CALLS.load(Ordering::Relaxed), 4);
+    }
+
+    #[test]
+    fn builder_from_value_preserves_untouched_fields() {
+        let base = ServerConfig { host: "example.org".into(), port: 80, workers: 8 };
+        let tweaked = ServerConfigBuilder::from(base.clone()).port(443).build().unwrap();
+        assert_eq!(tweaked, ServerConfig { port: 443, ..base });
+        assert_eq!(ServerConfig::builder().port(1).build(), Err("missing field `host`"));
+    }
+
+    #[test]
+    fn display_fields_tuple_struct() {
+        assert_eq!(Rgb(255, 128, 0).to_string(), "Rgb(255, 128, 0)");
+    }
+
+    #[test]
+    fn display_fields_custom_name_with_braces() {
+        #[derive(Display)]
+        #[display("Pair{T}")]
+        #[display(fields)]
+        struct Pair { a: u8, b: u8 }
+        #[derive(Display)]
+        #[display("{}")]
+        #[display(fields)]
+        struct Wrap(u8);
+        assert_eq!(Pair { a: 1, b: 2 }.to_string(), "Pair{T} { a: 1, b: 2 }");
+        assert_eq!(Wrap(7).to_string(), "{}(7)");
+    }
+
+    #[test]
+    fn display_on_const_generic_struct() {
+        let tail = vec![4u8, 5];
+        assert_eq!(Buf { head: &[1u8, 2, 3], tail: &tail }.to_string(), "Buffer");
+    }
+}
@@ -10,6 +10,8 @@ use syn::{parse_macro_input, DeriveInput};
 /// - `#[display("CustomName")]` on types and variants  
 /// - Automatic variant name display for enums
 /// - Automatic type name display for structs
+/// - `#[display(fields)]` on structs to print field values (`Name { a: 1 }` / `Name(1)`),
+///   skipping fields marked `#[display(skip)]`
 #[proc_macro_derive(Display, attributes(display))]
 pub fn derive_display(input: TokenStream) -> TokenStream {
     let input: DeriveInput = parse_macro_input!(input);
@@ -70,12 +72,31 @@ mod display_impl {
                     Some(s) => s,
                     None => default_name,
                 };
-                match st.fields {
+                match &st.fields {
+                    FieldKind::Named(fields) if display_word(&spec.attrs, "fields") => {
+                        let shown: Vec<_> = fields.iter().filter(|f| !display_word(&f.attrs, "skip")).collect();
+                        if shown.is_empty() {
+                            quote! { f.write_str(#name) }
+                        } else {
+                            let parts: Vec<String> = shown.iter().map(|f| format!("{}: {{}}", f.ident.as_ref().unwrap())).collect();
+                            // The name is an argument, not part of the format string: it may contain braces
+                            let fmt = format!("{{}} {{{{ {} }}}}", parts.join(", "));
+                            let idents = shown.iter().map(|f| f.ident.as_ref().unwrap());
+                            quote! { write!(f, #fmt, #name, #( self.#idents ),*) }
+                        }
+                    }
+                    FieldKind::Unnamed(fields) if display_word(&spec.attrs, "fields") => {
+                        let shown: Vec<_> = fields.iter().filter(|f| !display_word(&f.attrs, "skip")).collect();
+                        let fmt = format!("{{}}({})", vec!["{}"; shown.len()].join(", "));
+                        let indices = shown.iter().map(|f| syn::Index::from(f.index));
+                        quote! { write!(f, #fmt, #name, #( self.#indices ),*) }
+                    }
                     FieldKind::Named(_) | FieldKind::Unnamed(_) | FieldKind::Unit => {
                         quote! { f.write_str(#name) }
                     }
                 }
             }
+            TypeKind::Union(_) => return Err(err_at_span(spec.span, "Display does not support unions")),
         };
 
         // Step 3: Use the builder pattern to generate clean implementation
@@ -90,9 +111,17 @@ mod display_impl {
 
         Ok(impl_block)
     }
+
+    /// True if any `#[display(word)]` attribute carries the bare identifier `word`.
+    fn display_word(attrs: &[syn::Attribute], word: &str) -> bool {
+        attrs.iter().any(|a| a.path().is_ident("display") && a.parse_args::<syn::Ident>().is_ok_and(|id| id == word))
+    }
 }
 
 /// Example of a more complex derive macro showing advanced framework usage
+///
+/// `#[skip]` leaves a named field out of the output and warns at the field;
+/// `#[skip(quiet)]` marks the skip as intentional and emits no warning.
 #[proc_macro_derive(DebugVerbose, attributes(debug_verbose, skip))]
 pub fn derive_debug_verbose(input: TokenStream) -> TokenStream {
     let input: DeriveInput = parse_macro_input!(input);
@@ -102,13 +131,13 @@ pub fn derive_debug_verbose(input: TokenStream) -> TokenStream {
 mod debug_verbose_impl {
     use macrokid_core::{
         ir::{FieldKind, TypeKind, TypeSpec},
-        attrs::{attr_string_value, has_attr},
-        diag::{suggest_with_note, err_at_span},
+        attrs::attr_string_value,
+        diag::{err_at_span, warn_at_span},
         builders::ImplBuilder,
     };
     use proc_macro2::TokenStream as TokenStream2;
     use quote::quote;
-    use syn::DeriveInput;
+    use syn::{spanned::Spanned, DeriveInput};
 
     pub fn expand(input: DeriveInput) -> TokenStream2 {
         match expand_inner(input) {
@@ -120,6 +149,7 @@ mod debug_verbose_impl {
     fn expand_inner(input: DeriveInput) -> syn::Result<TokenStream2> {
         let spec = TypeSpec::from_derive_input(input)?;
         let ident = &spec.ident;
+        let mut warnings = TokenStream2::new();
 
         let body = match &spec.kind {
             TypeKind::Enum(en) => {
@@ -184,17 +214,19 @@ mod debug_verbose_impl {
                         quote! { f.debug_struct(#custom_name).finish() }
                     }
                     FieldKind::Named(fields) => {
-                        let field_debug = fields.iter().map(|field| {
+                        let mut field_debug = Vec::new();
+                        for field in fields {
                             let field_ident = field.ident.as_ref().unwrap();
                             let field_name = field_ident.to_string();
-                            if has_attr(&field.attrs, "skip") {
-                                // Showcase diagnostics helper for a benign hint
-                                let _ = suggest_with_note(&field.ident, "field is skipped", "remove #[skip] to include in DebugVerbose");
-                                quote! {} // Skip this field
-                            } else {
-                                quote! { .field(#field_name, &self.#field_ident) }
+                            match skip_mode(&field.attrs)? {
+                                Some(true) => {}
+                                Some(false) => {
+                                    let msg = format!("field `{}` is skipped; remove #[skip] to include it in DebugVerbose, or use #[skip(quiet)] if that is intended", field_name);
+                                    warnings.extend(warn_at_span(field_ident.span(), &msg));
+                                }
+                                None => field_debug.push(quote! { .field(#field_name, &self.#field_ident) }),
                             }
-                        });
+                        }
                         quote! {
                             f.debug_struct(#custom_name)#(#field_debug)*.finish()
                         }
@@ -211,6 +243,7 @@ mod debug_verbose_impl {
                     }
                 }
             }
+            TypeKind::Union(_) => return Err(err_at_span(spec.span, "DebugVerbose does not support unions: the active field is unknown")),
         };
 
         let impl_block = ImplBuilder::new(ident.clone(), spec.generics)
@@ -222,7 +255,19 @@ mod debug_verbose_impl {
             })
             .build();
 
-        Ok(impl_block)
+        Ok(quote! { #impl_block #warnings })
+    }
+
+    /// `None` without `#[skip]`, `Some(quiet)` with `#[skip]` / `#[skip(quiet)]`.
+    fn skip_mode(attrs: &[syn::Attribute]) -> syn::Result<Option<bool>> {
+        let Some(attr) = attrs.iter().find(|a| a.path().is_ident("skip")) else { return Ok(None) };
+        if matches!(attr.meta, syn::Meta::Path(_)) {
+            return Ok(Some(false));
+        }
+        match attr.parse_args::<syn::Ident>() {
+            Ok(id) if id == "quiet" => Ok(Some(true)),
+            _ => Err(err_at_span(attr.span(), "expected `#[skip]` or `#[skip(quiet)]`")),
+        }
     }
 }
 
@@ -264,8 +309,8 @@ mod display2_impl {
                 })
                 .build_match(quote! { self })
             }
-            TypeKind::Struct(_) => {
-                // Fallback: type name for structs
+            TypeKind::Struct(_) | TypeKind::Union(_) => {
+                // Fallback: type name for structs and unions
                 let name = ident.to_string();
                 quote! { f.write_str(#name) }
             }
@@ -400,7 +445,7 @@ mod display_dsl_impl {
                 }
                 b.build_match(quote! { self })
             }
-            TypeKind::Struct(_) => {
+            TypeKind::Struct(_) | TypeKind::Union(_) => {
                 let name = ident.to_string();
                 quote! { f.write_str(#name) }
             }
@@ -447,3 +492,129 @@ mod assoc_impl {
             .build()
     }
 }
+
+/// Derive `Foo::field_types() -> &'static [custom_derive_support::FieldType]` for named-field
+/// structs. The entries own `String`s, so the slice comes from `codegen::lazy_slice_mod`.
+#[proc_macro_derive(FieldTypes)]
+pub fn derive_field_types(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(input);
+    field_types_impl::expand(input).into()
+}
+
+mod field_types_impl {
+    use macrokid_core::{common::codegen, ir::TypeSpec};
+    use proc_macro2::TokenStream as TokenStream2;
+    use quote::quote;
+    use syn::DeriveInput;
+
+    pub fn expand(input: DeriveInput) -> TokenStream2 {
+        match expand_inner(input) {
+            Ok(ts) => ts,
+            Err(e) => e.to_compile_error(),
+        }
+    }
+
+    fn expand_inner(input: DeriveInput) -> syn::Result<TokenStream2> {
+        let spec = TypeSpec::from_derive_input(input)?;
+        let entries: Vec<TokenStream2> = spec.named_fields()?.iter().map(|f| {
+            let name = f.ident.as_ref().unwrap().to_string();
+            let ty = &f.ty;
+            let ty = quote!(#ty).to_string();
+            quote! { custom_derive_support::FieldType { name: ::std::string::String::from(#name), ty: ::std::string::String::from(#ty) } }
+        }).collect();
+        let ty = quote! { custom_derive_support::FieldType };
+        let hint = format!("field_types_{}", spec.ident);
+        let (mod_ident, module) = codegen::lazy_slice_mod(&hint, ty.clone(), entries);
+        let inherent = codegen::impl_inherent_methods(&spec, &[quote! {
+            pub fn field_types() -> &'static [#ty] { #mod_ident::data() }
+        }]);
+        Ok(quote! { #module #inherent })
+    }
+}
+
+/// Classic builder for named-field structs: `Foo::builder()` returns a `FooBuilder` whose
+/// fields are all `Option<T>`, with one setter per field and a `build()` that fails on the
+/// first unset field.
+///
+/// `#[builder(from_value)]` also emits `impl From<Foo> for FooBuilder`, pre-filling every
+/// setter so an existing value can be tweaked (`FooBuilder::from(foo.clone()).port(9).build()`).
+///
+/// Tuple and unit structs, enums and generic types are rejected:
+///
+/// ```compile_fail
+/// use custom_derive::Builder;
+/// #[derive(Builder)]
+/// struct Point(f32, f32);
+/// ```
+///
+/// ```compile_fail
+/// use custom_derive::Builder;
+/// #[derive(Builder)]
+/// struct Wrapper<T> { value: T }
+/// ```
+#[proc_macro_derive(Builder, attributes(builder))]
+pub fn derive_builder(input: TokenStream) -> TokenStream {
+    let input: DeriveInput = parse_macro_input!(input);
+    builder_impl::expand(input).into()
+}
+
+mod builder_impl {
+    use macrokid_core::{builders::ImplBuilder, ir::TypeSpec};
+    use proc_macro2::TokenStream as TokenStream2;
+    use quote::{format_ident, quote};
+    use syn::DeriveInput;
+
+    pub fn expand(input: DeriveInput) -> TokenStream2 {
+        match expand_inner(input) {
+            Ok(ts) => ts,
+            Err(e) => e.to_compile_error(),
+        }
+    }
+
+    fn expand_inner(input: DeriveInput) -> syn::Result<TokenStream2> {
+        let spec = TypeSpec::from_derive_input(input)?;
+        spec.forbid_generics()?;
+        let fields = spec.named_fields()?;
+        let ident = &spec.ident;
+        let vis = &spec.vis;
+        let builder = format_ident!("{}Builder", ident);
+
+        let mut from_value = false;
+        for a in spec.attrs.iter().filter(|a| a.path().is_ident("builder")) {
+            a.parse_nested_meta(|meta| {
+                if meta.path.is_ident("from_value") { from_value = true; Ok(()) } else { Err(meta.error("expected `from_value`")) }
+            })?;
+        }
+
+        let names: Vec<_> = fields.iter().map(|f| f.ident.as_ref().unwrap()).collect();
+        let tys: Vec<_> = fields.iter().map(|f| &f.ty).collect();
+        let missing: Vec<String> = names.iter().map(|n| format!("missing field `{}`", n)).collect();
+
+        let builder_methods = ImplBuilder::new(builder.clone(), spec.generics.clone())
+            .add_method(quote! {
+                pub fn build(self) -> ::core::result::Result<#ident, &'static str> {
+                    Ok(#ident { #( #names: self.#names.ok_or(#missing)? ),* })
+                }
+            });
+        let builder_methods = names.iter().zip(&tys).fold(builder_methods, |b, (name, ty)| b.add_method(quote! {
+            pub fn #name(mut self, value: #ty) -> Self { self.#name = Some(value); self }
+        })).build();
+        let entry = ImplBuilder::new(ident.clone(), spec.generics.clone())
+            .add_method(quote! { pub fn builder() -> #builder { #builder::default() } })
+            .build();
+        let from_impl = if from_value {
+            ImplBuilder::new(builder.clone(), spec.generics.clone())
+                .implement_trait(quote! { ::core::convert::From<#ident> })
+                .add_method(quote! { fn from(value: #ident) -> Self { Self { #( #names: Some(value.#names) ),* } } })
+                .build()
+        } else { quote! {} };
+
+        Ok(quote! {
+            #[derive(Default)]
+            #vis struct #builder { #( #names: ::core::option::Option<#tys> ),* }
+            #entry
+            #builder_methods
+            #from_impl
+        })
+    }
+}
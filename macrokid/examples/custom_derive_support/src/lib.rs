@@ -1,3 +1,10 @@
+/// One entry of `FieldTypes::field_types()`: a field's name and its type as written.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldType {
+    pub name: String,
+    pub ty: String,
+}
+
 pub trait AssocDemo {
     type Output;
     const COUNT: usize;
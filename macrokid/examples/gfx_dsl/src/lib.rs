@@ -6,7 +6,8 @@ use syn::{
     parse::{Parse, ParseStream},
     parse_macro_input, Ident, LitBool, LitInt, LitStr, Result, Token,
 };
-use macrokid_core::diag::err_on;
+use macrokid_core::diag::{err_on, Collector};
+use std::path::Path;
 
 mod kw {
     syn::custom_keyword!(app);
@@ -19,6 +20,7 @@ mod kw {
     syn::custom_keyword!(fs);
     syn::custom_keyword!(topology);
     syn::custom_keyword!(depth);
+    syn::custom_keyword!(check_shaders);
 }
 
 // Minimal Vulkan-first graphics DSL as a token-based macro.
@@ -30,6 +32,8 @@ struct EngineCfgAst {
     app: Option<LitStr>,
     window: Option<WindowCfgAst>,
     graph: Option<GraphCfgAst>,
+    /// `check_shaders: true` makes expansion fail on shader paths missing from disk.
+    check_shaders: Option<LitBool>,
 }
 
 #[derive(Debug, Clone)]
@@ -66,6 +70,7 @@ impl Parse for EngineCfgAst {
         let mut app = None;
         let mut window = None;
         let mut graph = None;
+        let mut check_shaders = None;
 
         while !content.is_empty() {
             if content.peek(kw::app) {
@@ -74,14 +79,16 @@ impl Parse for EngineCfgAst {
                 content.parse::<kw::window>()?; content.parse::<Token![:]>()?; window = Some(content.parse()?);
             } else if content.peek(kw::graph) {
                 content.parse::<kw::graph>()?; content.parse::<Token![:]>()?; graph = Some(content.parse()?);
+            } else if content.peek(kw::check_shaders) {
+                content.parse::<kw::check_shaders>()?; content.parse::<Token![:]>()?; check_shaders = Some(content.parse()?);
             } else {
                 let look: Ident = content.parse()?;
-                return Err(err_on(&look, "expected one of: app, window, graph"));
+                return Err(err_on(&look, "expected one of: app, window, graph, check_shaders"));
             }
             let _ = content.parse::<Token![,]>();
         }
 
-        Ok(Self { app, window, graph })
+        Ok(Self { app, window, graph, check_shaders })
     }
 }
 
@@ -161,6 +168,29 @@ impl Parse for GraphCfgAst {
     }
 }
 
+// ===================== Validation =====================
+// Shader sources embedded in the path string (the lighting crate's convention) have no file.
+const INLINE_PREFIXES: &[&str] = &["inline.vert:", "inline.frag:"];
+
+impl EngineCfgAst {
+    /// With `check_shaders: true`, require every `vs`/`fs` path to exist relative to `root`
+    /// (the invoking crate's `CARGO_MANIFEST_DIR`); one error per missing file.
+    fn check_shader_paths(&self, root: &Path) -> Result<()> {
+        if !self.check_shaders.as_ref().is_some_and(|b| b.value) { return Ok(()); }
+        let mut errs = Collector::new();
+        let pipelines = self.graph.iter().flat_map(|g| &g.passes).flat_map(|p| &p.pipelines);
+        for lit in pipelines.flat_map(|p| p.vs.iter().chain(p.fs.iter())) {
+            let path = lit.value();
+            if INLINE_PREFIXES.iter().any(|pre| path.starts_with(pre)) { continue; }
+            let resolved = root.join(&path);
+            if !resolved.is_file() {
+                errs.push(err_on(lit, &format!("shader file not found: {}", resolved.display())));
+            }
+        }
+        errs.into_result(())
+    }
+}
+
 // ===================== Codegen helpers =====================
 struct CfgTokens { cfg_mod: proc_macro2::TokenStream }
 
@@ -238,6 +268,10 @@ impl EngineCfgAst {
 #[proc_macro]
 pub fn vk_engine(input: TokenStream) -> TokenStream {
     let cfg = parse_macro_input!(input as EngineCfgAst);
+    let root = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    if let Err(e) = cfg.check_shader_paths(Path::new(&root)) {
+        return e.to_compile_error().into();
+    }
     let parts = cfg.to_tokens();
     let CfgTokens { cfg_mod } = parts;
     let out = quote! {
@@ -289,4 +323,25 @@ mod tests {
         let t = "{ app: \"A\", window: { width: 1, height: 2, vsync: true }, graph: { pass main { pipelines: [ pipeline p { unknown: 1 } ] } } }";
         assert!(parse_err_contains(t, "unknown pipeline key"));
     }
+
+    fn check(tokens: &str) -> Result<()> {
+        let cfg = syn::parse_str::<EngineCfgAst>(tokens).unwrap();
+        cfg.check_shader_paths(Path::new(env!("CARGO_MANIFEST_DIR")))
+    }
+
+    #[test]
+    fn check_shaders_reports_missing_file() {
+        let t = "{ check_shaders: true, graph: { pass main { pipelines: [ pipeline p { vs: \"src/lib.rs\", fs: \"shaders/missing.frag\" } ] } } }";
+        let msg = check(t).unwrap_err().to_string();
+        let expected = Path::new(env!("CARGO_MANIFEST_DIR")).join("shaders/missing.frag");
+        assert_eq!(msg, format!("shader file not found: {}", expected.display()));
+    }
+
+    #[test]
+    fn check_shaders_is_opt_in_and_skips_inline() {
+        let off = "{ graph: { pass main { pipelines: [ pipeline p { vs: \"nope.vert\", fs: \"nope.frag\" } ] } } }";
+        assert!(check(off).is_ok());
+        let inline = "{ check_shaders: true, graph: { pass main { pipelines: [ pipeline p { vs: \"inline.vert:void main() {}\", fs: \"inline.frag:void main() {}\" } ] } } }";
+        assert!(check(inline).is_ok());
+    }
 }
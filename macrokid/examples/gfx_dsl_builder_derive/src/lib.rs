@@ -1,7 +1,7 @@
 use proc_macro::TokenStream;
 use macrokid_core::{ir::TypeSpec, builders::ImplBuilder};
-use proc_macro2::TokenStream as TokenStream2;
-use quote::{quote, quote_spanned};
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{quote, quote_spanned, ToTokens};
 use syn::{parse_macro_input, spanned::Spanned, DeriveInput, Ident, LitStr};
 
 #[proc_macro_derive(FluentBuilder, attributes(builder, builder_transition))]
@@ -20,16 +20,20 @@ fn expand(input: DeriveInput) -> TokenStream2 {
 fn expand_inner(input: DeriveInput) -> syn::Result<TokenStream2> {
     let spec = TypeSpec::from_derive_input(input.clone())?;
     let ident = spec.ident.clone();
+    let TypeOptions { in_place, error } = parse_type_options(&spec)?;
+    // Setters go on the struct itself (`#[builder(in_place)]`) or on a companion `<Name>Builder`
+    // holding `Option<T>` per field, finished by a validating `build()`.
+    let builder_ident = Ident::new(&format!("{}Builder", ident), ident.span());
     let mut methods: Vec<TokenStream2> = Vec::new();
-
-    // Expect a struct with named fields
-    let field_specs: Vec<macrokid_core::ir::FieldSpec> = match &spec.kind {
-        macrokid_core::TypeKind::Struct(st) => match st.fields() {
-            macrokid_core::FieldKind::Named(v) => v.clone(),
-            _ => return Err(syn::Error::new(spec.span, "FluentBuilder expects a struct with named fields")),
-        },
-        _ => return Err(syn::Error::new(spec.span, "FluentBuilder expects a struct type")),
+    let mut impl_builder = if in_place {
+        ImplBuilder::new(ident.clone(), spec.generics.clone())
+    } else {
+        ImplBuilder::new(builder_ident.clone(), spec.generics.clone())
     };
+    let mut fields_init: Vec<TokenStream2> = Vec::new();
+    let mut required_checks: Vec<TokenStream2> = Vec::new();
+
+    let field_specs: Vec<macrokid_core::ir::FieldSpec> = spec.named_fields()?.to_vec();
 
     for f in field_specs.iter() {
         let fid = if let Some(id) = &f.ident { id } else { continue };
@@ -37,6 +41,9 @@ fn expand_inner(input: DeriveInput) -> syn::Result<TokenStream2> {
         // Parse #[builder(...)] options
         let mut method_name: Option<Ident> = None;
         let mut tuple_spec: Option<TupleSpec> = None;
+        let mut into = false;
+        let mut extend: Option<Span> = None;
+        let mut required = false;
         for attr in &f.attrs {
             if !attr.path().is_ident("builder") { continue; }
             match &attr.meta {
@@ -46,6 +53,14 @@ fn expand_inner(input: DeriveInput) -> syn::Result<TokenStream2> {
                 syn::Meta::List(list) => {
                     for nested in &list.parse_args_with(syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated)? {
                         match nested {
+                            syn::Meta::Path(p) if p.is_ident("into") => into = true,
+                            syn::Meta::Path(p) if p.is_ident("extend") => extend = Some(p.span()),
+                            syn::Meta::Path(p) if p.is_ident("required") => {
+                                if in_place {
+                                    return Err(syn::Error::new(p.span(), "builder(required) needs the companion builder; remove #[builder(in_place)]"));
+                                }
+                                required = true;
+                            }
                             syn::Meta::NameValue(kv) if kv.path.is_ident("name") => {
                                 if let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }) = &kv.value {
                                     method_name = Some(Ident::new(&s.value(), s.span()));
@@ -61,7 +76,7 @@ fn expand_inner(input: DeriveInput) -> syn::Result<TokenStream2> {
                                     return Err(syn::Error::new(kv.value.span(), "builder(tuple = \"...\") expects string"));
                                 }
                             }
-                            other => return Err(syn::Error::new(other.span(), "unknown builder option; expected name/tuple")),
+                            other => return Err(syn::Error::new(other.span(), "unknown builder option; expected name/tuple/into/required/extend")),
                         }
                     }
                 }
@@ -70,22 +85,80 @@ fn expand_inner(input: DeriveInput) -> syn::Result<TokenStream2> {
         }
 
         let m_ident = method_name.unwrap_or_else(|| fid.clone());
+        let assign = |value: TokenStream2| if in_place {
+            quote! { self.#fid = #value; }
+        } else {
+            quote! { self.#fid = ::core::option::Option::Some(#value); }
+        };
+        if !in_place {
+            let name = fid.to_string();
+            if required {
+                required_checks.push(quote! { if self.#fid.is_none() { missing.push(#name); } });
+                fields_init.push(quote! { #fid: self.#fid.unwrap() });
+            } else {
+                fields_init.push(quote! { #fid: self.#fid.unwrap_or_default() });
+            }
+        }
+
+        if let Some(span) = extend {
+            // #[builder(extend)]: `add_<field>(item)` (or `name = "..."`) plus `<field>_all(iter)`
+            if into || tuple_spec.is_some() {
+                return Err(syn::Error::new(span, "builder(extend) cannot be combined with into/tuple"));
+            }
+            let elem = collection_elem(ty).ok_or_else(|| syn::Error::new(ty.span(), "builder(extend) expects a Vec<T>, HashSet<T> or BTreeSet<T> field"))?;
+            let add = if m_ident == *fid { Ident::new(&format!("add_{}", fid), fid.span()) } else { m_ident };
+            let all = Ident::new(&format!("{}_all", fid), fid.span());
+            let target = if in_place {
+                quote! { &mut self.#fid }
+            } else {
+                quote! { self.#fid.get_or_insert_with(::core::default::Default::default) }
+            };
+            methods.push(quote_spanned! { fid.span() =>
+                pub fn #add(mut self, item: #elem) -> Self { ::core::iter::Extend::extend(#target, ::core::iter::once(item)); self }
+            });
+            impl_builder = impl_builder.add_generic_method(
+                all,
+                syn::parse_quote!(<I: ::core::iter::IntoIterator<Item = #elem>>),
+                [syn::parse_quote!(mut self), syn::parse_quote!(items: I)],
+                Some(syn::parse_quote!(Self)),
+                quote! { ::core::iter::Extend::extend(#target, items); self },
+            );
+            continue;
+        }
+
+        if into {
+            if tuple_spec.is_some() {
+                return Err(syn::Error::new(fid.span(), "builder(into) cannot be combined with tuple"));
+            }
+            // #[builder(into)]: `fn field<V: Into<Ty>>(mut self, value: V) -> Self`
+            let set = assign(quote! { ::core::convert::Into::into(value) });
+            impl_builder = impl_builder.add_generic_method(
+                m_ident,
+                syn::parse_quote!(<V: ::core::convert::Into<#ty>>),
+                [syn::parse_quote!(mut self), syn::parse_quote!(value: V)],
+                Some(syn::parse_quote!(Self)),
+                quote! { #set self },
+            );
+            continue;
+        }
 
         let method = if let Some(tspec) = tuple_spec {
             let params = tspec.params.iter().map(|(id, ty)| quote! { #id: #ty });
-            let expr = &tspec.expr;
+            let set = assign(tspec.expr.to_token_stream());
             quote_spanned! { fid.span() =>
-                pub fn #m_ident(mut self, #( #params ),* ) -> Self { self.#fid = #expr; self }
+                pub fn #m_ident(mut self, #( #params ),* ) -> Self { #set self }
             }
         } else {
+            let set = assign(quote! { value });
             quote_spanned! { fid.span() =>
-                pub fn #m_ident(mut self, value: #ty) -> Self { self.#fid = value; self }
+                pub fn #m_ident(mut self, value: #ty) -> Self { #set self }
             }
         };
         methods.push(method);
     }
 
     // Parse struct-level transitions: #[builder_transition(method = "finish", to = "Type", receiver = "self|&mut self", body = "{ ... }")]
+    let mut target_methods: Vec<TokenStream2> = Vec::new();
     let transitions = parse_transitions(&spec)?;
     for tr in transitions {
         let Transition { method, receiver, ret_ty, body } = tr;
@@ -93,11 +166,94 @@ fn expand_inner(input: DeriveInput) -> syn::Result<TokenStream2> {
             ReceiverKind::ByValue => quote! { pub fn #method(self) -> #ret_ty },
             ReceiverKind::ByMutRef => quote! { pub fn #method(&mut self) -> #ret_ty },
         };
-        methods.push(quote! { #sig #body });
+        target_methods.push(quote! { #sig #body });
+    }
+
+    if in_place {
+        methods.extend(target_methods);
+        return Ok(impl_builder.add_method(quote! { #( #methods )* }).build());
+    }
+
+    // Companion builder: unset fields are None until build() checks the required ones
+    let vis = &spec.vis;
+    let generics = &spec.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let names: Vec<&Ident> = field_specs.iter().filter_map(|f| f.ident.as_ref()).collect();
+    let tys = field_specs.iter().map(|f| &f.ty);
+    let builder_doc = format!("Builder for [`{}`]; see `{}::builder()`.", ident, ident);
+    let companion = quote! {
+        #[doc = #builder_doc]
+        #vis struct #builder_ident #generics #where_clause { #( #names: ::core::option::Option<#tys> ),* }
+        impl #impl_generics ::core::default::Default for #builder_ident #ty_generics #where_clause {
+            fn default() -> Self { Self { #( #names: ::core::option::Option::None ),* } }
+        }
+    };
+    // `#[builder(error = "path")]` swaps in any error type built `From<Vec<&'static str>>`
+    let error = error.map_or_else(|| quote! { ::gfx_dsl_support::BuilderError }, |p| p.to_token_stream());
+    methods.push(quote! {
+        /// Build the value; fails naming every `#[builder(required)]` field left unset.
+        /// Other unset fields take their `Default`.
+        pub fn build(self) -> ::core::result::Result<#ident #ty_generics, #error> {
+            let mut missing: ::std::vec::Vec<&'static str> = ::std::vec::Vec::new();
+            #( #required_checks )*
+            if !missing.is_empty() {
+                return ::core::result::Result::Err(<#error as ::core::convert::From<::std::vec::Vec<&'static str>>>::from(missing));
+            }
+            ::core::result::Result::Ok(#ident { #( #fields_init ),* })
+        }
+    });
+    target_methods.push(quote! {
+        pub fn builder() -> #builder_ident #ty_generics { ::core::default::Default::default() }
+    });
+    let builder_impl = impl_builder.add_method(quote! { #( #methods )* }).build();
+    let target_impl = ImplBuilder::new(ident.clone(), spec.generics.clone()).add_method(quote! { #( #target_methods )* }).build();
+    Ok(quote! { #companion #builder_impl #target_impl })
+}
+
+/// Element type `T` of a `Vec<T>`, `HashSet<T>` or `BTreeSet<T>` field (matched on the last path segment).
+fn collection_elem(ty: &syn::Type) -> Option<&syn::Type> {
+    let syn::Type::Path(tp) = ty else { return None };
+    let seg = tp.path.segments.last()?;
+    if !matches!(seg.ident.to_string().as_str(), "Vec" | "HashSet" | "BTreeSet") { return None; }
+    let syn::PathArguments::AngleBracketed(args) = &seg.arguments else { return None };
+    match args.args.first()? {
+        syn::GenericArgument::Type(t) => Some(t),
+        _ => None,
     }
+}
 
-    let impl_block = ImplBuilder::new(ident.clone(), spec.generics).add_method(quote! { #( #methods )* }).build();
-    Ok(impl_block)
+/// Struct-level `#[builder(..)]` options.
+struct TypeOptions {
+    /// `in_place`: setters on the struct itself instead of a companion builder.
+    in_place: bool,
+    /// `error = "path"`: error type of the companion `build()` (default `gfx_dsl_support::BuilderError`).
+    error: Option<syn::Path>,
+}
+
+fn parse_type_options(spec: &TypeSpec) -> syn::Result<TypeOptions> {
+    let mut opts = TypeOptions { in_place: false, error: None };
+    let mut error_span = None;
+    for attr in &spec.attrs {
+        if !attr.path().is_ident("builder") { continue; }
+        for nested in &attr.parse_args_with(syn::punctuated::Punctuated::<syn::Meta, syn::Token![,]>::parse_terminated)? {
+            match nested {
+                syn::Meta::Path(p) if p.is_ident("in_place") => opts.in_place = true,
+                syn::Meta::NameValue(kv) if kv.path.is_ident("error") => {
+                    if let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }) = &kv.value {
+                        opts.error = Some(s.parse().map_err(|e| syn::Error::new(s.span(), format!("invalid error type path: {}", e)))?);
+                        error_span = Some(kv.span());
+                    } else {
+                        return Err(syn::Error::new(kv.value.span(), "builder(error = \"...\") expects string"));
+                    }
+                }
+                other => return Err(syn::Error::new(other.span(), "unknown builder option; expected in_place/error")),
+            }
+        }
+    }
+    if let (true, Some(span)) = (opts.in_place, error_span) {
+        return Err(syn::Error::new(span, "builder(error) needs the companion builder; remove #[builder(in_place)]"));
+    }
+    Ok(opts)
 }
 
 struct Transition { method: Ident, receiver: ReceiverKind, ret_ty: syn::Type, body: syn::Block }
@@ -31,6 +31,7 @@ impl EngineBuilder<HasApp> {
 }
 
 #[derive(gfx_dsl_builder_derive::FluentBuilder)]
+#[builder(in_place)]
 #[builder_transition(
     method = "finish",
     to = "EngineBuilder<HasGraph>",
@@ -44,6 +45,7 @@ impl EngineBuilder<HasWindow> {
 }
 
 #[derive(gfx_dsl_builder_derive::FluentBuilder)]
+#[builder(in_place)]
 #[builder_transition(
     method = "finish_pass",
     to = "GraphBuilder",
@@ -60,6 +62,7 @@ impl GraphBuilder {
 }
 
 #[derive(gfx_dsl_builder_derive::FluentBuilder)]
+#[builder(in_place)]
 #[builder_transition(
     method = "finish",
     to = "PassBuilder",
@@ -71,7 +74,7 @@ pub struct PipelineBuilder {
     name: &'static str,
     #[builder(tuple = "(vs: &'static str, fs: &'static str) => ShaderPaths { vs, fs }", name = "shaders")]
     shaders: ShaderPaths,
-    #[builder]
+    #[builder(into)]
     topology: Topology,
     #[builder]
     depth: bool,
@@ -103,6 +106,29 @@ impl EngineBuilder<HasGraph> {
     }
 }
 
+/// Error from a `build()` generated by `#[derive(FluentBuilder)]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuilderError {
+    /// `#[builder(required)]` fields that were never set, in declaration order.
+    MissingFields(Vec<&'static str>),
+}
+
+impl std::fmt::Display for BuilderError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BuilderError::MissingFields(fields) => write!(f, "missing required field(s): {}", fields.join(", ")),
+        }
+    }
+}
+
+impl std::error::Error for BuilderError {}
+
+/// How a generated `build()` reports unset required fields; a `#[builder(error = "..")]` type
+/// needs the same impl.
+impl From<Vec<&'static str>> for BuilderError {
+    fn from(fields: Vec<&'static str>) -> Self { BuilderError::MissingFields(fields) }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum ValidationError {
     NoPipelines,
@@ -135,6 +161,7 @@ mod tests {
                 .add_pass("main")
                     .add_pipeline("triangle")
                         .shaders("vs", "fs")
+                        .topology(Topology::LineList)
                         .finish()
                 .finish_pass()
             .finish()
@@ -142,6 +169,82 @@ mod tests {
             .expect("valid");
         assert_eq!(cfg.window.width, 800);
         assert_eq!(cfg.pipelines.len(), 1);
+        assert!(matches!(cfg.pipelines[0].topology, Topology::LineList));
+    }
+
+    #[derive(gfx_dsl_builder_derive::FluentBuilder, Debug, PartialEq)]
+    struct Viewport {
+        #[builder(required)]
+        width: u32,
+        #[builder(required, name = "tall")]
+        height: u32,
+        #[builder(into)]
+        label: String,
+    }
+
+    #[test]
+    fn companion_builder_builds_when_required_fields_set() {
+        let vp = Viewport::builder().width(640).tall(480).label("main").build().unwrap();
+        assert_eq!(vp, Viewport { width: 640, height: 480, label: "main".to_string() });
+        // Optional fields fall back to Default
+        assert_eq!(Viewport::builder().tall(1).width(2).build().unwrap().label, "");
+    }
+
+    #[test]
+    fn companion_builder_names_missing_required_fields() {
+        let err = ViewportBuilder::default().label("x").build().unwrap_err();
+        assert_eq!(err, BuilderError::MissingFields(vec!["width", "height"]));
+        assert_eq!(err.to_string(), "missing required field(s): width, height");
+        let err = Viewport::builder().width(1).build().unwrap_err();
+        assert_eq!(err, BuilderError::MissingFields(vec!["height"]));
+    }
+
+    #[derive(Debug, PartialEq)]
+    struct ConfigError(String);
+
+    impl From<Vec<&'static str>> for ConfigError {
+        fn from(fields: Vec<&'static str>) -> Self { ConfigError(format!("unset: {}", fields.join("/"))) }
+    }
+
+    #[derive(gfx_dsl_builder_derive::FluentBuilder, Debug)]
+    #[builder(error = "ConfigError")]
+    struct Limits {
+        #[builder(required)]
+        max: u32,
+        min: u32,
+    }
+
+    #[test]
+    fn companion_builder_uses_custom_error_type() {
+        assert_eq!(Limits::builder().min(1).build().unwrap_err(), ConfigError("unset: max".into()));
+        let limits = Limits::builder().max(8).build().unwrap();
+        assert_eq!((limits.max, limits.min), (8, 0));
+    }
+
+    #[derive(gfx_dsl_builder_derive::FluentBuilder)]
+    struct Batch {
+        #[builder(extend)]
+        ids: Vec<u32>,
+        #[builder(extend, name = "tag")]
+        tags: std::collections::BTreeSet<&'static str>,
+    }
+
+    #[derive(gfx_dsl_builder_derive::FluentBuilder, Default)]
+    #[builder(in_place)]
+    struct Layers {
+        #[builder(extend)]
+        names: Vec<&'static str>,
+    }
+
+    #[test]
+    fn extend_fields_push_items() {
+        let batch = Batch::builder().add_ids(3).add_ids(1).ids_all([4, 1]).tag("b").tag("a").tag("b").build().unwrap();
+        assert_eq!(batch.ids, vec![3, 1, 4, 1]);
+        assert_eq!(batch.tags.into_iter().collect::<Vec<_>>(), vec!["a", "b"]);
+        assert!(Batch::builder().build().unwrap().ids.is_empty());
+
+        let layers = Layers::default().add_names("base").names_all(vec!["detail", "decal"]);
+        assert_eq!(layers.names, vec!["base", "detail", "decal"]);
     }
 
     #[test]
@@ -1,3 +1,6 @@
+// Lets `FluentBuilder` output name `::gfx_dsl_support::BuilderError` inside this crate too
+extern crate self as gfx_dsl_support;
+
 pub mod ir;
 pub mod runtime;
 pub mod builder;
@@ -44,7 +44,9 @@ fn main() {
 
     let extra = collect_clang_args();
     let extra_refs: Vec<&str> = extra.iter().map(|s| s.as_str()).collect();
-    match macrokid_clang_exec::analyze_header(&header, &extra_refs) {
+    // Reuse the IR from earlier builds while the header, flags and clang version are unchanged
+    let cache_dir = std::path::Path::new(&out_dir).join("clang_cache");
+    match macrokid_clang_exec::analyze_header_cached(&header, &extra_refs, &cache_dir) {
         Ok(ir) => {
             let hdr = macrokid_clang_exec::emit_cpp_header(&ir, Some("mk"));
             std::fs::write(&out_cpp, hdr).ok();
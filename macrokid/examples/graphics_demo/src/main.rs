@@ -46,9 +46,9 @@ fn main() {
     touch_vertex_fields(&v);
 
     #[derive(GraphicsPipeline)]
-    #[pipeline(vs = "shaders/triangle.vert", fs = "shaders/triangle.frag", topology = "TriangleList", depth = true)]
+    #[pipeline(vs = "shaders/triangle.vert", fs = "shaders/triangle.frag", topology = "TriangleList")]
     struct TrianglePipeline;
 
     let p = TrianglePipeline::describe_pipeline();
-    println!("\n== Pipeline ==\nname={} vs={} fs={} topo={:?} depth={}", p.name, p.shaders.vs, p.shaders.fs, p.topology, p.depth);
+    println!("\n== Pipeline ==\nname={} vs={} fs={} topo={:?} depth_stencil={:?}", p.name, p.shaders.vs, p.shaders.fs, p.topology, p.depth_stencil);
 }
@@ -1,14 +1,43 @@
 use proc_macro::TokenStream;
 use macrokid_core::builders::ImplBuilder;
 use quote::quote;
-use syn::{parse::{Parse, ParseStream}, parse_macro_input, DeriveInput, Ident, ItemImpl, LitStr, Token};
+use macrokid_core::diag::Collector;
+use syn::{parse::{Parse, ParseStream}, parse_macro_input, punctuated::Punctuated, spanned::Spanned, visit_mut::{self, VisitMut}, DeriveInput, Ident, ItemImpl, LitStr, Token};
 
 /// Simple function-like macro for creating complete Iced applications
 /// 
 /// This macro generates a complete Iced application with minimal boilerplate.
 /// 
+/// `state` fields become public struct fields (with a `Default` impl from their initializers),
+/// each `messages` entry becomes a `Message` variant handled by its closure in `update_app`,
+/// and `view` renders the app in `view_app`.
+///
+/// A closure parameter may carry a type (`|app: &mut CounterApp|`); it is checked against `self`.
+///
 /// # Example
 /// ```rust
+/// # mod iced {
+/// #     pub type Result = ::std::result::Result<(), ()>;
+/// #     pub struct Command<M>(::std::marker::PhantomData<M>);
+/// #     impl<M> Command<M> { pub fn none() -> Self { Command(::std::marker::PhantomData) } }
+/// #     pub struct Element<M>(::std::marker::PhantomData<M>);
+/// #     pub fn run<S, M>(_: &str, _: fn(&mut S, M) -> Command<M>, _: fn(&S) -> Element<M>) -> Result { Ok(()) }
+/// #     pub mod widget {
+/// #         pub struct Text;
+/// #         pub struct Button<M>(Option<M>);
+/// #         pub fn text(_: impl ToString) -> Text { Text }
+/// #         pub fn button<M>(_: Text) -> Button<M> { Button(None) }
+/// #         impl<M> Button<M> { pub fn on_press(self, m: M) -> Self { Button(Some(m)) } }
+/// #         impl<M> From<Button<M>> for super::Element<M> { fn from(_: Button<M>) -> Self { super::Element(::std::marker::PhantomData) } }
+/// #     }
+/// # }
+/// # pub trait IcedAppTrait {
+/// #     type Message;
+/// #     fn update_app(&mut self, message: Self::Message) -> iced::Command<Self::Message>;
+/// #     fn view_app(&self) -> iced::Element<Self::Message>;
+/// # }
+/// use iced_macrokid::iced_app;
+///
 /// iced_app! {
 ///     CounterApp {
 ///         title: "Counter",
@@ -16,43 +45,90 @@ use syn::{parse::{Parse, ParseStream}, parse_macro_input, DeriveInput, Ident, It
 ///         messages: {
 ///             Increment => |app| app.value += 1,
 ///             Decrement => |app| app.value -= 1,
-///             Reset => |app| app.value = 0,
+///             Reset => |app: &mut CounterApp| app.value = 0,
 ///         },
 ///         view: |app| {
-///             iced::widget::column![
-///                 iced::widget::text(format!("Value: {}", app.value)),
-///                 iced::widget::button("Increment").on_press(Message::Increment),
-///                 iced::widget::button("Decrement").on_press(Message::Decrement),
-///                 iced::widget::button("Reset").on_press(Message::Reset),
-///             ]
+///             iced::widget::button(iced::widget::text(format!("Value: {}", app.value)))
+///                 .on_press(Message::Increment)
 ///         }
 ///     }
 /// }
+///
+/// # fn main() {
+/// let mut app = CounterApp::default();
+/// app.update_app(Message::Increment);
+/// app.update_app(Message::Increment);
+/// app.update_app(Message::Decrement);
+/// assert_eq!(app.value, 1);
+/// # let _ = app.view_app();
+/// # let _ = CounterApp::run;
+/// # }
 /// ```
 #[proc_macro]
 pub fn iced_app(input: TokenStream) -> TokenStream {
     let input: IcedAppInput = parse_macro_input!(input as IcedAppInput);
+    match expand_iced_app(input) {
+        Ok(ts) => ts.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
 
+fn expand_iced_app(input: IcedAppInput) -> syn::Result<proc_macro2::TokenStream> {
     let app_ident = input.name;
     let msg_ident = Ident::new("Message", app_ident.span());
     let title = input.title.map(|s| s.value()).unwrap_or_else(|| app_ident.to_string());
 
-    // Skeleton struct and empty message enum
-    let struct_def = quote! { pub struct #app_ident; };
-    let message_def = quote! { #[derive(Clone, Debug)] pub enum #msg_ident {} };
+    // State struct with a Default impl built from the field initializers
+    let names: Vec<&Ident> = input.state.iter().map(|f| &f.name).collect();
+    let tys = input.state.iter().map(|f| &f.ty);
+    let defaults = input.state.iter().map(|f| match &f.default {
+        Some(e) => quote! { #e },
+        None => quote! { ::core::default::Default::default() },
+    });
+    let struct_def = quote! {
+        pub struct #app_ident { #( pub #names: #tys ),* }
+        impl ::core::default::Default for #app_ident {
+            fn default() -> Self { Self { #( #names: #defaults ),* } }
+        }
+    };
+    let variants: Vec<&Ident> = input.messages.iter().map(|m| &m.variant).collect();
+    let message_def = quote! { #[derive(Clone, Debug)] pub enum #msg_ident { #( #variants ),* } };
+
+    // Handlers bind their `|app|` pattern to `self` directly instead of calling the closure,
+    // so field accesses in the body type-check without annotations. A typed parameter keeps its
+    // type on that binding.
+    let arms = input.messages.iter().map(|m| {
+        let (variant, pat, body) = (&m.variant, &m.handler.pat, &m.handler.body);
+        let ty = m.handler.ty.as_ref().map(|t| quote! { #t }).unwrap_or_else(|| quote! { &mut Self });
+        quote! { #msg_ident::#variant => { let #pat: #ty = self; #body; } }
+    });
+    let update_body = if input.messages.is_empty() {
+        quote! { let _ = message; }
+    } else {
+        quote! { match message { #( #arms )* } }
+    };
+    let view_body = match &input.view {
+        Some(v) => {
+            let (pat, body) = (&v.pat, &v.body);
+            let ty = v.ty.as_ref().map(|t| quote! { #t }).unwrap_or_else(|| quote! { &Self });
+            quote! { let #pat: #ty = self; (#body).into() }
+        }
+        None => quote! { iced::widget::text("Hello from macrokid + iced").into() },
+    };
 
     // Implement IcedAppTrait for the app using ImplBuilder (assoc type + methods)
     let trait_impl = ImplBuilder::new(app_ident.clone(), syn::Generics::default())
         .implement_trait(quote! { crate::IcedAppTrait })
         .add_assoc_type(Ident::new("Message", app_ident.span()), quote! { #msg_ident })
         .add_method(quote! {
-            fn update_app(&mut self, _message: Self::Message) -> iced::Command<Self::Message> {
+            fn update_app(&mut self, message: Self::Message) -> iced::Command<Self::Message> {
+                #update_body
                 iced::Command::none()
             }
         })
         .add_method(quote! {
             fn view_app(&self) -> iced::Element<Self::Message> {
-                iced::widget::text("Hello from macrokid + iced").into()
+                #view_body
             }
         })
         .build();
@@ -68,7 +144,7 @@ pub fn iced_app(input: TokenStream) -> TokenStream {
         })
         .build();
 
-    TokenStream::from(quote! {
+    Ok(quote! {
         #struct_def
         #message_def
         #trait_impl
@@ -76,19 +152,101 @@ pub fn iced_app(input: TokenStream) -> TokenStream {
     })
 }
 
-// Minimal input for iced_app!: `iced_app!(AppName, title: "Title")`
-struct IcedAppInput { name: Ident, title: Option<LitStr> }
+// Input for iced_app!: either the short `iced_app!(AppName, title: "Title")` or the block form
+// `AppName { title: "..", state: { .. }, messages: { .. }, view: |app| .. }` shown above.
+struct IcedAppInput {
+    name: Ident,
+    title: Option<LitStr>,
+    state: Vec<StateField>,
+    messages: Vec<MessageHandler>,
+    view: Option<AppClosure>,
+}
+
+/// `field: Type = default` in the `state` block; without `= default`, `Default::default()` is used.
+struct StateField { name: Ident, ty: syn::Type, default: Option<syn::Expr> }
+
+/// `Variant => |app| expr` in the `messages` block.
+struct MessageHandler { variant: Ident, handler: AppClosure }
+
+/// A one-argument closure `|app| body` or `|app: Ty| body`, kept as its parts.
+struct AppClosure { pat: syn::Pat, ty: Option<syn::Type>, body: syn::Expr }
+
+impl Parse for AppClosure {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let closure: syn::ExprClosure = input.parse()?;
+        if closure.inputs.len() != 1 {
+            return Err(syn::Error::new_spanned(&closure.inputs, "expected a closure taking the app state, e.g. `|app| ...`"));
+        }
+        let (pat, ty) = match closure.inputs.into_iter().next().unwrap() {
+            syn::Pat::Type(typed) => (*typed.pat, Some(*typed.ty)),
+            pat => (pat, None),
+        };
+        Ok(Self { pat, ty, body: *closure.body })
+    }
+}
 
 impl Parse for IcedAppInput {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let name: Ident = input.parse()?;
-        if input.is_empty() { return Ok(Self { name, title: None }); }
-        let _comma: Token![,] = input.parse()?;
-        let key: Ident = input.parse()?;
-        if key != "title" { return Err(syn::Error::new_spanned(key, "expected `title`")); }
-        let _colon: Token![:] = input.parse()?;
-        let lit: LitStr = input.parse()?;
-        Ok(Self { name, title: Some(lit) })
+        let mut app = Self { name, title: None, state: Vec::new(), messages: Vec::new(), view: None };
+        if input.is_empty() { return Ok(app); }
+        if !input.peek(syn::token::Brace) {
+            let _comma: Token![,] = input.parse()?;
+            let key: Ident = input.parse()?;
+            if key != "title" { return Err(syn::Error::new_spanned(key, "expected `title`")); }
+            let _colon: Token![:] = input.parse()?;
+            app.title = Some(input.parse()?);
+            return Ok(app);
+        }
+
+        let body;
+        syn::braced!(body in input);
+        let mut seen: Vec<String> = Vec::new();
+        while !body.is_empty() {
+            let key: Ident = body.parse()?;
+            let _colon: Token![:] = body.parse()?;
+            let k = key.to_string();
+            if seen.contains(&k) { return Err(syn::Error::new_spanned(&key, format!("duplicate key `{}`", k))); }
+            match k.as_str() {
+                "title" => app.title = Some(body.parse()?),
+                "state" => {
+                    let block;
+                    syn::braced!(block in body);
+                    while !block.is_empty() {
+                        let name: Ident = block.parse()?;
+                        if app.state.iter().any(|f| f.name == name) {
+                            return Err(syn::Error::new_spanned(&name, format!("duplicate state field `{}`", name)));
+                        }
+                        let _colon: Token![:] = block.parse()?;
+                        let ty: syn::Type = block.parse()?;
+                        let default = if block.parse::<Option<Token![=]>>()?.is_some() { Some(block.parse()?) } else { None };
+                        app.state.push(StateField { name, ty, default });
+                        if block.is_empty() { break; }
+                        let _comma: Token![,] = block.parse()?;
+                    }
+                }
+                "messages" => {
+                    let block;
+                    syn::braced!(block in body);
+                    while !block.is_empty() {
+                        let variant: Ident = block.parse()?;
+                        if app.messages.iter().any(|m| m.variant == variant) {
+                            return Err(syn::Error::new_spanned(&variant, format!("duplicate message `{}`", variant)));
+                        }
+                        let _arrow: Token![=>] = block.parse()?;
+                        app.messages.push(MessageHandler { variant, handler: block.parse()? });
+                        if block.is_empty() { break; }
+                        let _comma: Token![,] = block.parse()?;
+                    }
+                }
+                "view" => app.view = Some(body.parse()?),
+                _ => return Err(syn::Error::new_spanned(&key, "expected one of: title, state, messages, view")),
+            }
+            seen.push(k);
+            if body.is_empty() { break; }
+            let _comma: Token![,] = body.parse()?;
+        }
+        Ok(app)
     }
 }
 
@@ -101,7 +259,7 @@ pub fn derive_simple_iced_app(input: TokenStream) -> TokenStream {
     let title = input.attrs.iter()
         .find(|attr| attr.path().is_ident("title"))
         .and_then(|attr| {
-            if let Ok(syn::Meta::NameValue(meta)) = attr.meta.clone() {
+            if let syn::Meta::NameValue(meta) = attr.meta.clone() {
                 if let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }) = meta.value {
                     return Some(s.value());
                 }
@@ -138,13 +296,16 @@ pub fn derive_simple_iced_app(input: TokenStream) -> TokenStream {
 
 /// Attribute macro for simplifying view function implementations
 /// 
-/// This macro provides helper macros for common Iced widgets:
-/// - `button!(text, message)` - Creates button with text and message
-/// - `text!(value)` - Creates text widget
-/// - `column![...]` and `row![...]` - Layout containers
+/// Rewrites widget helper invocations in the impl body into Iced calls during expansion:
+/// - `button!(text, message)` → `iced::widget::button(text).on_press(message)`
+/// - `text!(value)` → `iced::widget::text(value)`
+/// - `column![...]` and `row![...]` → `iced::widget::column![...]` / `iced::widget::row![...]`,
+///   with helpers inside them rewritten too
+///
+/// Everything else in the impl is kept as written; no helper macros are defined in the user's module.
 /// 
 /// # Example
-/// ```rust
+/// ```rust,ignore
 /// #[iced_view]
 /// impl MyApp {
 ///     fn view(&self) -> Element<Message> {
@@ -157,28 +318,79 @@ pub fn derive_simple_iced_app(input: TokenStream) -> TokenStream {
 /// ```
 #[proc_macro_attribute]
 pub fn iced_view(_attr: TokenStream, item: TokenStream) -> TokenStream {
-    let input: ItemImpl = parse_macro_input!(item);
-    
-    // For now, just pass through the implementation
-    // Later we can add macro expansions for button!(), text!(), etc.
-    let expanded = quote! {
-        #input
-        
-        // Helper macros for the view implementation
-        macro_rules! button {
-            ($text:expr, $message:expr) => {
-                iced::widget::button($text).on_press($message)
-            };
+    let mut input: ItemImpl = parse_macro_input!(item);
+    match expand_iced_view(&mut input) {
+        Ok(()) => TokenStream::from(quote! { #input }),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand_iced_view(item: &mut ItemImpl) -> syn::Result<()> {
+    let mut rewriter = ViewHelpers { errors: Collector::new() };
+    rewriter.visit_item_impl_mut(item);
+    rewriter.errors.into_result(())
+}
+
+/// Replaces `button!`/`text!`/`column!`/`row!` invocations with their `iced::widget` forms.
+struct ViewHelpers { errors: Collector }
+
+impl ViewHelpers {
+    fn rewrite(&mut self, mac: &syn::Macro) -> syn::Result<Option<syn::Expr>> {
+        let Some(name) = mac.path.get_ident().map(|i| i.to_string()) else { return Ok(None) };
+        let args = || mac.parse_body_with(Punctuated::<syn::Expr, Token![,]>::parse_terminated);
+        let expr = match name.as_str() {
+            "button" => {
+                let args = args()?;
+                if args.len() != 2 {
+                    return Err(syn::Error::new_spanned(mac, "button! expects `(label, message)`"));
+                }
+                let (label, message) = (&args[0], &args[1]);
+                quote! { iced::widget::button(#label).on_press(#message) }
+            }
+            "text" => {
+                let args = args()?;
+                if args.len() != 1 {
+                    return Err(syn::Error::new_spanned(mac, "text! expects a single value"));
+                }
+                let value = &args[0];
+                quote! { iced::widget::text(#value) }
+            }
+            "column" | "row" => {
+                let mut items = args()?;
+                for item in items.iter_mut() { self.visit_expr_mut(item); }
+                let layout = Ident::new(&name, mac.path.span());
+                let items = items.iter();
+                quote! { iced::widget::#layout![ #( #items ),* ] }
+            }
+            _ => return Ok(None),
+        };
+        syn::parse2(expr).map(Some)
+    }
+}
+
+impl VisitMut for ViewHelpers {
+    fn visit_expr_mut(&mut self, expr: &mut syn::Expr) {
+        if let syn::Expr::Macro(m) = expr {
+            match self.rewrite(&m.mac) {
+                Ok(Some(new)) => { *expr = new; }
+                Ok(None) => {}
+                Err(e) => self.errors.push(e),
+            }
         }
-        
-        macro_rules! text {
-            ($value:expr) => {
-                iced::widget::text($value)
-            };
+        visit_mut::visit_expr_mut(self, expr);
+    }
+
+    // A trailing `column![..]` in a block parses as a statement macro, not an expression
+    fn visit_stmt_mut(&mut self, stmt: &mut syn::Stmt) {
+        if let syn::Stmt::Macro(m) = stmt {
+            match self.rewrite(&m.mac) {
+                Ok(Some(new)) => { *stmt = syn::Stmt::Expr(new, m.semi_token); }
+                Ok(None) => {}
+                Err(e) => self.errors.push(e),
+            }
         }
-    };
-    
-    TokenStream::from(expanded)
+        visit_mut::visit_stmt_mut(self, stmt);
+    }
 }
 
 // Helper structures and functions
@@ -195,24 +407,118 @@ fn find_message_methods(_attrs: &[syn::Attribute]) -> Vec<MessageMethod> {
 }
 
 fn to_pascal_case(ident: &syn::Ident) -> String {
-    let s = ident.to_string();
-    let mut result = String::new();
-    let mut capitalize_next = true;
-    
-    for c in s.chars() {
-        if c == '_' {
-            capitalize_next = true;
-        } else if capitalize_next {
-            result.push(c.to_ascii_uppercase());
-            capitalize_next = false;
-        } else {
-            result.push(c);
-        }
-    }
-    
-    result
+    macrokid_core::ir::rename::to_pascal(&ident.to_string())
 }
 
 // Note: We intentionally do NOT depend on or re-export iced types from this
 // proc-macro crate to keep it independent at compile time. The generated code
 // references `iced::...` in the user's crate, which must include Iced.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COUNTER: &str = r#"
+        CounterApp {
+            title: "Counter",
+            state: { value: i32 = 0 },
+            messages: {
+                Increment => |app| app.value += 1,
+                Decrement => |app| app.value -= 1,
+                Reset => |app| app.value = 0,
+            },
+            view: |app| {
+                iced::widget::column![
+                    iced::widget::text(format!("Value: {}", app.value)),
+                    iced::widget::button("Increment").on_press(Message::Increment),
+                    iced::widget::button("Decrement").on_press(Message::Decrement),
+                    iced::widget::button("Reset").on_press(Message::Reset),
+                ]
+            }
+        }
+    "#;
+
+    #[test]
+    fn parses_counter_example() {
+        let app: IcedAppInput = syn::parse_str(COUNTER).unwrap();
+        assert_eq!(app.name, "CounterApp");
+        assert_eq!(app.title.unwrap().value(), "Counter");
+        assert_eq!(app.state.len(), 1);
+        assert_eq!(app.state[0].name, "value");
+        let variants: Vec<String> = app.messages.iter().map(|m| m.variant.to_string()).collect();
+        assert_eq!(variants, ["Increment", "Decrement", "Reset"]);
+        assert!(app.view.is_some());
+    }
+
+    #[test]
+    fn counter_expansion_has_fields_variants_and_dispatch() {
+        let out = expand_iced_app(syn::parse_str(COUNTER).unwrap()).unwrap().to_string();
+        assert!(out.contains("pub struct CounterApp { pub value : i32 }"), "{}", out);
+        assert!(out.contains("Self { value : 0 }"));
+        assert!(out.contains("pub enum Message { Increment , Decrement , Reset }"));
+        assert!(out.contains("Message :: Increment => { let app : & mut Self = self ; app . value += 1 ; }"));
+        assert!(out.contains("let app : & Self = self ;"));
+    }
+
+    #[test]
+    fn typed_closure_param_keeps_its_type() {
+        let src = "App { state: { n: u8 }, messages: { Bump => |app: &mut App| app.n += 1 }, view: |app: &App| iced::widget::text(app.n) }";
+        let out = expand_iced_app(syn::parse_str(src).unwrap()).unwrap().to_string();
+        assert!(out.contains("Message :: Bump => { let app : & mut App = self ; app . n += 1 ; }"), "{}", out);
+        assert!(out.contains("let app : & App = self ;"));
+        assert!(!out.contains("app : & mut App : "));
+    }
+
+    #[test]
+    fn short_form_and_errors() {
+        let app: IcedAppInput = syn::parse_str(r#"Hello, title: "Hi""#).unwrap();
+        assert_eq!(app.title.unwrap().value(), "Hi");
+        assert!(app.messages.is_empty());
+
+        let err = |src: &str| syn::parse_str::<IcedAppInput>(src).err().unwrap().to_string();
+        assert_eq!(err("App { colour: 1 }"), "expected one of: title, state, messages, view");
+        assert_eq!(err("App { messages: { A => |app| (), A => |app| () } }"), "duplicate message `A`");
+        assert_eq!(err("App { view: |app, extra| () }"), "expected a closure taking the app state, e.g. `|app| ...`");
+        // Fields without an initializer fall back to Default
+        let app: IcedAppInput = syn::parse_str("App { state: { name: String, n: u8 = 3 } }").unwrap();
+        assert!(app.state[0].default.is_none() && app.state[1].default.is_some());
+    }
+
+    #[test]
+    fn iced_view_rewrites_helpers_inline() {
+        let mut item: ItemImpl = syn::parse_quote! {
+            impl Counter {
+                fn view(&self) -> Element<Message> {
+                    let header = text!("Counter");
+                    column![
+                        header,
+                        row![button!("+", Message::Increment), text!(self.value)],
+                        self.footer(),
+                    ]
+                    .into()
+                }
+                fn footer(&self) -> Element<Message> { format!("{}", self.value).into() }
+            }
+        };
+        expand_iced_view(&mut item).unwrap();
+        let out = quote! { #item }.to_string();
+        assert!(out.contains("let header = iced :: widget :: text (\"Counter\")"), "{}", out);
+        assert!(out.contains("iced :: widget :: column ! [header , iced :: widget :: row ! [iced :: widget :: button (\"+\") . on_press (Message :: Increment) , iced :: widget :: text (self . value)] , self . footer ()]"), "{}", out);
+        assert!(out.contains("format ! (\"{}\" , self . value)"));
+        assert!(!out.contains("macro_rules"));
+    }
+
+    #[test]
+    fn iced_view_rewrites_trailing_macro_and_reports_arity() {
+        let mut item: ItemImpl = syn::parse_quote! {
+            impl App { fn view(&self) -> Element<Message> { column![text!(1)] } }
+        };
+        expand_iced_view(&mut item).unwrap();
+        assert!(quote! { #item }.to_string().contains("{ iced :: widget :: column ! [iced :: widget :: text (1)] }"));
+
+        let mut bad: ItemImpl = syn::parse_quote! {
+            impl App { fn view(&self) -> Element<Message> { button!("only label") } }
+        };
+        assert_eq!(expand_iced_view(&mut bad).unwrap_err().to_string(), "button! expects `(label, message)`");
+    }
+}
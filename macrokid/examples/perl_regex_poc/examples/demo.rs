@@ -1,4 +1,4 @@
-use perl_regex_poc::{regex_match, regex_subst};
+use perl_regex_poc::{regex_match, regex_subst, regex_tr};
 // The runtime types are used via the macros; no direct imports needed here.
 
 fn main() {
@@ -19,6 +19,10 @@ fn main() {
         println!("Found year: {}", year);
     }
     
+    // Capture groups: named and positional
+    let date = regex_match!("Released 2024-07", r"(?P<year>\d{4})-(\d{2})");
+    println!("Year: {:?}, month: {:?}", date.name("year"), date.group(2));
+
     println!();
     
     // Substitution
@@ -40,6 +44,12 @@ fn main() {
     
     let censored = regex_subst!(email_text, r"[a-zA-Z0-9._%+-]+@[a-zA-Z0-9.-]+\.[a-zA-Z]{2,}", "[EMAIL]");
     println!("Censored: {}", censored.result);
+
+    // Transliteration (tr///)
+    let upper = regex_tr!(text, "a-z", "A-Z");
+    println!("Upper: {} ({} chars changed)", upper, upper.count);
+    let no_digits = regex_tr!(text, "0-9", "", "d");
+    println!("Without digits: {}", no_digits);
 }
 
 // This demonstrates what the eventual full Perl syntax could look like:
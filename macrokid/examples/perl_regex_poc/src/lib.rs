@@ -6,9 +6,11 @@
 //!   extended whitespace/comments (x), and swap-greed (U).
 //! - Global substitution semantics for `regex_subst!` (like `/g`).
 //!
-//! This PoC exposes two macros:
-//! - `regex_match!(text, pattern[, flags])` → returns a `PerlRegexMatch` with `.matched` and `.full_match()`.
+//! This PoC exposes three macros:
+//! - `regex_match!(text, pattern[, flags])` → returns a `PerlRegexMatch` with `.matched`, `.full_match()`,
+//!   and capture groups via `.group(1)` (Perl `$1`) and `.name("year")` (Perl `$+{year}`).
 //! - `regex_subst!(text, pattern, replacement[, flags])` → returns a `PerlRegexSubst { result, count }`.
+//! - `regex_tr!(text, search, replace[, "d"])` → Perl `tr///`; returns a `PerlTr { result, count }`.
 //!
 //! Flags (optional third/ fourth literal argument):
 //! - `i` = case-insensitive, `m` = multi-line, `s` = dot matches newline, `x` = ignore whitespace, `U` = swap greed.
@@ -17,10 +19,8 @@
 //! Note: For ergonomics this PoC prefixes the pattern with an inline `(?imxsU)` construct generated at
 //! macro-expansion time when flags are passed. This leverages Rust regex inline-flags behavior.
 //!
-//! regex_match! remains simple: captures only expose full matches via `all_matches()`. If you want per-capture
-//! groups (Perl `$1`, `$2`), we can extend `PerlRegexMatch` with a vector of capture sets and add ergonomics.
-//! If you want, I can add a couple of usage snippets inline next to the macro definitions showing flags combinations
-//! (e.g., "imsxU", "g") and their effects.
+//! With the `g` flag only full matches are collected (via `all_matches()`); capture groups are kept for the
+//! first match of a non-global match.
 
 use proc_macro::TokenStream;
 use syn::{parse_macro_input, Expr, LitStr};
@@ -43,6 +43,14 @@ pub fn regex_match(input: TokenStream) -> TokenStream {
 /// Proof of concept: Perl-like substitution
 /// 
 /// Usage: regex_subst!(text, "pattern", "replacement")
+///
+/// The replacement may reference groups as `$1`, `$name` or `${name}` (`$$` is a literal `$`);
+/// a reference to a group the pattern doesn't define is a compile error.
+///
+/// ```compile_fail
+/// use perl_regex_poc::regex_subst;
+/// let swapped = regex_subst!("2024-07", r"(\d{4})-\d{2}", "$2");
+/// ```
 #[proc_macro]  
 pub fn regex_subst(input: TokenStream) -> TokenStream {
     let subst_expr = parse_macro_input!(input as RegexSubstExpr);
@@ -52,6 +60,21 @@ pub fn regex_subst(input: TokenStream) -> TokenStream {
     }.into()
 }
 
+/// Perl-like transliteration (`tr/search/replace/`)
+///
+/// Usage: regex_tr!(text, "a-z", "A-Z") or regex_tr!(text, "0-9", "", "d")
+///
+/// Sets accept ranges like `a-z`; a `-` at either end is literal. Ranges are expanded at
+/// expansion time. The `d` flag deletes searched chars with no replacement counterpart.
+#[proc_macro]
+pub fn regex_tr(input: TokenStream) -> TokenStream {
+    let tr_expr = parse_macro_input!(input as RegexSubstExpr);
+    match perl_regex_impl::expand_regex_tr(tr_expr) {
+        Ok(ts) => ts,
+        Err(e) => e.to_compile_error(),
+    }.into()
+}
+
 // Simplified parsing structures for proof of concept
 struct RegexMatchExpr {
     target: Expr,
@@ -151,7 +174,7 @@ mod perl_regex_impl {
                     PerlRegexMatch::from_all(&#target, all)
                 } else {
                     if let Some(caps) = re.captures(&#target) {
-                        PerlRegexMatch::new_match(&#target, caps)
+                        PerlRegexMatch::new_match(&#target, re, caps)
                     } else {
                         PerlRegexMatch::no_match(&#target)
                     }
@@ -160,9 +183,98 @@ mod perl_regex_impl {
         })
     }
 
+    /// Expand a `tr` set: `a-z` becomes every char from `a` to `z`; a leading or trailing `-`
+    /// is literal.
+    pub(crate) fn expand_tr_set(set: &LitStr) -> syn::Result<Vec<char>> {
+        let chars: Vec<char> = set.value().chars().collect();
+        let mut out = Vec::new();
+        let mut i = 0;
+        while i < chars.len() {
+            if i + 2 < chars.len() && chars[i + 1] == '-' {
+                let (lo, hi) = (chars[i], chars[i + 2]);
+                if lo > hi {
+                    return Err(err_at_span(set.span(), &format!("invalid range '{}-{}' in tr set: start is after end", lo, hi)));
+                }
+                out.extend(lo..=hi);
+                i += 3;
+            } else {
+                out.push(chars[i]);
+                i += 1;
+            }
+        }
+        Ok(out)
+    }
+
+    pub fn expand_regex_tr(expr: RegexSubstExpr) -> syn::Result<TokenStream2> {
+        let target = &expr.target;
+        let mut delete = false;
+        if let Some(f) = &expr.flags {
+            for ch in f.value().chars() {
+                match ch {
+                    'd' => delete = true,
+                    other => return Err(err_at_span(f.span(), &format!("unsupported tr flag '{}': expected d", other))),
+                }
+            }
+        }
+        let from = expand_tr_set(&expr.pattern)?;
+        let to = expand_tr_set(&expr.replacement)?;
+
+        Ok(quote! {
+            perl_regex_runtime::PerlTr::apply(&#target, &[ #( #from ),* ], &[ #( #to ),* ], #delete)
+        })
+    }
+
+    /// Group references in a `Regex::replace_all` replacement, following its syntax: `$$` is a
+    /// literal `$`, `${name}` is braced, and `$name` takes the longest run of `[_0-9A-Za-z]`.
+    fn replacement_refs(replacement: &str) -> Vec<&str> {
+        let mut refs = Vec::new();
+        let mut rest = replacement;
+        while let Some(i) = rest.find('$') {
+            rest = &rest[i + 1..];
+            if let Some(tail) = rest.strip_prefix('$') {
+                rest = tail;
+            } else if let Some(braced) = rest.strip_prefix('{') {
+                if let Some(end) = braced.find('}') {
+                    refs.push(&braced[..end]);
+                    rest = &braced[end + 1..];
+                }
+            } else {
+                let end = rest.find(|c: char| !(c == '_' || c.is_ascii_alphanumeric())).unwrap_or(rest.len());
+                if end > 0 { refs.push(&rest[..end]); }
+                rest = &rest[end..];
+            }
+        }
+        refs
+    }
+
+    /// Reject replacement references to groups the pattern doesn't define; at runtime those
+    /// silently expand to nothing. Invalid patterns are left to fail at runtime as before.
+    pub(crate) fn check_replacement_refs(pattern: &LitStr, replacement: &LitStr) -> syn::Result<()> {
+        let re = match perl_regex_runtime::Regex::new(&pattern.value()) {
+            Ok(re) => re,
+            Err(_) => return Ok(()),
+        };
+        let groups = re.captures_len() - 1;
+        for name in replacement_refs(&replacement.value()) {
+            let resolves = match name.parse::<usize>() {
+                Ok(n) => n <= groups,
+                Err(_) => re.capture_names().flatten().any(|g| g == name),
+            };
+            if !resolves {
+                let msg = match name.parse::<usize>() {
+                    Ok(_) => format!("replacement references group ${} but the pattern has {} capture group(s)", name, groups),
+                    Err(_) => format!("replacement references group ${{{}}} but the pattern has no group named '{}'", name, name),
+                };
+                return Err(err_at_span(replacement.span(), &msg));
+            }
+        }
+        Ok(())
+    }
+
     pub fn expand_regex_subst(expr: RegexSubstExpr) -> syn::Result<TokenStream2> {
         let target = &expr.target;
         let (pattern, _global) = process_flags(&expr.pattern, expr.flags)?;
+        check_replacement_refs(&pattern, &expr.replacement)?;
         let replacement = expr.replacement.value();
 
         Ok(quote! {
@@ -186,3 +298,41 @@ mod perl_regex_impl {
 }
 
 // Note: Runtime support lives in `perl_regex_runtime` to keep proc-macro crate lean.
+
+#[cfg(test)]
+mod tests {
+    use super::perl_regex_impl::check_replacement_refs;
+    use proc_macro2::Span;
+    use syn::LitStr;
+
+    fn check(pattern: &str, replacement: &str) -> Result<(), String> {
+        let lit = |s: &str| LitStr::new(s, Span::call_site());
+        check_replacement_refs(&lit(pattern), &lit(replacement)).map_err(|e| e.to_string())
+    }
+
+    #[test]
+    fn tr_sets_expand_ranges() {
+        let set = |s: &str| super::perl_regex_impl::expand_tr_set(&LitStr::new(s, Span::call_site())).map_err(|e| e.to_string());
+        assert_eq!(set("a-e").unwrap(), vec!['a', 'b', 'c', 'd', 'e']);
+        assert_eq!(set("x0-2y").unwrap(), vec!['x', '0', '1', '2', 'y']);
+        assert_eq!(set("-a-").unwrap(), vec!['-', 'a', '-']);
+        assert_eq!(set("z-a").unwrap_err(), "invalid range 'z-a' in tr set: start is after end");
+    }
+
+    #[test]
+    fn replacement_refs_must_resolve() {
+        assert_eq!(check(r"(\d{4})-\d{2}", "$2").unwrap_err(), "replacement references group $2 but the pattern has 1 capture group(s)");
+        assert_eq!(check(r"(?P<year>\d{4})", "${month}").unwrap_err(), "replacement references group ${month} but the pattern has no group named 'month'");
+        // `$1a` names a group "1a", as in `Regex::replace_all`
+        assert!(check(r"(\d)", "$1a").is_err());
+    }
+
+    #[test]
+    fn resolvable_and_escaped_refs_pass() {
+        assert!(check(r"(?P<year>\d{4})-(\d{2})", "$2/${year} $0 ${1}x $year").is_ok());
+        assert!(check(r"\d+", "$$5 costs $$").is_ok());
+        assert!(check(r"\d+", "trailing $").is_ok());
+        // Invalid patterns still fail at runtime, not here
+        assert!(check(r"(", "$3").is_ok());
+    }
+}
@@ -2,21 +2,31 @@
 
 pub use regex::Regex;
 use regex::Captures;
+use std::collections::BTreeMap;
 
 pub struct PerlRegexMatch {
     pub matched: bool,
     pub full_text: String,
     captures: Option<String>, // Full match (group 0) when not using /g; stored to avoid lifetime issues
     all: Vec<String>,         // When using global flag, collect all full matches (group 0)
+    groups: Vec<Option<String>>,     // Every group of the first match, group 0 included; None if it didn't participate
+    named: BTreeMap<String, String>, // Named groups that participated in the first match
 }
 
 impl PerlRegexMatch {
-    pub fn new_match(text: &str, caps: Captures) -> Self {
+    /// Build from the captures of `re`, which supplies the group names.
+    pub fn new_match(text: &str, re: &Regex, caps: Captures) -> Self {
+        let groups: Vec<Option<String>> = caps.iter().map(|g| g.map(|m| m.as_str().to_string())).collect();
+        let named = re.capture_names().flatten()
+            .filter_map(|name| caps.name(name).map(|m| (name.to_string(), m.as_str().to_string())))
+            .collect();
         Self {
             matched: true,
             full_text: text.to_string(),
             captures: caps.get(0).map(|m| m.as_str().to_string()),
             all: caps.get(0).map(|m| vec![m.as_str().to_string()]).unwrap_or_default(),
+            groups,
+            named,
         }
     }
 
@@ -26,6 +36,8 @@ impl PerlRegexMatch {
             full_text: text.to_string(),
             captures: None,
             all: Vec::new(),
+            groups: Vec::new(),
+            named: BTreeMap::new(),
         }
     }
 
@@ -34,6 +46,17 @@ impl PerlRegexMatch {
         self.captures.as_deref()
     }
 
+    /// Capture group `i` of the first match (Perl's `$1`, `$2`, ...; 0 is the full match).
+    /// Not populated for the /g flag.
+    pub fn group(&self, i: usize) -> Option<&str> {
+        self.groups.get(i).and_then(|g| g.as_deref())
+    }
+
+    /// Named capture group of the first match (Perl's `$+{name}`).
+    pub fn name(&self, name: &str) -> Option<&str> {
+        self.named.get(name).map(String::as_str)
+    }
+
     /// All full matches (group 0) when using the /g flag; empty otherwise.
     pub fn all_matches(&self) -> &[String] {
         &self.all
@@ -42,7 +65,7 @@ impl PerlRegexMatch {
     /// Build from a list of full matches (for the /g flag).
     pub fn from_all(text: &str, all: Vec<String>) -> Self {
         let captures = all.get(0).cloned();
-        Self { matched: !all.is_empty(), full_text: text.to_string(), captures, all }
+        Self { matched: !all.is_empty(), full_text: text.to_string(), captures, all, groups: Vec::new(), named: BTreeMap::new() }
     }
 }
 
@@ -56,3 +79,78 @@ impl std::fmt::Display for PerlRegexSubst {
         write!(f, "{}", self.result)
     }
 }
+
+/// Result of a Perl `tr///` transliteration.
+pub struct PerlTr {
+    pub result: String,
+    pub count: usize, // Number of characters found in the search set (replaced or deleted)
+}
+
+impl PerlTr {
+    /// Map each char of `text` found in `from` to the char at the same index in `to`. When `to`
+    /// is shorter, its last char is reused, or with `delete` (Perl's `d` flag) the char is dropped;
+    /// an empty `to` without `delete` leaves the text unchanged and only counts.
+    /// `from`/`to` are the range-expanded sets; the first occurrence of a char in `from` wins.
+    pub fn apply(text: &str, from: &[char], to: &[char], delete: bool) -> Self {
+        let mut result = String::with_capacity(text.len());
+        let mut count = 0;
+        for c in text.chars() {
+            let Some(i) = from.iter().position(|&f| f == c) else { result.push(c); continue };
+            count += 1;
+            match to.get(i) {
+                Some(&r) => result.push(r),
+                None if delete => {}
+                None => result.push(to.last().copied().unwrap_or(c)),
+            }
+        }
+        Self { result, count }
+    }
+}
+
+impl std::fmt::Display for PerlTr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(s: &str) -> Vec<char> { s.chars().collect() }
+
+    #[test]
+    fn tr_maps_chars_by_position() {
+        let tr = PerlTr::apply("abcabc", &chars("abc"), &chars("xyz"), false);
+        assert_eq!((tr.result.as_str(), tr.count), ("xyzxyz", 6));
+        // Short replacement set: the last char repeats
+        let tr = PerlTr::apply("hello", &chars("elo"), &chars("3"), false);
+        assert_eq!((tr.to_string(), tr.count), ("h3333".to_string(), 4));
+        // Empty replacement set only counts
+        let tr = PerlTr::apply("banana", &chars("a"), &[], false);
+        assert_eq!((tr.result.as_str(), tr.count), ("banana", 3));
+    }
+
+    #[test]
+    fn tr_deletes_unmapped_with_d() {
+        let tr = PerlTr::apply("a1b2c3", &chars("0123456789"), &[], true);
+        assert_eq!((tr.result.as_str(), tr.count), ("abc", 3));
+        let tr = PerlTr::apply("abcd", &chars("abcd"), &chars("AB"), true);
+        assert_eq!((tr.result.as_str(), tr.count), ("AB", 4));
+    }
+
+    #[test]
+    fn named_and_positional_groups() {
+        let re = Regex::new(r"(?P<year>\d{4})-(\d{2})(-(\d{2}))?").unwrap();
+        let text = "released 2024-07";
+        let m = PerlRegexMatch::new_match(text, &re, re.captures(text).unwrap());
+        assert_eq!(m.full_match(), Some("2024-07"));
+        assert_eq!(m.name("year"), Some("2024"));
+        assert_eq!(m.group(1), Some("2024"));
+        assert_eq!(m.group(2), Some("07"));
+        // Optional group that didn't participate, and out-of-range indices
+        assert_eq!(m.group(4), None);
+        assert_eq!(m.group(9), None);
+        assert_eq!(m.name("month"), None);
+    }
+}
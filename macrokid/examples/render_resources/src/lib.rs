@@ -27,13 +27,7 @@ fn expand(input: DeriveInput) -> proc_macro2::TokenStream {
 fn expand_inner(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     let spec = TypeSpec::from_derive_input(input)?;
     let ident = spec.ident.clone();
-    let fields = match &spec.kind {
-        macrokid_core::TypeKind::Struct(st) => match st.fields() {
-            FieldKind::Named(v) => v.clone(),
-            _ => return Err(syn::Error::new(spec.span, "ResourceBinding expects a struct with named fields")),
-        },
-        _ => return Err(syn::Error::new(spec.span, "ResourceBinding expects a struct")),
-    };
+    let fields = spec.named_fields()?.to_vec();
 
     let schema = [
         AttrSpec { key: "set", required: true, ty: AttrType::Int },
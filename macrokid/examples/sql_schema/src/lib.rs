@@ -0,0 +1,445 @@
+//! `sql_schema!`: generate a Rust struct from a `CREATE TABLE` statement.
+//!
+//! ```ignore
+//! use sql_schema::sql_schema;
+//! use sql_schema_support::SqlTable;
+//!
+//! sql_schema!("CREATE TABLE users (id INT PRIMARY KEY, name TEXT, email VARCHAR(255) NULL)");
+//! // => #[derive(Debug, Clone, PartialEq)]
+//! //    pub struct Users { pub id: i32, pub name: String, pub email: Option<String> }
+//! //    impl SqlTable for Users { const TABLE = "users"; const COLUMNS = &[..]; }
+//! assert_eq!(Users::COLUMNS[2].rust_type, "Option<String>");
+//! ```
+//!
+//! Supported DDL subset:
+//! - `CREATE TABLE [IF NOT EXISTS] [schema.]name ( column, ... [, PRIMARY KEY (a, b)] )`, optional `;`.
+//! - Columns: `name TYPE[(n[, m])] [NOT NULL | NULL] [PRIMARY KEY] [...]`; other column constraints
+//!   (`DEFAULT`, `UNIQUE`, `REFERENCES t(c)`, ...) are accepted and ignored, as are table-level
+//!   `UNIQUE`/`FOREIGN KEY`/`CHECK`/`CONSTRAINT` clauses.
+//! - Types: `INT`/`INTEGER` → `i32`, `SMALLINT` → `i16`, `TINYINT` → `i8`, `BIGINT` → `i64`,
+//!   `REAL`/`FLOAT`/`DOUBLE [PRECISION]` → `f64`, `TEXT`/`VARCHAR`/`CHAR` → `String`,
+//!   `BOOLEAN`/`BOOL` → `bool`, `BLOB`/`BYTEA` → `Vec<u8>`.
+//!
+//! Unlike SQL, columns are non-null unless declared `NULL`, which wraps the field in `Option`;
+//! primary key columns are never optional. Keywords are case-insensitive. Unsupported types and
+//! syntax errors point into the string literal where the compiler allows it.
+//!
+//! The expansion implements `sql_schema_support::SqlTable`, so callers depend on that crate too.
+
+use std::ops::Range;
+
+use proc_macro::TokenStream;
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{parse_macro_input, LitStr};
+use macrokid_core::diag::err_at_span;
+
+/// Generate a struct and `SqlTable` impl from a `CREATE TABLE` string literal.
+#[proc_macro]
+pub fn sql_schema(input: TokenStream) -> TokenStream {
+    let lit = parse_macro_input!(input as LitStr);
+    expand_sql_schema(&lit).unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+fn expand_sql_schema(lit: &LitStr) -> syn::Result<TokenStream2> {
+    let sql = lit.value();
+    let table = parse_create_table(&sql).map_err(|e| err_at_span(literal_subspan(lit, &sql, e.range), &e.msg))?;
+
+    let struct_ident = struct_ident(&table.name)
+        .ok_or_else(|| err_at_span(literal_subspan(lit, &sql, table.range.clone()), &format!("table '{}' is not a valid Rust type name", table.name)))?;
+    let mut fields = Vec::new();
+    let mut columns = Vec::new();
+    for c in &table.columns {
+        let field = field_ident(&c.name)
+            .ok_or_else(|| err_at_span(literal_subspan(lit, &sql, c.range.clone()), &format!("column '{}' is not a valid Rust field name", c.name)))?;
+        let rust_type = c.rust_type();
+        let ty: syn::Type = syn::parse_str(&rust_type)?;
+        fields.push(quote! { pub #field: #ty });
+        let (name, sql_type, nullable, pk) = (&c.name, &c.sql_type, c.nullable, c.primary_key);
+        columns.push(quote! {
+            ::sql_schema_support::Column { name: #name, sql_type: #sql_type, rust_type: #rust_type, nullable: #nullable, primary_key: #pk }
+        });
+    }
+    let table_name = &table.name;
+    let doc = format!("Row of the `{}` table, generated from its `CREATE TABLE` statement.", table.name);
+
+    Ok(quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, PartialEq)]
+        pub struct #struct_ident { #( #fields ),* }
+
+        impl ::sql_schema_support::SqlTable for #struct_ident {
+            const TABLE: &'static str = #table_name;
+            const COLUMNS: &'static [::sql_schema_support::Column] = &[ #( #columns ),* ];
+        }
+    })
+}
+
+/// Point at `range` of the literal's contents when its source text is exactly the value (no
+/// escapes, not a raw string) and the compiler supports subspans; otherwise the whole literal.
+fn literal_subspan(lit: &LitStr, value: &str, range: Range<usize>) -> Span {
+    let token = lit.token();
+    if token.to_string() == format!("\"{}\"", value) {
+        if let Some(span) = token.subspan(range.start + 1..range.end + 1) {
+            return span;
+        }
+    }
+    lit.span()
+}
+
+fn pascal_case(name: &str) -> String {
+    name.split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|w| !w.is_empty())
+        .map(|w| {
+            let mut cs = w.chars();
+            cs.next().map(|f| f.to_ascii_uppercase().to_string() + cs.as_str()).unwrap_or_default()
+        })
+        .collect()
+}
+
+/// PascalCase struct name for a table (`order_items` → `OrderItems`); `None` when that is not an
+/// identifier, e.g. it starts with a digit or has no alphanumerics at all.
+fn struct_ident(table: &str) -> Option<Ident> {
+    syn::parse_str::<Ident>(&pascal_case(table)).ok()
+}
+
+/// snake_case field name for a column (`createdAt` → `created_at`), raw when it is a keyword.
+fn field_ident(column: &str) -> Option<Ident> {
+    let mut snake = String::new();
+    let mut prev_lower = false;
+    for ch in column.chars() {
+        if ch.is_ascii_uppercase() && prev_lower { snake.push('_'); }
+        prev_lower = ch.is_ascii_lowercase() || ch.is_ascii_digit();
+        snake.push(ch.to_ascii_lowercase());
+    }
+    match syn::parse_str::<Ident>(&snake) {
+        Ok(id) => Some(id),
+        Err(_) => syn::parse_str::<Ident>(&format!("r#{}", snake)).ok(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// DDL parsing
+// ---------------------------------------------------------------------------
+
+#[derive(Debug)]
+struct Table {
+    name: String,
+    /// Byte range of the table name in the SQL string.
+    range: Range<usize>,
+    columns: Vec<ColumnDef>,
+}
+
+#[derive(Debug)]
+struct ColumnDef {
+    name: String,
+    /// Upper-cased SQL type including any `(n, m)` arguments.
+    sql_type: String,
+    /// Rust type for a non-null value.
+    base: &'static str,
+    nullable: bool,
+    primary_key: bool,
+    /// Byte range of the column name in the SQL string.
+    range: Range<usize>,
+}
+
+impl ColumnDef {
+    fn rust_type(&self) -> String {
+        if self.nullable && !self.primary_key { format!("Option<{}>", self.base) } else { self.base.to_string() }
+    }
+}
+
+/// Parse failure with the byte range of the offending text.
+#[derive(Debug)]
+struct SqlError {
+    msg: String,
+    range: Range<usize>,
+}
+
+fn sql_err<T>(msg: impl Into<String>, range: Range<usize>) -> Result<T, SqlError> {
+    Err(SqlError { msg: msg.into(), range })
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokKind {
+    /// Bare word or quoted identifier (`"name"` / `` `name` ``).
+    Word(String),
+    Number(String),
+    Punct(char),
+}
+
+#[derive(Debug, Clone)]
+struct Tok {
+    kind: TokKind,
+    range: Range<usize>,
+}
+
+fn lex(sql: &str) -> Result<Vec<Tok>, SqlError> {
+    let bytes = sql.as_bytes();
+    let mut toks = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        let c = bytes[i] as char;
+        let start = i;
+        if c.is_ascii_whitespace() {
+            i += 1;
+        } else if c.is_ascii_alphabetic() || c == '_' {
+            while i < bytes.len() && ((bytes[i] as char).is_ascii_alphanumeric() || bytes[i] == b'_') { i += 1; }
+            toks.push(Tok { kind: TokKind::Word(sql[start..i].to_string()), range: start..i });
+        } else if c.is_ascii_digit() {
+            while i < bytes.len() && ((bytes[i] as char).is_ascii_digit() || bytes[i] == b'.') { i += 1; }
+            toks.push(Tok { kind: TokKind::Number(sql[start..i].to_string()), range: start..i });
+        } else if c == '"' || c == '`' || c == '\'' {
+            let Some(len) = sql[i + 1..].find(c) else { return sql_err(format!("unterminated {} quote", c), start..sql.len()) };
+            i += len + 2;
+            // String literals only appear in ignored clauses such as DEFAULT 'x'
+            let kind = if c == '\'' { TokKind::Number(sql[start..i].to_string()) } else { TokKind::Word(sql[start + 1..i - 1].to_string()) };
+            toks.push(Tok { kind, range: start..i });
+        } else if "(),;.-+*=<>".contains(c) {
+            i += 1;
+            toks.push(Tok { kind: TokKind::Punct(c), range: start..i });
+        } else {
+            return sql_err(format!("unexpected character '{}'", c), start..start + c.len_utf8());
+        }
+    }
+    Ok(toks)
+}
+
+struct Parser {
+    toks: Vec<Tok>,
+    pos: usize,
+    len: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Tok> { self.toks.get(self.pos) }
+
+    fn end_range(&self) -> Range<usize> { self.len.saturating_sub(1)..self.len }
+
+    fn peek_keyword(&self, kw: &str) -> bool {
+        matches!(self.peek(), Some(Tok { kind: TokKind::Word(w), .. }) if w.eq_ignore_ascii_case(kw))
+    }
+
+    fn eat_keyword(&mut self, kw: &str) -> bool {
+        let hit = self.peek_keyword(kw);
+        if hit { self.pos += 1; }
+        hit
+    }
+
+    fn expect_keyword(&mut self, kw: &str) -> Result<(), SqlError> {
+        if self.eat_keyword(kw) { return Ok(()); }
+        let range = self.peek().map(|t| t.range.clone()).unwrap_or_else(|| self.end_range());
+        sql_err(format!("expected {}", kw), range)
+    }
+
+    fn eat_punct(&mut self, c: char) -> bool {
+        let hit = matches!(self.peek(), Some(Tok { kind: TokKind::Punct(p), .. }) if *p == c);
+        if hit { self.pos += 1; }
+        hit
+    }
+
+    fn expect_punct(&mut self, c: char) -> Result<(), SqlError> {
+        if self.eat_punct(c) { return Ok(()); }
+        let range = self.peek().map(|t| t.range.clone()).unwrap_or_else(|| self.end_range());
+        sql_err(format!("expected '{}'", c), range)
+    }
+
+    fn ident(&mut self, what: &str) -> Result<(String, Range<usize>), SqlError> {
+        match self.peek().cloned() {
+            Some(Tok { kind: TokKind::Word(w), range }) => { self.pos += 1; Ok((w, range)) }
+            Some(t) => sql_err(format!("expected {}", what), t.range),
+            None => sql_err(format!("expected {}", what), self.end_range()),
+        }
+    }
+
+    /// Skip tokens up to (not including) the next `,` or `)` outside parentheses.
+    fn skip_clause(&mut self) -> Result<(), SqlError> {
+        let mut depth = 0usize;
+        while let Some(t) = self.peek() {
+            match t.kind {
+                TokKind::Punct('(') => depth += 1,
+                TokKind::Punct(')') if depth == 0 => return Ok(()),
+                TokKind::Punct(')') => depth -= 1,
+                TokKind::Punct(',') if depth == 0 => return Ok(()),
+                _ => {}
+            }
+            self.pos += 1;
+        }
+        sql_err("unterminated column list; expected ')'", self.end_range())
+    }
+
+    /// `(n[, m])` after a type name, returned as written.
+    fn type_args(&mut self) -> Result<String, SqlError> {
+        if !self.eat_punct('(') { return Ok(String::new()); }
+        let mut args = Vec::new();
+        loop {
+            match self.peek().cloned() {
+                Some(Tok { kind: TokKind::Number(n), .. }) => { self.pos += 1; args.push(n); }
+                Some(t) => return sql_err("expected a numeric type argument", t.range),
+                None => return sql_err("expected ')'", self.end_range()),
+            }
+            if !self.eat_punct(',') { break; }
+        }
+        self.expect_punct(')')?;
+        Ok(format!("({})", args.join(", ")))
+    }
+}
+
+fn map_type(upper: &str) -> Option<&'static str> {
+    Some(match upper {
+        "INT" | "INTEGER" => "i32",
+        "SMALLINT" => "i16",
+        "TINYINT" => "i8",
+        "BIGINT" => "i64",
+        "REAL" | "FLOAT" | "DOUBLE" => "f64",
+        "TEXT" | "VARCHAR" | "CHAR" => "String",
+        "BOOLEAN" | "BOOL" => "bool",
+        "BLOB" | "BYTEA" => "Vec<u8>",
+        _ => return None,
+    })
+}
+
+const TABLE_CONSTRAINTS: &[&str] = &["CONSTRAINT", "UNIQUE", "FOREIGN", "CHECK"];
+
+fn parse_create_table(sql: &str) -> Result<Table, SqlError> {
+    let mut p = Parser { toks: lex(sql)?, pos: 0, len: sql.len() };
+    p.expect_keyword("CREATE")?;
+    p.expect_keyword("TABLE")?;
+    if p.eat_keyword("IF") {
+        p.expect_keyword("NOT")?;
+        p.expect_keyword("EXISTS")?;
+    }
+    let (mut name, mut range) = p.ident("table name")?;
+    // Keep only the table part of `schema.table`
+    while p.eat_punct('.') { (name, range) = p.ident("table name")?; }
+    p.expect_punct('(')?;
+
+    let mut columns: Vec<ColumnDef> = Vec::new();
+    loop {
+        if p.peek_keyword("PRIMARY") {
+            // Table-level PRIMARY KEY (a, b)
+            p.pos += 1;
+            p.expect_keyword("KEY")?;
+            p.expect_punct('(')?;
+            loop {
+                let (col, range) = p.ident("column name")?;
+                match columns.iter_mut().find(|c| c.name == col) {
+                    Some(c) => c.primary_key = true,
+                    None => return sql_err(format!("PRIMARY KEY names unknown column '{}'", col), range),
+                }
+                if !p.eat_punct(',') { break; }
+            }
+            p.expect_punct(')')?;
+        } else if TABLE_CONSTRAINTS.iter().any(|kw| p.peek_keyword(kw)) {
+            p.skip_clause()?;
+        } else {
+            let (col, range) = p.ident("column name")?;
+            if columns.iter().any(|c| c.name == col) {
+                return sql_err(format!("duplicate column '{}'", col), range);
+            }
+            let (ty, ty_range) = p.ident("column type")?;
+            let mut sql_type = ty.to_ascii_uppercase();
+            let Some(base) = map_type(&sql_type) else {
+                return sql_err(
+                    format!("unsupported SQL type '{}' for column '{}'; expected INT, BIGINT, REAL, TEXT, VARCHAR, BOOLEAN or BLOB", ty, col),
+                    ty_range,
+                );
+            };
+            if sql_type == "DOUBLE" && p.eat_keyword("PRECISION") { sql_type.push_str(" PRECISION"); }
+            sql_type.push_str(&p.type_args()?);
+
+            let mut column = ColumnDef { name: col, sql_type, base, nullable: false, primary_key: false, range };
+            while !matches!(p.peek(), None | Some(Tok { kind: TokKind::Punct(',' | ')'), .. })) {
+                if p.eat_keyword("NOT") {
+                    p.expect_keyword("NULL")?;
+                    column.nullable = false;
+                } else if p.eat_keyword("NULL") {
+                    column.nullable = true;
+                } else if p.eat_keyword("PRIMARY") {
+                    p.expect_keyword("KEY")?;
+                    column.primary_key = true;
+                } else {
+                    // DEFAULT, UNIQUE, REFERENCES, ... carry no type information
+                    p.pos += 1;
+                    if p.eat_punct('(') {
+                        p.skip_clause()?;
+                        p.expect_punct(')')?;
+                    }
+                }
+            }
+            columns.push(column);
+        }
+        if !p.eat_punct(',') { break; }
+    }
+    p.expect_punct(')')?;
+    p.eat_punct(';');
+    if let Some(t) = p.peek() {
+        return sql_err("unexpected tokens after CREATE TABLE statement", t.range.clone());
+    }
+    if columns.is_empty() {
+        return sql_err(format!("table '{}' has no columns", name), 0..sql.len());
+    }
+    Ok(Table { name, range, columns })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn types(sql: &str) -> Vec<(String, String)> {
+        parse_create_table(sql).unwrap().columns.iter().map(|c| (c.name.clone(), c.rust_type())).collect()
+    }
+
+    #[test]
+    fn maps_column_types_and_nullability() {
+        let cols = types("create table Items (id BIGINT PRIMARY KEY, price REAL NOT NULL, title VARCHAR(64), note TEXT NULL, \
+                          active BOOLEAN DEFAULT true, data BLOB NULL, qty INT REFERENCES stock(qty));");
+        let expect = [("id", "i64"), ("price", "f64"), ("title", "String"), ("note", "Option<String>"),
+                      ("active", "bool"), ("data", "Option<Vec<u8>>"), ("qty", "i32")];
+        assert_eq!(cols, expect.iter().map(|(a, b)| (a.to_string(), b.to_string())).collect::<Vec<_>>());
+        let table = parse_create_table("CREATE TABLE t (title VARCHAR(64), ratio DOUBLE PRECISION)").unwrap();
+        assert_eq!(table.columns[0].sql_type, "VARCHAR(64)");
+        assert_eq!(table.columns[1].sql_type, "DOUBLE PRECISION");
+    }
+
+    #[test]
+    fn table_level_primary_key_and_qualified_name() {
+        let t = parse_create_table("CREATE TABLE IF NOT EXISTS app.order_items (order_id INT NULL, item_id INT, \
+                                    PRIMARY KEY (order_id, item_id), UNIQUE (item_id))").unwrap();
+        assert_eq!(t.name, "order_items");
+        assert_eq!(pascal_case(&t.name), "OrderItems");
+        assert!(t.columns.iter().all(|c| c.primary_key));
+        // Primary keys are never optional even when declared NULL
+        assert_eq!(t.columns[0].rust_type(), "i32");
+    }
+
+    #[test]
+    fn unsupported_type_points_at_type_name() {
+        let sql = "CREATE TABLE users (id INT, meta JSONB)";
+        let err = parse_create_table(sql).unwrap_err();
+        assert!(err.msg.contains("unsupported SQL type 'JSONB' for column 'meta'"), "{}", err.msg);
+        assert_eq!(&sql[err.range], "JSONB");
+
+        let err = parse_create_table("CREATE TABLE users (id INT").unwrap_err();
+        assert!(err.msg.contains("expected ')'"), "{}", err.msg);
+    }
+
+    #[test]
+    fn field_names_are_snake_case_and_keyword_safe() {
+        assert_eq!(field_ident("createdAt").unwrap().to_string(), "created_at");
+        assert_eq!(field_ident("type").unwrap().to_string(), "r#type");
+    }
+
+    #[test]
+    fn invalid_table_names_are_errors_at_the_name() {
+        assert_eq!(struct_ident("order_items").unwrap().to_string(), "OrderItems");
+        assert!(struct_ident("2024_sales").is_none());
+        assert!(struct_ident("__").is_none());
+
+        let lit: LitStr = syn::parse_quote!("CREATE TABLE \"2024_sales\" (id INT)");
+        let err = expand_sql_schema(&lit).unwrap_err();
+        assert_eq!(err.to_string(), "table '2024_sales' is not a valid Rust type name");
+        let lit: LitStr = syn::parse_quote!("CREATE TABLE `__` (id INT)");
+        assert!(expand_sql_schema(&lit).is_err());
+    }
+}
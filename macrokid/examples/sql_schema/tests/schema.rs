@@ -0,0 +1,17 @@
+use sql_schema::sql_schema;
+use sql_schema_support::SqlTable;
+
+sql_schema!("CREATE TABLE users (id INT PRIMARY KEY, name TEXT, email VARCHAR(255) NULL, score REAL, createdAt BIGINT)");
+
+#[test]
+fn generates_struct_and_column_metadata() {
+    let u = Users { id: 1, name: "ada".to_string(), email: None, score: 9.5, created_at: 1_700_000_000 };
+    assert_eq!(format!("{:?}", u), r#"Users { id: 1, name: "ada", email: None, score: 9.5, created_at: 1700000000 }"#);
+
+    assert_eq!(Users::TABLE, "users");
+    let names: Vec<_> = Users::COLUMNS.iter().map(|c| c.name).collect();
+    assert_eq!(names, ["id", "name", "email", "score", "createdAt"]);
+    let email = Users::column("email").unwrap();
+    assert_eq!((email.sql_type, email.rust_type, email.nullable), ("VARCHAR(255)", "Option<String>", true));
+    assert!(Users::column("id").unwrap().primary_key);
+}
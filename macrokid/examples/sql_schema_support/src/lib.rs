@@ -0,0 +1,23 @@
+/// One column of a table generated by `sql_schema!`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Column {
+    pub name: &'static str,
+    /// SQL type as written in the DDL, upper-cased (e.g. `"VARCHAR(64)"`).
+    pub sql_type: &'static str,
+    /// Rust field type the column maps to (e.g. `"Option<String>"`).
+    pub rust_type: &'static str,
+    pub nullable: bool,
+    pub primary_key: bool,
+}
+
+/// Implemented for every struct generated by `sql_schema!`.
+pub trait SqlTable {
+    /// Table name as written in the DDL.
+    const TABLE: &'static str;
+    /// Columns in declaration order.
+    const COLUMNS: &'static [Column];
+
+    fn column(name: &str) -> Option<&'static Column> {
+        Self::COLUMNS.iter().find(|c| c.name == name)
+    }
+}
@@ -1,6 +1,13 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
 use macrokid_core::threads::ThreadPool;
 use macrokid_threads_derive::{Job, System, Schedule};
 
+// Execution log so runs on different schedulers can be checked for stage order
+static RUN_LOG: Mutex<Vec<&'static str>> = Mutex::new(Vec::new());
+fn log_run(name: &'static str) { println!("[{}] running", name); RUN_LOG.lock().unwrap().push(name); }
+
 // Dummy resource types for ResourceAccess metadata
 struct Transform;
 struct PhysicsState;
@@ -10,23 +17,70 @@ struct DrawList;
 #[derive(Clone, Job, System)]
 #[reads(Transform)]
 struct Extract;
-impl Extract { fn run(self) { println!("[extract] running"); } }
+impl Extract { fn run(self) { log_run("extract"); } }
 
 #[derive(Clone, Job, System)]
 #[reads(PhysicsState)]
 struct PhysicsSim;
-impl PhysicsSim { fn run(self) { println!("[physics] running"); } }
+impl PhysicsSim { fn run(self) { log_run("physics"); } }
 
 #[derive(Clone, Job, System)]
 #[reads(RenderData)]
 #[writes(DrawList)]
 struct Prepare;
-impl Prepare { fn run(self) { println!("[prepare] running"); } }
+impl Prepare { fn run(self) { log_run("prepare"); } }
 
 #[derive(Clone, Job, System)]
 #[reads(DrawList)]
 struct Record;
-impl Record { fn run(self) { println!("[record] running"); } }
+impl Record { fn run(self) { log_run("record"); } }
+
+#[derive(Clone, Job, System)]
+#[writes(PhysicsState)]
+struct Integrate;
+impl Integrate { fn run(self) { log_run("integrate"); } }
+
+#[derive(Clone, Job, System)]
+#[reads(PhysicsState)]
+struct Collide;
+impl Collide { fn run(self) { log_run("collide"); } }
+
+// Concurrency tracking for the max_threads-limited stage
+static ACTIVE: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+// Independent streaming job (no shared writes), so a whole stage of these forms one layer
+#[derive(Clone, Job, System)]
+#[reads(RenderData)]
+struct Stream;
+impl Stream {
+    fn run(self) {
+        let now = ACTIVE.fetch_add(1, Ordering::SeqCst) + 1;
+        PEAK.fetch_max(now, Ordering::SeqCst);
+        std::thread::sleep(Duration::from_millis(20));
+        ACTIVE.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+// Leave headroom for other threads: at most two Stream jobs run at once
+#[derive(Schedule)]
+struct StreamingSchedule {
+    #[stage(name = "stream", max_threads = 2)] stream: (Stream, Stream, Stream, Stream, Stream),
+}
+
+// A sub-schedule nested as a single stage of a larger frame
+#[derive(Schedule)]
+struct PhysicsSchedule {
+    #[stage(name = "integrate")] integrate: (Integrate,),
+    #[stage(name = "collide", after = "integrate")] collide: (Collide,),
+}
+
+#[derive(Schedule)]
+struct NestedFrame {
+    #[stage(name = "extract")] extract: (Extract,),
+    #[stage(sub, name = "physics", after = "extract")] physics: PhysicsSchedule,
+    #[stage(name = "record", after = "physics")] record: (Record,),
+}
 
 #[derive(Schedule)]
 struct FrameSchedule {
@@ -40,4 +94,299 @@ fn main() {
     let sched = ThreadPool::new(4);
     let frame = FrameSchedule { extract: (Extract,), physics: (PhysicsSim,), prepare: (Prepare,), record: (Record,) };
     frame.run(&sched);
+
+    let nested = NestedFrame {
+        extract: (Extract,),
+        physics: PhysicsSchedule { integrate: (Integrate,), collide: (Collide,) },
+        record: (Record,),
+    };
+    nested.run(&sched);
+
+    StreamingSchedule { stream: (Stream, Stream, Stream, Stream, Stream) }.run(&sched);
+    println!("[stream] peak concurrency {}", PEAK.load(Ordering::SeqCst));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Tests share RUN_LOG, so run them one at a time
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    #[derive(Debug)]
+    struct LoadError(&'static str);
+    impl std::fmt::Display for LoadError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "missing asset {}", self.0) }
+    }
+    impl std::error::Error for LoadError {}
+
+    #[derive(Clone, Job)]
+    #[job(method = "load", fallible)]
+    struct LoadAsset { path: &'static str }
+    impl LoadAsset {
+        fn load(self) -> Result<(), LoadError> {
+            if self.path.is_empty() { Err(LoadError("<empty path>")) } else { Ok(()) }
+        }
+    }
+
+    #[test]
+    fn fallible_job_surfaces_error_through_run_checked() {
+        use macrokid_core::threads::{JobRun, SpawnExt};
+        let _guard = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        assert!(LoadAsset { path: "mesh.gltf" }.run_checked().is_ok());
+        let err = LoadAsset { path: "" }.run_checked().unwrap_err();
+        assert_eq!(err.to_string(), "missing asset <empty path>");
+        // Plain spawn still works; the error is logged instead of propagated
+        LoadAsset { path: "" }.spawn(&ThreadPool::new(1));
+        // Infallible derives keep the default run_checked
+        assert!(Extract.run_checked().is_ok());
+    }
+
+    #[derive(Schedule)]
+    #[schedule(profile)]
+    struct ProfiledFrame {
+        #[stage(name = "extract")] extract: (Extract,),
+        #[stage(name = "physics", after = "extract", sub)] physics: PhysicsSchedule,
+        #[stage(name = "record", after = "physics")] record: (Record,),
+        timings: macrokid_core::threads::StageTimings,
+    }
+
+    fn profiled_frame() -> ProfiledFrame {
+        ProfiledFrame {
+            extract: (Extract,),
+            physics: PhysicsSchedule { integrate: (Integrate,), collide: (Collide,) },
+            record: (Record,),
+            timings: Default::default(),
+        }
+    }
+
+    #[test]
+    fn profiled_schedule_records_stage_timings() {
+        let _guard = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let frame = profiled_frame();
+        let idle = profiled_frame();
+        assert!(frame.last_run_timings().is_empty());
+        frame.run(&ThreadPool::new(2));
+        let names: Vec<&str> = frame.last_run_timings().iter().map(|(n, _)| *n).collect();
+        assert_eq!(names, vec!["extract", "physics", "record"]);
+        // Each run replaces the previous timings
+        frame.run(&ThreadPool::new(2));
+        assert_eq!(frame.last_run_timings().len(), 3);
+        // Timings belong to the instance, not the type
+        assert!(idle.last_run_timings().is_empty());
+    }
+
+    #[derive(Schedule)]
+    #[schedule(profile)]
+    struct ProfiledOverlay {
+        #[stage(name = "prepare", after = "ProfiledFrame::extract", before = "ProfiledFrame::record")] prepare: (Prepare,),
+        timings: macrokid_core::threads::StageTimings,
+    }
+
+    #[test]
+    fn merged_schedule_records_stage_timings() {
+        use macrokid_core::threads::MergedSchedule;
+        let _guard = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let frame = profiled_frame();
+        let overlay = ProfiledOverlay { prepare: (Prepare,), timings: Default::default() };
+        let merged = MergedSchedule::new(&[&frame, &overlay]).unwrap();
+        merged.run(&ThreadPool::new(2));
+        let names: Vec<&str> = frame.last_run_timings().iter().map(|(n, _)| *n).collect();
+        assert_eq!(names, vec!["extract", "physics", "record"]);
+        let names: Vec<&str> = overlay.last_run_timings().iter().map(|(n, _)| *n).collect();
+        assert_eq!(names, vec!["prepare"]);
+        // A merged run replaces the timings of a previous one
+        merged.run(&ThreadPool::new(2));
+        assert_eq!(frame.last_run_timings().len(), 3);
+    }
+
+    #[test]
+    fn sub_schedule_runs_in_place() {
+        let _guard = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let nested = NestedFrame {
+            extract: (Extract,),
+            physics: PhysicsSchedule { integrate: (Integrate,), collide: (Collide,) },
+            record: (Record,),
+        };
+        RUN_LOG.lock().unwrap().clear();
+        nested.run(&ThreadPool::new(2));
+        let log = RUN_LOG.lock().unwrap().clone();
+        assert_eq!(log, vec!["extract", "integrate", "collide", "record"]);
+        assert_eq!(NestedFrame::topo_groups(), vec![vec!["extract"], vec!["physics"], vec!["record"]]);
+    }
+
+    #[test]
+    fn max_threads_caps_stage_concurrency() {
+        let _guard = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        PEAK.store(0, Ordering::SeqCst);
+        StreamingSchedule { stream: (Stream, Stream, Stream, Stream, Stream) }.run(&ThreadPool::new(4));
+        let peak = PEAK.load(Ordering::SeqCst);
+        assert!((1..=2).contains(&peak), "peak concurrency {}", peak);
+        assert_eq!(ACTIVE.load(Ordering::SeqCst), 0);
+    }
+
+    #[derive(Clone, Job, System)]
+    #[reads(RenderData)]
+    #[system(priority = 10)]
+    struct Urgent;
+    impl Urgent { fn run(self) { log_run("urgent"); } }
+
+    #[derive(Clone, Job, System)]
+    #[reads(Transform)]
+    #[system(priority = -5)]
+    struct Background;
+    impl Background { fn run(self) { log_run("background"); } }
+
+    #[derive(Schedule)]
+    struct PrioritizedFrame {
+        #[stage(name = "mixed", priority = 1)] mixed: (Background, Extract, Urgent, PhysicsSim),
+    }
+
+    #[test]
+    fn system_priorities_order_a_layer() {
+        let _guard = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let frame = PrioritizedFrame { mixed: (Background, Extract, Urgent, PhysicsSim) };
+        // One conflict-free layer; a single worker starts jobs strictly by priority
+        RUN_LOG.lock().unwrap().clear();
+        frame.run(&ThreadPool::new(1));
+        assert_eq!(*RUN_LOG.lock().unwrap(), vec!["urgent", "extract", "physics", "background"]);
+    }
+
+    // A negative stage priority shifts every system without flipping their order
+    #[derive(Schedule)]
+    struct DeprioritizedFrame {
+        #[stage(name = "mixed", priority = -20)] mixed: (Background, Urgent, PhysicsSim),
+        #[stage(name = "plugins", after = "mixed", priority = -20, dynamic)] plugins: Vec<Box<dyn macrokid_core::threads::System>>,
+    }
+
+    #[test]
+    fn negative_stage_priority_keeps_system_order() {
+        let _guard = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let frame = DeprioritizedFrame {
+            mixed: (Background, Urgent, PhysicsSim),
+            plugins: vec![Box::new(Background), Box::new(Urgent), Box::new(PhysicsSim)],
+        };
+        RUN_LOG.lock().unwrap().clear();
+        frame.run(&ThreadPool::new(1));
+        assert_eq!(*RUN_LOG.lock().unwrap(), vec![
+            "urgent", "physics", "background",
+            "urgent", "physics", "background",
+        ]);
+    }
+
+    // Plugins registered at runtime run between two fixed stages
+    #[derive(Schedule)]
+    struct PluginFrame {
+        #[stage(name = "extract")] extract: (Extract,),
+        #[stage(name = "plugins", after = "extract", dynamic)] plugins: Vec<Box<dyn macrokid_core::threads::System>>,
+        #[stage(name = "physics", after = "plugins")] physics: (PhysicsSim,),
+    }
+
+    #[test]
+    fn dynamic_stage_runs_runtime_systems() {
+        let _guard = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let mut frame = PluginFrame { extract: (Extract,), plugins: Vec::new(), physics: (PhysicsSim,) };
+        RUN_LOG.lock().unwrap().clear();
+        frame.run(&ThreadPool::new(1));
+        assert_eq!(*RUN_LOG.lock().unwrap(), vec!["extract", "physics"]);
+
+        // Record reads the DrawList that Prepare writes, so Prepare waits for a second layer
+        frame.plugins.push(Box::new(Record));
+        frame.plugins.push(Box::new(Prepare));
+        frame.plugins.push(Box::new(Urgent));
+        RUN_LOG.lock().unwrap().clear();
+        frame.run(&ThreadPool::new(1));
+        assert_eq!(*RUN_LOG.lock().unwrap(), vec!["extract", "urgent", "record", "prepare", "physics"]);
+        assert_eq!(PluginFrame::explain()[1], vec![vec!["<dynamic>"]]);
+    }
+
+    // One stage mixing conflicting and independent systems; only explained, never run
+    #[allow(dead_code)]
+    #[derive(Schedule)]
+    struct MixedStage {
+        #[stage(name = "prep")] prep: (Prepare, Record, Extract, PhysicsSim),
+    }
+
+    #[test]
+    fn explain_groups_non_conflicting_systems() {
+        assert_eq!(FrameSchedule::explain(), vec![
+            vec![vec!["Extract"]],
+            vec![vec!["PhysicsSim"]],
+            vec![vec!["Prepare"]],
+            vec![vec!["Record"]],
+        ]);
+        // Record reads the DrawList that Prepare writes, so it is pushed to a second layer
+        assert_eq!(MixedStage::explain(), vec![vec![vec!["Prepare", "Extract", "PhysicsSim"], vec!["Record"]]]);
+        assert_eq!(NestedFrame::explain()[1], vec![vec!["PhysicsSchedule"]]);
+        // max_threads splits each layer into the sub-batches `run` submits
+        assert_eq!(StreamingSchedule::explain(), vec![vec![
+            vec!["Stream", "Stream"], vec!["Stream", "Stream"], vec!["Stream"],
+        ]]);
+        assert_eq!(CappedStage::explain(), vec![vec![vec!["Urgent", "Extract"], vec!["Background"]]]);
+    }
+
+    // Non-conflicting systems of different priorities under a cap; only explained, never run
+    #[allow(dead_code)]
+    #[derive(Schedule)]
+    struct CappedStage {
+        #[stage(name = "mixed", max_threads = 2)] mixed: (Background, Extract, Urgent),
+    }
+
+    // Two schedules that only agree on ordering through qualified stage labels
+    #[derive(Schedule)]
+    struct CoreSchedule {
+        #[stage(name = "input")] input: (Extract,),
+        #[stage(name = "physics", after = "input")] physics: (PhysicsSim,),
+        #[stage(name = "render", after = "physics")] render: (Record,),
+    }
+
+    #[derive(Schedule)]
+    struct PluginSchedule {
+        #[stage(name = "prepare", after = "CoreSchedule::physics", before = "CoreSchedule::render")] prepare: (Prepare,),
+        #[stage(sub, name = "sim", after = "CoreSchedule::input, prepare")] sim: PhysicsSchedule,
+    }
+
+    #[test]
+    fn merged_schedules_honor_cross_references() {
+        use macrokid_core::threads::{MergedSchedule, ScheduleMergeError};
+        let _guard = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let core = CoreSchedule { input: (Extract,), physics: (PhysicsSim,), render: (Record,) };
+        let plugin = PluginSchedule {
+            prepare: (Prepare,),
+            sim: PhysicsSchedule { integrate: (Integrate,), collide: (Collide,) },
+        };
+        // Plugin listed first: labels still pull its stages into the core timeline
+        let merged = MergedSchedule::new(&[&plugin, &core]).unwrap();
+        assert_eq!(merged.labels(), vec![
+            "CoreSchedule::input", "CoreSchedule::physics", "PluginSchedule::prepare",
+            "PluginSchedule::sim", "CoreSchedule::render",
+        ]);
+        RUN_LOG.lock().unwrap().clear();
+        merged.run(&ThreadPool::new(2));
+        let log = RUN_LOG.lock().unwrap().clone();
+        assert_eq!(log, vec!["extract", "physics", "prepare", "integrate", "collide", "record"]);
+
+        // Without the core schedule the qualified labels cannot be resolved
+        let err = MergedSchedule::new(&[&plugin]).err().unwrap();
+        assert_eq!(err, ScheduleMergeError::UnknownStage {
+            stage: "PluginSchedule::prepare".into(),
+            target: "CoreSchedule::physics".into(),
+        });
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn frame_schedule_runs_on_rayon() {
+        use macrokid_core::threads::RayonScheduler;
+        let _guard = SERIAL.lock().unwrap_or_else(|e| e.into_inner());
+        let frame = FrameSchedule { extract: (Extract,), physics: (PhysicsSim,), prepare: (Prepare,), record: (Record,) };
+        RUN_LOG.lock().unwrap().clear();
+        frame.run(&RayonScheduler::global());
+        let log = RUN_LOG.lock().unwrap().clone();
+        let pos = |n: &str| log.iter().position(|s| *s == n).unwrap();
+        assert_eq!(log.len(), 4);
+        assert!(pos("extract") < pos("prepare"));
+        assert!(pos("prepare") < pos("record"));
+        assert!(pos("physics") < pos("record"));
+    }
 }
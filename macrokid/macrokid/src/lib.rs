@@ -1,5 +1,5 @@
 use proc_macro::TokenStream;
-use syn::{parse::{Parse, ParseStream}, parse_macro_input, ItemFn, LitBool, LitStr, Token, Ident};
+use syn::{parse::{Parse, ParseStream}, parse_macro_input, ItemFn, LitBool, LitInt, LitStr, Token, Ident};
 
 // =====================
 // Attribute macro: #[trace]  
@@ -13,6 +13,8 @@ pub fn trace(attr: TokenStream, item: TokenStream) -> TokenStream {
     let mut cfg = macrokid_core::attr::trace::TraceConfig::default();
     if let Some(prefix) = args.prefix { cfg.prefix = prefix.value(); }
     if let Some(rel) = args.release { cfg.release = rel.value; }
+    cfg.min_ms = args.min_ms;
+    cfg.sample = args.sample;
     if let Some(logger) = args.logger {
         let s = logger.value();
         cfg.logger = match s.as_str() {
@@ -24,6 +26,21 @@ pub fn trace(attr: TokenStream, item: TokenStream) -> TokenStream {
     macrokid_core::attr::trace::expand_trace(func, cfg).into()
 }
 
+// =====================
+// Attribute macro: #[memoize] / #[memoize(capacity = 100)]
+// Caches results of a pure function keyed on its (owned) arguments
+// =====================
+#[proc_macro_attribute]
+pub fn memoize(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let func: ItemFn = parse_macro_input!(item as ItemFn);
+    let args = parse_macro_input!(attr as MemoizeArgs);
+
+    let cfg = macrokid_core::attr::memoize::MemoizeConfig { capacity: args.capacity };
+    macrokid_core::attr::memoize::expand_memoize(func, cfg)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
 // =====================
 // NOTE: Derive macros like Display are now demonstrated in the 
 // examples/custom_derive crate to show how to use macrokid_core
@@ -41,11 +58,42 @@ pub fn make_enum(input: TokenStream) -> TokenStream {
     macrokid_core::function::make_enum::expand_make_enum(parsed_input).into()
 }
 
+// =====================
+// Function-like macro: include_dir_modules!("shaders")
+// Emits a `pub const` per file in the directory (relative to CARGO_MANIFEST_DIR).
+// =====================
+#[proc_macro]
+pub fn include_dir_modules(input: TokenStream) -> TokenStream {
+    let parsed_input: macrokid_core::function::include_dir::IncludeDirInput =
+        parse_macro_input!(input as macrokid_core::function::include_dir::IncludeDirInput);
+
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".to_string());
+    macrokid_core::function::include_dir::expand_include_dir_modules(parsed_input, std::path::Path::new(&manifest_dir))
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
+// =====================
+// Function-like macro: state_machine!(Door { states: [..], transitions: [A -> B on event, ..] })
+// Generates state + event enums and a checked `step` from the transition table.
+// =====================
+#[proc_macro]
+pub fn state_machine(input: TokenStream) -> TokenStream {
+    let parsed_input: macrokid_core::function::state_machine::StateMachineInput =
+        parse_macro_input!(input as macrokid_core::function::state_machine::StateMachineInput);
+
+    macrokid_core::function::state_machine::expand_state_machine(parsed_input)
+        .unwrap_or_else(|e| e.to_compile_error())
+        .into()
+}
+
 // --- Parsing for #[trace(...)] options ---
 struct TraceArgs {
     prefix: Option<LitStr>,
     release: Option<LitBool>,
     logger: Option<LitStr>,
+    min_ms: Option<u64>,
+    sample: Option<u64>,
 }
 
 impl Parse for TraceArgs {
@@ -53,6 +101,8 @@ impl Parse for TraceArgs {
         let mut prefix = None;
         let mut release = None;
         let mut logger = None;
+        let mut min_ms = None;
+        let mut sample = None;
 
         while !input.is_empty() {
             let key: Ident = input.parse()?;
@@ -61,11 +111,41 @@ impl Parse for TraceArgs {
                 "prefix" => { prefix = Some(input.parse::<LitStr>()?); },
                 "release" => { release = Some(input.parse::<LitBool>()?); },
                 "logger" => { logger = Some(input.parse::<LitStr>()?); },
+                "min_ms" => { min_ms = Some(input.parse::<LitInt>()?.base10_parse::<u64>()?); },
+                "sample" => {
+                    let lit = input.parse::<LitInt>()?;
+                    let n = lit.base10_parse::<u64>()?;
+                    if n == 0 { return Err(syn::Error::new_spanned(lit, "sample must be at least 1")); }
+                    sample = Some(n);
+                },
                 _ => return Err(syn::Error::new_spanned(key, "unknown trace option")),
             }
             let _ = input.parse::<Token![,]>();
         }
 
-        Ok(TraceArgs { prefix, release, logger })
+        Ok(TraceArgs { prefix, release, logger, min_ms, sample })
+    }
+}
+
+// --- Parsing for #[memoize(...)] options ---
+struct MemoizeArgs {
+    capacity: Option<usize>,
+}
+
+impl Parse for MemoizeArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut capacity = None;
+
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+            match key.to_string().as_str() {
+                "capacity" => { capacity = Some(input.parse::<LitInt>()?.base10_parse::<usize>()?); },
+                _ => return Err(syn::Error::new_spanned(key, "unknown memoize option")),
+            }
+            let _ = input.parse::<Token![,]>();
+        }
+
+        Ok(MemoizeArgs { capacity })
     }
 }
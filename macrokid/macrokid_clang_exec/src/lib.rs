@@ -1,4 +1,5 @@
-use std::path::Path;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
 use serde::{Deserialize, Serialize};
@@ -10,13 +11,31 @@ pub enum ClangExecError {
     #[error("clang not found or failed to execute: {0}")] Exec(String),
     #[error("clang returned non-zero status: {0}")] Status(String),
     #[error("invalid JSON from clang: {0}")] Json(String),
+    #[error("analysis cache error: {0}")] Cache(String),
 }
 
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct HeaderIR { pub structs: Vec<StructIR> }
 
+impl HeaderIR {
+    /// Structs grouped by the source file they were declared in (see [`StructIR::file`]).
+    /// Structs without a known location are grouped under the empty string.
+    pub fn by_file(&self) -> BTreeMap<&str, Vec<&StructIR>> {
+        let mut out: BTreeMap<&str, Vec<&StructIR>> = BTreeMap::new();
+        for s in &self.structs { out.entry(s.file.as_deref().unwrap_or("")).or_default().push(s); }
+        out
+    }
+}
+
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
-pub struct StructIR { pub name: String, pub attrs: Vec<AttrIR>, pub fields: Vec<FieldIR> }
+pub struct StructIR {
+    pub name: String,
+    pub attrs: Vec<AttrIR>,
+    pub fields: Vec<FieldIR>,
+    /// File the declaration came from, taken from clang's `loc` (None if clang reported none).
+    #[serde(default)]
+    pub file: Option<String>,
+}
 
 #[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct FieldIR { pub name: String, pub type_name: String, pub attrs: Vec<AttrIR> }
@@ -29,10 +48,115 @@ pub struct AttrIR {
     pub args: Vec<String>,
 }
 
+/// Structured clang flags for the `*_with` analysis functions.
+///
+/// ```
+/// use macrokid_clang_exec::ClangOptions;
+/// let opts = ClangOptions::new().include("include").define_value("MK_GPU", "1").std("c11");
+/// assert_eq!(opts.to_args(), ["-Iinclude", "-DMK_GPU=1", "-std=c11"]);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClangOptions {
+    /// Include directories, passed as `-I<path>`.
+    pub includes: Vec<PathBuf>,
+    /// Macro definitions, passed as `-DNAME` or `-DNAME=VALUE`.
+    pub defines: Vec<(String, Option<String>)>,
+    /// Language standard, passed as `-std=<std>` (e.g. `c11`, `c++17`).
+    pub std: Option<String>,
+    /// Target triple, passed as `--target=<triple>`.
+    pub target: Option<String>,
+    /// Raw arguments appended after everything else.
+    pub extra: Vec<String>,
+}
+
+impl ClangOptions {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn include(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.includes.push(dir.into());
+        self
+    }
+
+    /// `-DNAME`
+    pub fn define(mut self, name: impl Into<String>) -> Self {
+        self.defines.push((name.into(), None));
+        self
+    }
+
+    /// `-DNAME=VALUE`
+    pub fn define_value(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.defines.push((name.into(), Some(value.into())));
+        self
+    }
+
+    pub fn std(mut self, std: impl Into<String>) -> Self {
+        self.std = Some(std.into());
+        self
+    }
+
+    pub fn target(mut self, triple: impl Into<String>) -> Self {
+        self.target = Some(triple.into());
+        self
+    }
+
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.extra.push(arg.into());
+        self
+    }
+
+    /// The clang arguments, in order: includes, defines, `-std`, `--target`, then `extra`.
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args: Vec<String> = self.includes.iter().map(|p| format!("-I{}", p.display())).collect();
+        for (name, value) in &self.defines {
+            args.push(match value {
+                Some(v) => format!("-D{}={}", name, v),
+                None => format!("-D{}", name),
+            });
+        }
+        if let Some(std) = &self.std { args.push(format!("-std={}", std)); }
+        if let Some(target) = &self.target { args.push(format!("--target={}", target)); }
+        args.extend(self.extra.iter().cloned());
+        args
+    }
+
+    fn from_raw(extra_args: &[&str]) -> Self {
+        Self { extra: extra_args.iter().map(|a| a.to_string()).collect(), ..Self::default() }
+    }
+}
+
 /// Analyze a C/C++ header by shelling out to clang and parsing its JSON AST dump.
 /// This is a best-effort PoC; it extracts RecordDecl (struct/class) and FieldDecl with basic type names.
 pub fn analyze_header<P: AsRef<Path>>(path: P, extra_args: &[&str]) -> Result<HeaderIR, ClangExecError> {
-    let path = path.as_ref();
+    analyze_header_with(path, &ClangOptions::from_raw(extra_args))
+}
+
+/// [`analyze_header`] with flags built from [`ClangOptions`].
+pub fn analyze_header_with<P: AsRef<Path>>(path: P, opts: &ClangOptions) -> Result<HeaderIR, ClangExecError> {
+    let args = opts.to_args();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    analyze_header_via(path.as_ref(), &args, &mut run_clang)
+}
+
+/// Runs clang with the given arguments and returns stdout; swapped out in tests.
+type ClangRunner<'a> = dyn FnMut(&[&str]) -> Result<Vec<u8>, ClangExecError> + 'a;
+
+/// Run clang with `args` and return its stdout.
+fn run_clang(args: &[&str]) -> Result<Vec<u8>, ClangExecError> {
+    let output = Command::new("clang")
+        .args(args)
+        .output()
+        .map_err(|e| ClangExecError::Exec(e.to_string()))?;
+    if !output.status.success() {
+        return Err(ClangExecError::Status(String::from_utf8_lossy(&output.stderr).into()));
+    }
+    Ok(output.stdout)
+}
+
+fn analyze_header_via(
+    path: &Path,
+    extra_args: &[&str],
+    run: &mut ClangRunner,
+) -> Result<HeaderIR, ClangExecError> {
     let lossy = path.to_string_lossy();
     let mut args = vec![
         "-Xclang", "-ast-dump=json",
@@ -41,30 +165,142 @@ pub fn analyze_header<P: AsRef<Path>>(path: P, extra_args: &[&str]) -> Result<He
     ];
     args.extend(extra_args.iter().copied());
 
-    let output = Command::new("clang")
-        .args(&args)
-        .output()
-        .map_err(|e| ClangExecError::Exec(e.to_string()))?;
-    if !output.status.success() {
-        return Err(ClangExecError::Status(String::from_utf8_lossy(&output.stderr).into()));
-    }
-    let v: Value = serde_json::from_slice(&output.stdout)
+    let stdout = run(&args)?;
+    let v: Value = serde_json::from_slice(&stdout)
         .map_err(|e| ClangExecError::Json(e.to_string()))?;
     let mut ir = HeaderIR::default();
-    collect_from_ast(&v, &mut ir);
+    collect_from_ast(&v, &mut ir, &mut None);
+    Ok(ir)
+}
+
+/// Analyze several headers with a single clang run (a "unity build").
+///
+/// Writes a temporary `.c` file (`.cpp` if any header has a C++ extension) that `#include`s every
+/// header, dumps its AST once and attributes each struct to its header through clang's source
+/// locations. Only structs declared in one of `paths` are kept, with [`StructIR::file`] set to
+/// the path as passed in, so [`HeaderIR::by_file`] splits the result per header.
+pub fn analyze_headers(paths: &[&Path], extra_args: &[&str]) -> Result<HeaderIR, ClangExecError> {
+    analyze_headers_via(paths, extra_args, &mut run_clang)
+}
+
+fn analyze_headers_via(paths: &[&Path], extra_args: &[&str], run: &mut ClangRunner) -> Result<HeaderIR, ClangExecError> {
+    // clang reports the file as spelled in the #include, so include canonical paths and map back
+    let mut headers: Vec<(String, String)> = Vec::with_capacity(paths.len());
+    for p in paths {
+        let canon = std::fs::canonicalize(p).map_err(|e| ClangExecError::Exec(format!("{}: {}", p.display(), e)))?;
+        headers.push((canon.to_string_lossy().into_owned(), p.to_string_lossy().into_owned()));
+    }
+    let cpp = paths.iter().any(|p| matches!(p.extension().and_then(|e| e.to_str()), Some("hpp" | "hh" | "hxx" | "h++")));
+    let mut unity = String::from("// Generated by macrokid_clang_exec::analyze_headers\n");
+    for (canon, _) in &headers { unity.push_str(&format!("#include \"{}\"\n", canon.replace('\\', "/"))); }
+
+    let key: String = headers.iter().map(|(c, _)| c.as_str()).collect::<Vec<_>>().join("\0");
+    let unity_path = std::env::temp_dir().join(format!(
+        "macrokid_unity_{}_{:016x}.{}", std::process::id(), fnv1a_64(key.as_bytes()), if cpp { "cpp" } else { "c" }
+    ));
+    std::fs::write(&unity_path, unity).map_err(|e| ClangExecError::Exec(format!("{}: {}", unity_path.display(), e)))?;
+    let result = analyze_header_via(&unity_path, extra_args, run);
+    let _ = std::fs::remove_file(&unity_path);
+
+    let mut ir = result?;
+    ir.structs.retain_mut(|s| {
+        let original = s.file.as_deref().and_then(|f| {
+            let f = std::fs::canonicalize(f).map(|c| c.to_string_lossy().into_owned()).unwrap_or_else(|_| f.to_string());
+            headers.iter().find(|(canon, _)| *canon == f).map(|(_, orig)| orig.clone())
+        });
+        s.file = original;
+        s.file.is_some()
+    });
+    Ok(ir)
+}
+
+/// `analyze_header` with an on-disk cache for build scripts.
+///
+/// The IR is stored as JSON in `cache_dir`, one entry per canonical header path, keyed by a hash
+/// of that path, the header's contents, `extra_args`, and `clang --version`. Any change to those
+/// inputs misses the cache, reruns clang, and replaces the stale entry for the same header; headers
+/// that share a file name in different directories keep separate entries.
+pub fn analyze_header_cached<P: AsRef<Path>>(path: P, extra_args: &[&str], cache_dir: &Path) -> Result<HeaderIR, ClangExecError> {
+    analyze_header_cached_via(path.as_ref(), extra_args, cache_dir, &mut run_clang)
+}
+
+fn analyze_header_cached_via(
+    path: &Path,
+    extra_args: &[&str],
+    cache_dir: &Path,
+    run: &mut ClangRunner,
+) -> Result<HeaderIR, ClangExecError> {
+    let cache_err = |e: std::io::Error| ClangExecError::Cache(format!("{}: {}", path.display(), e));
+    let canonical = std::fs::canonicalize(path).map_err(cache_err)?;
+    let contents = std::fs::read(&canonical).map_err(cache_err)?;
+    let version = run(&["--version"])?;
+
+    let canonical_str = canonical.to_string_lossy();
+    let mut key = Vec::with_capacity(canonical_str.len() + contents.len() + 64);
+    key.extend_from_slice(canonical_str.as_bytes());
+    key.push(0);
+    key.extend_from_slice(&contents);
+    key.push(0);
+    for a in extra_args { key.extend_from_slice(a.as_bytes()); key.push(0); }
+    key.extend_from_slice(&version);
+    let stem = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_else(|| "header".into());
+    // `{file name}-{path hash}-` identifies the header; the final hash identifies its inputs
+    let prefix = format!("{}-{:016x}-", stem, fnv1a_64(canonical_str.as_bytes()));
+    let entry = cache_dir.join(format!("{}{:016x}.json", prefix, fnv1a_64(&key)));
+
+    if let Ok(bytes) = std::fs::read(&entry) {
+        if let Ok(ir) = serde_json::from_slice::<HeaderIR>(&bytes) { return Ok(ir); }
+    }
+
+    let ir = analyze_header_via(path, extra_args, run)?;
+    let cache_err = |e: std::io::Error| ClangExecError::Cache(format!("{}: {}", cache_dir.display(), e));
+    std::fs::create_dir_all(cache_dir).map_err(cache_err)?;
+    // Drop entries for the same header under an older key
+    if let Ok(dir) = std::fs::read_dir(cache_dir) {
+        for e in dir.flatten() {
+            let name = e.file_name().to_string_lossy().into_owned();
+            if e.path() != entry && name.strip_prefix(&prefix).is_some_and(|r| r.len() == 21 && r.ends_with(".json")) {
+                let _ = std::fs::remove_file(e.path());
+            }
+        }
+    }
+    let json = serde_json::to_vec(&ir).map_err(|e| ClangExecError::Json(e.to_string()))?;
+    std::fs::write(&entry, json).map_err(cache_err)?;
     Ok(ir)
 }
 
-fn collect_from_ast(v: &Value, ir: &mut HeaderIR) {
+fn fnv1a_64(bytes: &[u8]) -> u64 {
+    let mut h: u64 = 0xcbf29ce484222325;
+    for b in bytes { h ^= *b as u64; h = h.wrapping_mul(0x100000001b3); }
+    h
+}
+
+/// Track the file of the most recent source location. clang's JSON dump only writes `file` when
+/// it differs from the previously printed location, so nodes must be visited in document order:
+/// `loc` (spelling, then expansion), then `range` begin/end, then children.
+fn track_loc_file(loc: Option<&Value>, file: &mut Option<String>) {
+    let Some(Value::Object(m)) = loc else { return };
+    for nested in ["spellingLoc", "expansionLoc"] { track_loc_file(m.get(nested), file); }
+    if let Some(f) = m.get("file").and_then(|f| f.as_str()) { *file = Some(f.to_string()); }
+}
+
+fn collect_from_ast(v: &Value, ir: &mut HeaderIR, file: &mut Option<String>) {
     match v {
         Value::Object(map) => {
+            // After `loc`, `file` is the declaration's file (the expansion site for macro-made decls)
+            track_loc_file(map.get("loc"), file);
+            let decl_file = file.clone();
+            if let Some(Value::Object(range)) = map.get("range") {
+                track_loc_file(range.get("begin"), file);
+                track_loc_file(range.get("end"), file);
+            }
             if let Some(Value::String(kind)) = map.get("kind") {
                 if kind.as_str() == "RecordDecl" {
                     // name if present
                     let name = map.get("name").and_then(|n| n.as_str()).unwrap_or("").to_string();
                     if !name.is_empty() {
                         let attrs = collect_attrs_from_node(map);
-                        let mut st = StructIR { name, attrs, fields: Vec::new() };
+                        let mut st = StructIR { name, attrs, fields: Vec::new(), file: decl_file };
                         if let Some(inner) = map.get("inner").and_then(|x| x.as_array()) {
                             for node in inner {
                                 if let Some(f) = parse_field_decl(node) { st.fields.push(f); }
@@ -74,12 +310,12 @@ fn collect_from_ast(v: &Value, ir: &mut HeaderIR) {
                     }
                 }
             }
-            for (_k, val) in map {
-                collect_from_ast(val, ir);
+            for (k, val) in map {
+                if k != "loc" && k != "range" { collect_from_ast(val, ir, file); }
             }
         }
         Value::Array(arr) => {
-            for item in arr { collect_from_ast(item, ir); }
+            for item in arr { collect_from_ast(item, ir, file); }
         }
         _ => {}
     }
@@ -158,22 +394,76 @@ pub fn emit_cpp_header(ir: &HeaderIR, ns: Option<&str>) -> String {
 
 // ================= mk:: annotation parsing =================
 
-use std::collections::BTreeMap;
-
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MkAnnotationKind { Struct, Vertex, Resource, Other(String) }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MkAnnotation {
     pub kind: MkAnnotationKind,
+    /// Values as written; list values are joined with `,` (`stages=[vs, fs]` -> `vs,fs`).
     pub args: BTreeMap<String, String>,
+    /// The same values, typed.
+    #[serde(default)]
+    pub values: BTreeMap<String, MkValue>,
     pub raw: String,
 }
 
+/// Typed annotation value. Quoted values are always `Str`; bare `true`/`false` are `Bool` and bare
+/// integers are `Int`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MkValue { Str(String), Int(i64), Bool(bool), List(Vec<MkValue>) }
+
+impl MkValue {
+    fn from_scalar(text: String, quoted: bool) -> Self {
+        if quoted { return MkValue::Str(text); }
+        match text.as_str() {
+            "true" => MkValue::Bool(true),
+            "false" => MkValue::Bool(false),
+            _ => text.parse().map(MkValue::Int).unwrap_or(MkValue::Str(text)),
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> { if let MkValue::Str(s) = self { Some(s) } else { None } }
+    pub fn as_int(&self) -> Option<i64> { if let MkValue::Int(n) = self { Some(*n) } else { None } }
+    pub fn as_bool(&self) -> Option<bool> { if let MkValue::Bool(b) = self { Some(*b) } else { None } }
+    pub fn as_list(&self) -> Option<&[MkValue]> { if let MkValue::List(l) = self { Some(l) } else { None } }
+}
+
+impl std::fmt::Display for MkValue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MkValue::Str(s) => f.write_str(s),
+            MkValue::Int(n) => write!(f, "{}", n),
+            MkValue::Bool(b) => write!(f, "{}", b),
+            MkValue::List(items) => {
+                for (i, v) in items.iter().enumerate() {
+                    if i > 0 { f.write_str(",")?; }
+                    write!(f, "{}", v)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Quoted string or bare token ending at one of `stops`; returns the text and whether it was quoted.
+fn parse_mk_scalar(chars: &[char], i: &mut usize, stops: &[char]) -> Option<(String, bool)> {
+    if *i < chars.len() && chars[*i] == '"' {
+        *i += 1; let vs = *i; while *i < chars.len() && chars[*i] != '"' { *i += 1; }
+        if *i >= chars.len() { return None; }
+        let v: String = chars[vs..*i].iter().collect(); *i += 1;
+        Some((v, true))
+    } else {
+        let vs = *i; while *i < chars.len() && !stops.contains(&chars[*i]) { *i += 1; }
+        Some((chars[vs..*i].iter().collect::<String>().trim().to_string(), false))
+    }
+}
+
 fn is_ident_start(c: char) -> bool { c.is_ascii_alphabetic() || c == '_' }
 fn is_ident_char(c: char) -> bool { c.is_ascii_alphanumeric() || c == '_' }
 
-/// Parse strings like: `mk::vertex(location=1,format=vec3)` into a typed form.
+/// Parse strings like: `mk::vertex(location=1,format=vec3)` into a typed form. Values may also be
+/// bracketed lists (`stages=[vs, fs]`) of quoted or bare items.
 pub fn parse_mk_annotation(s: &str) -> Option<MkAnnotation> {
     let s = s.trim();
     if !s.starts_with("mk::") { return None; }
@@ -193,6 +483,7 @@ pub fn parse_mk_annotation(s: &str) -> Option<MkAnnotation> {
     // Skip spaces
     while i < chars.len() && chars[i].is_whitespace() { i += 1; }
     let mut args = BTreeMap::new();
+    let mut values = BTreeMap::new();
     if i < chars.len() && chars[i] == '(' {
         i += 1; // consume '('
         loop {
@@ -208,23 +499,34 @@ pub fn parse_mk_annotation(s: &str) -> Option<MkAnnotation> {
             if i >= chars.len() || chars[i] != '=' { return None; }
             i += 1; // '='
             while i < chars.len() && chars[i].is_whitespace() { i += 1; }
-            // parse value: quoted string or ident/number
-            let val = if i < chars.len() && chars[i] == '"' {
-                i += 1; let vs = i; while i < chars.len() && chars[i] != '"' { i += 1; }
-                if i >= chars.len() { return None; }
-                let v: String = chars[vs..i].iter().collect(); i += 1; v
+            // parse value: [list], quoted string or ident/number
+            let (text, val) = if i < chars.len() && chars[i] == '[' {
+                i += 1; // '['
+                let mut items = Vec::new();
+                loop {
+                    while i < chars.len() && chars[i].is_whitespace() { i += 1; }
+                    if i >= chars.len() { return None; }
+                    if chars[i] == ']' { i += 1; break; }
+                    let (text, quoted) = parse_mk_scalar(&chars, &mut i, &[',', ']'])?;
+                    items.push(MkValue::from_scalar(text, quoted));
+                    while i < chars.len() && chars[i].is_whitespace() { i += 1; }
+                    if i < chars.len() && chars[i] == ',' { i += 1; }
+                }
+                let list = MkValue::List(items);
+                (list.to_string(), list)
             } else {
-                let vs = i; while i < chars.len() && ![',', ')'].contains(&chars[i]) { i += 1; }
-                let v: String = chars[vs..i].iter().collect::<String>().trim().to_string(); v
+                let (text, quoted) = parse_mk_scalar(&chars, &mut i, &[',', ')'])?;
+                (text.clone(), MkValue::from_scalar(text, quoted))
             };
-            args.insert(key, val);
+            args.insert(key.clone(), text);
+            values.insert(key, val);
             while i < chars.len() && chars[i].is_whitespace() { i += 1; }
             if i < chars.len() && chars[i] == ',' { i += 1; continue; }
             if i < chars.len() && chars[i] == ')' { break; }
             if i >= chars.len() { break; }
         }
     }
-    Some(MkAnnotation { kind, args, raw: s.to_string() })
+    Some(MkAnnotation { kind, args, values, raw: s.to_string() })
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -272,10 +574,18 @@ pub struct CHeaderIR {
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
-pub struct CStructIR { pub name: String, pub is_union: bool, pub fields: Vec<CFieldIR> }
+pub struct CStructIR {
+    pub name: String,
+    pub is_union: bool,
+    pub fields: Vec<CFieldIR>,
+    /// Enclosing record of an anonymous struct/union defined inside another. Such records get a
+    /// synthetic name `<parent>::__anonN` (N counts per parent), which the field using them refers to.
+    #[serde(default)]
+    pub parent: Option<String>,
+}
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
-pub struct CFieldIR { pub name: String, pub type_name: String }
+pub struct CFieldIR { pub name: String, pub type_name: String, pub ty: TypeInfo }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct CEnumIR { pub name: String, pub items: Vec<(String, String)> }
@@ -284,10 +594,334 @@ pub struct CEnumIR { pub name: String, pub items: Vec<(String, String)> }
 pub struct CTypedefIR { pub name: String, pub underlying: String }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
-pub struct CFunctionIR { pub name: String, pub ret: String, pub params: Vec<(String, String)> }
+pub struct CFunctionIR { pub name: String, pub ret: String, pub params: Vec<CParamIR> }
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CParamIR { pub name: String, pub type_name: String, pub ty: TypeInfo }
+
+/// Parsed view of a clang `qualType` string such as `const char *` or `float[4]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TypeInfo {
+    /// `const` qualifies the base type (`const int *`, `int const *`); pointer-level const is ignored.
+    pub is_const: bool,
+    /// Number of `*` declarators (`void **` -> 2).
+    pub pointer_depth: u32,
+    /// Array extents outermost first; `None` for an unsized `[]`.
+    pub array_dims: Vec<Option<u64>>,
+    /// The type with qualifiers, pointers and array suffixes removed (`const char *` -> `char`).
+    pub base_type: String,
+}
+
+/// Best-effort analysis of a clang `qualType` string. Function pointer types are kept verbatim as
+/// `base_type` with no pointer/array information.
+pub fn parse_type_info(qual_type: &str) -> TypeInfo {
+    let mut rest = qual_type.trim();
+    if rest.contains('(') {
+        return TypeInfo { base_type: rest.to_string(), ..Default::default() };
+    }
+    let mut array_dims = Vec::new();
+    while let Some(head) = rest.strip_suffix(']') {
+        let Some((before, extent)) = head.rsplit_once('[') else { break };
+        array_dims.insert(0, extent.trim().parse::<u64>().ok());
+        rest = before.trim_end();
+    }
+    let pointer_depth = rest.matches('*').count() as u32;
+    // Qualifiers before the first `*` apply to the base type; later ones apply to a pointer.
+    let base_part = rest.split('*').next().unwrap_or("");
+    let is_const = base_part.split_whitespace().any(|t| t == "const");
+    let base_type = base_part
+        .split_whitespace()
+        .filter(|t| !matches!(*t, "const" | "volatile" | "restrict" | "__restrict"))
+        .collect::<Vec<_>>()
+        .join(" ");
+    TypeInfo { is_const, pointer_depth, array_dims, base_type }
+}
+
+/// Edges `(struct, referenced)` from each named struct to the structs and typedefs its fields
+/// use, after stripping `const`, pointers, arrays and any `struct`/`union`/`enum` tag. Edges keep
+/// struct then field order and are listed once per pair; self-references (`struct Node *next`)
+/// are included.
+pub fn type_dependency_graph(ir: &CHeaderIR) -> Vec<(String, String)> {
+    let known: HashSet<&str> = ir.structs.iter().map(|s| s.name.as_str())
+        .chain(ir.typedefs.iter().map(|t| t.name.as_str()))
+        .filter(|n| !n.is_empty())
+        .collect();
+    let mut edges: Vec<(String, String)> = Vec::new();
+    for s in ir.structs.iter().filter(|s| !s.name.is_empty()) {
+        for f in &s.fields {
+            let base = f.ty.base_type.as_str();
+            let name = ["struct ", "union ", "enum "].iter().find_map(|tag| base.strip_prefix(tag)).unwrap_or(base);
+            let edge = (s.name.clone(), name.to_string());
+            if known.contains(name) && !edges.contains(&edge) { edges.push(edge); }
+        }
+    }
+    edges
+}
+
+/// Render dependency edges (e.g. from [`type_dependency_graph`]) as a Graphviz `digraph`.
+pub fn to_dot(edges: &[(String, String)]) -> String {
+    let mut out = String::from("digraph types {\n");
+    for (from, to) in edges {
+        out.push_str(&format!("  \"{}\" -> \"{}\";\n", from, to));
+    }
+    out.push_str("}\n");
+    out
+}
+
+// ================= Rust FFI emission =================
+
+/// Options for [`emit_rust_ffi`].
+#[derive(Debug, Clone, Default)]
+pub struct RustEmitCfg {
+    /// Emit each enum as a `c_int` alias plus one `pub const` per enumerator instead of a
+    /// `#[repr(C)] pub enum`. Enums with duplicate values always use constants.
+    pub enums_as_consts: bool,
+    /// Library for a `#[link(name = "...")]` attribute on the `extern "C"` block.
+    pub link_name: Option<String>,
+}
+
+const C_PRIMITIVES: &[(&str, &str)] = &[
+    ("void", "c_void"), ("char", "c_char"), ("signed char", "c_schar"), ("unsigned char", "c_uchar"),
+    ("short", "c_short"), ("short int", "c_short"), ("signed short", "c_short"),
+    ("unsigned short", "c_ushort"), ("unsigned short int", "c_ushort"),
+    ("int", "c_int"), ("signed", "c_int"), ("signed int", "c_int"), ("unsigned", "c_uint"), ("unsigned int", "c_uint"),
+    ("long", "c_long"), ("long int", "c_long"), ("signed long", "c_long"), ("unsigned long", "c_ulong"), ("unsigned long int", "c_ulong"),
+    ("long long", "c_longlong"), ("long long int", "c_longlong"),
+    ("unsigned long long", "c_ulonglong"), ("unsigned long long int", "c_ulonglong"),
+    ("float", "f32"), ("double", "f64"), ("_Bool", "bool"), ("bool", "bool"),
+    ("int8_t", "i8"), ("int16_t", "i16"), ("int32_t", "i32"), ("int64_t", "i64"),
+    ("uint8_t", "u8"), ("uint16_t", "u16"), ("uint32_t", "u32"), ("uint64_t", "u64"),
+    ("size_t", "usize"), ("uintptr_t", "usize"), ("ssize_t", "isize"), ("ptrdiff_t", "isize"), ("intptr_t", "isize"),
+];
+
+/// Type mapping state for [`emit_rust_ffi`]: names the IR defines, the `std::os::raw` imports used
+/// so far, opaque stand-ins as (Rust name, C spelling) for every other type, and the C names that
+/// are not `Copy` by value (opaque types and records embedding one).
+struct FfiTypes<'a> {
+    known: HashSet<&'a str>,
+    raw: BTreeSet<&'static str>,
+    opaque: Vec<(String, String)>,
+    no_copy: HashSet<&'a str>,
+}
+
+fn strip_tag(base: &str) -> &str {
+    ["struct ", "union ", "enum "].iter().find_map(|tag| base.strip_prefix(tag)).unwrap_or(base)
+}
+
+impl FfiTypes<'_> {
+    fn base(&mut self, base: &str) -> String {
+        if let Some((_, rust)) = C_PRIMITIVES.iter().find(|(c, _)| *c == base) {
+            if rust.starts_with("c_") { self.raw.insert(rust); }
+            return rust.to_string();
+        }
+        if let Some(fn_ptr) = self.fn_pointer(base) { return fn_ptr; }
+        let name = strip_tag(base);
+        if self.known.contains(name) { return rust_ident(name); }
+        self.opaque(name)
+    }
+
+    fn opaque(&mut self, spelling: &str) -> String {
+        if let Some((rust, _)) = self.opaque.iter().find(|(_, c)| c == spelling) { return rust.clone(); }
+        let is_ident = spelling.chars().next().is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+            && spelling.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+        let rust = if is_ident { rust_ident(spelling) } else { format!("Opaque{}", self.opaque.len()) };
+        self.opaque.push((rust.clone(), spelling.to_string()));
+        rust
+    }
+
+    /// `ret (*)(args)` as a nullable `Option<unsafe extern "C" fn(..)>`; `None` for spellings this
+    /// does not parse (e.g. function pointers taking or returning function pointers).
+    fn fn_pointer(&mut self, spelling: &str) -> Option<String> {
+        let (ret, args) = spelling.split_once("(*)")?;
+        let args = args.trim().strip_prefix('(')?.strip_suffix(')')?.trim();
+        if ret.contains('(') || args.contains('(') { return None; }
+        let mut params = Vec::new();
+        if args != "void" {
+            for arg in args.split(',').map(str::trim).filter(|a| !a.is_empty()) {
+                params.push(if arg == "..." { arg.to_string() } else { self.ty(&parse_type_info(arg), true) });
+            }
+        }
+        let ret = parse_type_info(ret);
+        let ret = if ret.base_type == "void" && ret.pointer_depth == 0 { String::new() } else { format!(" -> {}", self.ty(&ret, false)) };
+        Some(format!("Option<unsafe extern \"C\" fn({}){}>", params.join(", "), ret))
+    }
+
+    /// Whether a value of this type (not behind a pointer) lacks `Copy`: opaque types may only
+    /// be used behind pointers, so records holding one by value cannot derive it.
+    fn no_copy_by_value(&self, ty: &TypeInfo) -> bool {
+        let base = ty.base_type.as_str();
+        if ty.pointer_depth > 0 || C_PRIMITIVES.iter().any(|(c, _)| *c == base) || base.contains("(*)") { return false; }
+        let name = strip_tag(base);
+        !self.known.contains(name) || self.no_copy.contains(name)
+    }
+
+    /// Rust spelling of a C type. Parameters decay their outermost array extent to a pointer;
+    /// struct fields keep arrays, with a flexible `[]` member becoming `[T; 0]`.
+    fn ty(&mut self, ty: &TypeInfo, param: bool) -> String {
+        let mut dims = ty.array_dims.as_slice();
+        let mut depth = ty.pointer_depth;
+        if param && !dims.is_empty() { dims = &dims[1..]; depth += 1; }
+        let mut out = self.base(&ty.base_type);
+        for &n in dims.iter().rev() { out = format!("[{}; {}]", out, n.unwrap_or(0)); }
+        for level in 0..depth {
+            // `const` qualifies the pointee, i.e. the innermost pointer
+            let ptr = if level == 0 && ty.is_const { "*const" } else { "*mut" };
+            out = format!("{} {}", ptr, out);
+        }
+        out
+    }
+}
+
+/// C names as Rust identifiers: synthetic `Parent::__anonN` records become `Parent__anonN` and
+/// keywords are escaped.
+fn rust_ident(name: &str) -> String {
+    const KEYWORDS: &[&str] = &[
+        "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false", "fn",
+        "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return",
+        "static", "struct", "trait", "true", "type", "unsafe", "use", "where", "while", "abstract", "become",
+        "box", "do", "final", "macro", "override", "priv", "try", "typeof", "unsized", "virtual", "yield",
+    ];
+    let name = name.replace("::", "__");
+    if KEYWORDS.contains(&name.as_str()) { format!("r#{}", name) }
+    else if matches!(name.as_str(), "self" | "Self" | "super" | "crate") { format!("{}_", name) }
+    else { name }
+}
+
+/// Emit Rust `extern "C"` bindings for C-only IR: `#[repr(C)]` structs/unions, enums (or
+/// constants, see [`RustEmitCfg::enums_as_consts`]), type aliases for typedefs and one
+/// `extern "C"` block for the functions. C primitives map to `std::os::raw` and function pointers
+/// to `Option<unsafe extern "C" fn(..)>`; types the IR does not define are emitted as opaque
+/// `#[repr(C)]` structs with a warning comment. Records holding an opaque type by value do not
+/// derive `Clone`/`Copy` (union fields are wrapped in `ManuallyDrop` instead).
+pub fn emit_rust_ffi(ir: &CHeaderIR, cfg: &RustEmitCfg) -> String {
+    let mut types = FfiTypes {
+        known: ir.structs.iter().map(|s| s.name.as_str())
+            .chain(ir.enums.iter().map(|e| e.name.as_str()))
+            .chain(ir.typedefs.iter().map(|t| t.name.as_str()))
+            .filter(|n| !n.is_empty())
+            .collect(),
+        raw: BTreeSet::new(),
+        opaque: Vec::new(),
+        no_copy: ir.structs.iter().filter(|s| s.fields.is_empty()).map(|s| s.name.as_str()).collect(),
+    };
+    // Propagate non-`Copy` through records and typedefs that hold such a type by value
+    loop {
+        let found: Vec<&str> = ir.structs.iter()
+            .filter(|s| !types.no_copy.contains(s.name.as_str()) && s.fields.iter().any(|f| types.no_copy_by_value(&f.ty)))
+            .map(|s| s.name.as_str())
+            .chain(ir.typedefs.iter()
+                .filter(|t| !types.no_copy.contains(t.name.as_str()) && types.no_copy_by_value(&parse_type_info(&t.underlying)))
+                .map(|t| t.name.as_str()))
+            .collect();
+        if found.is_empty() { break; }
+        types.no_copy.extend(found);
+    }
+    let mut body = String::new();
+
+    for s in ir.structs.iter().filter(|s| !s.name.is_empty()) {
+        let name = rust_ident(&s.name);
+        if s.fields.is_empty() {
+            body.push_str(&format!("#[repr(C)]\npub struct {} {{\n    _private: [u8; 0],\n}}\n\n", name));
+            continue;
+        }
+        let keyword = if s.is_union { "union" } else { "struct" };
+        let derive = if !types.no_copy.contains(s.name.as_str()) { "#[derive(Clone, Copy)]\n" } else { "" };
+        body.push_str(&format!("#[repr(C)]\n{}pub {} {} {{\n", derive, keyword, name));
+        for (i, f) in s.fields.iter().enumerate() {
+            let field = if f.name.is_empty() { format!("__anon{}", i) } else { rust_ident(&f.name) };
+            let mut ty = types.ty(&f.ty, false);
+            if s.is_union && types.no_copy_by_value(&f.ty) { ty = format!("::std::mem::ManuallyDrop<{}>", ty); }
+            body.push_str(&format!("    pub {}: {},\n", field, ty));
+        }
+        body.push_str("}\n\n");
+    }
+
+    for e in ir.enums.iter() {
+        // Enumerators without an explicit value continue from the previous one; after a
+        // non-numeric value they count up from that expression.
+        let mut next = (None::<String>, 0i64);
+        let values: Vec<(String, String)> = e.items.iter().map(|(item, value)| {
+            let value = match value.parse::<i64>() {
+                Ok(v) => { next = (None, v + 1); v.to_string() }
+                Err(_) if value.is_empty() => {
+                    next.1 += 1;
+                    match &next.0 {
+                        Some(expr) => format!("({}) + {}", expr, next.1 - 1),
+                        None => (next.1 - 1).to_string(),
+                    }
+                }
+                Err(_) => { next = (Some(value.clone()), 1); value.clone() }
+            };
+            (rust_ident(item), value)
+        }).collect();
+        let distinct: HashSet<&str> = values.iter().map(|(_, v)| v.as_str()).collect();
+        let name = rust_ident(&e.name);
+        if cfg.enums_as_consts || distinct.len() != values.len() {
+            types.raw.insert("c_int");
+            body.push_str(&format!("pub type {} = c_int;\n", name));
+            for (item, value) in &values { body.push_str(&format!("pub const {}: {} = {};\n", item, name, value)); }
+            body.push('\n');
+        } else {
+            body.push_str(&format!("#[repr(C)]\n#[derive(Debug, Clone, Copy, PartialEq, Eq)]\npub enum {} {{\n", name));
+            for (item, value) in &values { body.push_str(&format!("    {} = {},\n", item, value)); }
+            body.push_str("}\n\n");
+        }
+    }
+
+    for t in &ir.typedefs {
+        if C_PRIMITIVES.iter().any(|(c, _)| *c == t.name) { continue; }
+        let info = parse_type_info(&t.underlying);
+        if info.base_type.contains('(') && types.fn_pointer(&info.base_type).is_none() {
+            // Spellings `fn_pointer` does not parse stay opaque under the typedef's name
+            types.known.remove(t.name.as_str());
+            types.opaque(&t.name);
+            continue;
+        }
+        let target = types.ty(&info, false);
+        // `typedef struct Point Point;` needs no alias
+        if target != rust_ident(&t.name) { body.push_str(&format!("pub type {} = {};\n\n", rust_ident(&t.name), target)); }
+    }
+
+    if !ir.functions.is_empty() {
+        if let Some(lib) = &cfg.link_name { body.push_str(&format!("#[link(name = \"{}\")]\n", lib)); }
+        body.push_str("extern \"C\" {\n");
+        for f in &ir.functions {
+            let params: Vec<String> = f.params.iter().enumerate().map(|(i, p)| {
+                let name = if p.name.is_empty() { format!("arg{}", i) } else { rust_ident(&p.name) };
+                format!("{}: {}", name, types.ty(&p.ty, true))
+            }).collect();
+            let ret = parse_type_info(&f.ret);
+            let ret = if ret.base_type == "void" && ret.pointer_depth == 0 { String::new() } else { format!(" -> {}", types.ty(&ret, false)) };
+            body.push_str(&format!("    pub fn {}({}){};\n", rust_ident(&f.name), params.join(", "), ret));
+        }
+        body.push_str("}\n\n");
+    }
+
+    for (rust, spelling) in &types.opaque {
+        body.push_str(&format!(
+            "// warning: unknown C type `{}`; emitted as an opaque struct, use it only behind pointers\n#[repr(C)]\npub struct {} {{\n    _private: [u8; 0],\n}}\n\n",
+            spelling, rust,
+        ));
+    }
+
+    let mut out = String::from("// Generated by macrokid_clang_exec::emit_rust_ffi\n");
+    if !types.raw.is_empty() {
+        out.push_str(&format!("use std::os::raw::{{{}}};\n", types.raw.iter().copied().collect::<Vec<_>>().join(", ")));
+    }
+    out.push('\n');
+    out.push_str(body.trim_end());
+    out.push('\n');
+    out
+}
 
 /// Analyze a header as C and extract C-only IR (structs/enums/typedefs/functions).
 pub fn analyze_header_c<P: AsRef<Path>>(path: P, extra_args: &[&str]) -> Result<CHeaderIR, ClangExecError> {
+    analyze_header_c_with(path, &ClangOptions::from_raw(extra_args))
+}
+
+/// [`analyze_header_c`] with flags built from [`ClangOptions`].
+pub fn analyze_header_c_with<P: AsRef<Path>>(path: P, opts: &ClangOptions) -> Result<CHeaderIR, ClangExecError> {
+    let extra_args = opts.to_args();
     let path = path.as_ref();
     let lossy = path.to_string_lossy();
     let mut args = vec![
@@ -296,7 +930,7 @@ pub fn analyze_header_c<P: AsRef<Path>>(path: P, extra_args: &[&str]) -> Result<
         "-fsyntax-only",
         lossy.as_ref(),
     ];
-    args.extend(extra_args.iter().copied());
+    args.extend(extra_args.iter().map(String::as_str));
     let output = Command::new("clang")
         .args(&args)
         .output()
@@ -312,6 +946,11 @@ pub fn analyze_header_c<P: AsRef<Path>>(path: P, extra_args: &[&str]) -> Result<
 }
 
 fn collect_c_from_ast(v: &Value, ir: &mut CHeaderIR) {
+    collect_c_walk(v, ir, &mut HashMap::new());
+}
+
+// `anon` holds anonymous record definitions by AST id until a typedef names them
+fn collect_c_walk(v: &Value, ir: &mut CHeaderIR, anon: &mut HashMap<String, serde_json::Map<String, Value>>) {
     match v {
         Value::Object(map) => {
             if let Some(Value::String(kind)) = map.get("kind") {
@@ -319,13 +958,10 @@ fn collect_c_from_ast(v: &Value, ir: &mut CHeaderIR) {
                     "RecordDecl" => {
                         let name = map.get("name").and_then(|n| n.as_str()).unwrap_or("");
                         if !name.is_empty() {
-                            let tag = map.get("tagUsed").and_then(|t| t.as_str()).unwrap_or("struct");
-                            let is_union = tag == "union";
-                            let mut fields = Vec::new();
-                            if let Some(inner) = map.get("inner").and_then(|x| x.as_array()) {
-                                for node in inner { if let Some(f) = parse_c_field(node) { fields.push(f); } }
-                            }
-                            ir.structs.push(CStructIR { name: name.to_string(), is_union, fields });
+                            parse_c_record(map, name, None, &mut ir.structs);
+                        } else if let (Some(id), true) = (map.get("id").and_then(|i| i.as_str()), map.contains_key("inner")) {
+                            // Only definitions carry `inner`; bare `decl` references reuse the id
+                            anon.insert(id.to_string(), map.clone());
                         }
                     }
                     "EnumDecl" => {
@@ -348,7 +984,21 @@ fn collect_c_from_ast(v: &Value, ir: &mut CHeaderIR) {
                                 .and_then(|qt| qt.as_str())
                                 .or_else(|| map.get("underlyingType").and_then(|t| t.get("qualType")).and_then(|qt| qt.as_str()))
                                 .unwrap_or("");
-                            ir.typedefs.push(CTypedefIR { name: name.to_string(), underlying: underlying.to_string() });
+                            let mut underlying = underlying.to_string();
+                            // `typedef struct { ... } Name;`: the record is anonymous, so it is
+                            // emitted under the typedef's name and the typedef points at it
+                            if let Some(record) = map.get("inner").and_then(anonymous_record_ref) {
+                                let linked = match record.get("inner") {
+                                    Some(_) => Some(record.clone()),
+                                    None => record.get("id").and_then(|i| i.as_str()).and_then(|id| anon.remove(id)),
+                                };
+                                if let Some(def) = linked {
+                                    let tag = if is_union_record(&def) { "union" } else { "struct" };
+                                    underlying = format!("{} {}", tag, name);
+                                    parse_c_record(&def, name, None, &mut ir.structs);
+                                }
+                            }
+                            ir.typedefs.push(CTypedefIR { name: name.to_string(), underlying });
                         }
                     }
                     "FunctionDecl" => {
@@ -368,19 +1018,71 @@ fn collect_c_from_ast(v: &Value, ir: &mut CHeaderIR) {
                     _ => {}
                 }
             }
-            for (_k, val) in map { collect_c_from_ast(val, ir); }
+            for (_k, val) in map { collect_c_walk(val, ir, anon); }
         }
-        Value::Array(arr) => { for item in arr { collect_c_from_ast(item, ir); } }
+        Value::Array(arr) => { for item in arr { collect_c_walk(item, ir, anon); } }
         _ => {}
     }
 }
 
+fn is_union_record(map: &serde_json::Map<String, Value>) -> bool {
+    map.get("tagUsed").and_then(|t| t.as_str()) == Some("union")
+}
+
+/// Push the record `name` followed by the anonymous records defined inside it. clang lists a nested
+/// `union { ... }` definition just before the (possibly unnamed) FieldDecl of type
+/// `union (unnamed union at ...)`, so that field is pointed at the synthetic record instead.
+fn parse_c_record(map: &serde_json::Map<String, Value>, name: &str, parent: Option<&str>, out: &mut Vec<CStructIR>) {
+    let slot = out.len();
+    out.push(CStructIR { name: name.to_string(), is_union: is_union_record(map), fields: Vec::new(), parent: parent.map(str::to_string) });
+    let mut fields = Vec::new();
+    let mut last_anon: Option<String> = None;
+    let mut anon_count = 0;
+    for node in map.get("inner").and_then(|x| x.as_array()).into_iter().flatten() {
+        let Some(m) = node.as_object() else { continue };
+        let unnamed = m.get("name").and_then(|n| n.as_str()).map_or(true, str::is_empty);
+        if m.get("kind").and_then(|k| k.as_str()) == Some("RecordDecl") && unnamed && m.contains_key("inner") {
+            let nested = format!("{}::__anon{}", name, anon_count);
+            anon_count += 1;
+            parse_c_record(m, &nested, Some(name), out);
+            last_anon = Some(nested);
+        } else if let Some(mut f) = parse_c_field(node) {
+            let anon_at = f.type_name.find("(unnamed").or_else(|| f.type_name.find("(anonymous"));
+            if let (Some(nested), Some(open)) = (&last_anon, anon_at) {
+                // `union (unnamed union at v.h:3:5)[2]` -> `union Value::__anon0[2]`
+                let close = f.type_name[open..].find(')').map_or(f.type_name.len(), |c| open + c + 1);
+                f.type_name.replace_range(open..close, nested);
+                f.ty = parse_type_info(&f.type_name);
+            }
+            fields.push(f);
+        }
+    }
+    out[slot].fields = fields;
+}
+
+/// First unnamed `RecordDecl` under a typedef: either an inline definition or a
+/// `decl` / `ownedTagDecl` reference to one declared just before it.
+fn anonymous_record_ref(v: &Value) -> Option<&serde_json::Map<String, Value>> {
+    match v {
+        Value::Object(m) => {
+            let unnamed = m.get("name").and_then(|n| n.as_str()).map_or(true, str::is_empty);
+            if m.get("kind").and_then(|k| k.as_str()) == Some("RecordDecl") && unnamed {
+                return Some(m);
+            }
+            m.values().find_map(anonymous_record_ref)
+        }
+        Value::Array(arr) => arr.iter().find_map(anonymous_record_ref),
+        _ => None,
+    }
+}
+
 fn parse_c_field(node: &Value) -> Option<CFieldIR> {
     if let Value::Object(m) = node {
         if m.get("kind").and_then(|k| k.as_str()) == Some("FieldDecl") {
             let name = m.get("name").and_then(|n| n.as_str()).unwrap_or("").to_string();
             let type_name = m.get("type").and_then(|t| t.get("qualType")).and_then(|qt| qt.as_str()).unwrap_or("").to_string();
-            return Some(CFieldIR { name, type_name });
+            let ty = parse_type_info(&type_name);
+            return Some(CFieldIR { name, type_name, ty });
         }
     }
     None
@@ -400,12 +1102,13 @@ fn parse_c_enum_const(node: &Value) -> Option<(String, String)> {
     None
 }
 
-fn parse_c_param(node: &Value) -> Option<(String, String)> {
+fn parse_c_param(node: &Value) -> Option<CParamIR> {
     if let Value::Object(m) = node {
         if m.get("kind").and_then(|k| k.as_str()) == Some("ParmVarDecl") {
             let name = m.get("name").and_then(|n| n.as_str()).unwrap_or("").to_string();
             let type_name = m.get("type").and_then(|t| t.get("qualType")).and_then(|qt| qt.as_str()).unwrap_or("").to_string();
-            return Some((name, type_name));
+            let ty = parse_type_info(&type_name);
+            return Some(CParamIR { name, type_name, ty });
         }
     }
     None
@@ -416,6 +1119,12 @@ pub struct MacroIR { pub name: String, pub value: String }
 
 /// Extract preprocessor macros by invoking `clang -dM -E`.
 pub fn analyze_macros_c<P: AsRef<Path>>(header: P, extra_args: &[&str]) -> Result<Vec<MacroIR>, ClangExecError> {
+    analyze_macros_c_with(header, &ClangOptions::from_raw(extra_args))
+}
+
+/// [`analyze_macros_c`] with flags built from [`ClangOptions`].
+pub fn analyze_macros_c_with<P: AsRef<Path>>(header: P, opts: &ClangOptions) -> Result<Vec<MacroIR>, ClangExecError> {
+    let extra_args = opts.to_args();
     let header = header.as_ref();
     let lossy = header.to_string_lossy();
     let mut args = vec![
@@ -424,7 +1133,7 @@ pub fn analyze_macros_c<P: AsRef<Path>>(header: P, extra_args: &[&str]) -> Resul
         "-include", lossy.as_ref(),
         "/dev/null",
     ];
-    args.extend(extra_args.iter().copied());
+    args.extend(extra_args.iter().map(String::as_str));
     let output = Command::new("clang")
         .args(&args)
         .output()
@@ -445,3 +1154,378 @@ pub fn analyze_macros_c<P: AsRef<Path>>(header: P, extra_args: &[&str]) -> Resul
     }
     Ok(out)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AST: &str = r#"{"kind":"TranslationUnitDecl","inner":[{"kind":"RecordDecl","name":"Vertex","inner":[
+        {"kind":"FieldDecl","name":"pos","type":{"qualType":"float[3]"}}]}]}"#;
+
+    // Stands in for clang: answers `--version` and counts AST dumps
+    fn fake_clang<'a>(version: &'static str, dumps: &'a std::cell::Cell<u32>) -> impl FnMut(&[&str]) -> Result<Vec<u8>, ClangExecError> + 'a {
+        move |args: &[&str]| {
+            if args == ["--version"] { return Ok(version.as_bytes().to_vec()); }
+            dumps.set(dumps.get() + 1);
+            Ok(AST.as_bytes().to_vec())
+        }
+    }
+
+    #[test]
+    fn cached_analysis_skips_clang_when_unchanged() {
+        let dir = std::env::temp_dir().join(format!("mk_clang_cache_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let header = dir.join("vertex.h");
+        std::fs::write(&header, "struct Vertex { float pos[3]; };").unwrap();
+        let cache = dir.join("cache");
+        let dumps = std::cell::Cell::new(0);
+
+        let first = analyze_header_cached_via(&header, &["-DX=1"], &cache, &mut fake_clang("clang 17", &dumps)).unwrap();
+        assert_eq!(dumps.get(), 1);
+        let second = analyze_header_cached_via(&header, &["-DX=1"], &cache, &mut fake_clang("clang 17", &dumps)).unwrap();
+        assert_eq!(dumps.get(), 1, "unchanged inputs must be served from cache");
+        assert_eq!(second.structs[0].name, first.structs[0].name);
+        assert_eq!(second.structs[0].fields[0].type_name, "float[3]");
+
+        // Config, clang version and header contents all invalidate
+        analyze_header_cached_via(&header, &["-DX=2"], &cache, &mut fake_clang("clang 17", &dumps)).unwrap();
+        assert_eq!(dumps.get(), 2);
+        analyze_header_cached_via(&header, &["-DX=2"], &cache, &mut fake_clang("clang 18", &dumps)).unwrap();
+        assert_eq!(dumps.get(), 3);
+        std::fs::write(&header, "struct Vertex { float pos[3]; float uv[2]; };").unwrap();
+        analyze_header_cached_via(&header, &["-DX=2"], &cache, &mut fake_clang("clang 18", &dumps)).unwrap();
+        assert_eq!(dumps.get(), 4);
+        // Stale entries for the same header are replaced, not accumulated
+        assert_eq!(std::fs::read_dir(&cache).unwrap().count(), 1);
+
+        // The same header reached through another spelling of its path is still a hit
+        let dotted = dir.join(".").join("vertex.h");
+        analyze_header_cached_via(&dotted, &["-DX=2"], &cache, &mut fake_clang("clang 18", &dumps)).unwrap();
+        assert_eq!(dumps.get(), 4);
+
+        // A header with the same file name elsewhere gets its own entry and leaves this one alone
+        std::fs::create_dir_all(dir.join("other")).unwrap();
+        let other = dir.join("other").join("vertex.h");
+        std::fs::write(&other, "struct Vertex { float pos[3]; };").unwrap();
+        analyze_header_cached_via(&other, &["-DX=2"], &cache, &mut fake_clang("clang 18", &dumps)).unwrap();
+        assert_eq!(dumps.get(), 5);
+        assert_eq!(std::fs::read_dir(&cache).unwrap().count(), 2);
+        analyze_header_cached_via(&header, &["-DX=2"], &cache, &mut fake_clang("clang 18", &dumps)).unwrap();
+        analyze_header_cached_via(&other, &["-DX=2"], &cache, &mut fake_clang("clang 18", &dumps)).unwrap();
+        assert_eq!(dumps.get(), 5, "headers sharing a file name must not evict each other");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn unity_build_attributes_structs_to_headers() {
+        let dir = std::env::temp_dir().join(format!("mk_clang_unity_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let (a, b) = (dir.join("mesh.h"), dir.join("light.h"));
+        std::fs::write(&a, "struct Vertex { float pos[3]; }; struct Index { unsigned i; };").unwrap();
+        std::fs::write(&b, "struct Light { float dir[3]; };").unwrap();
+        let canon = |p: &Path| std::fs::canonicalize(p).unwrap().to_string_lossy().replace('\\', "/");
+        let (ca, cb) = (canon(&a), canon(&b));
+
+        // clang omits `file` when it matches the previous location, so `Index` inherits mesh.h
+        let ast = format!(r#"{{"kind":"TranslationUnitDecl","inner":[
+            {{"kind":"RecordDecl","name":"size_t_like","loc":{{"file":"/usr/include/stddef.h","line":1}}}},
+            {{"kind":"RecordDecl","name":"Vertex","loc":{{"file":"{ca}","line":1,"includedFrom":{{"file":"unity.c"}}}},"range":{{"begin":{{"line":1}},"end":{{"line":1}}}},
+              "inner":[{{"kind":"FieldDecl","name":"pos","loc":{{"line":1}},"type":{{"qualType":"float[3]"}}}}]}},
+            {{"kind":"RecordDecl","name":"Index","loc":{{"line":1}}}},
+            {{"kind":"RecordDecl","name":"Light","loc":{{"spellingLoc":{{"file":"{cb}","line":1}},"expansionLoc":{{"line":1}}}}}}]}}"#);
+        let mut invocations = 0;
+        let mut fake = |args: &[&str]| -> Result<Vec<u8>, ClangExecError> {
+            invocations += 1;
+            let unity = std::fs::read_to_string(args.iter().find(|a| a.ends_with(".c")).unwrap()).unwrap();
+            assert!(unity.contains(&format!("#include \"{}\"", ca)) && unity.contains(&format!("#include \"{}\"", cb)));
+            Ok(ast.clone().into_bytes())
+        };
+
+        let ir = analyze_headers_via(&[a.as_path(), b.as_path()], &[], &mut fake).unwrap();
+        assert_eq!(invocations, 1);
+        let by_file = ir.by_file();
+        let names = |f: &Path| by_file[f.to_string_lossy().as_ref()].iter().map(|s| s.name.as_str()).collect::<Vec<_>>();
+        assert_eq!(names(&a), vec!["Vertex", "Index"]);
+        assert_eq!(names(&b), vec!["Light"]);
+        assert_eq!(by_file.len(), 2, "decls from other files are dropped");
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn clang_options_translate_to_flags() {
+        let opts = ClangOptions::new()
+            .include("third_party/vk")
+            .include(PathBuf::from("include"))
+            .define("MK_DEBUG")
+            .define_value("MK_MAX_LIGHTS", "8")
+            .std("c11")
+            .target("aarch64-linux-gnu")
+            .arg("-Wno-everything");
+        assert_eq!(opts.to_args(), [
+            "-Ithird_party/vk", "-Iinclude", "-DMK_DEBUG", "-DMK_MAX_LIGHTS=8",
+            "-std=c11", "--target=aarch64-linux-gnu", "-Wno-everything",
+        ]);
+        // The `&[&str]` entry points pass raw arguments through untouched
+        assert_eq!(ClangOptions::from_raw(&["-I.", "-DX=1"]).to_args(), ["-I.", "-DX=1"]);
+    }
+
+    #[test]
+    fn mk_annotation_lists_and_bools() {
+        let a = parse_mk_annotation(r#"mk::vertex(stages=[vs, "fs"], location=1, format=vec3)"#).unwrap();
+        assert_eq!(a.kind, MkAnnotationKind::Vertex);
+        assert_eq!(a.values["stages"], MkValue::List(vec![MkValue::Str("vs".into()), MkValue::Str("fs".into())]));
+        assert_eq!(a.args["stages"], "vs,fs");
+        assert_eq!(a.values["location"].as_int(), Some(1));
+        assert_eq!(a.args["location"], "1");
+        // Scalars keep their spelling in `args`
+        assert_eq!(parse_mk_annotation("mk::vertex(location=+01)").unwrap().args["location"], "+01");
+        assert_eq!(a.values["format"].as_str(), Some("vec3"));
+        assert_eq!(parse_mk_annotation("mk::vertex(stages=[])").unwrap().values["stages"], MkValue::List(vec![]));
+        assert!(parse_mk_annotation("mk::vertex(stages=[vs, fs)").is_none(), "unterminated list");
+
+        let r = parse_mk_annotation(r#"mk::resource(readonly=true, name="true")"#).unwrap();
+        assert_eq!(r.values["readonly"].as_bool(), Some(true));
+        assert_eq!(r.args["readonly"], "true");
+        // Quoted values stay strings
+        assert_eq!(r.values["name"], MkValue::Str("true".into()));
+    }
+
+    #[test]
+    fn type_info_const_pointer() {
+        let t = parse_type_info("const int *");
+        assert!(t.is_const);
+        assert_eq!(t.pointer_depth, 1);
+        assert!(t.array_dims.is_empty());
+        assert_eq!(t.base_type, "int");
+        // Pointer-level const does not make the pointee const.
+        let t = parse_type_info("int *const");
+        assert!(!t.is_const);
+        assert_eq!(t.pointer_depth, 1);
+        assert_eq!(t.base_type, "int");
+    }
+
+    #[test]
+    fn type_info_array() {
+        let t = parse_type_info("char[16]");
+        assert!(!t.is_const);
+        assert_eq!(t.pointer_depth, 0);
+        assert_eq!(t.array_dims, vec![Some(16)]);
+        assert_eq!(t.base_type, "char");
+        assert_eq!(parse_type_info("float[2][3]").array_dims, vec![Some(2), Some(3)]);
+        assert_eq!(parse_type_info("int[]").array_dims, vec![None]);
+    }
+
+    #[test]
+    fn type_info_double_pointer() {
+        let t = parse_type_info("void **");
+        assert!(!t.is_const);
+        assert_eq!(t.pointer_depth, 2);
+        assert_eq!(t.base_type, "void");
+        assert_eq!(parse_type_info("const struct Foo *").base_type, "struct Foo");
+    }
+
+    #[test]
+    fn typedef_names_anonymous_struct() {
+        // Shape of clang's JSON for `typedef struct { int x; float y[2]; } Point;`
+        // plus an inline `typedef union { ... } Bits;`
+        let field = |id: &str, name: &str, qual: &str| serde_json::json!({ "id": id, "kind": "FieldDecl", "name": name, "type": { "qualType": qual } });
+        let ast = serde_json::json!({
+            "kind": "TranslationUnitDecl",
+            "inner": [
+                { "id": "0x10", "kind": "RecordDecl", "tagUsed": "struct", "completeDefinition": true,
+                  "inner": [field("0x11", "x", "int"), field("0x12", "y", "float[2]")] },
+                { "id": "0x20", "kind": "TypedefDecl", "name": "Point",
+                  "type": { "qualType": "struct Point" },
+                  "inner": [{ "id": "0x21", "kind": "ElaboratedType", "type": { "qualType": "struct Point" },
+                              "ownedTagDecl": { "id": "0x10", "kind": "RecordDecl", "name": "" },
+                              "inner": [{ "id": "0x22", "kind": "RecordType", "type": { "qualType": "Point" },
+                                          "decl": { "id": "0x10", "kind": "RecordDecl", "name": "" } }] }] },
+                { "id": "0x30", "kind": "TypedefDecl", "name": "Bits",
+                  "type": { "qualType": "union (unnamed at bits.h:1:9)" },
+                  "inner": [{ "id": "0x31", "kind": "RecordDecl", "tagUsed": "union",
+                              "inner": [field("0x32", "u", "unsigned int"), field("0x33", "f", "float")] }] },
+            ]
+        });
+        let mut ir = CHeaderIR::default();
+        collect_c_from_ast(&ast, &mut ir);
+        let point = ir.structs.iter().find(|s| s.name == "Point").expect("Point struct");
+        assert!(!point.is_union);
+        let fields: Vec<(&str, &str)> = point.fields.iter().map(|f| (f.name.as_str(), f.type_name.as_str())).collect();
+        assert_eq!(fields, vec![("x", "int"), ("y", "float[2]")]);
+        let bits = ir.structs.iter().find(|s| s.name == "Bits").expect("Bits union");
+        assert!(bits.is_union);
+        assert_eq!(bits.fields.len(), 2);
+        assert_eq!(ir.structs.len(), 2);
+        let underlying: Vec<(&str, &str)> = ir.typedefs.iter().map(|t| (t.name.as_str(), t.underlying.as_str())).collect();
+        assert_eq!(underlying, vec![("Point", "struct Point"), ("Bits", "union Bits")]);
+    }
+
+    #[test]
+    fn anonymous_nested_records_get_synthetic_names() {
+        // Shape of clang's JSON for
+        //   struct Value { int tag; union { int i; float f; }; struct { float x, y; } pos[2]; };
+        let field = |name: &str, qual: &str| serde_json::json!({ "kind": "FieldDecl", "name": name, "type": { "qualType": qual } });
+        let ast = serde_json::json!({
+            "kind": "TranslationUnitDecl",
+            "inner": [{ "id": "0x10", "kind": "RecordDecl", "name": "Value", "tagUsed": "struct", "completeDefinition": true, "inner": [
+                field("tag", "int"),
+                { "id": "0x11", "kind": "RecordDecl", "tagUsed": "union", "completeDefinition": true,
+                  "inner": [field("i", "int"), field("f", "float")] },
+                { "kind": "FieldDecl", "isImplicit": true, "type": { "qualType": "union (anonymous union at value.h:1:25)" } },
+                { "kind": "IndirectFieldDecl", "name": "i" },
+                { "id": "0x12", "kind": "RecordDecl", "tagUsed": "struct", "completeDefinition": true,
+                  "inner": [field("x", "float"), field("y", "float")] },
+                field("pos", "struct (unnamed struct at value.h:1:56)[2]"),
+            ] }]
+        });
+        let mut ir = CHeaderIR::default();
+        collect_c_from_ast(&ast, &mut ir);
+        let names: Vec<(&str, bool, Option<&str>)> = ir.structs.iter().map(|s| (s.name.as_str(), s.is_union, s.parent.as_deref())).collect();
+        assert_eq!(names, vec![
+            ("Value", false, None),
+            ("Value::__anon0", true, Some("Value")),
+            ("Value::__anon1", false, Some("Value")),
+        ]);
+        let value = &ir.structs[0];
+        let fields: Vec<(&str, &str)> = value.fields.iter().map(|f| (f.name.as_str(), f.type_name.as_str())).collect();
+        assert_eq!(fields, vec![("tag", "int"), ("", "union Value::__anon0"), ("pos", "struct Value::__anon1[2]")]);
+        assert_eq!(value.fields[2].ty.array_dims, vec![Some(2)]);
+        assert_eq!(value.fields[2].ty.base_type, "struct Value::__anon1");
+        assert_eq!(ir.structs[1].fields.len(), 2, "union members are captured");
+    }
+
+    #[test]
+    fn rust_ffi_golden() {
+        // typedef unsigned int Handle;
+        // typedef void (*LogFn)(const char *);
+        // enum Mode { MODE_FILL, MODE_LINE = 4, MODE_POINT };
+        // struct Vertex { float pos[3]; const char *label; struct Vertex *next; Handle h; };
+        // struct Texture;
+        // struct Stream { FILE file; int fd; };
+        // union Bits { unsigned u; float f; };
+        // int mk_draw(struct Vertex *verts, unsigned long count, enum Mode mode);
+        // const char *mk_name(const struct Texture *type);
+        // void mk_log(FILE *out, LogFn fn);
+        let field = |name: &str, qual: &str| CFieldIR { name: name.into(), type_name: qual.into(), ty: parse_type_info(qual) };
+        let param = |name: &str, qual: &str| CParamIR { name: name.into(), type_name: qual.into(), ty: parse_type_info(qual) };
+        let ir = CHeaderIR {
+            structs: vec![
+                CStructIR { name: "Vertex".into(), fields: vec![
+                    field("pos", "float[3]"), field("label", "const char *"), field("next", "struct Vertex *"), field("h", "Handle"),
+                ], ..Default::default() },
+                CStructIR { name: "Texture".into(), ..Default::default() },
+                CStructIR { name: "Stream".into(), fields: vec![field("file", "FILE"), field("fd", "int")], ..Default::default() },
+                CStructIR { name: "Bits".into(), is_union: true, fields: vec![field("u", "unsigned int"), field("f", "float")], ..Default::default() },
+            ],
+            enums: vec![CEnumIR { name: "Mode".into(), items: vec![
+                ("MODE_FILL".into(), String::new()), ("MODE_LINE".into(), "4".into()), ("MODE_POINT".into(), String::new()),
+            ] }],
+            typedefs: vec![
+                CTypedefIR { name: "Handle".into(), underlying: "unsigned int".into() },
+                CTypedefIR { name: "LogFn".into(), underlying: "void (*)(const char *)".into() },
+            ],
+            functions: vec![
+                CFunctionIR { name: "mk_draw".into(), ret: "int".into(), params: vec![
+                    param("verts", "struct Vertex *"), param("count", "unsigned long"), param("mode", "enum Mode"),
+                ] },
+                CFunctionIR { name: "mk_name".into(), ret: "const char *".into(), params: vec![param("type", "const struct Texture *")] },
+                CFunctionIR { name: "mk_log".into(), ret: "void".into(), params: vec![param("out", "FILE *"), param("fn", "LogFn")] },
+            ],
+        };
+        let golden = r#"// Generated by macrokid_clang_exec::emit_rust_ffi
+use std::os::raw::{c_char, c_int, c_uint, c_ulong};
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub struct Vertex {
+    pub pos: [f32; 3],
+    pub label: *const c_char,
+    pub next: *mut Vertex,
+    pub h: Handle,
+}
+
+#[repr(C)]
+pub struct Texture {
+    _private: [u8; 0],
+}
+
+#[repr(C)]
+pub struct Stream {
+    pub file: FILE,
+    pub fd: c_int,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy)]
+pub union Bits {
+    pub u: c_uint,
+    pub f: f32,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    MODE_FILL = 0,
+    MODE_LINE = 4,
+    MODE_POINT = 5,
+}
+
+pub type Handle = c_uint;
+
+pub type LogFn = Option<unsafe extern "C" fn(*const c_char)>;
+
+#[link(name = "mk")]
+extern "C" {
+    pub fn mk_draw(verts: *mut Vertex, count: c_ulong, mode: Mode) -> c_int;
+    pub fn mk_name(r#type: *const Texture) -> *const c_char;
+    pub fn mk_log(out: *mut FILE, r#fn: LogFn);
+}
+
+// warning: unknown C type `FILE`; emitted as an opaque struct, use it only behind pointers
+#[repr(C)]
+pub struct FILE {
+    _private: [u8; 0],
+}
+"#;
+        let cfg = RustEmitCfg { link_name: Some("mk".into()), ..Default::default() };
+        assert_eq!(emit_rust_ffi(&ir, &cfg), golden);
+
+        let consts = emit_rust_ffi(&CHeaderIR { enums: ir.enums.clone(), ..Default::default() }, &RustEmitCfg { enums_as_consts: true, ..Default::default() });
+        assert!(consts.contains("pub type Mode = c_int;\npub const MODE_FILL: Mode = 0;\npub const MODE_LINE: Mode = 4;\npub const MODE_POINT: Mode = 5;\n"), "{}", consts);
+
+        // enum Flags { FLAG_A = 1 << 2, FLAG_B, FLAG_C, FLAG_D = 1 };
+        let flags = CEnumIR { name: "Flags".into(), items: vec![
+            ("FLAG_A".into(), "1 << 2".into()), ("FLAG_B".into(), String::new()), ("FLAG_C".into(), String::new()), ("FLAG_D".into(), "1".into()),
+        ] };
+        let consts = emit_rust_ffi(&CHeaderIR { enums: vec![flags], ..Default::default() }, &RustEmitCfg { enums_as_consts: true, ..Default::default() });
+        assert!(consts.contains("pub const FLAG_A: Flags = 1 << 2;\npub const FLAG_B: Flags = (1 << 2) + 1;\npub const FLAG_C: Flags = (1 << 2) + 2;\npub const FLAG_D: Flags = 1;\n"), "{}", consts);
+    }
+
+    #[test]
+    fn dependency_graph_links_embedding_struct() {
+        let field = |name: &str, qual: &str| CFieldIR { name: name.into(), type_name: qual.into(), ty: parse_type_info(qual) };
+        let ir = CHeaderIR {
+            structs: vec![
+                CStructIR { name: "Vec3".into(), fields: vec![field("x", "float"), field("y", "float"), field("z", "float")], ..Default::default() },
+                CStructIR { name: "Transform".into(), fields: vec![
+                    field("position", "struct Vec3"),
+                    field("scale", "Vec3"),
+                    field("parent", "const struct Transform *"),
+                    field("handle", "Handle"),
+                ], ..Default::default() },
+            ],
+            typedefs: vec![CTypedefIR { name: "Handle".into(), underlying: "unsigned int".into() }],
+            ..Default::default()
+        };
+        let edges = type_dependency_graph(&ir);
+        let pairs: Vec<(&str, &str)> = edges.iter().map(|(a, b)| (a.as_str(), b.as_str())).collect();
+        assert_eq!(pairs, vec![("Transform", "Vec3"), ("Transform", "Transform"), ("Transform", "Handle")]);
+        let dot = to_dot(&edges);
+        assert!(dot.starts_with("digraph types {\n"));
+        assert!(dot.contains("  \"Transform\" -> \"Vec3\";\n"));
+    }
+}
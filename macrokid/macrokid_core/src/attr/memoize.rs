@@ -0,0 +1,139 @@
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{FnArg, ItemFn, ReturnType, Type};
+
+use crate::common::type_utils::{is_reference, type_has_lifetime};
+
+/// Memoize configuration
+#[derive(Clone, Debug, Default)]
+pub struct MemoizeConfig {
+    /// Bound the cache to this many entries, evicting the least recently used one.
+    /// `None` keeps every result for the lifetime of the program.
+    pub capacity: Option<usize>,
+}
+
+/// Generate a caching wrapper around a pure function.
+///
+/// All arguments together form the cache key (a tuple when there are several), so they must be
+/// owned `Hash + Eq + Clone` types; the return type must be `Clone`. The lock is released while
+/// the body runs, so recursive memoized functions work. Methods, generic/async functions and
+/// borrowed arguments are rejected because they cannot key a single `static` cache.
+pub fn expand_memoize(mut func: ItemFn, cfg: MemoizeConfig) -> syn::Result<TokenStream2> {
+    let sig = &func.sig;
+    if let Some(a) = &sig.asyncness {
+        return Err(syn::Error::new_spanned(a, "#[memoize] does not support async functions"));
+    }
+    if !sig.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(&sig.generics, "#[memoize] cannot cache generic functions: the cache is a single static"));
+    }
+    if cfg.capacity == Some(0) {
+        return Err(syn::Error::new(Span::call_site(), "#[memoize] capacity must be at least 1"));
+    }
+
+    let mut arg_names = Vec::new();
+    let mut arg_types: Vec<Type> = Vec::new();
+    let mut rebinds = Vec::new();
+    for (i, input) in func.sig.inputs.iter_mut().enumerate() {
+        let pt = match input {
+            FnArg::Receiver(r) => return Err(syn::Error::new_spanned(r, "#[memoize] cannot cache methods taking `self`")),
+            FnArg::Typed(pt) => pt,
+        };
+        if is_reference(&pt.ty).is_some() || type_has_lifetime(&pt.ty) {
+            return Err(syn::Error::new_spanned(&pt.ty, "#[memoize] arguments must be owned values usable as a cache key; borrowed types cannot be stored"));
+        }
+        // Bind each argument to a plain name so any pattern (e.g. tuple destructuring) works.
+        let name = format_ident!("__mk_memo_arg{}", i);
+        let pat = std::mem::replace(&mut *pt.pat, syn::parse_quote!(#name));
+        rebinds.push(quote! { let #pat = #name; });
+        arg_names.push(name);
+        arg_types.push((*pt.ty).clone());
+    }
+
+    let (key_ty, key_expr) = match arg_names.len() {
+        1 => { let (n, t) = (&arg_names[0], &arg_types[0]); (quote!(#t), quote!(::core::clone::Clone::clone(&#n))) }
+        _ => (quote!(( #( #arg_types, )* )), quote!(( #( ::core::clone::Clone::clone(&#arg_names), )* ))),
+    };
+    let ret_ty = match &func.sig.output {
+        ReturnType::Default => quote!(()),
+        ReturnType::Type(_, ty) => quote!(#ty),
+    };
+    let orig_block = &func.block;
+
+    let (cache_ty, lookup, store) = match cfg.capacity {
+        None => (
+            quote! { ::std::collections::HashMap<#key_ty, #ret_ty> },
+            quote! { __cache.get(&__key).cloned() },
+            quote! { __cache.insert(__key, ::core::clone::Clone::clone(&__ret)); },
+        ),
+        // Entries carry a last-use tick; eviction scans for the oldest, which is fine for the
+        // small capacities this is meant for.
+        Some(cap) => (
+            quote! { (::std::collections::HashMap<#key_ty, (#ret_ty, u64)>, u64) },
+            quote! {{
+                let (map, tick) = &mut *__cache;
+                *tick += 1;
+                map.get_mut(&__key).map(|e| { e.1 = *tick; ::core::clone::Clone::clone(&e.0) })
+            }},
+            quote! {
+                let (map, tick) = &mut *__cache;
+                if !map.contains_key(&__key) && map.len() >= #cap {
+                    let oldest = map.iter().min_by_key(|(_, e)| e.1).map(|(k, _)| ::core::clone::Clone::clone(k));
+                    if let Some(k) = oldest { map.remove(&k); }
+                }
+                *tick += 1;
+                map.insert(__key, (::core::clone::Clone::clone(&__ret), *tick));
+            },
+        ),
+    };
+
+    func.block = syn::parse_quote!({
+        static __MK_MEMO: ::std::sync::OnceLock<::std::sync::Mutex<#cache_ty>> = ::std::sync::OnceLock::new();
+        let __memo = __MK_MEMO.get_or_init(::core::default::Default::default);
+        let __key: #key_ty = #key_expr;
+        {
+            let mut __cache = __memo.lock().unwrap_or_else(|e| e.into_inner());
+            if let ::core::option::Option::Some(v) = #lookup { return v; }
+        }
+        let __ret: #ret_ty = (move || -> #ret_ty { #( #rebinds )* #orig_block })();
+        {
+            let mut __cache = __memo.lock().unwrap_or_else(|e| e.into_inner());
+            #store
+        }
+        __ret
+    });
+
+    Ok(quote!(#func))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn keys_on_tuple_of_args() {
+        let f: ItemFn = parse_quote! { fn area(w: u32, h: u32) -> u64 { (w as u64) * (h as u64) } };
+        let out = expand_memoize(f, MemoizeConfig::default()).unwrap().to_string();
+        assert!(out.contains("HashMap < (u32 , u32 ,) , u64 >"), "{}", out);
+        assert!(out.contains("fn area (__mk_memo_arg0 : u32 , __mk_memo_arg1 : u32)"));
+        assert!(out.contains("let w = __mk_memo_arg0 ;"));
+    }
+
+    #[test]
+    fn capacity_uses_lru_cache() {
+        let f: ItemFn = parse_quote! { fn square(x: i64) -> i64 { x * x } };
+        let out = expand_memoize(f, MemoizeConfig { capacity: Some(2) }).unwrap().to_string();
+        assert!(out.contains("HashMap < i64 , (i64 , u64) >"), "{}", out);
+        assert!(out.contains("map . len () >= 2usize"));
+    }
+
+    #[test]
+    fn rejects_self_and_references() {
+        let m: ItemFn = parse_quote! { fn get(&self, k: u32) -> u32 { k } };
+        assert!(expand_memoize(m, MemoizeConfig::default()).unwrap_err().to_string().contains("self"));
+        let r: ItemFn = parse_quote! { fn len(s: &str) -> usize { s.len() } };
+        assert!(expand_memoize(r, MemoizeConfig::default()).unwrap_err().to_string().contains("borrowed"));
+        let g: ItemFn = parse_quote! { fn id<T: Clone>(t: T) -> T { t } };
+        assert!(expand_memoize(g, MemoizeConfig::default()).unwrap_err().to_string().contains("generic"));
+    }
+}
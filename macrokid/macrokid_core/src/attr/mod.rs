@@ -1,2 +1,3 @@
 // Attribute macro helpers
-pub mod trace;
\ No newline at end of file
+pub mod trace;
+pub mod memoize;
@@ -15,6 +15,11 @@ pub struct TraceConfig {
     pub prefix: String,
     pub release: bool,
     pub logger: TraceLogger,
+    /// Only log calls that take longer than this many milliseconds.
+    pub min_ms: Option<u64>,
+    /// Only log one in this many calls (the 1st, N+1th, ...), counted with a relaxed atomic.
+    /// Combined with `min_ms`, a sampled call is still dropped if it was fast.
+    pub sample: Option<u64>,
 }
 
 impl Default for TraceConfig {
@@ -23,11 +28,18 @@ impl Default for TraceConfig {
             prefix: "[macrokid::trace]".to_string(),
             release: true,
             logger: TraceLogger::Eprintln,
+            min_ms: None,
+            sample: None,
         }
     }
 }
 
-/// Generate a tracing wrapper for a function using the provided config
+/// Generate a tracing wrapper for a function using the provided config.
+///
+/// Synchronous bodies run in a closure so `return` still reaches the log. For `async fn` the body
+/// becomes an `async move` block that is awaited in place: the timer starts when the returned
+/// future is first polled and the log fires after the body's last `.await`. The block's type is
+/// pinned to the declared return type so `?` converts errors as it would in the original body.
 pub fn expand_trace(mut func: ItemFn, cfg: TraceConfig) -> TokenStream2 {
     let name = func.sig.ident.to_string();
     let prefix = cfg.prefix;
@@ -36,32 +48,66 @@ pub fn expand_trace(mut func: ItemFn, cfg: TraceConfig) -> TokenStream2 {
     // Create unique variable names to avoid conflicts
     let start_var = format_ident!("__macrokid_trace_start_{}", func.sig.ident);
     let ret_var = format_ident!("__macrokid_trace_ret_{}", func.sig.ident);
+    let elapsed_var = format_ident!("__macrokid_trace_elapsed_{}", func.sig.ident);
 
     // Select logger
     let log_stmt = match cfg.logger {
-        TraceLogger::Eprintln => quote! { eprintln!("{} {} took {:?}", #prefix, #name, #start_var.elapsed()); },
+        TraceLogger::Eprintln => quote! { eprintln!("{} {} took {:?}", #prefix, #name, #elapsed_var); },
         TraceLogger::Log => quote! {
             #[cfg(feature = "log")]
-            log::trace!("{} {} took {:?}", #prefix, #name, #start_var.elapsed());
+            log::trace!("{} {} took {:?}", #prefix, #name, #elapsed_var);
             #[cfg(not(feature = "log"))]
-            eprintln!("{} {} took {:?}", #prefix, #name, #start_var.elapsed());
+            eprintln!("{} {} took {:?}", #prefix, #name, #elapsed_var);
         },
     };
 
+    // Sampling counts every call; the threshold is checked on the measured duration
+    let mut conditions = Vec::new();
+    if let Some(n) = cfg.sample.filter(|&n| n > 1) {
+        let n = n as usize;
+        conditions.push(quote! {{
+            static CALLS: ::std::sync::atomic::AtomicUsize = ::std::sync::atomic::AtomicUsize::new(0);
+            CALLS.fetch_add(1, ::std::sync::atomic::Ordering::Relaxed) % #n == 0
+        }});
+    }
+    if let Some(ms) = cfg.min_ms {
+        conditions.push(quote! { #elapsed_var > ::std::time::Duration::from_millis(#ms) });
+    }
+    let log_stmt = if conditions.is_empty() {
+        log_stmt
+    } else {
+        quote! { if #( #conditions )&&* { #log_stmt } }
+    };
+
     // Optionally gate in release builds
     let emit_stmt = if cfg.release {
-        log_stmt
+        quote! { let #elapsed_var = #start_var.elapsed(); #log_stmt }
     } else {
-        quote! { if cfg!(debug_assertions) { #log_stmt } }
+        quote! { if cfg!(debug_assertions) { let #elapsed_var = #start_var.elapsed(); #log_stmt } }
     };
 
     // Replace the function body with a timed wrapper
-    func.block = parse_quote!({
-        let #start_var = ::std::time::Instant::now();
-        let #ret_var = (|| #orig_block)();
-        #emit_stmt
-        #ret_var
-    });
+    func.block = if func.sig.asyncness.is_some() {
+        // `let x: impl Trait` is not allowed, so only spell out concrete return types
+        let ret_ty = match &func.sig.output {
+            syn::ReturnType::Default => quote! { : () },
+            syn::ReturnType::Type(_, ty) if !quote!(#ty).to_string().contains("impl ") => quote! { : #ty },
+            syn::ReturnType::Type(..) => quote! {},
+        };
+        parse_quote!({
+            let #start_var = ::std::time::Instant::now();
+            let #ret_var #ret_ty = async move #orig_block.await;
+            #emit_stmt
+            #ret_var
+        })
+    } else {
+        parse_quote!({
+            let #start_var = ::std::time::Instant::now();
+            let #ret_var = (|| #orig_block)();
+            #emit_stmt
+            #ret_var
+        })
+    };
 
     quote!(#func)
 }
@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 use syn::Attribute;
 
-use crate::common::attrs::{validate_attrs, AttrSpec as LowSpec, AttrType, AttrValue};
+use crate::common::attrs::{attr_value_spans, validate_attrs, AttrSpec as LowSpec, AttrType, AttrValue};
 use crate::ir::{FieldSpec, TypeSpec, VariantSpec};
 
 /// Typed wrapper around `validate_attrs` with a fluent builder API.
@@ -9,10 +9,14 @@ use crate::ir::{FieldSpec, TypeSpec, VariantSpec};
 pub struct AttrSchema {
     pub name: &'static str,
     pub specs: Vec<LowSpec>,
+    /// Keys still accepted but slated for removal, with a migration hint.
+    pub deprecated: Vec<(&'static str, &'static str)>,
+    /// Values filled in for optional keys the attribute leaves out.
+    pub defaults: Vec<(&'static str, AttrValue)>,
 }
 
 impl AttrSchema {
-    pub fn new(name: &'static str) -> Self { Self { name, specs: Vec::new() } }
+    pub fn new(name: &'static str) -> Self { Self { name, specs: Vec::new(), deprecated: Vec::new(), defaults: Vec::new() } }
 
     pub fn req_str(mut self, key: &'static str) -> Self { self.specs.push(LowSpec { key, required: true, ty: AttrType::Str }); self }
     pub fn req_bool(mut self, key: &'static str) -> Self { self.specs.push(LowSpec { key, required: true, ty: AttrType::Bool }); self }
@@ -23,20 +27,73 @@ impl AttrSchema {
     pub fn opt_bool(mut self, key: &'static str) -> Self { self.specs.push(LowSpec { key, required: false, ty: AttrType::Bool }); self }
     pub fn opt_int(mut self, key: &'static str) -> Self { self.specs.push(LowSpec { key, required: false, ty: AttrType::Int }); self }
     pub fn opt_float(mut self, key: &'static str) -> Self { self.specs.push(LowSpec { key, required: false, ty: AttrType::Float }); self }
+    /// Optional keys with a default: when the key (or the whole attribute) is absent, the parsed
+    /// attrs report `default` through the usual `get_*`/`try_get_*` accessors.
+    pub fn opt_str_default(self, key: &'static str, default: &str) -> Self { self.opt_str(key).with_default(key, AttrValue::Str(default.to_string())) }
+    pub fn opt_bool_default(self, key: &'static str, default: bool) -> Self { self.opt_bool(key).with_default(key, AttrValue::Bool(default)) }
+    pub fn opt_int_default(self, key: &'static str, default: i64) -> Self { self.opt_int(key).with_default(key, AttrValue::Int(default)) }
+
+    fn with_default(mut self, key: &'static str, value: AttrValue) -> Self { self.defaults.push((key, value)); self }
+
+    /// Key accepting either a bool or a string literal; read it back with `get_bool` or `get_str`.
+    pub fn opt_str_or_bool(mut self, key: &'static str) -> Self { self.specs.push(LowSpec { key, required: false, ty: AttrType::StrOrBool }); self }
+
+    /// Optional bool that may also be written bare: `#[x(flag)]` is `flag = true`.
+    pub fn opt_flag(mut self, key: &'static str) -> Self { self.specs.push(LowSpec { key, required: false, ty: AttrType::Flag }); self }
+
+    /// String key restricted to `allowed`; any other value is rejected at the literal with
+    /// "unknown <key> '<value>': expected A|B|C".
+    pub fn opt_enum(mut self, key: &'static str, allowed: &'static [&'static str]) -> Self { self.specs.push(LowSpec { key, required: false, ty: AttrType::Enum(allowed) }); self }
+
+    /// Key holding one level of nested pairs, e.g. `color = (src = "one", dst = "zero")`.
+    pub fn req_nested(mut self, key: &'static str, inner: &'static [LowSpec]) -> Self { self.specs.push(LowSpec { key, required: true, ty: AttrType::Nested(inner) }); self }
+    pub fn opt_nested(mut self, key: &'static str, inner: &'static [LowSpec]) -> Self { self.specs.push(LowSpec { key, required: false, ty: AttrType::Nested(inner) }); self }
+
+    /// Mark an already-declared key as deprecated. Its value is still honored; parsing records
+    /// a note carrying `note` (e.g. the replacement keys) for the derive to emit as a warning.
+    pub fn deprecate_key(mut self, key: &'static str, note: &'static str) -> Self { self.deprecated.push((key, note)); self }
 
     pub fn parse(&self, attrs: &[Attribute]) -> syn::Result<ParsedAttrs> {
-        let map = validate_attrs(attrs, self.name, &self.specs)?;
-        Ok(ParsedAttrs { map })
+        let mut map = validate_attrs(attrs, self.name, &self.specs)?;
+        let mut notes = Vec::new();
+        for &(key, note) in &self.deprecated {
+            if !map.contains_key(key) { continue; }
+            let span = attrs.iter()
+                .filter(|a| a.path().is_ident(self.name))
+                .find_map(|a| attr_value_spans(a, key))
+                .unwrap_or_else(proc_macro2::Span::call_site);
+            notes.push(DeprecationNote { key, message: format!("`{}` in #[{}] is deprecated: {}", key, self.name, note), span });
+        }
+        for (key, value) in &self.defaults {
+            map.entry(key.to_string()).or_insert_with(|| value.clone());
+        }
+        Ok(ParsedAttrs { map, notes })
     }
 }
 
+/// A deprecated key found while parsing, pointing at its value.
+#[derive(Clone, Debug)]
+pub struct DeprecationNote {
+    pub key: &'static str,
+    pub message: String,
+    pub span: proc_macro2::Span,
+}
+
 /// Result of parsing an attribute with a schema.
 #[derive(Clone, Debug)]
 pub struct ParsedAttrs {
     pub map: HashMap<String, AttrValue>,
+    /// Deprecated keys that were present; see `AttrSchema::deprecate_key`.
+    pub notes: Vec<DeprecationNote>,
 }
 
 impl ParsedAttrs {
+    /// Warnings for every deprecated key present (via `diag::deprecation_note`); splice into the
+    /// derive output.
+    pub fn deprecation_tokens(&self) -> proc_macro2::TokenStream {
+        self.notes.iter().map(|n| crate::common::diag::deprecation_note(n.span, &n.message)).collect()
+    }
+
     pub fn get_str(&self, k: &str) -> Option<&str> {
         if let Some(AttrValue::Str(s)) = self.map.get(k) {
             Some(s.as_str())
@@ -47,6 +104,8 @@ impl ParsedAttrs {
     pub fn get_bool(&self, k: &str) -> Option<bool> { match self.map.get(k) { Some(AttrValue::Bool(b)) => Some(*b), _ => None } }
     pub fn get_int(&self, k: &str) -> Option<i64> { match self.map.get(k) { Some(AttrValue::Int(i)) => Some(*i), _ => None } }
     pub fn get_float(&self, k: &str) -> Option<f64> { match self.map.get(k) { Some(AttrValue::Float(f)) => Some(*f), _ => None } }
+    /// Sub-map for a nested key, wrapped so the same getters apply.
+    pub fn get_nested(&self, k: &str) -> Option<ParsedAttrs> { match self.map.get(k) { Some(AttrValue::Nested(m)) => Some(ParsedAttrs { map: m.clone(), notes: Vec::new() }), _ => None } }
 
     pub fn try_get_str(&self, k: &str) -> syn::Result<&str> {
         match self.map.get(k) {
@@ -87,24 +146,28 @@ impl AttrSchemaSet {
     pub fn push(mut self, schema: AttrSchema) -> Self { self.entries.push(schema); self }
 
     /// Parse against the set. Returns None if none of the attributes are present.
-    /// Errors if more than one is present or if present but invalid.
+    /// Errors if more than one is present or if present but invalid. A conflict is
+    /// reported on the later attribute, with a second error pointing at the earlier one.
     pub fn parse(&self, attrs: &[Attribute]) -> syn::Result<Option<(String, ParsedAttrs)>> {
-        let mut found: Option<(String, ParsedAttrs)> = None;
+        let mut found: Option<(usize, &AttrSchema)> = None;
         for sch in &self.entries {
-            // Try parse; if missing and schema has required keys, validate_attrs would error.
-            // We want presence-only detection first.
-            if attrs.iter().any(|a| a.path().is_ident(sch.name)) {
-                let parsed = sch.parse(attrs)?;
-                if found.is_some() {
-                    return Err(syn::Error::new(proc_macro2::Span::call_site(), format!(
-                        "multiple mutually exclusive attributes present: '{}' and '{}'",
-                        found.as_ref().unwrap().0, sch.name
-                    )));
-                }
-                found = Some((sch.name.to_string(), parsed));
+            let Some(pos) = attrs.iter().position(|a| a.path().is_ident(sch.name)) else { continue };
+            if let Some((first_pos, first)) = found {
+                let (earlier, later) = if first_pos < pos { ((first_pos, first.name), (pos, sch.name)) } else { ((pos, sch.name), (first_pos, first.name)) };
+                let mut err = syn::Error::new_spanned(attrs[later.0].path(), format!(
+                    "multiple mutually exclusive attributes present: '{}' and '{}'",
+                    earlier.1, later.1
+                ));
+                err.combine(syn::Error::new_spanned(attrs[earlier.0].path(), format!("'{}' first specified here", earlier.1)));
+                return Err(err);
             }
+            found = Some((pos, sch));
+        }
+        // Only parse once the set is known to be unambiguous, so a conflict is not masked by key errors.
+        match found {
+            Some((_, sch)) => Ok(Some((sch.name.to_string(), sch.parse(attrs)?))),
+            None => Ok(None),
         }
-        Ok(found)
     }
 
     /// Require exactly one attribute from the set.
@@ -132,9 +195,10 @@ pub mod scope {
 }
 
 /// Macro sugar to build an AttrSchemaSet with required keys per attribute.
+/// Optional keys use the `opt_` prefixed type names (`opt_int`, `opt_str`, `opt_bool`, `opt_float`).
 /// Syntax:
 /// exclusive_schemas![
-///     uniform(set: int, binding: int),
+///     uniform(set: int, binding: int, push: opt_bool),
 ///     texture(set: int, binding: int),
 ///     sampler(set: int, binding: int),
 /// ]
@@ -155,6 +219,10 @@ macro_rules! exclusive_schemas {
     (@push $schema:ident, $k:ident, str) => { $schema.req_str(stringify!($k)) };
     (@push $schema:ident, $k:ident, bool) => { $schema.req_bool(stringify!($k)) };
     (@push $schema:ident, $k:ident, float) => { $schema.req_float(stringify!($k)) };
+    (@push $schema:ident, $k:ident, opt_int) => { $schema.opt_int(stringify!($k)) };
+    (@push $schema:ident, $k:ident, opt_str) => { $schema.opt_str(stringify!($k)) };
+    (@push $schema:ident, $k:ident, opt_bool) => { $schema.opt_bool(stringify!($k)) };
+    (@push $schema:ident, $k:ident, opt_float) => { $schema.opt_float(stringify!($k)) };
 }
 
 #[cfg(test)]
@@ -175,6 +243,23 @@ mod tests {
         assert_eq!(res.get_int("count"), Some(2));
     }
 
+    #[test]
+    fn deprecated_key_still_applies_and_records_note() {
+        let schema = AttrSchema::new("pipeline")
+            .opt_bool("depth")
+            .opt_bool("depth_test")
+            .deprecate_key("depth", "use depth_test/depth_write instead");
+        let attr: Attribute = parse_quote!(#[pipeline(depth = false)]);
+        let res = schema.parse(&[attr]).unwrap();
+        assert_eq!(res.get_bool("depth"), Some(false));
+        assert_eq!(res.notes.len(), 1);
+        assert_eq!(res.notes[0].message, "`depth` in #[pipeline] is deprecated: use depth_test/depth_write instead");
+        assert!(res.deprecation_tokens().to_string().contains("use depth_test/depth_write instead"));
+
+        let attr: Attribute = parse_quote!(#[pipeline(depth_test = true)]);
+        assert!(schema.parse(&[attr]).unwrap().notes.is_empty());
+    }
+
     #[test]
     fn schema_set_exclusive() {
         let s1 = AttrSchema::new("uniform").req_int("binding");
@@ -185,6 +270,21 @@ mod tests {
         assert!(matches!(out, Some((ref n, _)) if n == "texture"));
     }
 
+    #[test]
+    fn schema_set_conflict_names_both_keys() {
+        let set = exclusive_schemas![uniform(binding: int), texture(binding: int)];
+        let attrs: Vec<Attribute> = vec![
+            syn::parse_quote!(#[texture(binding = 1)]),
+            syn::parse_quote!(#[uniform(binding = 0)]),
+        ];
+        let err = set.parse(&attrs).unwrap_err();
+        let msgs: Vec<String> = err.into_iter().map(|e| e.to_string()).collect();
+        assert_eq!(msgs.len(), 2);
+        // Reported on the later attribute in source order, naming both.
+        assert_eq!(msgs[0], "multiple mutually exclusive attributes present: 'texture' and 'uniform'");
+        assert_eq!(msgs[1], "'texture' first specified here");
+    }
+
     #[test]
     fn parse_float_schema() {
         let schema = AttrSchema::new("primitive")
@@ -214,6 +314,88 @@ mod tests {
         assert_eq!(res3.get_float("scale"), Some(10.25));
     }
 
+    #[test]
+    fn parse_nested_blend_schema() {
+        static EQ: [LowSpec; 2] = [
+            LowSpec { key: "src", required: true, ty: AttrType::Str },
+            LowSpec { key: "dst", required: true, ty: AttrType::Str },
+        ];
+        let schema = AttrSchema::new("blend").req_nested("color", &EQ).opt_nested("alpha", &EQ).opt_bool("enable");
+        let attr: Attribute = parse_quote!(#[blend(enable = true, color = (src = "src_alpha", dst = "one_minus_src_alpha"))]);
+        let res = schema.parse(&[attr]).expect("parse should succeed");
+        let color = res.get_nested("color").expect("color group");
+        assert_eq!(color.get_str("src"), Some("src_alpha"));
+        assert_eq!(color.get_str("dst"), Some("one_minus_src_alpha"));
+        assert!(res.get_nested("alpha").is_none());
+        assert_eq!(res.get_bool("enable"), Some(true));
+
+        let too_deep: Attribute = parse_quote!(#[blend(color = (src = (x = "a"), dst = "zero"))]);
+        assert!(schema.parse(&[too_deep]).is_err());
+    }
+
+    #[test]
+    fn bare_key_only_for_flags() {
+        let schema = AttrSchema::new("pipeline").opt_flag("register").opt_bool("depth");
+        let bare: Attribute = parse_quote!(#[pipeline(register)]);
+        let explicit: Attribute = parse_quote!(#[pipeline(register = false)]);
+        assert_eq!(schema.parse(&[bare]).unwrap().get_bool("register"), Some(true));
+        assert_eq!(schema.parse(&[explicit]).unwrap().get_bool("register"), Some(false));
+        let bare_bool: Attribute = parse_quote!(#[pipeline(depth)]);
+        assert!(schema.parse(&[bare_bool]).is_err());
+    }
+
+    #[test]
+    fn parse_str_or_bool_key() {
+        let schema = AttrSchema::new("pipeline").opt_str_or_bool("blend");
+        let b: Attribute = parse_quote!(#[pipeline(blend = true)]);
+        let s: Attribute = parse_quote!(#[pipeline(blend = "additive")]);
+        let i: Attribute = parse_quote!(#[pipeline(blend = 1)]);
+        assert_eq!(schema.parse(&[b]).unwrap().get_bool("blend"), Some(true));
+        assert_eq!(schema.parse(&[s]).unwrap().get_str("blend"), Some("additive"));
+        assert!(schema.parse(&[i]).is_err());
+    }
+
+    #[test]
+    fn optional_keys_report_defaults() {
+        let schema = AttrSchema::new("window")
+            .opt_int_default("width", 1280)
+            .opt_str_default("title", "Untitled")
+            .opt_bool_default("vsync", true)
+            .opt_int("height");
+        let attr: Attribute = parse_quote!(#[window(height = 600)]);
+        let res = schema.parse(&[attr]).unwrap();
+        assert_eq!(res.get_int("width"), Some(1280));
+        assert_eq!(res.try_get_int("width").unwrap(), 1280);
+        assert_eq!(res.get_str("title"), Some("Untitled"));
+        assert!(res.try_get_bool("vsync").unwrap());
+        assert_eq!(res.get_int("height"), Some(600));
+
+        // Explicit values win; a missing attribute still yields the defaults
+        let attr: Attribute = parse_quote!(#[window(width = 800, vsync = false)]);
+        let res = schema.parse(&[attr]).unwrap();
+        assert_eq!((res.get_int("width"), res.get_bool("vsync")), (Some(800), Some(false)));
+        let res = schema.parse(&[]).unwrap();
+        assert_eq!(res.get_int("width"), Some(1280));
+        assert_eq!(res.get_int("height"), None);
+    }
+
+    #[test]
+    fn parse_enum_key() {
+        let schema = AttrSchema::new("pipeline").opt_enum("topology", &["TriangleList", "LineList", "PointList"]);
+        let ok: Attribute = parse_quote!(#[pipeline(topology = "LineList")]);
+        assert_eq!(schema.parse(&[ok]).unwrap().get_str("topology"), Some("LineList"));
+        let absent: Attribute = parse_quote!(#[pipeline()]);
+        assert_eq!(schema.parse(&[absent]).unwrap().get_str("topology"), None);
+
+        let typo: Attribute = parse_quote!(#[pipeline(topology = "LineLst")]);
+        assert_eq!(schema.parse(&[typo]).unwrap_err().to_string(),
+            "unknown topology 'LineLst': expected TriangleList|LineList|PointList (did you mean 'LineList'?)");
+        let other: Attribute = parse_quote!(#[pipeline(topology = "Quads")]);
+        assert_eq!(schema.parse(&[other]).unwrap_err().to_string(), "unknown topology 'Quads': expected TriangleList|LineList|PointList");
+        let not_str: Attribute = parse_quote!(#[pipeline(topology = 3)]);
+        assert!(schema.parse(&[not_str]).is_err());
+    }
+
     #[test]
     fn parse_float_required_missing() {
         let schema = AttrSchema::new("primitive").req_float("scale");
@@ -158,9 +158,58 @@ pub fn get_nested_attr_value(attrs: &[Attribute], attr_name: &str, key: &str) ->
     None
 }
 
+/// Span of the value written for `key` inside a list attribute, e.g. the `3` in
+/// `#[binding(set = 0, binding = 3)]`, so diagnostics can point at the exact value.
+///
+/// Re-parses the attribute's tokens and scans top-level `key = value` entries; the value runs up
+/// to the next top-level comma. For the `key(..)` form the span of the parenthesized group is
+/// returned, and for a bare `key` (a flag) the key's own span. Multi-token values are joined into one span where the compiler supports it,
+/// otherwise the span of their first token is used.
+pub fn attr_value_spans(attr: &Attribute, key: &str) -> Option<proc_macro2::Span> {
+    use proc_macro2::TokenTree;
+    let Meta::List(list) = &attr.meta else { return None };
+    let tokens: Vec<TokenTree> = list.tokens.clone().into_iter().collect();
+    let is_comma = |t: &TokenTree| matches!(t, TokenTree::Punct(p) if p.as_char() == ',');
+    let mut i = 0;
+    while i < tokens.len() {
+        let at_key = matches!(&tokens[i], TokenTree::Ident(id) if id == key);
+        match tokens.get(i + 1) {
+            Some(TokenTree::Punct(p)) if at_key && p.as_char() == '=' => {
+                let value: Vec<&TokenTree> = tokens[i + 2..].iter().take_while(|t| !is_comma(t)).collect();
+                let first = value.first()?.span();
+                let last = value.last()?.span();
+                return Some(first.join(last).unwrap_or(first));
+            }
+            Some(TokenTree::Group(g)) if at_key => return Some(g.span()),
+            None if at_key => return Some(tokens[i].span()),
+            Some(t) if at_key && is_comma(t) => return Some(tokens[i].span()),
+            _ => {}
+        }
+        // Skip to the start of the next entry
+        while i < tokens.len() && !is_comma(&tokens[i]) { i += 1; }
+        i += 1;
+    }
+    None
+}
+
 /// Types accepted by `validate_attrs` for keys
 #[derive(Debug, Clone, Copy)]
-pub enum AttrType { Str, Bool, Int, Float }
+pub enum AttrType {
+    Str,
+    Bool,
+    /// A bool that may also be written as a bare key: `#[x(flag)]` reads as `flag = true`.
+    Flag,
+    Int,
+    Float,
+    /// Either a string or a bool literal (`blend = true` / `blend = "additive"`); parses to
+    /// `AttrValue::Str` or `AttrValue::Bool` accordingly.
+    StrOrBool,
+    /// A string restricted to a fixed set of values; parses to `AttrValue::Str`.
+    Enum(&'static [&'static str]),
+    /// One level of nested key/value pairs, written `key = (a = 1, b = "x")` or `key(a = 1, b = "x")`,
+    /// validated against the inner specs (which may not nest further).
+    Nested(&'static [AttrSpec]),
+}
 
 /// Schema specification for a nested attribute key
 #[derive(Debug, Clone, Copy)]
@@ -177,6 +226,8 @@ pub enum AttrValue {
     Bool(bool),
     Int(i64),
     Float(f64),
+    /// Sub-map parsed from an `AttrType::Nested` key.
+    Nested(HashMap<String, AttrValue>),
 }
 
 /// Validate nested attributes like `#[name(key = "value", flag = true, count = 2)]` against a schema.
@@ -186,12 +237,6 @@ pub fn validate_attrs(
     attr_name: &str,
     schema: &[AttrSpec],
 ) -> syn::Result<HashMap<String, AttrValue>> {
-    // Build a lookup for schema keys
-    let mut spec_by_key: HashMap<&str, &AttrSpec> = HashMap::new();
-    for spec in schema {
-        spec_by_key.insert(spec.key, spec);
-    }
-
     // Find the attribute
     let attr = match attrs.iter().find(|a| a.path().is_ident(attr_name)) {
         Some(a) => a,
@@ -209,40 +254,7 @@ pub fn validate_attrs(
     };
 
     let mut out: HashMap<String, AttrValue> = HashMap::new();
-
-    attr.parse_nested_meta(|meta| {
-        let ident = meta.path.get_ident().ok_or_else(|| meta.error("expected identifier"))?;
-        let key = ident.to_string();
-        let spec = spec_by_key.get(key.as_str()).ok_or_else(|| meta.error("unknown key"))?;
-
-        if out.contains_key(&key) {
-            return Err(meta.error("duplicate key"));
-        }
-
-        let val = match spec.ty {
-            AttrType::Str => {
-                let v: syn::LitStr = meta.value()?.parse()?;
-                AttrValue::Str(v.value())
-            }
-            AttrType::Bool => {
-                let v: syn::LitBool = meta.value()?.parse()?;
-                AttrValue::Bool(v.value())
-            }
-            AttrType::Int => {
-                let v: syn::LitInt = meta.value()?.parse()?;
-                AttrValue::Int(v.base10_parse::<i64>()
-                    .map_err(|_| syn::Error::new(v.span(), "expected integer"))?)
-            }
-            AttrType::Float => {
-                let v: syn::LitFloat = meta.value()?.parse()?;
-                AttrValue::Float(v.base10_parse::<f64>()
-                    .map_err(|_| syn::Error::new(v.span(), "expected float"))?)
-            }
-        };
-
-        out.insert(key, val);
-        Ok(())
-    })?;
+    attr.parse_nested_meta(|meta| parse_schema_entry(meta, schema, &mut out, true))?;
 
     // Post-check required keys
     for spec in schema {
@@ -254,6 +266,87 @@ pub fn validate_attrs(
     Ok(out)
 }
 
+/// Parse one `key = value` entry against `schema` into `out`. `allow_nested` is false inside a nested
+/// group so that hierarchies stay one level deep.
+fn parse_schema_entry(
+    meta: syn::meta::ParseNestedMeta,
+    schema: &[AttrSpec],
+    out: &mut HashMap<String, AttrValue>,
+    allow_nested: bool,
+) -> syn::Result<()> {
+    let ident = meta.path.get_ident().ok_or_else(|| meta.error("expected identifier"))?;
+    let key = ident.to_string();
+    let spec = schema.iter().find(|s| s.key == key).ok_or_else(|| meta.error("unknown key"))?;
+
+    if out.contains_key(&key) {
+        return Err(meta.error("duplicate key"));
+    }
+
+    let val = match spec.ty {
+        AttrType::Str => {
+            let v: syn::LitStr = meta.value()?.parse()?;
+            AttrValue::Str(v.value())
+        }
+        AttrType::Flag if meta.input.is_empty() || meta.input.peek(syn::Token![,]) => AttrValue::Bool(true),
+        AttrType::Bool | AttrType::Flag => {
+            let v: syn::LitBool = meta.value()?.parse()?;
+            AttrValue::Bool(v.value())
+        }
+        AttrType::Int => {
+            let v: syn::LitInt = meta.value()?.parse()?;
+            AttrValue::Int(v.base10_parse::<i64>()
+                .map_err(|_| syn::Error::new(v.span(), "expected integer"))?)
+        }
+        AttrType::Float => {
+            let v: syn::LitFloat = meta.value()?.parse()?;
+            AttrValue::Float(v.base10_parse::<f64>()
+                .map_err(|_| syn::Error::new(v.span(), "expected float"))?)
+        }
+        AttrType::StrOrBool => match meta.value()?.parse::<syn::Lit>()? {
+            syn::Lit::Str(v) => AttrValue::Str(v.value()),
+            syn::Lit::Bool(v) => AttrValue::Bool(v.value()),
+            other => return Err(syn::Error::new(other.span(), "expected string or bool literal")),
+        },
+        AttrType::Enum(allowed) => {
+            let v: syn::LitStr = meta.value()?.parse()?;
+            let got = v.value();
+            if !allowed.contains(&got.as_str()) {
+                let mut msg = format!("unknown {} '{}': expected {}", key, got, allowed.join("|"));
+                if let Some(s) = crate::common::diag::did_you_mean(&got, allowed) {
+                    msg.push_str(&format!(" (did you mean '{}'?)", s));
+                }
+                return Err(syn::Error::new(v.span(), msg));
+            }
+            AttrValue::Str(got)
+        }
+        AttrType::Nested(inner) => {
+            if !allow_nested {
+                return Err(meta.error("attribute lists may only be nested one level deep"));
+            }
+            let mut sub: HashMap<String, AttrValue> = HashMap::new();
+            if meta.input.peek(syn::token::Paren) {
+                meta.parse_nested_meta(|m| parse_schema_entry(m, inner, &mut sub, false))?;
+            } else {
+                let input = meta.value()?;
+                let content;
+                syn::parenthesized!(content in input);
+                let tokens: proc_macro2::TokenStream = content.parse()?;
+                let parser = syn::meta::parser(|m| parse_schema_entry(m, inner, &mut sub, false));
+                syn::parse::Parser::parse2(parser, tokens)?;
+            }
+            for s in inner {
+                if s.required && !sub.contains_key(s.key) {
+                    return Err(syn::Error::new(ident.span(), format!("missing required key: {}.{}", key, s.key)));
+                }
+            }
+            AttrValue::Nested(sub)
+        }
+    };
+
+    out.insert(key, val);
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -289,6 +382,26 @@ mod tests {
         assert_eq!(attr_int_value(&[c], "count"), None);
     }
 
+    #[test]
+    fn test_attr_value_spans_cover_value() {
+        let attr: Attribute = syn::parse_str::<syn::DeriveInput>(
+            "#[uniform(set = 0, binding = 3, stages = \"vs\", size = 4 * 16)] struct S;",
+        ).unwrap().attrs.remove(0);
+        let span = attr_value_spans(&attr, "binding").expect("binding span");
+        assert_eq!(span.source_text().as_deref(), Some("3"));
+        let (start, end) = (span.start(), span.end());
+        assert_eq!((start.line, start.column, end.column), (1, 29, 30));
+        assert_eq!(attr_value_spans(&attr, "stages").and_then(|s| s.source_text()).as_deref(), Some("\"vs\""));
+        assert_eq!(attr_value_spans(&attr, "size").and_then(|s| s.source_text()).as_deref(), Some("4 * 16"));
+        assert!(attr_value_spans(&attr, "missing").is_none());
+        let flag: Attribute = syn::parse_str::<syn::DeriveInput>("#[pipeline(register, vs = \"a\")] struct P;").unwrap().attrs.remove(0);
+        assert_eq!(attr_value_spans(&flag, "register").and_then(|s| s.source_text()).as_deref(), Some("register"));
+        // Keys inside a nested group are not top-level entries
+        let nested: Attribute = syn::parse_str::<syn::DeriveInput>("#[pass(outputs(set = 1), set = 2)] struct P;").unwrap().attrs.remove(0);
+        assert_eq!(attr_value_spans(&nested, "set").and_then(|s| s.source_text()).as_deref(), Some("2"));
+        assert!(attr_value_spans(&nested, "outputs").is_some());
+    }
+
     #[test]
     fn test_validate_attrs_ok() {
         let attr: Attribute = parse_quote!(#[cfgx(name = "X", enabled = true, count = 2)]);
@@ -301,6 +414,49 @@ mod tests {
         assert!(matches!(map.get("name"), Some(AttrValue::Str(s)) if s == "X"));
         assert!(matches!(map.get("enabled"), Some(AttrValue::Bool(true))));
         assert!(matches!(map.get("count"), Some(AttrValue::Int(2))));
+
+        // Only `Flag` keys may be written bare, in any position
+        let bare: Attribute = parse_quote!(#[cfgx(name = "X", enabled)]);
+        assert!(validate_attrs(&[bare], "cfgx", &schema).is_err());
+        let flags = [AttrSpec { key: "name", required: true, ty: AttrType::Str }, AttrSpec { key: "enabled", required: false, ty: AttrType::Flag }];
+        for attr in [parse_quote!(#[cfgx(enabled, name = "X")]), parse_quote!(#[cfgx(name = "X", enabled)]), parse_quote!(#[cfgx(name = "X", enabled = true)])] {
+            let map = validate_attrs(&[attr], "cfgx", &flags).expect("bare flag");
+            assert!(matches!(map.get("enabled"), Some(AttrValue::Bool(true))));
+        }
+    }
+
+    static BLEND_EQ: [AttrSpec; 2] = [
+        AttrSpec { key: "src", required: true, ty: AttrType::Str },
+        AttrSpec { key: "dst", required: true, ty: AttrType::Str },
+    ];
+    static BLEND: [AttrSpec; 2] = [
+        AttrSpec { key: "color", required: true, ty: AttrType::Nested(&BLEND_EQ) },
+        AttrSpec { key: "alpha", required: false, ty: AttrType::Nested(&BLEND_EQ) },
+    ];
+
+    #[test]
+    fn test_validate_attrs_nested() {
+        let attr: Attribute = parse_quote!(#[blend(color = (src = "one", dst = "zero"), alpha(src = "one", dst = "one"))]);
+        let map = validate_attrs(&[attr], "blend", &BLEND).expect("valid nested attrs");
+        let Some(AttrValue::Nested(color)) = map.get("color") else { panic!("color not nested") };
+        assert!(matches!(color.get("src"), Some(AttrValue::Str(s)) if s == "one"));
+        assert!(matches!(color.get("dst"), Some(AttrValue::Str(s)) if s == "zero"));
+        let Some(AttrValue::Nested(alpha)) = map.get("alpha") else { panic!("alpha not nested") };
+        assert!(matches!(alpha.get("dst"), Some(AttrValue::Str(s)) if s == "one"));
+    }
+
+    #[test]
+    fn test_validate_attrs_nested_malformed() {
+        let not_a_group: Attribute = parse_quote!(#[blend(color = "one")]);
+        assert!(validate_attrs(&[not_a_group], "blend", &BLEND).is_err());
+
+        let missing_inner: Attribute = parse_quote!(#[blend(color = (src = "one"))]);
+        let msg = validate_attrs(&[missing_inner], "blend", &BLEND).unwrap_err().to_string();
+        assert!(msg.contains("missing required key: color.dst"), "{msg}");
+
+        let unknown_inner: Attribute = parse_quote!(#[blend(color = (src = "one", dst = "zero", op = "add"))]);
+        let msg = validate_attrs(&[unknown_inner], "blend", &BLEND).unwrap_err().to_string();
+        assert!(msg.contains("unknown key"), "{msg}");
     }
 
     #[test]
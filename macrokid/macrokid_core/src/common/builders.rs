@@ -11,6 +11,10 @@ pub struct ImplBuilder {
     assoc_types: Vec<TokenStream2>,
     assoc_consts: Vec<TokenStream2>,
     impl_attrs: Vec<TokenStream2>,
+    /// Signatures from `add_generic_method`, without visibility.
+    generic_methods: Vec<TokenStream2>,
+    /// Extra `where` predicates, emitted after the ones already on `generics`.
+    where_predicates: Vec<TokenStream2>,
 }
 
 impl ImplBuilder {
@@ -23,6 +27,8 @@ impl ImplBuilder {
             assoc_types: Vec::new(),
             assoc_consts: Vec::new(),
             impl_attrs: Vec::new(),
+            generic_methods: Vec::new(),
+            where_predicates: Vec::new(),
         }
     }
 
@@ -38,6 +44,29 @@ impl ImplBuilder {
         self
     }
 
+    /// Add a generic method assembled from typed parts, so the generics, where clause and
+    /// signature are always well-formed. `args` includes the receiver (e.g. `mut self`) and
+    /// `body` is the block contents. Emitted as `pub fn` in inherent impls and plain `fn` in
+    /// trait impls (decided when `build` runs).
+    pub fn add_generic_method(
+        mut self,
+        name: Ident,
+        generics: Generics,
+        args: impl IntoIterator<Item = syn::FnArg>,
+        ret: Option<syn::Type>,
+        body: TokenStream2,
+    ) -> Self {
+        let params = &generics.params;
+        let where_clause = &generics.where_clause;
+        let args: Vec<syn::FnArg> = args.into_iter().collect();
+        let ret = ret.map(|t| quote! { -> #t });
+        let generic_params = if params.is_empty() { quote! {} } else { quote! { <#params> } };
+        self.generic_methods.push(quote! {
+            fn #name #generic_params ( #( #args ),* ) #ret #where_clause { #body }
+        });
+        self
+    }
+
     /// Add an associated type declaration: `type Name = Ty;`
     pub fn add_assoc_type(mut self, name: Ident, ty: TokenStream2) -> Self {
         let item = quote! { type #name = #ty; };
@@ -52,6 +81,13 @@ impl ImplBuilder {
         self
     }
 
+    /// Add a `where` predicate (e.g. `T: Send`) to the impl, merged with any where clause the
+    /// generics already carry
+    pub fn add_where_predicate(mut self, pred: TokenStream2) -> Self {
+        self.where_predicates.push(pred);
+        self
+    }
+
     /// Attach a doc comment to the impl block
     pub fn with_docs(mut self, docs: &str) -> Self {
         let d = docs.to_string();
@@ -70,10 +106,17 @@ impl ImplBuilder {
     pub fn build(self) -> TokenStream2 {
         let target_type = &self.target_type;
         let (impl_generics, ty_generics, where_clause) = self.generics.split_for_impl();
+        let predicates: Vec<TokenStream2> = where_clause
+            .iter()
+            .flat_map(|w| w.predicates.iter().map(|p| quote! { #p }))
+            .chain(self.where_predicates.iter().cloned())
+            .collect();
+        let where_clause = if predicates.is_empty() { quote! {} } else { quote! { where #( #predicates ),* } };
         let methods = &self.methods;
         let assoc_types = &self.assoc_types;
         let assoc_consts = &self.assoc_consts;
         let impl_attrs = &self.impl_attrs;
+        let generic_methods = &self.generic_methods;
 
         if let Some(trait_name) = &self.trait_name {
             quote! {
@@ -82,6 +125,7 @@ impl ImplBuilder {
                     #( #assoc_types )*
                     #( #assoc_consts )*
                     #( #methods )*
+                    #( #generic_methods )*
                 }
             }
         } else {
@@ -91,6 +135,7 @@ impl ImplBuilder {
                     #( #assoc_types )*
                     #( #assoc_consts )*
                     #( #methods )*
+                    #( pub #generic_methods )*
                 }
             }
         }
@@ -163,3 +208,75 @@ impl Default for MatchArmBuilder {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn generic_method_round_trips_through_syn() {
+        let generics: Generics = parse_quote!(<V: Into<String>, I>);
+        let mut generics = generics;
+        generics.make_where_clause().predicates.push(parse_quote!(I: IntoIterator<Item = V>));
+        let out = ImplBuilder::new(parse_quote!(Config), Generics::default())
+            .add_generic_method(
+                parse_quote!(with_tags),
+                generics,
+                [parse_quote!(mut self), parse_quote!(tags: I)],
+                Some(parse_quote!(Self)),
+                quote! { self.tags.extend(tags.into_iter().map(Into::into)); self },
+            )
+            .build();
+
+        let item: syn::ItemImpl = syn::parse2(out).expect("valid impl block");
+        let syn::ImplItem::Fn(f) = &item.items[0] else { panic!("expected a method") };
+        assert!(matches!(f.vis, syn::Visibility::Public(_)));
+        assert_eq!(f.sig.ident, "with_tags");
+        assert_eq!(f.sig.generics.params.len(), 2);
+        assert_eq!(f.sig.generics.where_clause.as_ref().map(|w| w.predicates.len()), Some(1));
+        assert_eq!(f.sig.inputs.len(), 2);
+        assert!(matches!(f.sig.output, syn::ReturnType::Type(..)));
+    }
+
+    #[test]
+    fn where_predicates_merge_with_existing_clause() {
+        let mut generics: Generics = parse_quote!(<T, U>);
+        generics.make_where_clause().predicates.push(parse_quote!(U: core::fmt::Debug));
+        let out = ImplBuilder::new(parse_quote!(Pair), generics)
+            .implement_trait(quote! { Duplicate })
+            .add_where_predicate(quote! { T: Clone })
+            .add_method(quote! { fn duplicate(&self) -> Self { self.clone() } })
+            .build();
+        let item: syn::ItemImpl = syn::parse2(out.clone()).expect("valid impl block");
+        let preds: Vec<String> = item.generics.where_clause.unwrap().predicates.iter().map(|p| quote!(#p).to_string()).collect();
+        assert_eq!(preds, vec!["U : core :: fmt :: Debug", "T : Clone"]);
+
+        // Without generics the predicate still gets a where clause of its own
+        let out = ImplBuilder::new(parse_quote!(Plain), Generics::default()).add_where_predicate(quote! { String: Clone }).build();
+        assert!(out.to_string().contains("impl Plain where String : Clone {"), "{}", out);
+    }
+
+    #[test]
+    fn const_generics_and_lifetimes_split_for_impl() {
+        let item: syn::DeriveInput = parse_quote! {
+            struct Buf<'a, 'b: 'a, const N: usize, T: Clone = u8> where T: Default { head: &'a [T; N], tail: &'b [T] }
+        };
+        let out = ImplBuilder::new(item.ident, item.generics).implement_trait(quote! { Marker }).build();
+        assert_eq!(
+            out.to_string(),
+            "impl < 'a , 'b : 'a , const N : usize , T : Clone > Marker for Buf < 'a , 'b , N , T > where T : Default { }"
+        );
+    }
+
+    #[test]
+    fn generic_method_in_trait_impl_has_no_visibility() {
+        let out = ImplBuilder::new(parse_quote!(Config), Generics::default())
+            .implement_trait(quote! { Convert })
+            .add_generic_method(parse_quote!(convert), parse_quote!(<T: From<u32>>), [parse_quote!(&self)], Some(parse_quote!(T)), quote! { T::from(1) })
+            .build();
+        let item: syn::ItemImpl = syn::parse2(out).expect("valid impl block");
+        let syn::ImplItem::Fn(f) = &item.items[0] else { panic!("expected a method") };
+        assert!(matches!(f.vis, syn::Visibility::Inherited));
+    }
+}
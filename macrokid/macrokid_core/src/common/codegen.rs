@@ -35,6 +35,39 @@ pub fn static_slice_mod(
     (mod_ident, module)
 }
 
+/// Like `static_slice_mod`, for entries that can't be `const` (owned `String`s, `Vec`s, ...).
+/// Returns (module_ident, module_tokens).
+///
+/// The module exposes `pub fn data() -> &'static [Ty]`, backed by a `OnceLock<Vec<Ty>>` that
+/// evaluates the entries on first access; every later call returns the same slice.
+///
+/// ```ignore
+/// let ty = quote! { my_crate::Field };
+/// let entries = names.iter().map(|n| quote! { my_crate::Field { name: String::from(#n) } });
+/// let (mod_ident, module) = codegen::lazy_slice_mod("fields", ty.clone(), entries);
+/// let inherent = codegen::impl_inherent_methods(&spec, &[quote! {
+///     pub fn fields() -> &'static [#ty] { #mod_ident::data() }
+/// }]);
+/// ```
+pub fn lazy_slice_mod(
+    hint: &str,
+    item_ty: TokenStream2,
+    items: impl IntoIterator<Item = TokenStream2>,
+) -> (Ident, TokenStream2) {
+    let mod_ident = Ident::new(&format!("__mk_{hint}"), Span::call_site());
+    let data_items: Vec<TokenStream2> = items.into_iter().collect();
+    let module = quote! {
+        #[allow(non_snake_case, non_upper_case_globals)]
+        mod #mod_ident {
+            pub fn data() -> &'static [#item_ty] {
+                static DATA: ::std::sync::OnceLock<::std::vec::Vec<#item_ty>> = ::std::sync::OnceLock::new();
+                DATA.get_or_init(|| ::std::vec![ #( #data_items ),* ])
+            }
+        }
+    };
+    (mod_ident, module)
+}
+
 /// Implement a trait method that returns the static slice in `mod_ident::DATA`.
 ///
 /// This stitches the generated module from `static_slice_mod` into a trait impl,
@@ -1,7 +1,7 @@
 use std::collections::HashSet;
 use std::hash::Hash;
 
-use crate::ir::{FieldKind, FieldSpec, StructSpec};
+use crate::ir::{EnumSpec, FieldKind, FieldSpec, StructSpec, VariantSpec};
 
 /// Process only named fields of a struct, applying the mapper.
 /// - mapper returns Ok(Some(T)) to include an item, Ok(None) to skip the field.
@@ -24,6 +24,63 @@ where
     }
 }
 
+/// Process every variant of an enum, applying the mapper.
+/// - mapper returns Ok(Some(T)) to include an item, Ok(None) to skip the variant.
+pub fn from_variants<T, F>(en: &EnumSpec, mut mapper: F) -> syn::Result<Vec<T>>
+where
+    F: FnMut(&VariantSpec) -> syn::Result<Option<T>>,
+{
+    let mut out = Vec::new();
+    for v in &en.variants {
+        if let Some(t) = mapper(v)? { out.push(t); }
+    }
+    Ok(out)
+}
+
+/// Discriminant of each variant as (base expression, offset). Integer literals fold into the
+/// offset (`A = 4, B` -> (None, 4), (None, 5)); other expressions become the base
+/// (`C = X, D` -> (Some("X"), 0), (Some("X"), 1)).
+fn discriminant_keys(en: &EnumSpec) -> Vec<(Option<String>, i128)> {
+    let mut next: (Option<String>, i128) = (None, 0);
+    en.variants.iter().map(|v| {
+        if let Some(expr) = &v.discriminant {
+            next = match literal_discriminant(expr) {
+                Some(n) => (None, n),
+                None => (Some(quote::quote!(#expr).to_string()), 0),
+            };
+        }
+        let key = next.clone();
+        next.1 += 1;
+        key
+    }).collect()
+}
+
+fn literal_discriminant(expr: &syn::Expr) -> Option<i128> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Int(i), .. }) => i.base10_parse().ok(),
+        syn::Expr::Unary(syn::ExprUnary { op: syn::UnOp::Neg(_), expr, .. }) => literal_discriminant(expr).map(|n| -n),
+        syn::Expr::Group(g) => literal_discriminant(&g.expr),
+        syn::Expr::Paren(p) => literal_discriminant(&p.expr),
+        _ => None,
+    }
+}
+
+/// `unique_by` keyed on the discriminant of the variant each item came from (named by
+/// `variant_of`), so two items for the same variant, or for variants sharing a value, are rejected.
+pub fn unique_by_discriminant<T, KF>(items: Vec<T>, en: &EnumSpec, mut variant_of: KF, msg: &str) -> syn::Result<Vec<T>>
+where
+    KF: FnMut(&T) -> (&proc_macro2::Ident, proc_macro2::Span),
+{
+    let keys = discriminant_keys(en);
+    unique_by(items, |item| {
+        let (ident, span) = variant_of(item);
+        let pos = en.variants.iter().position(|v| v.ident == *ident);
+        // Unknown variants key on their name so they never collide with a real discriminant
+        let key = pos.map_or_else(|| (Some(format!("?{}", ident)), 0), |i| keys[i].clone());
+        (key, span)
+    }, msg)
+}
+
 /// Ensure uniqueness by key; returns error with provided message if duplicate is found.
 pub fn unique_by<T, K, KF>(items: Vec<T>, mut keyf: KF, msg: &str) -> syn::Result<Vec<T>>
 where
@@ -47,3 +104,56 @@ pub fn require_non_empty<T>(items: Vec<T>, msg: &str) -> syn::Result<Vec<T>> {
     }
     Ok(items)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{TypeKind, TypeSpec};
+
+    fn enum_spec(input: syn::DeriveInput) -> EnumSpec {
+        match TypeSpec::from_derive_input(input).unwrap().kind {
+            TypeKind::Enum(en) => en,
+            _ => panic!("expected enum"),
+        }
+    }
+
+    #[derive(Clone)]
+    struct Record { variant: proc_macro2::Ident, label: String, span: proc_macro2::Span }
+
+    // One record per variant carrying `#[marker = "..."]`
+    fn marked(v: &VariantSpec) -> syn::Result<Option<Record>> {
+        let Some(attr) = v.attrs.iter().find(|a| a.path().is_ident("marker")) else { return Ok(None) };
+        let syn::Meta::NameValue(nv) = &attr.meta else { return Err(syn::Error::new_spanned(attr, "expected #[marker = \"..\"]")) };
+        let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }) = &nv.value else { return Err(syn::Error::new_spanned(&nv.value, "expected a string")) };
+        Ok(Some(Record { variant: v.ident.clone(), label: s.value(), span: v.span }))
+    }
+
+    #[test]
+    fn from_variants_collects_marked_variants() {
+        let en = enum_spec(syn::parse_quote! {
+            enum Pass { #[marker = "shadow"] Shadow, Gbuffer(u32), #[marker = "light"] Lighting { tiles: u32 } }
+        });
+        let records = from_variants(&en, marked).unwrap();
+        let got: Vec<(String, &str)> = records.iter().map(|r| (r.variant.to_string(), r.label.as_str())).collect();
+        assert_eq!(got, vec![("Shadow".into(), "shadow"), ("Lighting".into(), "light")]);
+
+        let bad = enum_spec(syn::parse_quote! { enum Pass { #[marker(x)] Shadow } });
+        assert_eq!(from_variants(&bad, marked).err().unwrap().to_string(), "expected #[marker = \"..\"]");
+    }
+
+    #[test]
+    fn unique_by_discriminant_catches_shared_values() {
+        let en = enum_spec(syn::parse_quote! {
+            enum Op { #[marker = "a"] A = 2, #[marker = "b"] B, #[marker = "c"] C = 3, #[marker = "d"] D = K, #[marker = "e"] E }
+        });
+        let records = from_variants(&en, marked).unwrap();
+        let only = |names: &[&str]| records.iter().filter(|r| names.contains(&r.variant.to_string().as_str()))
+            .cloned().collect::<Vec<_>>();
+
+        // B is implicitly 3, like C
+        let err = unique_by_discriminant(only(&["A", "B", "C"]), &en, |r| (&r.variant, r.span), "duplicate discriminant").err().unwrap();
+        assert_eq!(err.to_string(), "duplicate discriminant");
+        // D = K and E = K + 1 never equal a literal value
+        assert_eq!(unique_by_discriminant(only(&["A", "C", "D", "E"]), &en, |r| (&r.variant, r.span), "dup").unwrap().len(), 4);
+    }
+}
@@ -18,6 +18,72 @@ pub fn err_at_span(span: Span, msg: &str) -> SynError {
     SynError::new(span, msg)
 }
 
+/// Standard "unknown value" error naming the attribute `key` and listing the accepted values,
+/// with a did-you-mean hint when `got` is close to one of them.
+pub fn expected_one_of(span: Span, key: &str, got: &str, expected: &[&str]) -> SynError {
+    let mut msg = format!("unknown value '{}' for `{}`: expected one of {}", got, key, expected.join("|"));
+    if let Some(s) = did_you_mean(got, expected) {
+        msg.push_str(&format!(" (did you mean '{}'?)", s));
+    }
+    SynError::new(span, msg)
+}
+
+/// Pick the closest candidate to `got`: a case-insensitive match, or the smallest edit
+/// distance within roughly a third of the input length.
+pub fn did_you_mean<'a>(got: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    if let Some(c) = candidates.iter().find(|c| c.eq_ignore_ascii_case(got)) {
+        return Some(c);
+    }
+    let max = (got.chars().count() / 3).max(1);
+    candidates
+        .iter()
+        .map(|c| (edit_distance(&got.to_ascii_lowercase(), &c.to_ascii_lowercase()), *c))
+        .filter(|(d, _)| *d <= max)
+        .min_by_key(|(d, _)| *d)
+        .map(|(_, c)| c)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut cur = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let sub = prev[j] + usize::from(ca != *cb);
+            cur[j + 1] = sub.min(prev[j + 1] + 1).min(cur[j] + 1);
+        }
+        prev = cur;
+    }
+    prev[b.len()]
+}
+
+/// Warning for stable proc macros, which cannot emit notes directly: expands to a use of a
+/// local `#[deprecated]` item spanned at `span`, so rustc reports `note` as a deprecation warning.
+pub fn deprecation_note(span: Span, note: &str) -> proc_macro2::TokenStream {
+    let marker = proc_macro2::Ident::new("__mk_deprecated", span);
+    quote::quote_spanned! {span=>
+        const _: () = {
+            #[deprecated(note = #note)]
+            #[allow(non_camel_case_types)]
+            struct #marker;
+            let _ = #marker;
+        };
+    }
+}
+
+/// Non-fatal warning at `span`; splice the returned tokens into the macro output.
+///
+/// With the `nightly` feature, inside a proc-macro invocation, this emits a real warning via
+/// `proc_macro::Diagnostic` and returns no tokens. Otherwise it falls back to `deprecation_note`.
+pub fn warn_at_span(span: Span, msg: &str) -> proc_macro2::TokenStream {
+    #[cfg(feature = "nightly")]
+    if proc_macro::is_available() {
+        span.unwrap().warning(msg).emit();
+        return proc_macro2::TokenStream::new();
+    }
+    deprecation_note(span, msg)
+}
+
 /// Collector that aggregates multiple syn::Error values and returns a single error.
 #[derive(Default)]
 pub struct Collector {
@@ -58,4 +124,24 @@ mod tests {
         assert!(msg.contains("bad"));
         assert!(msg.contains("try something else"));
     }
+
+    #[test]
+    fn test_expected_one_of_message_and_suggestion() {
+        let opts = ["TriangleList", "LineList", "PointList"];
+        let err = expected_one_of(Span::call_site(), "topology", "TriangleLst", &opts);
+        assert_eq!(
+            err.to_string(),
+            "unknown value 'TriangleLst' for `topology`: expected one of TriangleList|LineList|PointList (did you mean 'TriangleList'?)"
+        );
+        let err = expected_one_of(Span::call_site(), "topology", "Quads", &opts);
+        assert_eq!(err.to_string(), "unknown value 'Quads' for `topology`: expected one of TriangleList|LineList|PointList");
+        assert_eq!(did_you_mean("linelist", &opts), Some("LineList"));
+    }
+
+    #[test]
+    fn test_warn_at_span_falls_back_outside_proc_macro() {
+        let out = warn_at_span(Span::call_site(), "field is skipped").to_string();
+        assert!(out.contains("deprecated") && out.contains("\"field is skipped\""), "{out}");
+        assert!(syn::parse2::<syn::ItemConst>(warn_at_span(Span::call_site(), "x")).is_ok());
+    }
 }
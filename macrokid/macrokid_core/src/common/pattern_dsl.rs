@@ -12,6 +12,8 @@ pub enum PatternSpec {
     Struct { path: Path, fields: StructFields },
     Tuple { path: Path, elements: Vec<PatternSpec> },
     Or(Vec<PatternSpec>),
+    /// `name` on its own, or `name @ sub` when `sub` is set.
+    Binding { name: Ident, sub: Option<Box<PatternSpec>> },
     Guarded { base: Box<PatternSpec>, guard: Expr },
 }
 
@@ -29,6 +31,11 @@ impl PatternSpec {
         }
     }
 
+    /// Bind the value matched by `self` to `name` (`name @ self`).
+    pub fn bind(self, name: Ident) -> PatternSpec {
+        PatternSpec::Binding { name, sub: Some(Box::new(self)) }
+    }
+
     pub fn with_guard(self, guard: Expr) -> PatternSpec {
         PatternSpec::Guarded { base: Box::new(self), guard }
     }
@@ -63,6 +70,15 @@ impl PatternSpec {
                 let parts = parts.into_iter().map(|p| p.into_tokens());
                 quote! { #( #parts )|* }
             }
+            PatternSpec::Binding { name, sub: None } => quote! { #name },
+            PatternSpec::Binding { name, sub: Some(sub) } => {
+                // `x @ A | B` would bind only in the first alternative
+                let sub_ts = match *sub {
+                    PatternSpec::Or(_) => { let ts = sub.into_tokens(); quote! { (#ts) } }
+                    other => other.into_tokens(),
+                };
+                quote! { #name @ #sub_ts }
+            }
             PatternSpec::Guarded { base, guard } => {
                 let base_ts = base.into_tokens();
                 quote! { #base_ts if #guard }
@@ -141,4 +157,34 @@ mod tests {
         // Ensure parses as a pattern via match arm context.
         let _parsed: syn::Pat = parse_via_match_arm(ts);
     }
+
+    #[test]
+    fn or_pattern_match_arm() {
+        let pat = PatternSpec::Path(syn::parse_quote!(Self::A)).or(PatternSpec::Path(syn::parse_quote!(Self::B)));
+        let ts = pat.into_tokens();
+        let arm: syn::Arm = syn::parse2(quote! { #ts => "ab", }).expect("match arm");
+        assert_eq!(quote!(#arm).to_string(), "Self :: A | Self :: B => \"ab\" ,");
+        assert!(matches!(arm.pat, syn::Pat::Or(ref o) if o.cases.len() == 2));
+    }
+
+    #[test]
+    fn binding_tuple_field() {
+        let ident = |s: &str| syn::Ident::new(s, proc_macro2::Span::call_site());
+        let pat = PatternSpec::Tuple {
+            path: syn::parse_quote!(Self::Pair),
+            elements: vec![
+                PatternSpec::Binding { name: ident("first"), sub: None },
+                PatternSpec::Literal(syn::parse_quote!(1)).or(PatternSpec::Literal(syn::parse_quote!(2))).bind(ident("second")),
+            ],
+        };
+        let ts = pat.into_tokens();
+        assert_eq!(ts.to_string(), "Self :: Pair (first , second @ (1 | 2))");
+        match parse_via_match_arm(ts) {
+            syn::Pat::TupleStruct(t) => {
+                assert!(matches!(&t.elems[0], syn::Pat::Ident(p) if p.ident == "first" && p.subpat.is_none()));
+                assert!(matches!(&t.elems[1], syn::Pat::Ident(p) if p.ident == "second" && p.subpat.is_some()));
+            }
+            other => panic!("expected Pat::TupleStruct, got {:?}", other),
+        }
+    }
 }
@@ -19,6 +19,24 @@ where
     builder
 }
 
+/// Like `match_variants`, but the mapper returns (pattern, optional guard, body) so arms can be
+/// conditional (`pat if guard => body`). A guarded arm does not make the match exhaustive; add a
+/// fallback arm for the same variant or a wildcard.
+pub fn match_variants_guarded<F>(en: &EnumSpec, mut mapper: F) -> MatchArmBuilder
+where
+    F: FnMut(&VariantSpec) -> (TokenStream2, Option<TokenStream2>, TokenStream2),
+{
+    let mut builder = MatchArmBuilder::new();
+    for v in &en.variants {
+        let (pat, guard, body) = mapper(v);
+        builder = match guard {
+            Some(guard) => builder.add_guarded_arm(pat, guard, body),
+            None => builder.add_arm(pat, body),
+        };
+    }
+    builder
+}
+
 /// Build a MatchArmBuilder with arms derived from struct/tuple fields.
 /// The mapper returns an optional (pattern, body) per field; `None` skips the field.
 pub fn match_fields<F>(fields: &FieldKind, mut mapper: F) -> MatchArmBuilder
@@ -54,3 +72,33 @@ pub fn suggest_wildcard_if_non_exhaustive(
     }
     builder
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::{TypeKind, TypeSpec};
+
+    #[test]
+    fn guarded_variant_arms() {
+        let spec = TypeSpec::from_derive_input(syn::parse_quote! {
+            enum Shape { Circle(f32), Square(f32), Point }
+        }).unwrap();
+        let TypeKind::Enum(en) = &spec.kind else { panic!("expected enum") };
+        let arms = match_variants_guarded(en, |v| {
+            let ident = &v.ident;
+            match &v.fields {
+                FieldKind::Unit => (quote! { Self::#ident }, None, quote! { "point" }),
+                _ => (quote! { Self::#ident(size) }, Some(quote! { *size > 1.0 }), quote! { "large" }),
+            }
+        })
+        .add_wildcard(quote! { "small" })
+        .build_match(quote! { self });
+
+        let text = arms.to_string();
+        assert!(text.contains("Self :: Circle (size) if * size > 1.0 => \"large\""), "{}", text);
+        assert!(text.contains("Self :: Point => \"point\""), "{}", text);
+        assert_eq!(text.matches(" if ").count(), 2);
+        let expr: syn::ExprMatch = syn::parse2(arms).expect("valid match");
+        assert_eq!(expr.arms.iter().filter(|a| a.guard.is_some()).count(), 2);
+    }
+}
@@ -9,18 +9,49 @@
 //! (e.g., `macrokid_threads`) without breaking users. The API here focuses on
 //! closures as jobs; more advanced traits can layer above.
 
+use std::panic::{self, AssertUnwindSafe};
 use std::sync::{mpsc, Arc, Mutex, Condvar};
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
 use std::thread::{self, JoinHandle};
-use std::any::TypeId;
+use std::collections::BinaryHeap;
+use std::time::Duration;
+use std::any::{Any, TypeId};
+use std::marker::PhantomData;
 
 /// A unit of work. Implemented as a boxed `FnOnce()` for ergonomics.
 pub type Job = Box<dyn FnOnce() + Send + 'static>;
 
+/// A job that may borrow data living at least `'a`; see `ThreadPool::scope`.
+pub type ScopedJob<'a> = Box<dyn FnOnce() + Send + 'a>;
+
 /// A scheduler is able to accept jobs. Implementations may run jobs immediately
 /// (direct) or distribute to workers (thread pool).
 pub trait Scheduler: Send + Sync + 'static {
     fn schedule(&self, job: Job);
+
+    /// Run all jobs and block until they finish. The default schedules each job and
+    /// waits on a shared counter; schedulers with a native fork/join can override it.
+    fn join_all(&self, jobs: Vec<Job>) {
+        join_counted(self, jobs);
+    }
+
+    /// Like `join_all`, with a priority per job (higher starts first). The default ignores the
+    /// priorities; `ThreadPool` drains pending higher-priority jobs before lower ones.
+    fn submit_prioritized(&self, jobs: Vec<(i32, Job)>) {
+        self.join_all(jobs.into_iter().map(|(_, job)| job).collect());
+    }
+}
+
+/// Default `join_all`: a job that panics still counts as finished, so the wait can't hang.
+fn join_counted<S: Scheduler + ?Sized>(sched: &S, jobs: Vec<Job>) {
+    let state = Arc::new(ScopeState::new());
+    for job in jobs {
+        state.incr();
+        let finished = Finished(state.clone());
+        sched.schedule(Box::new(move || { let _finished = finished; (job)(); }));
+    }
+    state.wait_all();
+    state.resume_if_poisoned();
 }
 
 /// Runs jobs immediately on the calling thread.
@@ -30,52 +61,449 @@ impl Scheduler for Direct {
     fn schedule(&self, job: Job) { (job)(); }
 }
 
+/// Identifies one job scheduled on a `ThreadPool`; ids increase monotonically per pool.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct JobId(pub u64);
+
+/// Callbacks a `ThreadPool` worker invokes around every job, e.g. to feed an external profiler.
+/// They run on the worker thread, outside the queue lock.
+pub struct Hooks {
+    pub on_start: Box<dyn Fn(JobId) + Send + Sync>,
+    pub on_end: Box<dyn Fn(JobId) + Send + Sync>,
+}
+
 enum Message {
-    Run(Job),
+    Run(Job, JobId, Option<Arc<Hooks>>),
+    /// Run the highest-priority job in `ThreadPool::prioritized`.
+    RunNext,
     Shutdown,
 }
 
+/// A job waiting in a `ThreadPool`'s priority queue. Ordered by priority, then by submission
+/// (lower `JobId` first) among equal priorities.
+struct Prioritized {
+    priority: i32,
+    id: JobId,
+    job: Job,
+    hooks: Option<Arc<Hooks>>,
+}
+
+impl Ord for Prioritized {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.priority.cmp(&other.priority).then_with(|| other.id.cmp(&self.id))
+    }
+}
+impl PartialOrd for Prioritized {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> { Some(self.cmp(other)) }
+}
+impl PartialEq for Prioritized {
+    fn eq(&self, other: &Self) -> bool { self.cmp(other).is_eq() }
+}
+impl Eq for Prioritized {}
+
+/// What a `ThreadPool` worker does when a job panics. The worker itself always survives
+/// (except under `Abort`), so a panicking job never shrinks the pool.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PanicPolicy {
+    /// Resume the panic on the thread waiting for the job: the join point it was submitted
+    /// through (`join_all`, `submit_prioritized`, `Scope::join_all`, the end of `scope`,
+    /// `map_reduce`) re-raises the first panic among its own jobs. A panic in a detached
+    /// `schedule`d job has no join point and poisons the pool instead (see `take_panic`).
+    #[default]
+    Propagate,
+    /// Abort the process.
+    Abort,
+    /// Log the panic and keep going.
+    Ignore,
+}
+
+/// Configures a `ThreadPool`; see `ThreadPool::builder`.
+#[derive(Clone, Debug, Default)]
+pub struct ThreadPoolBuilder {
+    threads: Option<usize>,
+    name_prefix: Option<String>,
+    panic_policy: PanicPolicy,
+}
+
+impl ThreadPoolBuilder {
+    /// Number of worker threads (defaults to the available parallelism).
+    pub fn threads(mut self, n: usize) -> Self { self.threads = Some(n); self }
+    /// Name workers `{prefix}-0`, `{prefix}-1`, ... (unnamed by default).
+    pub fn name_prefix(mut self, prefix: impl Into<String>) -> Self { self.name_prefix = Some(prefix.into()); self }
+    pub fn panic_policy(mut self, policy: PanicPolicy) -> Self { self.panic_policy = policy; self }
+
+    pub fn build(self) -> ThreadPool {
+        ThreadPool::with_queue(self, None)
+    }
+
+    fn worker_count(&self) -> usize {
+        self.threads.unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get()))
+    }
+}
+
 /// A tiny thread pool with a fixed number of worker threads.
 pub struct ThreadPool {
     tx: mpsc::Sender<Message>,
     workers: Vec<JoinHandle<()>>,
+    queue: Option<Arc<QueueGate>>,
+    hooks: Option<Arc<Hooks>>,
+    next_id: AtomicU64,
+    /// First panic of a detached job caught under `PanicPolicy::Propagate`.
+    poison: Arc<Mutex<Option<Box<dyn Any + Send>>>>,
+    /// Jobs submitted with a priority; each has a matching `Message::RunNext` in the channel.
+    prioritized: Arc<Mutex<BinaryHeap<Prioritized>>>,
+}
+
+/// Tracks queued (not yet started) jobs of a bounded pool.
+struct QueueGate {
+    capacity: usize,
+    queued: Mutex<usize>,
+    space: Condvar,
+}
+
+impl QueueGate {
+    fn try_acquire(&self) -> bool {
+        let mut q = self.queued.lock().unwrap();
+        if *q < self.capacity { *q += 1; true } else { false }
+    }
+    fn acquire_blocking(&self) {
+        let mut q = self.queued.lock().unwrap();
+        while *q >= self.capacity { q = self.space.wait(q).unwrap(); }
+        *q += 1;
+    }
+    fn acquire_unchecked(&self) { *self.queued.lock().unwrap() += 1; }
+    fn release(&self) {
+        *self.queued.lock().unwrap() -= 1;
+        self.space.notify_one();
+    }
 }
 
 impl ThreadPool {
     /// Create a pool with `workers` threads.
     pub fn new(workers: usize) -> Self {
+        Self::builder().threads(workers).build()
+    }
+
+    /// Configure thread count, worker names and the `PanicPolicy`:
+    /// `ThreadPool::builder().threads(4).name_prefix("mk-worker").panic_policy(PanicPolicy::Ignore).build()`.
+    pub fn builder() -> ThreadPoolBuilder { ThreadPoolBuilder::default() }
+
+    /// Create a pool whose queue holds at most `capacity` jobs that have not started yet.
+    /// `try_schedule` / `SpawnExt::try_spawn` fail and `schedule_blocking` /
+    /// `SpawnExt::spawn_blocking_when_full` wait while the queue is full; plain
+    /// `Scheduler::schedule` never blocks and may overfill it.
+    pub fn bounded(workers: usize, capacity: usize) -> Self {
+        assert!(capacity > 0, "bounded thread pool requires a queue capacity of at least one");
+        let queue = Arc::new(QueueGate { capacity, queued: Mutex::new(0), space: Condvar::new() });
+        Self::with_queue(Self::builder().threads(workers), Some(queue))
+    }
+
+    fn with_queue(cfg: ThreadPoolBuilder, queue: Option<Arc<QueueGate>>) -> Self {
+        let workers = cfg.worker_count();
         assert!(workers > 0, "thread pool requires at least one worker");
         let (tx, rx) = mpsc::channel::<Message>();
         let rx = Arc::new(Mutex::new(rx));
+        let poison: Arc<Mutex<Option<Box<dyn Any + Send>>>> = Arc::new(Mutex::new(None));
+        let prioritized: Arc<Mutex<BinaryHeap<Prioritized>>> = Arc::new(Mutex::new(BinaryHeap::new()));
         let mut handles = Vec::with_capacity(workers);
-        for _ in 0..workers {
+        for i in 0..workers {
             let rx_cloned = Arc::clone(&rx);
-            handles.push(thread::spawn(move || loop {
-                let msg = { rx_cloned.lock().unwrap().recv().unwrap() };
-                match msg {
-                    Message::Run(job) => { (job)(); }
-                    Message::Shutdown => break,
+            let gate = queue.clone();
+            let (policy, poison, prioritized) = (cfg.panic_policy, poison.clone(), prioritized.clone());
+            let mut builder = thread::Builder::new();
+            if let Some(prefix) = &cfg.name_prefix { builder = builder.name(format!("{}-{}", prefix, i)); }
+            handles.push(builder.spawn(move || {
+                UNWOUND.with(|u| *u.borrow_mut() = Some(Vec::new()));
+                loop {
+                    let msg = { rx_cloned.lock().unwrap().recv().unwrap() };
+                    let (job, id, hooks) = match msg {
+                        Message::Run(job, id, hooks) => (job, id, hooks),
+                        Message::RunNext => match prioritized.lock().unwrap().pop() {
+                            Some(p) => (p.job, p.id, p.hooks),
+                            None => continue,
+                        },
+                        Message::Shutdown => break,
+                    };
+                    if let Some(g) = &gate { g.release(); }
+                    if let Some(h) = &hooks { (h.on_start)(id); }
+                    if let Err(payload) = panic::catch_unwind(AssertUnwindSafe(job)) {
+                        match policy {
+                            PanicPolicy::Propagate => {
+                                // Hand the panic to the batch of the job this worker ran: its guard
+                                // dropped last. Earlier entries belong to join points nested inside
+                                // the job, which the unwind already left.
+                                let owner = UNWOUND.with(|u| u.borrow().as_ref().and_then(|v| v.last().cloned()));
+                                match owner {
+                                    Some(state) => state.record_panic(payload),
+                                    None => {
+                                        let mut p = poison.lock().unwrap();
+                                        if p.is_none() { *p = Some(payload); }
+                                    }
+                                }
+                            }
+                            PanicPolicy::Ignore => {
+                                #[cfg(feature = "log")]
+                                log::warn!("job {:?} panicked: {}; continuing", id, panic_message(&*payload));
+                                #[cfg(not(feature = "log"))]
+                                eprintln!("job {:?} panicked: {}; continuing", id, panic_message(&*payload));
+                            }
+                            PanicPolicy::Abort => std::process::abort(),
+                        }
+                        let unwound = UNWOUND.with(|u| u.borrow_mut().as_mut().map(std::mem::take).unwrap_or_default());
+                        for state in unwound { state.decr(); }
+                    }
+                    if let Some(h) = &hooks { (h.on_end)(id); }
                 }
-            }));
+            }).expect("failed to spawn thread pool worker"));
         }
-        Self { tx, workers: handles }
+        Self { tx, workers: handles, queue, hooks: None, next_id: AtomicU64::new(0), poison, prioritized }
+    }
+
+    /// Whether a detached job panicked under `PanicPolicy::Propagate` since the last `take_panic`.
+    /// Panics of jobs submitted through a join point are raised there and never poison the pool.
+    pub fn is_poisoned(&self) -> bool { self.poison.lock().unwrap().is_some() }
+
+    /// Take (and clear) the first panic of a detached job, e.g. to resume it on this thread.
+    pub fn take_panic(&self) -> Option<Box<dyn Any + Send>> { self.poison.lock().unwrap().take() }
+
+    /// Invoke `hooks` around every job scheduled from now on. Each job gets the next `JobId`
+    /// when it is scheduled, so ids follow submission order.
+    pub fn with_hooks(mut self, hooks: Hooks) -> Self {
+        self.hooks = Some(Arc::new(hooks));
+        self
+    }
+
+    fn send(&self, job: Job) {
+        let id = JobId(self.next_id.fetch_add(1, Ordering::Relaxed));
+        let _ = self.tx.send(Message::Run(job, id, self.hooks.clone()));
+    }
+
+    /// Schedule `job` ahead of every pending job with a lower priority. Jobs sent through
+    /// `Scheduler::schedule` bypass the priority queue and are not reordered.
+    pub fn schedule_with_priority(&self, priority: i32, job: Job) {
+        self.push_prioritized(vec![(priority, job)]);
+    }
+
+    /// Queue all jobs under one lock, so a batch is drained in priority order.
+    fn push_prioritized(&self, jobs: Vec<(i32, Job)>) {
+        let count = jobs.len();
+        {
+            let mut heap = self.prioritized.lock().unwrap();
+            for (priority, job) in jobs {
+                if let Some(g) = &self.queue { g.acquire_unchecked(); }
+                let id = JobId(self.next_id.fetch_add(1, Ordering::Relaxed));
+                heap.push(Prioritized { priority, id, job, hooks: self.hooks.clone() });
+            }
+        }
+        for _ in 0..count { let _ = self.tx.send(Message::RunNext); }
+    }
+
+    /// Schedule unless the bounded queue is full, handing the job back in that case.
+    /// Always succeeds on an unbounded pool.
+    pub fn try_schedule(&self, job: Job) -> Result<(), Job> {
+        if let Some(g) = &self.queue {
+            if !g.try_acquire() { return Err(job); }
+        }
+        self.send(job);
+        Ok(())
+    }
+
+    /// Schedule, blocking the caller until the bounded queue has space.
+    /// Do not call from a job running on this pool: a full queue would never drain.
+    pub fn schedule_blocking(&self, job: Job) {
+        if let Some(g) = &self.queue { g.acquire_blocking(); }
+        self.send(job);
+    }
+
+    /// Schedule `f` under a watchdog: once it has run for `timeout`, the returned handle reports
+    /// `TimedOut` and the job's `CancelToken` is cancelled. Threads are never killed, so the job
+    /// keeps running until it notices the token (or finishes on its own).
+    pub fn schedule_with_timeout<F>(&self, timeout: Duration, f: F) -> TimeoutHandle
+    where
+        F: FnOnce(&CancelToken) + Send + 'static,
+    {
+        let handle = TimeoutHandle { state: Arc::new((Mutex::new(JobPhase::Queued), Condvar::new())), token: CancelToken::new() };
+        let (state, token) = (handle.state.clone(), handle.token.clone());
+        self.schedule(Box::new(move || {
+            *state.0.lock().unwrap() = JobPhase::Running;
+            let (watch_state, watch_token) = (state.clone(), token.clone());
+            thread::spawn(move || {
+                let (lock, cv) = &*watch_state;
+                let (mut phase, _) = cv.wait_timeout_while(lock.lock().unwrap(), timeout, |p| *p == JobPhase::Running).unwrap();
+                if *phase == JobPhase::Running {
+                    *phase = JobPhase::Done(JobOutcome::TimedOut);
+                    watch_token.cancel();
+                    cv.notify_all();
+                }
+            });
+            let result = panic::catch_unwind(AssertUnwindSafe(|| f(&token)));
+            {
+                let (lock, cv) = &*state;
+                let mut phase = lock.lock().unwrap();
+                if *phase == JobPhase::Running {
+                    let outcome = if result.is_ok() { JobOutcome::Completed } else { JobOutcome::Panicked };
+                    *phase = JobPhase::Done(outcome);
+                }
+                cv.notify_all();
+            }
+            // Let the worker report the panic as it does for any other job
+            if let Err(payload) = result { panic::resume_unwind(payload); }
+        }));
+        handle
     }
 
     /// Spawn a scope, allowing jobs to be scheduled and then joined before returning.
-    pub fn scope<F>(&self, f: F)
+    ///
+    /// Jobs spawned on the scope may borrow anything that outlives the `scope` call (`'env`),
+    /// including `&mut` data from the caller's frame, without `Arc` wrapping:
+    ///
+    /// ```ignore
+    /// let mut data = vec![0u32; 64];
+    /// pool.scope(|s| {
+    ///     for chunk in data.chunks_mut(16) {
+    ///         s.spawn(move || chunk.iter_mut().for_each(|v| *v += 1));
+    ///     }
+    /// });
+    /// ```
+    pub fn scope<'env, F, R>(&self, f: F) -> R
     where
-        F: FnOnce(&Scope<'_>),
+        F: FnOnce(&Scope<'_, 'env>) -> R,
     {
+        let scope = Scope { pool: self, state: Arc::new(ScopeState::new()), _env: PhantomData };
+        // Wait for all jobs spawned via this scope to finish, even if `f` panics: they may
+        // still be borrowing from the caller's frame.
+        let wait = WaitOnDrop(&scope.state);
+        let out = f(&scope);
+        drop(wait);
+        scope.state.resume_if_poisoned();
+        out
+    }
+
+    /// Map `items` in parallel and combine the results with `reduce_fn`.
+    ///
+    /// Items are split into one contiguous chunk per worker; each worker folds its chunk into a
+    /// private partial starting from `identity`, and the caller folds the partials in chunk order.
+    /// Workers therefore never contend on shared state. `reduce_fn` must be associative and
+    /// `identity` its neutral element (e.g. `0` for `+`, `i64::MIN` for `max`).
+    /// Blocks until every chunk is done; panics if a map or reduce call panicked.
+    pub fn map_reduce<T, M, R, F>(&self, items: Vec<T>, map_fn: M, identity: R, reduce_fn: F) -> R
+    where
+        T: Send + 'static,
+        M: Fn(T) -> R + Send + Sync + 'static,
+        R: Clone + Send + 'static,
+        F: Fn(R, R) -> R + Send + Sync + 'static,
+    {
+        if items.is_empty() { return identity; }
+        let chunk = (items.len() + self.workers.len() - 1) / self.workers.len();
+        let (map_fn, reduce_fn) = (Arc::new(map_fn), Arc::new(reduce_fn));
+        let (tx, rx) = mpsc::channel::<(usize, R)>();
+        let batch = Arc::new(ScopeState::new());
+        let mut items = items.into_iter();
+        let mut chunks = 0;
+        loop {
+            let part: Vec<T> = items.by_ref().take(chunk).collect();
+            if part.is_empty() { break; }
+            let (map_fn, reduce_fn, tx, init) = (map_fn.clone(), reduce_fn.clone(), tx.clone(), identity.clone());
+            let index = chunks;
+            batch.incr();
+            let finished = Finished(batch.clone());
+            self.schedule(Box::new(move || {
+                let _finished = finished;
+                let partial = part.into_iter().fold(init, |acc, item| reduce_fn(acc, map_fn(item)));
+                let _ = tx.send((index, partial));
+            }));
+            chunks += 1;
+        }
+        drop(tx);
+
+        let mut partials: Vec<Option<R>> = vec![None; chunks];
+        for _ in 0..chunks {
+            let (index, partial) = rx.recv().unwrap_or_else(|_| {
+                // A job died without sending; its panic is recorded once the batch settles
+                batch.wait_all();
+                batch.resume_if_poisoned();
+                panic!("map_reduce job panicked")
+            });
+            partials[index] = Some(partial);
+        }
+        partials.into_iter().flatten().fold(identity, |acc, p| reduce_fn(acc, p))
+    }
+}
+
+/// Cooperative cancellation flag shared between a job and whoever supervises it.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<std::sync::atomic::AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self { Self::default() }
+    pub fn cancel(&self) { self.0.store(true, Ordering::Release); }
+    pub fn is_cancelled(&self) -> bool { self.0.load(Ordering::Acquire) }
+}
+
+/// How a job run with a timeout ended.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum JobOutcome {
+    Completed,
+    /// Still running when its timeout elapsed (it may finish later).
+    TimedOut,
+    /// Panicked before its timeout elapsed.
+    Panicked,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum JobPhase { Queued, Running, Done(JobOutcome) }
+
+/// Handle to a job scheduled with `ThreadPool::schedule_with_timeout`. The timeout counts from
+/// when a worker starts the job, not from submission.
+pub struct TimeoutHandle {
+    state: Arc<(Mutex<JobPhase>, Condvar)>,
+    token: CancelToken,
+}
+
+impl TimeoutHandle {
+    /// Outcome so far; `None` while the job is queued or running within its budget.
+    pub fn outcome(&self) -> Option<JobOutcome> {
+        match *self.state.0.lock().unwrap() { JobPhase::Done(o) => Some(o), _ => None }
+    }
+
+    /// Block until the job completes, panics or times out, whichever happens first.
+    pub fn wait(&self) -> JobOutcome {
+        let (lock, cv) = &*self.state;
+        let phase = cv.wait_while(lock.lock().unwrap(), |p| !matches!(p, JobPhase::Done(_))).unwrap();
+        match *phase { JobPhase::Done(o) => o, _ => unreachable!() }
+    }
+
+    /// The token cancelled on timeout; cancel it early to ask the job to stop.
+    pub fn token(&self) -> &CancelToken { &self.token }
+}
+
+impl Scheduler for ThreadPool {
+    fn schedule(&self, job: Job) {
+        if let Some(g) = &self.queue { g.acquire_unchecked(); }
+        self.send(job);
+    }
+
+    fn join_all(&self, jobs: Vec<Job>) {
+        join_counted(self, jobs);
+    }
+
+    fn submit_prioritized(&self, jobs: Vec<(i32, Job)>) {
         let state = Arc::new(ScopeState::new());
-        let scope = Scope { pool: self, state: Arc::clone(&state) };
-        f(&scope);
-        // Wait for all jobs spawned via this scope to finish.
+        let jobs = jobs.into_iter().map(|(priority, job)| {
+            state.incr();
+            let finished = Finished(state.clone());
+            (priority, Box::new(move || { let _finished = finished; (job)(); }) as Job)
+        }).collect();
+        self.push_prioritized(jobs);
         state.wait_all();
+        state.resume_if_poisoned();
     }
 }
 
-impl Scheduler for ThreadPool {
-    fn schedule(&self, job: Job) { let _ = self.tx.send(Message::Run(job)); }
+fn panic_message(payload: &(dyn Any + Send)) -> &str {
+    payload.downcast_ref::<&str>().copied()
+        .or_else(|| payload.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("<non-string panic payload>")
 }
 
 impl Drop for ThreadPool {
@@ -85,13 +513,25 @@ impl Drop for ThreadPool {
     }
 }
 
+/// Completion counter of one join point (a scope or a batch), plus the first panic among its
+/// jobs under `PanicPolicy::Propagate`.
 struct ScopeState {
     remaining: AtomicUsize,
     pair: (Mutex<()>, Condvar),
+    poison: Mutex<Option<Box<dyn Any + Send>>>,
 }
 
 impl ScopeState {
-    fn new() -> Self { Self { remaining: AtomicUsize::new(0), pair: (Mutex::new(()), Condvar::new()) } }
+    fn new() -> Self { Self { remaining: AtomicUsize::new(0), pair: (Mutex::new(()), Condvar::new()), poison: Mutex::new(None) } }
+    fn record_panic(&self, payload: Box<dyn Any + Send>) {
+        let mut p = self.poison.lock().unwrap();
+        if p.is_none() { *p = Some(payload); }
+    }
+    /// Join point: re-raise (and clear) the first panic recorded for this state's jobs.
+    fn resume_if_poisoned(&self) {
+        let payload = self.poison.lock().unwrap().take();
+        if let Some(payload) = payload { panic::resume_unwind(payload); }
+    }
     fn incr(&self) { self.remaining.fetch_add(1, Ordering::AcqRel); }
     fn decr(&self) {
         if self.remaining.fetch_sub(1, Ordering::AcqRel) == 1 {
@@ -104,21 +544,86 @@ impl ScopeState {
     }
 }
 
+/// Blocks in `drop` until the state's jobs have finished.
+struct WaitOnDrop<'a>(&'a ScopeState);
+impl Drop for WaitOnDrop<'_> {
+    fn drop(&mut self) { self.0.wait_all(); }
+}
+
+/// Marks one job of a `ScopeState` finished when dropped, including when the job unwinds.
+struct Finished(Arc<ScopeState>);
+impl Drop for Finished {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            let deferred = UNWOUND.with(|u| u.borrow_mut().as_mut().map(|v| v.push(self.0.clone())).is_some());
+            if deferred { return; }
+        }
+        self.0.decr();
+    }
+}
+
+thread_local! {
+    /// On pool workers: states whose `Finished` guard dropped while a job unwound. The worker
+    /// records the panic on the job's own (outermost) state and only then releases them, so the
+    /// join point they wake sees it.
+    static UNWOUND: std::cell::RefCell<Option<Vec<Arc<ScopeState>>>> = const { std::cell::RefCell::new(None) };
+}
+
 /// A scope that allows spawning jobs tied to a join point at the end of the scope.
-pub struct Scope<'p> {
+///
+/// `'env` is the lifetime spawned jobs may borrow for; it is invariant so a scope can't be
+/// coerced into accepting shorter borrows.
+pub struct Scope<'p, 'env> {
     pool: &'p ThreadPool,
     state: Arc<ScopeState>,
+    _env: PhantomData<&'env mut &'env ()>,
 }
 
-impl<'p> Scope<'p> {
+impl<'p, 'env> Scope<'p, 'env> {
     /// Spawn a job into the pool; scope will wait for its completion.
     pub fn spawn<F>(&self, f: F)
     where
-        F: FnOnce() + Send + 'static,
+        F: FnOnce() + Send + 'env,
     {
-        self.state.incr();
-        let state = Arc::clone(&self.state);
-        self.pool.schedule(Box::new(move || { f(); state.decr(); }));
+        self.submit(Box::new(f), &self.state);
+    }
+
+    /// Run a batch of jobs and wait for all of them before returning, as `Scheduler::join_all`
+    /// does; use it for the conflict-free layers of a stage. Since the batch is joined here,
+    /// its jobs only need to borrow for this call, so a later batch may borrow the same data.
+    pub fn join_all<'b>(&self, jobs: Vec<ScopedJob<'b>>) {
+        let batch = Arc::new(ScopeState::new());
+        let wait = WaitOnDrop(&batch);
+        for job in jobs { self.submit(job, &batch); }
+        drop(wait);
+        batch.resume_if_poisoned();
+    }
+
+    fn submit<'b>(&self, job: ScopedJob<'b>, state: &Arc<ScopeState>) {
+        state.incr();
+        // SAFETY: the job only borrows data valid for 'b. Every caller waits on `state` before
+        // 'b can end (`scope` and `join_all` via `WaitOnDrop`, also when unwinding), and
+        // `ScopedRun` releases `finished` only after the job has returned, unwound or been
+        // dropped unrun.
+        let job: Job = unsafe { std::mem::transmute::<ScopedJob<'b>, Job>(job) };
+        let run = ScopedRun { job, finished: Finished(Arc::clone(state)) };
+        self.pool.schedule(Box::new(move || run.run()));
+    }
+}
+
+/// A scoped job and its completion guard. Fields drop in declaration order, so if the pool
+/// discards the job without running it, the job's borrows end before the waiter is released.
+struct ScopedRun {
+    job: Job,
+    finished: Finished,
+}
+
+impl ScopedRun {
+    fn run(self) {
+        let ScopedRun { job, finished } = self;
+        // The call consumes `job`, so it is gone (returned or unwound) before `finished` drops
+        job();
+        drop(finished);
     }
 }
 
@@ -130,31 +635,87 @@ where
     S: Scheduler,
     I: IntoIterator<Item = Job>,
 {
-    let state = Arc::new(ScopeState::new());
-    let st2 = state.clone();
-    for job in jobs {
-        st2.incr();
-        let st3 = st2.clone();
-        sched.schedule(Box::new(move || { (job)(); st3.decr(); }));
+    sched.join_all(jobs.into_iter().collect());
+}
+
+// ===========================
+// Rayon-backed scheduler (feature `rayon`)
+// ===========================
+
+/// Runs jobs on a rayon thread pool (the global one by default).
+///
+/// `join_all` maps onto `rayon::scope`, so stage barriers use rayon's work-stealing
+/// join instead of a condvar, and a waiting worker helps execute the batch.
+#[cfg(feature = "rayon")]
+#[derive(Clone, Default)]
+pub struct RayonScheduler {
+    pool: Option<Arc<rayon::ThreadPool>>,
+}
+
+#[cfg(feature = "rayon")]
+impl RayonScheduler {
+    /// Use rayon's global thread pool.
+    pub fn global() -> Self { Self { pool: None } }
+    /// Use an existing rayon thread pool.
+    pub fn with_pool(pool: Arc<rayon::ThreadPool>) -> Self { Self { pool: Some(pool) } }
+}
+
+#[cfg(feature = "rayon")]
+impl Scheduler for RayonScheduler {
+    fn schedule(&self, job: Job) {
+        match &self.pool {
+            Some(pool) => pool.spawn(job),
+            None => rayon::spawn(job),
+        }
+    }
+
+    fn join_all(&self, jobs: Vec<Job>) {
+        let run = |s: &rayon::Scope<'_>| {
+            for job in jobs { s.spawn(move |_| (job)()); }
+        };
+        match &self.pool {
+            Some(pool) => pool.scope(run),
+            None => rayon::scope(run),
+        }
     }
-    state.wait_all();
 }
 
 // ===========================
 // Job traits for derive usage
 // ===========================
 
+/// Report a failed job the way workers report an ignored panic: through `log::warn!` with the
+/// `log` feature, stderr otherwise. `#[job(fallible)]` calls this from `run`.
+pub fn report_job_error(job: &str, err: &dyn std::error::Error) {
+    #[cfg(feature = "log")]
+    log::warn!("job `{}` failed: {}", job, err);
+    #[cfg(not(feature = "log"))]
+    eprintln!("job `{}` failed: {}", job, err);
+}
+
 /// Trait implemented by types that can run as jobs.
 ///
-/// Derives or manual impls should provide the body for `run(self)`.
+/// Derives or manual impls should provide the body for `run(self)`. Jobs that can fail also
+/// override `run_checked`; the default just runs the job and reports success.
 pub trait JobRun {
     fn run(self);
+
+    fn run_checked(self) -> Result<(), Box<dyn std::error::Error + Send>>
+    where
+        Self: Sized,
+    {
+        self.run();
+        Ok(())
+    }
 }
 
 /// Convenience extension to spawn jobs on any Scheduler.
 ///
 /// - `spawn(self, sched)`: moves the job and schedules it.
 /// - `spawn_ref(&self, sched)`: clones the job and schedules it (requires Clone).
+/// - `try_spawn(self, pool)`: returns the job if the pool's bounded queue is full.
+/// - `spawn_blocking_when_full(self, pool)`: waits for queue space instead (backpressure).
+/// - `spawn_with_timeout(self, pool, timeout)`: reports `TimedOut` if the job overruns.
 pub trait SpawnExt: JobRun + Sized {
     fn spawn<S: Scheduler>(self, sched: &S)
     where
@@ -170,6 +731,33 @@ pub trait SpawnExt: JobRun + Sized {
         let cloned = self.clone();
         sched.schedule(Box::new(move || cloned.run()));
     }
+
+    fn try_spawn(self, pool: &ThreadPool) -> Result<(), Self>
+    where
+        Self: Send + 'static,
+    {
+        if let Some(g) = &pool.queue {
+            if !g.try_acquire() { return Err(self); }
+        }
+        pool.send(Box::new(move || self.run()));
+        Ok(())
+    }
+
+    fn spawn_blocking_when_full(self, pool: &ThreadPool)
+    where
+        Self: Send + 'static,
+    {
+        pool.schedule_blocking(Box::new(move || self.run()));
+    }
+
+    /// Run under a watchdog; see `ThreadPool::schedule_with_timeout`. Jobs wanting to bail on
+    /// timeout should hold a clone of a `CancelToken` and use the pool method directly.
+    fn spawn_with_timeout(self, pool: &ThreadPool, timeout: Duration) -> TimeoutHandle
+    where
+        Self: Send + 'static,
+    {
+        pool.schedule_with_timeout(timeout, move |_| self.run())
+    }
 }
 
 impl<T: JobRun> SpawnExt for T {}
@@ -184,6 +772,189 @@ impl<T: JobRun> SpawnExt for T {}
 pub trait ResourceAccess {
     fn reads() -> &'static [TypeId] { &[] }
     fn writes() -> &'static [TypeId] { &[] }
+    /// Submission priority within a stage (higher starts first); see `Scheduler::submit_prioritized`.
+    fn priority() -> i32 { 0 }
+}
+
+/// Object-safe view of a system, for `#[stage(dynamic)]` fields holding `Vec<Box<dyn System>>`
+/// assembled at runtime. Implemented for every `JobRun + ResourceAccess + Clone` type, so any
+/// `#[derive(Job, System)]` type can be boxed.
+pub trait System: Send + Sync {
+    /// A fresh job running a clone of the system.
+    fn job(&self) -> Job;
+    fn reads(&self) -> &'static [TypeId];
+    fn writes(&self) -> &'static [TypeId];
+    fn priority(&self) -> i32;
+}
+
+impl<T> System for T
+where
+    T: JobRun + ResourceAccess + Clone + Send + Sync + 'static,
+{
+    fn job(&self) -> Job {
+        let sys = self.clone();
+        Box::new(move || sys.run())
+    }
+    fn reads(&self) -> &'static [TypeId] { <T as ResourceAccess>::reads() }
+    fn writes(&self) -> &'static [TypeId] { <T as ResourceAccess>::writes() }
+    fn priority(&self) -> i32 { <T as ResourceAccess>::priority() }
+}
+
+// ===========================
+// Cross-schedule stage ordering
+// ===========================
+
+/// One stage of a schedule as declared by `#[derive(Schedule)]`.
+///
+/// Entries in `after` / `before` are either local stage names (`"physics"`) or labels of
+/// another schedule's stage (`"CoreSchedule::physics"`). Qualified labels are only
+/// resolved when schedules are merged with [`MergedSchedule`].
+#[derive(Debug, Clone, Copy)]
+pub struct StageDecl {
+    pub name: &'static str,
+    pub after: &'static [&'static str],
+    pub before: &'static [&'static str],
+}
+
+/// Labeled stages of a schedule, runnable one at a time. Implemented by `#[derive(Schedule)]`.
+pub trait StageGraph {
+    /// Label qualifying this schedule's stages (the type name for derived schedules).
+    fn schedule_label(&self) -> &'static str;
+    /// Stages in declaration order.
+    fn stages(&self) -> &'static [StageDecl];
+    /// Declaration indices in the schedule's own (local) execution order.
+    fn local_order(&self) -> &'static [usize];
+    /// Run the stage at declaration index `index` and wait for it to finish.
+    fn run_stage(&self, index: usize, sched: &dyn Scheduler);
+    /// Where this instance records stage wall times, if it is profiled (`#[schedule(profile)]`).
+    fn stage_timings(&self) -> Option<&StageTimings> { None }
+}
+
+/// Wall time of each stage in a schedule's most recent run, held by the schedule instance.
+///
+/// A `#[schedule(profile)]` struct keeps one of these as a plain (non-stage) field, so two
+/// instances of the same schedule type never share numbers.
+#[derive(Debug, Default)]
+pub struct StageTimings {
+    entries: Mutex<Vec<(&'static str, Duration)>>,
+}
+
+impl StageTimings {
+    pub fn new() -> Self { Self::default() }
+    /// Forget the previous run; called at the start of each run.
+    pub fn clear(&self) { self.entries.lock().unwrap_or_else(|e| e.into_inner()).clear(); }
+    pub fn record(&self, stage: &'static str, elapsed: Duration) {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).push((stage, elapsed));
+    }
+    /// Recorded `(stage, wall time)` pairs in execution order.
+    pub fn snapshot(&self) -> Vec<(&'static str, Duration)> {
+        self.entries.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+/// Errors resolving labels while merging schedules.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScheduleMergeError {
+    /// Two merged schedules share a label.
+    DuplicateSchedule(String),
+    /// `stage` references a label that no merged schedule declares.
+    UnknownStage { stage: String, target: String },
+    /// The combined ordering constraints form a cycle through these stages.
+    Cycle(Vec<String>),
+}
+
+impl std::fmt::Display for ScheduleMergeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::DuplicateSchedule(l) => write!(f, "schedule '{}' merged more than once", l),
+            Self::UnknownStage { stage, target } => write!(f, "stage '{}' references unknown stage '{}'", stage, target),
+            Self::Cycle(stages) => write!(f, "cycle detected between stages: {}", stages.join(", ")),
+        }
+    }
+}
+
+impl std::error::Error for ScheduleMergeError {}
+
+/// Several schedules whose stages are ordered together, honoring both local and
+/// cross-schedule (`"Other::stage"`) `after` / `before` constraints.
+///
+/// Ties are broken by merge order, then declaration order, so independent schedules
+/// run one after another as they would separately.
+pub struct MergedSchedule<'a> {
+    schedules: Vec<&'a dyn StageGraph>,
+    order: Vec<(usize, usize)>,
+}
+
+impl<'a> MergedSchedule<'a> {
+    pub fn new(schedules: &[&'a dyn StageGraph]) -> Result<Self, ScheduleMergeError> {
+        let mut nodes: Vec<(usize, usize)> = Vec::new();
+        let mut labels: Vec<String> = Vec::new();
+        let mut index = std::collections::HashMap::<String, usize>::new();
+        for (si, s) in schedules.iter().enumerate() {
+            if schedules[..si].iter().any(|o| o.schedule_label() == s.schedule_label()) {
+                return Err(ScheduleMergeError::DuplicateSchedule(s.schedule_label().to_string()));
+            }
+            for (k, st) in s.stages().iter().enumerate() {
+                let label = format!("{}::{}", s.schedule_label(), st.name);
+                index.insert(label.clone(), nodes.len());
+                nodes.push((si, k));
+                labels.push(label);
+            }
+        }
+        let n = nodes.len();
+        let mut indeg = vec![0usize; n];
+        let mut adj: Vec<Vec<usize>> = vec![Vec::new(); n];
+        for (u, &(si, k)) in nodes.iter().enumerate() {
+            let sched = schedules[si];
+            let decl = &sched.stages()[k];
+            let resolve = |dep: &str| {
+                let key = if dep.contains("::") { dep.to_string() } else { format!("{}::{}", sched.schedule_label(), dep) };
+                index.get(&key).copied().ok_or_else(|| ScheduleMergeError::UnknownStage { stage: labels[u].clone(), target: dep.to_string() })
+            };
+            for dep in decl.after { let v = resolve(dep)?; adj[v].push(u); indeg[u] += 1; }
+            for dep in decl.before { let v = resolve(dep)?; adj[u].push(v); indeg[v] += 1; }
+        }
+        // Kahn's algorithm, always taking the earliest ready node
+        let mut done = vec![false; n];
+        let mut order = Vec::with_capacity(n);
+        while let Some(u) = (0..n).find(|&i| !done[i] && indeg[i] == 0) {
+            done[u] = true;
+            order.push(nodes[u]);
+            for &v in &adj[u] { indeg[v] -= 1; }
+        }
+        if order.len() != n {
+            let stuck = (0..n).filter(|&i| !done[i]).map(|i| labels[i].clone()).collect();
+            return Err(ScheduleMergeError::Cycle(stuck));
+        }
+        Ok(Self { schedules: schedules.to_vec(), order })
+    }
+
+    /// Qualified stage labels in execution order.
+    pub fn labels(&self) -> Vec<String> {
+        self.order.iter().map(|&(si, k)| {
+            let s = self.schedules[si];
+            format!("{}::{}", s.schedule_label(), s.stages()[k].name)
+        }).collect()
+    }
+
+    /// Run every stage in merged order; each stage completes before the next starts.
+    /// Profiled schedules record their stages' wall times as their own `run` would.
+    pub fn run(&self, sched: &dyn Scheduler) {
+        for s in &self.schedules {
+            if let Some(t) = s.stage_timings() { t.clear(); }
+        }
+        for &(si, k) in &self.order {
+            let s = self.schedules[si];
+            match s.stage_timings() {
+                Some(t) => {
+                    let start = std::time::Instant::now();
+                    s.run_stage(k, sched);
+                    t.record(s.stages()[k].name, start.elapsed());
+                }
+                None => s.run_stage(k, sched),
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -196,7 +967,7 @@ mod tests {
         let s = Direct;
         let flag = Arc::new(AtomicUsize::new(0));
         let f2 = flag.clone();
-        s.schedule(Box::new(move || f2.fetch_add(1, Ordering::AcqRel)));
+        s.schedule(Box::new(move || { f2.fetch_add(1, Ordering::AcqRel); }));
         assert_eq!(flag.load(Ordering::Acquire), 1);
     }
 
@@ -212,4 +983,268 @@ mod tests {
         });
         assert_eq!(n.load(Ordering::Acquire), 8);
     }
+
+    #[test]
+    fn scoped_jobs_borrow_stack_data() {
+        let pool = ThreadPool::new(3);
+        let mut data = vec![0u32; 64];
+        let touched = AtomicUsize::new(0);
+        pool.scope(|s| {
+            for (i, chunk) in data.chunks_mut(16).enumerate() {
+                let touched = &touched;
+                s.spawn(move || {
+                    for v in chunk.iter_mut() { *v = i as u32 + 1; }
+                    touched.fetch_add(chunk.len(), Ordering::AcqRel);
+                });
+            }
+        });
+        assert_eq!(touched.into_inner(), 64);
+        assert!(data.chunks(16).enumerate().all(|(i, c)| c.iter().all(|&v| v == i as u32 + 1)));
+
+        let mut log: Vec<&str> = Vec::new();
+        let len = pool.scope(|s| {
+            s.spawn(|| log.push("pushed"));
+            7
+        });
+        assert_eq!((len, log), (7, vec!["pushed"]));
+    }
+
+    #[test]
+    fn unrun_scoped_job_drops_before_release() {
+        // A pool whose channel is closed: `send` drops each job without running it
+        let (tx, rx) = mpsc::channel();
+        drop(rx);
+        let pool = ThreadPool {
+            tx, workers: Vec::new(), queue: None, hooks: None, next_id: AtomicU64::new(0),
+            poison: Arc::default(), prioritized: Arc::default(),
+        };
+        let scope = Scope { pool: &pool, state: Arc::new(ScopeState::new()), _env: PhantomData };
+        struct Probe<'a>(&'a ScopeState, &'a AtomicUsize);
+        impl Drop for Probe<'_> {
+            fn drop(&mut self) { self.1.store(self.0.remaining.load(Ordering::Acquire), Ordering::Release); }
+        }
+        let seen = AtomicUsize::new(usize::MAX);
+        let probe = Probe(&scope.state, &seen);
+        scope.spawn(move || drop(probe));
+        // The job (and its borrows) went away while the scope still counted it as pending
+        assert_eq!(seen.load(Ordering::Acquire), 1);
+        assert_eq!(scope.state.remaining.load(Ordering::Acquire), 0);
+    }
+
+    #[test]
+    fn scoped_join_all_runs_layers_in_order() {
+        let pool = ThreadPool::new(2);
+        let (mut a, mut b) = (vec![1], vec![2]);
+        let mut summary = Vec::new();
+        pool.scope(|s| {
+            // Layer 1 writes `a` and `b` independently; layer 2 reads both
+            s.join_all(vec![Box::new(|| a.push(10)), Box::new(|| b.push(20))]);
+            let (a, b) = (&a, &b);
+            s.join_all(vec![Box::new(|| summary.push(a.len() + b.len()))]);
+        });
+        assert_eq!((a, b, summary), (vec![1, 10], vec![2, 20], vec![4]));
+    }
+
+    #[test]
+    fn ignored_panic_keeps_pool_running() {
+        let pool = ThreadPool::builder().threads(1).name_prefix("mk-worker").panic_policy(PanicPolicy::Ignore).build();
+        let names = Mutex::new(Vec::new());
+        pool.scope(|s| {
+            s.spawn(|| panic!("ignored"));
+            s.spawn(|| names.lock().unwrap().push(thread::current().name().map(str::to_string)));
+        });
+        // The single worker survived the panic and ran the second job
+        assert_eq!(names.into_inner().unwrap(), vec![Some("mk-worker-0".to_string())]);
+        assert!(!pool.is_poisoned());
+        let n = Arc::new(AtomicUsize::new(0));
+        let n2 = n.clone();
+        pool.join_all(vec![Box::new(move || { n2.fetch_add(1, Ordering::AcqRel); })]);
+        assert_eq!(n.load(Ordering::Acquire), 1);
+    }
+
+    #[test]
+    fn propagated_panic_surfaces_at_join() {
+        let pool = ThreadPool::builder().threads(2).panic_policy(PanicPolicy::Propagate).build();
+        let ran = Arc::new(AtomicUsize::new(0));
+        let ran2 = ran.clone();
+        let jobs: Vec<Job> = vec![Box::new(|| panic!("boom")), Box::new(move || { ran2.fetch_add(1, Ordering::AcqRel); })];
+        let err = panic::catch_unwind(AssertUnwindSafe(|| pool.join_all(jobs))).unwrap_err();
+        assert_eq!(panic_message(&*err), "boom");
+        assert_eq!(ran.load(Ordering::Acquire), 1);
+        // The join point consumed the poison; the pool keeps working
+        assert!(!pool.is_poisoned());
+        pool.join_all(vec![Box::new(|| {})]);
+
+        let err = panic::catch_unwind(AssertUnwindSafe(|| pool.scope(|s| s.spawn(|| panic!("scoped"))))).unwrap_err();
+        assert_eq!(panic_message(&*err), "scoped");
+    }
+
+    #[test]
+    fn panics_surface_only_at_their_own_join() {
+        let pool = ThreadPool::builder().threads(2).panic_policy(PanicPolicy::Propagate).build();
+        // Concurrent callers: only the one whose batch panicked sees it
+        thread::scope(|t| {
+            let failing = t.spawn(|| {
+                (0..20).filter(|_| panic::catch_unwind(AssertUnwindSafe(|| pool.join_all(vec![Box::new(|| panic!("mine"))]))).is_err()).count()
+            });
+            for _ in 0..20 { pool.scope(|s| s.spawn(|| {})); }
+            assert_eq!(failing.join().unwrap(), 20);
+        });
+        assert!(!pool.is_poisoned());
+
+        // A detached job has no join point: its panic stays on the pool until taken
+        pool.schedule(Box::new(|| panic!("detached")));
+        while !pool.is_poisoned() { thread::yield_now(); }
+        pool.join_all(vec![Box::new(|| {})]);
+        assert_eq!(panic_message(&*pool.take_panic().unwrap()), "detached");
+        assert!(!pool.is_poisoned());
+    }
+
+    #[test]
+    fn single_worker_drains_higher_priorities_first() {
+        let pool = ThreadPool::new(1);
+        let order = Arc::new(Mutex::new(Vec::new()));
+        let job = |name: &'static str| { let order = order.clone(); Box::new(move || order.lock().unwrap().push(name)) as Job };
+        pool.submit_prioritized(vec![(0, job("low")), (10, job("high")), (5, job("mid")), (10, job("high2"))]);
+        assert_eq!(*order.lock().unwrap(), ["high", "high2", "mid", "low"]);
+
+        // The default impl ignores priorities and keeps submission order
+        order.lock().unwrap().clear();
+        Direct.submit_prioritized(vec![(0, job("a")), (9, job("b"))]);
+        assert_eq!(*order.lock().unwrap(), ["a", "b"]);
+    }
+
+    struct Counted(Arc<AtomicUsize>, Option<mpsc::Receiver<()>>);
+    impl JobRun for Counted {
+        fn run(self) {
+            if let Some(rx) = self.1 { let _ = rx.recv(); }
+            self.0.fetch_add(1, Ordering::AcqRel);
+        }
+    }
+
+    #[test]
+    fn bounded_pool_applies_backpressure() {
+        let pool = Arc::new(ThreadPool::bounded(1, 1));
+        let ran = Arc::new(AtomicUsize::new(0));
+        let (release_tx, release_rx) = mpsc::channel();
+        let done = Arc::new(AtomicUsize::new(0));
+
+        // Job 1 parks the only worker, job 2 fills the queue, job 3 must wait for space.
+        let producer = {
+            let (pool, ran, done) = (pool.clone(), ran.clone(), done.clone());
+            thread::spawn(move || {
+                Counted(ran.clone(), Some(release_rx)).spawn_blocking_when_full(&pool);
+                for _ in 0..2 { Counted(ran.clone(), None).spawn_blocking_when_full(&pool); }
+                done.store(1, Ordering::Release);
+            })
+        };
+        thread::sleep(std::time::Duration::from_millis(50));
+        assert_eq!(done.load(Ordering::Acquire), 0, "producer should block while the queue is full");
+        assert!(Counted(ran.clone(), None).try_spawn(&pool).is_err());
+
+        release_tx.send(()).unwrap();
+        producer.join().unwrap();
+        drop(Arc::try_unwrap(pool).ok().expect("producer released its handle"));
+        assert_eq!(ran.load(Ordering::Acquire), 3);
+    }
+
+    #[test]
+    fn map_reduce_matches_sequential_sum_and_max() {
+        let pool = ThreadPool::new(4);
+        let values: Vec<i64> = (0..100_000).map(|i| (i * 7919) % 10_007 - 5_000).collect();
+
+        let sum = pool.map_reduce(values.clone(), |v| v * 2, 0i64, |a, b| a + b);
+        assert_eq!(sum, values.iter().map(|v| v * 2).sum::<i64>());
+
+        let max = pool.map_reduce(values.clone(), |v| v, i64::MIN, |a, b| a.max(b));
+        assert_eq!(max, *values.iter().max().unwrap());
+
+        assert_eq!(pool.map_reduce(Vec::<i64>::new(), |v| v, 0, |a, b| a + b), 0);
+    }
+
+    #[test]
+    fn hooks_fire_once_per_job_in_order() {
+        let events: Arc<Mutex<Vec<(&'static str, JobId)>>> = Arc::new(Mutex::new(Vec::new()));
+        let (starts, ends) = (events.clone(), events.clone());
+        let pool = ThreadPool::new(1).with_hooks(Hooks {
+            on_start: Box::new(move |id| starts.lock().unwrap().push(("start", id))),
+            on_end: Box::new(move |id| ends.lock().unwrap().push(("end", id))),
+        });
+        for _ in 0..3 {
+            let ev = events.clone();
+            pool.schedule(Box::new(move || ev.lock().unwrap().push(("run", JobId(u64::MAX)))));
+        }
+        // Dropping the pool joins the worker, so every on_end has fired
+        drop(pool);
+        let ev = events.lock().unwrap().clone();
+        let expected: Vec<_> = (0..3).flat_map(|i| [("start", JobId(i)), ("run", JobId(u64::MAX)), ("end", JobId(i))]).collect();
+        assert_eq!(ev, expected);
+    }
+
+    #[test]
+    fn timeout_is_reported_and_cancels_token() {
+        let pool = ThreadPool::new(2);
+        let stopped_early = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let flag = stopped_early.clone();
+        let slow = pool.schedule_with_timeout(Duration::from_millis(20), move |token| {
+            for _ in 0..100 {
+                if token.is_cancelled() { flag.store(true, Ordering::SeqCst); return; }
+                thread::sleep(Duration::from_millis(5));
+            }
+        });
+        assert_eq!(slow.wait(), JobOutcome::TimedOut);
+        assert!(slow.token().is_cancelled());
+
+        struct Quick;
+        impl JobRun for Quick { fn run(self) {} }
+        let quick = Quick.spawn_with_timeout(&pool, Duration::from_secs(5));
+        assert_eq!(quick.wait(), JobOutcome::Completed);
+        assert!(!quick.token().is_cancelled());
+        // Dropping the pool joins the workers, so the slow job has observed its token
+        drop(pool);
+        assert!(stopped_early.load(Ordering::SeqCst));
+        assert_eq!(slow.outcome(), Some(JobOutcome::TimedOut));
+    }
+
+    #[test]
+    fn panic_before_deadline_is_not_a_timeout() {
+        let pool = ThreadPool::new(1);
+        let boom = pool.schedule_with_timeout(Duration::from_secs(5), |_| panic!("boom"));
+        assert_eq!(boom.wait(), JobOutcome::Panicked);
+        assert!(!boom.token().is_cancelled());
+        // The worker survives the panic and keeps serving jobs
+        let after = pool.schedule_with_timeout(Duration::from_secs(5), |_| {});
+        assert_eq!(after.wait(), JobOutcome::Completed);
+    }
+
+    struct Fixed(&'static str, &'static [StageDecl]);
+    impl StageGraph for Fixed {
+        fn schedule_label(&self) -> &'static str { self.0 }
+        fn stages(&self) -> &'static [StageDecl] { self.1 }
+        fn local_order(&self) -> &'static [usize] { &[] }
+        fn run_stage(&self, _index: usize, _sched: &dyn Scheduler) {}
+    }
+
+    #[test]
+    fn merge_reports_unknown_labels_and_cycles() {
+        let a = Fixed("A", &[StageDecl { name: "x", after: &["B::y"], before: &[] }]);
+        let b = Fixed("B", &[StageDecl { name: "y", after: &["A::x"], before: &[] }]);
+        let err = MergedSchedule::new(&[&a, &b]).err().unwrap();
+        assert_eq!(err, ScheduleMergeError::Cycle(vec!["A::x".into(), "B::y".into()]));
+        let err = MergedSchedule::new(&[&a]).err().unwrap();
+        assert_eq!(err, ScheduleMergeError::UnknownStage { stage: "A::x".into(), target: "B::y".into() });
+        let err = MergedSchedule::new(&[&b, &b]).err().unwrap();
+        assert_eq!(err, ScheduleMergeError::DuplicateSchedule("B".into()));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn rayon_join_all_runs_every_job() {
+        let pool = Arc::new(rayon::ThreadPoolBuilder::new().num_threads(2).build().unwrap());
+        let sched = RayonScheduler::with_pool(pool);
+        let n = Arc::new(AtomicUsize::new(0));
+        let jobs: Vec<Job> = (0..8).map(|_| { let n2 = n.clone(); Box::new(move || { n2.fetch_add(1, Ordering::AcqRel); }) as Job }).collect();
+        join_all(&sched, jobs);
+        assert_eq!(n.load(Ordering::Acquire), 8);
+    }
 }
@@ -84,6 +84,48 @@ pub fn unwrap_phantom_data(ty: &Type) -> Option<&Type> {
     None
 }
 
+/// If type is a reference `&'a T` / `&mut T`, return its lifetime (`None` when elided) and mutability
+pub fn is_reference(ty: &Type) -> Option<(Option<&syn::Lifetime>, bool)> {
+    match ty {
+        Type::Reference(r) => Some((r.lifetime.as_ref(), r.mutability.is_some())),
+        Type::Paren(p) => is_reference(&p.elem),
+        Type::Group(g) => is_reference(&g.elem),
+        _ => None,
+    }
+}
+
+/// Returns true if the type borrows: it is or contains a reference, or names a lifetime anywhere
+/// (`&str`, `Cow<'a, str>`, `Box<dyn Trait + 'a>`). Function pointer signatures are not inspected.
+pub fn type_has_lifetime(ty: &Type) -> bool {
+    fn bounds_have_lifetime<'a>(mut bounds: impl Iterator<Item = &'a syn::TypeParamBound>) -> bool {
+        bounds.any(|b| matches!(b, syn::TypeParamBound::Lifetime(_)))
+    }
+    match ty {
+        Type::Reference(_) => true,
+        Type::Path(tp) => {
+            tp.qself.as_ref().is_some_and(|q| type_has_lifetime(&q.ty))
+                || tp.path.segments.iter().any(|seg| match &seg.arguments {
+                    PathArguments::AngleBracketed(ab) => ab.args.iter().any(|ga| match ga {
+                        GenericArgument::Lifetime(_) => true,
+                        GenericArgument::Type(t) => type_has_lifetime(t),
+                        GenericArgument::AssocType(a) => type_has_lifetime(&a.ty),
+                        _ => false,
+                    }),
+                    _ => false,
+                })
+        }
+        Type::Array(a) => type_has_lifetime(&a.elem),
+        Type::Slice(s) => type_has_lifetime(&s.elem),
+        Type::Ptr(p) => type_has_lifetime(&p.elem),
+        Type::Paren(p) => type_has_lifetime(&p.elem),
+        Type::Group(g) => type_has_lifetime(&g.elem),
+        Type::Tuple(t) => t.elems.iter().any(type_has_lifetime),
+        Type::TraitObject(t) => bounds_have_lifetime(t.bounds.iter()),
+        Type::ImplTrait(t) => bounds_have_lifetime(t.bounds.iter()),
+        _ => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -124,4 +166,26 @@ mod tests {
         assert!(is_phantom_data(&ty));
         assert!(unwrap_phantom_data(&ty).is_some());
     }
+
+    #[test]
+    fn reference_and_lifetime_helpers() {
+        let s: Type = parse_quote!(&'a str);
+        let (lt, is_mut) = is_reference(&s).expect("reference");
+        assert_eq!(lt.map(|l| l.ident.to_string()), Some("a".to_string()));
+        assert!(!is_mut);
+        assert!(type_has_lifetime(&s));
+
+        let m: Type = parse_quote!(&mut T);
+        assert!(matches!(is_reference(&m), Some((None, true))));
+        assert!(type_has_lifetime(&m));
+
+        let cow: Type = parse_quote!(std::borrow::Cow<'a, str>);
+        assert!(is_reference(&cow).is_none());
+        assert!(type_has_lifetime(&cow));
+
+        let owned: Type = parse_quote!(Vec<[f32; 3]>);
+        assert!(is_reference(&owned).is_none());
+        assert!(!type_has_lifetime(&owned));
+        assert!(type_has_lifetime(&parse_quote!(Option<(u32, &'static str)>)));
+    }
 }
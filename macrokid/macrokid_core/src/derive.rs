@@ -12,6 +12,29 @@ where
     f(spec)
 }
 
+/// Build the input's `TypeSpec`, run `validate` on it and, if it passes, hand the same spec to
+/// `handler`. Backs `derive_entry!(..., validate = f, spec_handler = h)`; a shared shape check such
+/// as "must be a struct with named fields" lives in `validate` instead of every handler.
+pub fn validate_then<F>(input: DeriveInput, validate: fn(&TypeSpec) -> syn::Result<()>, handler: F) -> syn::Result<TokenStream2>
+where
+    F: FnOnce(TypeSpec) -> syn::Result<TokenStream2>,
+{
+    let spec = TypeSpec::from_derive_input(input)?;
+    validate(&spec)?;
+    handler(spec)
+}
+
+/// Like `validate_then`, but the handler receives the original `DeriveInput`. Backs
+/// `derive_entry!(..., validate = f, handler = h)`.
+pub fn validate_input_then<F>(input: DeriveInput, validate: fn(&TypeSpec) -> syn::Result<()>, handler: F) -> syn::Result<TokenStream2>
+where
+    F: FnOnce(DeriveInput) -> syn::Result<TokenStream2>,
+{
+    let spec = TypeSpec::from_derive_input(input.clone())?;
+    validate(&spec)?;
+    handler(input)
+}
+
 /// Create an ImplBuilder for a given trait path on the target represented by `spec`.
 pub fn impl_for_trait(spec: &TypeSpec, trait_path: TokenStream2) -> ImplBuilder {
     ImplBuilder::new(spec.ident.clone(), spec.generics.clone()).implement_trait(trait_path)
@@ -19,35 +42,122 @@ pub fn impl_for_trait(spec: &TypeSpec, trait_path: TokenStream2) -> ImplBuilder
 
 /// Macro to generate a proc_macro_derive entrypoint with minimal boilerplate.
 /// Must be invoked from a proc-macro crate.
+///
+/// `handler = h` takes the `DeriveInput`; `spec_handler = h` takes the input's `TypeSpec` instead.
+/// An optional `validate = f` before either runs `f: fn(&TypeSpec) -> syn::Result<()>` first and
+/// reports its error instead of calling the handler (see `validate_then`).
 #[macro_export]
 macro_rules! derive_entry {
     (
         $name:ident,
         attrs = [ $( $attr:ident ),* $(,)? ],
+        validate = $validate:path,
+        spec_handler = $handler:path
+    ) => {
+        $crate::derive_entry!(@emit $name, ($name, attributes( $( $attr ),* )), |di| $crate::derive::validate_then(di, $validate, $handler));
+    };
+    (
+        $name:ident,
+        validate = $validate:path,
+        spec_handler = $handler:path
+    ) => {
+        $crate::derive_entry!(@emit $name, ($name), |di| $crate::derive::validate_then(di, $validate, $handler));
+    };
+    (
+        $name:ident,
+        attrs = [ $( $attr:ident ),* $(,)? ],
+        spec_handler = $handler:path
+    ) => {
+        $crate::derive_entry!(@emit $name, ($name, attributes( $( $attr ),* )), |di| $crate::derive::with_type_spec(di, $handler));
+    };
+    (
+        $name:ident,
+        spec_handler = $handler:path
+    ) => {
+        $crate::derive_entry!(@emit $name, ($name), |di| $crate::derive::with_type_spec(di, $handler));
+    };
+    (
+        $name:ident,
+        attrs = [ $( $attr:ident ),* $(,)? ],
+        validate = $validate:path,
         handler = $handler:path
     ) => {
-        #[allow(non_snake_case)]
-        #[proc_macro_derive($name, attributes( $( $attr ),* ))]
-        pub fn $name(input: ::proc_macro::TokenStream) -> ::proc_macro::TokenStream {
-            let di: ::syn::DeriveInput = ::syn::parse_macro_input!(input as ::syn::DeriveInput);
-            match $handler(di) {
-                Ok(ts) => ts.into(),
-                Err(e) => e.to_compile_error().into(),
-            }
-        }
+        $crate::derive_entry!(@emit $name, ($name, attributes( $( $attr ),* )), |di| $crate::derive::validate_input_then(di, $validate, $handler));
     };
     (
         $name:ident,
+        validate = $validate:path,
         handler = $handler:path
     ) => {
+        $crate::derive_entry!(@emit $name, ($name), |di| $crate::derive::validate_input_then(di, $validate, $handler));
+    };
+    (
+        $name:ident,
+        attrs = [ $( $attr:ident ),* $(,)? ],
+        handler = $handler:path
+    ) => {
+        $crate::derive_entry!(@emit $name, ($name, attributes( $( $attr ),* )), |di| $handler(di));
+    };
+    (
+        $name:ident,
+        handler = $handler:path
+    ) => {
+        $crate::derive_entry!(@emit $name, ($name), |di| $handler(di));
+    };
+    // Shared entry body: `$derive` is the `proc_macro_derive(...)` argument list
+    (@emit $name:ident, ( $( $derive:tt )* ), |$di:ident| $call:expr) => {
         #[allow(non_snake_case)]
-        #[proc_macro_derive($name)]
+        #[proc_macro_derive( $( $derive )* )]
         pub fn $name(input: ::proc_macro::TokenStream) -> ::proc_macro::TokenStream {
-            let di: ::syn::DeriveInput = ::syn::parse_macro_input!(input as ::syn::DeriveInput);
-            match $handler(di) {
+            let $di: ::syn::DeriveInput = ::syn::parse_macro_input!(input as ::syn::DeriveInput);
+            match $call {
                 Ok(ts) => ts.into(),
                 Err(e) => e.to_compile_error().into(),
             }
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    fn no_enums(spec: &TypeSpec) -> syn::Result<()> {
+        spec.require_struct().map(|_| ())
+    }
+
+    fn handler(spec: TypeSpec) -> syn::Result<TokenStream2> {
+        let ident = spec.ident;
+        Ok(quote::quote! { impl #ident {} })
+    }
+
+    #[test]
+    fn validate_then_short_circuits_at_type() {
+        let di: DeriveInput = syn::parse_str("enum Mode { A, B }").unwrap();
+        let ident_span = di.ident.span();
+        let err = validate_then(di, no_enums, |_| panic!("handler must not run")).unwrap_err();
+        assert_eq!(err.to_string(), "`Mode` must be a struct, found an enum");
+        let (got, want) = (err.span().start(), ident_span.start());
+        assert_eq!((got.line, got.column), (want.line, want.column));
+        assert_eq!(want.column, 5);
+
+        let ok: DeriveInput = parse_quote! { struct Cfg { a: u8 } };
+        assert_eq!(validate_then(ok, no_enums, handler).unwrap().to_string(), "impl Cfg { }");
+    }
+
+    #[test]
+    fn validate_input_then_passes_the_derive_input() {
+        let di: DeriveInput = parse_quote! { enum Mode { A, B } };
+        let err = validate_input_then(di, no_enums, |_| panic!("handler must not run")).unwrap_err();
+        assert_eq!(err.to_string(), "`Mode` must be a struct, found an enum");
+
+        let ok: DeriveInput = parse_quote! { #[doc = "kept"] struct Cfg { a: u8 } };
+        let out = validate_input_then(ok, no_enums, |di| {
+            assert_eq!(di.attrs.len(), 1);
+            let ident = di.ident;
+            Ok(quote::quote! { impl #ident {} })
+        });
+        assert_eq!(out.unwrap().to_string(), "impl Cfg { }");
+    }
+}
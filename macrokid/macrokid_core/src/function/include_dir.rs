@@ -0,0 +1,98 @@
+use std::path::Path;
+
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{parse::{Parse, ParseStream}, LitStr};
+
+/// Input structure for include_dir_modules!("dir") macro
+pub struct IncludeDirInput {
+    pub dir: LitStr,
+}
+
+impl Parse for IncludeDirInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let dir: LitStr = input.parse()?;
+        Ok(Self { dir })
+    }
+}
+
+/// Turn a file name into a SCREAMING_SNAKE_CASE const identifier (`toon.vert` -> `TOON_VERT`).
+pub fn sanitize_const_name(file_name: &str) -> String {
+    let mut out: String = file_name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' })
+        .collect();
+    if out.is_empty() || out.starts_with(|c: char| c.is_ascii_digit()) {
+        out.insert(0, '_');
+    }
+    out
+}
+
+/// Generate one `pub const` per file in `dir` (resolved against `manifest_dir`).
+///
+/// UTF-8 files become `&str` via `include_str!`; anything else (e.g. SPIR-V) becomes
+/// `&[u8]` via `include_bytes!`. Subdirectories are skipped and entries are sorted so
+/// the expansion is deterministic.
+pub fn expand_include_dir_modules(input: IncludeDirInput, manifest_dir: &Path) -> syn::Result<TokenStream2> {
+    let dir = manifest_dir.join(input.dir.value());
+    if !dir.is_dir() {
+        return Err(syn::Error::new(input.dir.span(), format!("directory not found: {}", dir.display())));
+    }
+
+    let read_err = |e: std::io::Error| syn::Error::new(input.dir.span(), format!("failed to read {}: {}", dir.display(), e));
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(&dir).map_err(read_err)? {
+        let path = entry.map_err(read_err)?.path();
+        if path.is_file() { files.push(path); }
+    }
+    files.sort();
+
+    let mut seen = std::collections::HashSet::new();
+    let mut consts = Vec::with_capacity(files.len());
+    for path in files {
+        let file_name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+        let name = sanitize_const_name(&file_name);
+        if !seen.insert(name.clone()) {
+            return Err(syn::Error::new(input.dir.span(), format!("file '{}' maps to duplicate const name '{}'", file_name, name)));
+        }
+        let ident = Ident::new(&name, Span::call_site());
+        let abs = path.to_string_lossy().into_owned();
+        let bytes = std::fs::read(&path).map_err(read_err)?;
+        consts.push(if std::str::from_utf8(&bytes).is_ok() {
+            quote! { pub const #ident: &str = include_str!(#abs); }
+        } else {
+            quote! { pub const #ident: &[u8] = include_bytes!(#abs); }
+        });
+    }
+
+    Ok(quote! { #( #consts )* })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn sanitizes_file_names() {
+        assert_eq!(sanitize_const_name("toon.vert"), "TOON_VERT");
+        assert_eq!(sanitize_const_name("gbuffer-pass.frag.spv"), "GBUFFER_PASS_FRAG_SPV");
+        assert_eq!(sanitize_const_name("1x1.png"), "_1X1_PNG");
+    }
+
+    #[test]
+    fn expands_fixture_directory() {
+        let input = IncludeDirInput { dir: parse_quote!("tests/fixtures/include_dir") };
+        let out = expand_include_dir_modules(input, Path::new(env!("CARGO_MANIFEST_DIR"))).unwrap().to_string();
+        assert!(out.contains("pub const NOTES_TXT : & str = include_str !"));
+        assert!(out.contains("pub const SHADER_VERT : & str = include_str !"));
+        assert!(out.contains("pub const BLOB_BIN : & [u8] = include_bytes !"));
+    }
+
+    #[test]
+    fn missing_directory_errors() {
+        let input = IncludeDirInput { dir: parse_quote!("tests/fixtures/does_not_exist") };
+        let err = expand_include_dir_modules(input, Path::new(env!("CARGO_MANIFEST_DIR"))).unwrap_err();
+        assert!(err.to_string().contains("directory not found"));
+    }
+}
@@ -78,9 +78,22 @@ pub fn expand_make_enum(input: MakeEnumInput) -> TokenStream2 {
         })
         .build();
 
+    // Every variant in declaration order, for menus and exhaustive tests
+    let all_impl = ImplBuilder::new(enum_name.clone(), syn::Generics::default())
+        .add_method(quote! {
+            pub const ALL: &'static [Self] = &[ #( Self::#variant_names ),* ];
+        })
+        .add_method(quote! {
+            pub fn iter() -> impl ::core::iter::Iterator<Item = Self> {
+                Self::ALL.iter().copied()
+            }
+        })
+        .build();
+
     quote! {
         #enum_def
         #display_impl
         #from_str_impl
+        #all_impl
     }
 }
\ No newline at end of file
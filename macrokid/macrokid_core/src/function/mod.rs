@@ -1,3 +1,5 @@
 // Function-like macro helpers
 pub mod make_enum;
-pub mod bracket_enum;
\ No newline at end of file
+pub mod bracket_enum;
+pub mod include_dir;
+pub mod state_machine;
@@ -0,0 +1,205 @@
+use proc_macro2::{Ident, Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{bracketed, braced, ext::IdentExt, parse::{Parse, ParseStream}, punctuated::Punctuated, Token};
+
+/// Input structure for
+/// `state_machine!(Door { states: [Closed, Open], transitions: [Closed -> Open on open] })`
+pub struct StateMachineInput {
+    pub name: Ident,
+    pub states: Vec<Ident>,
+    pub transitions: Vec<Transition>,
+}
+
+/// One `From -> To on event` row of the transition table
+pub struct Transition {
+    pub from: Ident,
+    pub to: Ident,
+    pub event: Ident,
+}
+
+impl Parse for Transition {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let from: Ident = input.parse()?;
+        input.parse::<Token![->]>()?;
+        let to: Ident = input.parse()?;
+        let on: Ident = input.parse()?;
+        if on != "on" {
+            return Err(syn::Error::new(on.span(), "expected `on <event>` after the target state"));
+        }
+        let event: Ident = input.parse()?;
+        Ok(Self { from, to, event })
+    }
+}
+
+impl Parse for StateMachineInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let name: Ident = input.parse()?;
+        let body;
+        braced!(body in input);
+
+        let mut states: Option<Vec<Ident>> = None;
+        let mut transitions: Option<Vec<Transition>> = None;
+        while !body.is_empty() {
+            let key: Ident = body.parse()?;
+            body.parse::<Token![:]>()?;
+            let list;
+            bracketed!(list in body);
+            match key.to_string().as_str() {
+                "states" if states.is_none() => {
+                    states = Some(Punctuated::<Ident, Token![,]>::parse_terminated(&list)?.into_iter().collect());
+                }
+                "transitions" if transitions.is_none() => {
+                    transitions = Some(Punctuated::<Transition, Token![,]>::parse_terminated(&list)?.into_iter().collect());
+                }
+                "states" | "transitions" => return Err(syn::Error::new(key.span(), "duplicate key")),
+                _ => return Err(syn::Error::new(key.span(), "unknown key: expected `states` or `transitions`")),
+            }
+            if !body.is_empty() { body.parse::<Token![,]>()?; }
+        }
+
+        let states = states.ok_or_else(|| syn::Error::new(name.span(), "missing `states: [..]`"))?;
+        let transitions = transitions.unwrap_or_default();
+        Ok(Self { name, states, transitions })
+    }
+}
+
+/// `open_door` -> `OpenDoor`, used to turn event names into enum variants. Errors when the
+/// result is not a usable variant name (`__` has no words, `_2fast` starts with a digit).
+fn event_variant(event: &Ident) -> syn::Result<Ident> {
+    let name = event.unraw().to_string();
+    let pascal = crate::ir::rename::to_pascal(name.trim_matches('_'));
+    syn::parse_str::<Ident>(&pascal)
+        .map(|_| Ident::new(&pascal, event.span()))
+        .map_err(|_| syn::Error::new(event.span(), format!("event '{}' does not form a valid variant name (got '{}')", name, pascal)))
+}
+
+/// Generate the state enum, an `<Name>Event` enum, an `<Name>InvalidTransition` error and
+/// `step`/`TRANSITIONS` on the state enum.
+///
+/// Every transition endpoint must be a declared state and each `(state, event)` pair may appear
+/// at most once so that `step` is deterministic.
+pub fn expand_state_machine(input: StateMachineInput) -> syn::Result<TokenStream2> {
+    let name = &input.name;
+    if input.states.is_empty() {
+        return Err(syn::Error::new(name.span(), "state machine needs at least one state"));
+    }
+    for (i, s) in input.states.iter().enumerate() {
+        if input.states[..i].contains(s) {
+            return Err(syn::Error::new(s.span(), format!("duplicate state '{}'", s)));
+        }
+    }
+
+    let mut events: Vec<Ident> = Vec::new();
+    for (i, t) in input.transitions.iter().enumerate() {
+        for endpoint in [&t.from, &t.to] {
+            if !input.states.contains(endpoint) {
+                return Err(syn::Error::new(endpoint.span(), format!("'{}' is not a declared state of {}", endpoint, name)));
+            }
+        }
+        if input.transitions[..i].iter().any(|p| p.from == t.from && p.event == t.event) {
+            return Err(syn::Error::new(t.event.span(), format!("duplicate transition from '{}' on '{}'", t.from, t.event)));
+        }
+        let ev = event_variant(&t.event)?;
+        if !events.contains(&ev) { events.push(ev); }
+    }
+
+    let event_enum = Ident::new(&format!("{}Event", name), Span::call_site());
+    let error_ty = Ident::new(&format!("{}InvalidTransition", name), Span::call_site());
+    let states = &input.states;
+    let froms: Vec<&Ident> = input.transitions.iter().map(|t| &t.from).collect();
+    let tos: Vec<&Ident> = input.transitions.iter().map(|t| &t.to).collect();
+    let evs: Vec<Ident> = input.transitions.iter().map(|t| event_variant(&t.event)).collect::<syn::Result<_>>()?;
+
+    Ok(quote! {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum #name {
+            #( #states ),*
+        }
+
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum #event_enum {
+            #( #events ),*
+        }
+
+        /// Returned by `step` when the current state has no transition for the event.
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub struct #error_ty {
+            pub from: #name,
+            pub event: #event_enum,
+        }
+
+        impl ::core::fmt::Display for #error_ty {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                write!(f, "no transition from {:?} on {:?}", self.from, self.event)
+            }
+        }
+
+        impl ::std::error::Error for #error_ty {}
+
+        impl #name {
+            /// Transition table as `(from, event, to)` rows, in declaration order.
+            pub const TRANSITIONS: &'static [(#name, #event_enum, #name)] = &[
+                #( (#name::#froms, #event_enum::#evs, #name::#tos) ),*
+            ];
+
+            /// Apply `event`, returning the next state or an error if the table has no such edge.
+            #[allow(unreachable_patterns)]
+            pub fn step(self, event: #event_enum) -> ::core::result::Result<Self, #error_ty> {
+                match (self, event) {
+                    #( (#name::#froms, #event_enum::#evs) => ::core::result::Result::Ok(#name::#tos), )*
+                    (from, event) => ::core::result::Result::Err(#error_ty { from, event }),
+                }
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn expands_door_machine() {
+        let input: StateMachineInput = parse_quote! {
+            Door {
+                states: [Closed, Open, Locked],
+                transitions: [Closed -> Open on open, Open -> Closed on close, Closed -> Locked on lock, Locked -> Closed on unlock],
+            }
+        };
+        let out = expand_state_machine(input).unwrap().to_string();
+        assert!(out.contains("pub enum Door { Closed , Open , Locked }"));
+        assert!(out.contains("pub enum DoorEvent { Open , Close , Lock , Unlock }"));
+        assert!(out.contains("(Door :: Closed , DoorEvent :: Open) => :: core :: result :: Result :: Ok (Door :: Open)"));
+        assert!(out.contains("pub struct DoorInvalidTransition"));
+    }
+
+    #[test]
+    fn rejects_undeclared_endpoint() {
+        let input: StateMachineInput = parse_quote! { Door { states: [Closed, Open], transitions: [Closed -> Ajar on nudge] } };
+        let err = expand_state_machine(input).unwrap_err();
+        assert!(err.to_string().contains("'Ajar' is not a declared state of Door"));
+    }
+
+    #[test]
+    fn rejects_ambiguous_transition() {
+        let input: StateMachineInput = parse_quote! { Door { states: [Closed, Open, Locked], transitions: [Closed -> Open on open, Closed -> Locked on open] } };
+        assert!(expand_state_machine(input).unwrap_err().to_string().contains("duplicate transition"));
+    }
+
+    #[test]
+    fn event_names_become_pascal_case() {
+        assert_eq!(event_variant(&parse_quote!(force_open)).unwrap().to_string(), "ForceOpen");
+        assert_eq!(event_variant(&parse_quote!(lock)).unwrap().to_string(), "Lock");
+        assert_eq!(event_variant(&parse_quote!(r#type)).unwrap().to_string(), "Type");
+    }
+
+    #[test]
+    fn unusable_event_names_are_errors() {
+        let input: StateMachineInput = parse_quote! { Door { states: [Closed, Open], transitions: [Closed -> Open on __] } };
+        let err = expand_state_machine(input).unwrap_err();
+        assert_eq!(err.to_string(), "event '__' does not form a valid variant name (got '')");
+        assert!(event_variant(&parse_quote!(_2fast)).is_err());
+        assert!(event_variant(&parse_quote!(self_)).is_err());
+    }
+}
@@ -1,5 +1,5 @@
 use proc_macro2::Span;
-use syn::{spanned::Spanned, Attribute, Data, DeriveInput, Field, Fields, Generics, Ident, Type, Visibility, Expr};
+use syn::{spanned::Spanned, Attribute, Data, DeriveInput, Field, Fields, Generics, Ident, Type, TypeParamBound, Visibility, WherePredicate, Expr};
 
 #[derive(Debug, Clone)]
 pub struct TypeSpec {
@@ -15,6 +15,7 @@ pub struct TypeSpec {
 pub enum TypeKind {
     Struct(StructSpec),
     Enum(EnumSpec),
+    Union(UnionSpec),
 }
 
 #[derive(Debug, Clone)]
@@ -27,6 +28,12 @@ pub struct EnumSpec {
     pub variants: Vec<VariantSpec>,
 }
 
+/// Union fields are always named; kept as `FieldSpec`s so field helpers work unchanged.
+#[derive(Debug, Clone)]
+pub struct UnionSpec {
+    pub fields: Vec<FieldSpec>,
+}
+
 #[derive(Debug, Clone)]
 pub struct VariantSpec {
     pub ident: Ident,
@@ -51,6 +58,8 @@ pub struct FieldSpec {
     pub vis: Visibility,
     pub ty: Type,
     pub span: Span,
+    /// The field's `#[cfg(...)]` attributes (also present in `attrs`).
+    pub cfgs: Vec<Attribute>,
 }
 
 impl TypeSpec {
@@ -58,10 +67,84 @@ impl TypeSpec {
     pub fn is_struct(&self) -> bool { matches!(self.kind, TypeKind::Struct(_)) }
     /// Returns true if this is an enum
     pub fn is_enum(&self) -> bool { matches!(self.kind, TypeKind::Enum(_)) }
+    /// Returns true if this is a union
+    pub fn is_union(&self) -> bool { matches!(self.kind, TypeKind::Union(_)) }
     /// Borrow as StructSpec if struct
     pub fn as_struct(&self) -> Option<&StructSpec> { if let TypeKind::Struct(ref s) = self.kind { Some(s) } else { None } }
     /// Borrow as EnumSpec if enum
     pub fn as_enum(&self) -> Option<&EnumSpec> { if let TypeKind::Enum(ref e) = self.kind { Some(e) } else { None } }
+    /// Borrow as UnionSpec if union
+    pub fn as_union(&self) -> Option<&UnionSpec> { if let TypeKind::Union(ref u) = self.kind { Some(u) } else { None } }
+    /// Declared bounds of each type parameter, in declaration order. Bounds from the parameter list
+    /// (`T: Clone`) come first, followed by `where T: ...` predicates on that bare parameter.
+    pub fn generic_bounds(&self) -> Vec<(Ident, Vec<TypeParamBound>)> {
+        let mut out: Vec<(Ident, Vec<TypeParamBound>)> = self
+            .generics
+            .type_params()
+            .map(|tp| (tp.ident.clone(), tp.bounds.iter().cloned().collect()))
+            .collect();
+        if let Some(wc) = &self.generics.where_clause {
+            for pred in &wc.predicates {
+                let WherePredicate::Type(pt) = pred else { continue };
+                let Type::Path(tp) = &pt.bounded_ty else { continue };
+                if tp.qself.is_some() { continue; }
+                let Some(id) = tp.path.get_ident() else { continue };
+                if let Some((_, bounds)) = out.iter_mut().find(|(p, _)| p == id) {
+                    bounds.extend(pt.bounds.iter().cloned());
+                }
+            }
+        }
+        out
+    }
+    /// The type parameters in declaration order (lifetimes and const params excluded).
+    pub fn type_params(&self) -> Vec<&syn::TypeParam> { self.generics.type_params().collect() }
+    /// True if type parameter `param` declares a trait bound whose last path segment is `trait_name`
+    /// (so `Clone`, `std::clone::Clone` and `::core::clone::Clone` all match "Clone").
+    pub fn has_bound(&self, param: &str, trait_name: &str) -> bool {
+        self.generic_bounds().iter().filter(|(p, _)| p == param).flat_map(|(_, b)| b).any(|b| {
+            matches!(b, TypeParamBound::Trait(t) if t.path.segments.last().is_some_and(|s| s.ident == trait_name))
+        })
+    }
+    /// The type's doc comment, one entry per `///` line or `#[doc = "..."]` attribute.
+    pub fn docs(&self) -> Vec<String> { doc_lines(&self.attrs) }
+    /// What kind of item this is, for error messages ("an enum", "a tuple struct", ...).
+    fn kind_name(&self) -> &'static str {
+        match &self.kind {
+            TypeKind::Struct(st) => match st.fields {
+                FieldKind::Named(_) => "a struct",
+                FieldKind::Unnamed(_) => "a tuple struct",
+                FieldKind::Unit => "a unit struct",
+            },
+            TypeKind::Enum(_) => "an enum",
+            TypeKind::Union(_) => "a union",
+        }
+    }
+    /// Guard: error at the type name unless this is a struct. Chains with the other `require_*`/`forbid_*` guards.
+    pub fn require_struct(&self) -> syn::Result<&Self> {
+        if self.is_struct() { return Ok(self); }
+        Err(syn::Error::new(self.span, format!("`{}` must be a struct, found {}", self.ident, self.kind_name())))
+    }
+    /// Guard: error at the type name unless this is an enum.
+    pub fn require_enum(&self) -> syn::Result<&Self> {
+        if self.is_enum() { return Ok(self); }
+        Err(syn::Error::new(self.span, format!("`{}` must be an enum, found {}", self.ident, self.kind_name())))
+    }
+    /// Guard: error at the type name unless this is a struct with named fields.
+    pub fn require_named(&self) -> syn::Result<&Self> {
+        self.named_fields().map(|_| self)
+    }
+    /// Guard: error at the generic parameter list if the type declares any generics (incl. lifetimes).
+    pub fn forbid_generics(&self) -> syn::Result<&Self> {
+        if self.generics.params.is_empty() { return Ok(self); }
+        Err(syn::Error::new_spanned(&self.generics, format!("`{}` must not be generic", self.ident)))
+    }
+    /// Fields of a named-field struct, with the same error as [`TypeSpec::require_named`] otherwise.
+    pub fn named_fields(&self) -> syn::Result<&[FieldSpec]> {
+        match &self.kind {
+            TypeKind::Struct(StructSpec { fields: FieldKind::Named(v) }) => Ok(v),
+            _ => Err(syn::Error::new(self.span, format!("`{}` must be a struct with named fields, found {}", self.ident, self.kind_name()))),
+        }
+    }
     pub fn from_derive_input(input: DeriveInput) -> syn::Result<Self> {
         let span = input.ident.span();
         let ident = input.ident;
@@ -87,9 +170,15 @@ impl TypeSpec {
                 }
                 TypeKind::Enum(EnumSpec { variants })
             }
-            Data::Union(u) => {
-                return Err(syn::Error::new(u.union_token.span(), "unions are not supported"));
-            }
+            Data::Union(u) => TypeKind::Union(UnionSpec {
+                fields: u
+                    .fields
+                    .named
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, f)| FieldSpec::from_field(f, i))
+                    .collect(),
+            }),
         };
 
         Ok(Self {
@@ -103,6 +192,47 @@ impl TypeSpec {
     }
 }
 
+/// Values of `#[doc = "..."]` attributes in order, each without the single leading space that
+/// `/// text` produces. `#[doc(hidden)]` and other list forms are skipped.
+fn doc_lines(attrs: &[Attribute]) -> Vec<String> {
+    attrs
+        .iter()
+        .filter(|a| a.path().is_ident("doc"))
+        .filter_map(|a| match &a.meta {
+            syn::Meta::NameValue(nv) => match &nv.value {
+                Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }) => Some(s.value()),
+                _ => None,
+            },
+            _ => None,
+        })
+        .map(|line| line.strip_prefix(' ').map(str::to_string).unwrap_or(line))
+        .collect()
+}
+
+/// Whether `ident` appears as a standalone name in `ts`: not a later path segment (`a::T`),
+/// not a lifetime (`'T`) and not a field access.
+fn tokens_mention(ts: proc_macro2::TokenStream, ident: &Ident) -> bool {
+    use proc_macro2::TokenTree;
+    let mut prev: Option<TokenTree> = None;
+    for tt in ts {
+        match &tt {
+            TokenTree::Group(g) if tokens_mention(g.stream(), ident) => return true,
+            TokenTree::Ident(id) if id == ident => {
+                let qualified = matches!(&prev, Some(TokenTree::Punct(p)) if matches!(p.as_char(), ':' | '\'' | '.'));
+                if !qualified { return true; }
+            }
+            _ => {}
+        }
+        prev = Some(tt);
+    }
+    false
+}
+
+impl VariantSpec {
+    /// The variant's doc comment lines; see `TypeSpec::docs`.
+    pub fn docs(&self) -> Vec<String> { doc_lines(&self.attrs) }
+}
+
 fn span_for_variant(fields: &FieldKind) -> Span {
     match fields {
         FieldKind::Named(v) | FieldKind::Unnamed(v) => v
@@ -143,7 +273,35 @@ impl FieldSpec {
     fn from_field(field: Field, index: usize) -> Self {
         let span = field.span();
         let ty = field.ty.clone();
-        Self { ident: field.ident, index, attrs: field.attrs, vis: field.vis, ty, span }
+        let cfgs = field.attrs.iter().filter(|a| a.path().is_ident("cfg")).cloned().collect();
+        Self { ident: field.ident, index, attrs: field.attrs, vis: field.vis, ty, span, cfgs }
+    }
+
+    /// `#[cfg(...)]` attributes gating this field. Derives that emit per-field items (slice
+    /// entries, size terms) should repeat them so the output matches the compiled struct.
+    pub fn cfg_attrs(&self) -> &[Attribute] { &self.cfgs }
+
+    /// The field's doc comment lines; see `TypeSpec::docs`. Join with `"\n"` for the full text.
+    pub fn docs(&self) -> Vec<String> { doc_lines(&self.attrs) }
+
+    /// True if the field's type refers to type parameter `ident` anywhere (`T`, `Vec<T>`,
+    /// `T::Output`, `[T; 4]`, ...). Use it to bound only the parameters a derive's fields use.
+    pub fn mentions_type_param(&self, ident: &Ident) -> bool {
+        use quote::ToTokens;
+        tokens_mention(self.ty.to_token_stream(), ident)
+    }
+
+    /// True when the field only exists under some `#[cfg(...)]`.
+    pub fn is_cfg_gated(&self) -> bool { !self.cfgs.is_empty() }
+
+    /// The field name in another case style, spanned at the field (tuple fields use `field<N>`).
+    /// A raw identifier's `r#` prefix is dropped before converting.
+    pub fn rename(&self, style: rename::RenameStyle) -> Ident {
+        let name = match &self.ident {
+            Some(id) => id.to_string().trim_start_matches("r#").to_string(),
+            None => format!("field{}", self.index),
+        };
+        Ident::new(&style.apply(&name), self.span)
     }
 }
 
@@ -158,6 +316,11 @@ impl StructSpec {
     pub fn is_unit(&self) -> bool { matches!(self.fields, FieldKind::Unit) }
 }
 
+impl UnionSpec {
+    pub fn fields(&self) -> &[FieldSpec] { &self.fields }
+    pub fn field_count(&self) -> usize { self.fields.len() }
+}
+
 impl EnumSpec {
     pub fn variants(&self) -> &[VariantSpec] { &self.variants }
     pub fn variant_count(&self) -> usize { self.variants.len() }
@@ -167,3 +330,267 @@ impl EnumSpec {
     /// Collect fields of all variants for quick scans
     pub fn fields_of_variants(&self) -> Vec<&FieldKind> { self.variants.iter().map(|v| &v.fields).collect() }
 }
+
+/// Case conversions for generated identifiers and renamed keys.
+///
+/// Words are split at underscores, at lower-to-upper boundaries (`fooBar`) and before the last
+/// capital of an acronym (`HTTPServer` -> `http`, `server`). Digits stay attached to the word
+/// they follow. Leading and trailing underscores are kept as-is.
+pub mod rename {
+    /// Target case style for [`super::FieldSpec::rename`].
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum RenameStyle { Camel, Pascal, Snake, ScreamingSnake }
+
+    impl RenameStyle {
+        /// Accepted spellings for `parse`, serde-style.
+        pub const NAMES: &'static [&'static str] = &["camelCase", "PascalCase", "snake_case", "SCREAMING_SNAKE_CASE"];
+
+        pub fn parse(s: &str) -> Option<Self> {
+            match s {
+                "camelCase" => Some(Self::Camel),
+                "PascalCase" => Some(Self::Pascal),
+                "snake_case" => Some(Self::Snake),
+                "SCREAMING_SNAKE_CASE" => Some(Self::ScreamingSnake),
+                _ => None,
+            }
+        }
+
+        pub fn apply(self, s: &str) -> String {
+            match self {
+                Self::Camel => to_camel(s),
+                Self::Pascal => to_pascal(s),
+                Self::Snake => to_snake(s),
+                Self::ScreamingSnake => to_screaming_snake(s),
+            }
+        }
+    }
+
+    pub fn to_camel(s: &str) -> String {
+        convert(s, "", |i, w| if i == 0 { w.to_ascii_lowercase() } else { capitalize(w) })
+    }
+    pub fn to_pascal(s: &str) -> String { convert(s, "", |_, w| capitalize(w)) }
+    pub fn to_snake(s: &str) -> String { convert(s, "_", |_, w| w.to_ascii_lowercase()) }
+    pub fn to_screaming_snake(s: &str) -> String { convert(s, "_", |_, w| w.to_ascii_uppercase()) }
+
+    fn capitalize(w: &str) -> String {
+        let lower = w.to_ascii_lowercase();
+        let mut chars = lower.chars();
+        chars.next().map(|c| c.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default()
+    }
+
+    fn convert(s: &str, sep: &str, word: impl Fn(usize, &str) -> String) -> String {
+        let core = s.trim_matches('_');
+        let lead = &s[..s.len() - s.trim_start_matches('_').len()];
+        let trail = if core.is_empty() { "" } else { &s[s.trim_end_matches('_').len()..] };
+        let words: Vec<String> = split_words(core).iter().enumerate().map(|(i, w)| word(i, w)).collect();
+        format!("{}{}{}", lead, words.join(sep), trail)
+    }
+
+    fn split_words(s: &str) -> Vec<&str> {
+        let mut words = Vec::new();
+        for part in s.split('_').filter(|p| !p.is_empty()) {
+            let chars: Vec<(usize, char)> = part.char_indices().collect();
+            let mut start = 0;
+            for k in 1..chars.len() {
+                let (prev, (i, c)) = (chars[k - 1].1, chars[k]);
+                let next_lower = matches!(chars.get(k + 1), Some((_, n)) if n.is_ascii_lowercase());
+                let boundary = c.is_ascii_uppercase()
+                    && ((prev.is_ascii_lowercase() || prev.is_ascii_digit()) || (prev.is_ascii_uppercase() && next_lower));
+                if boundary { words.push(&part[start..i]); start = i; }
+            }
+            words.push(&part[start..]);
+        }
+        words
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        #[test]
+        fn case_transforms() {
+            assert_eq!(to_camel("vertex_buffer_id"), "vertexBufferId");
+            assert_eq!(to_pascal("vertex_buffer_id"), "VertexBufferId");
+            assert_eq!(to_snake("VertexBufferId"), "vertex_buffer_id");
+            assert_eq!(to_screaming_snake("vertexBufferId"), "VERTEX_BUFFER_ID");
+            // Acronyms split before their last capital
+            assert_eq!(to_snake("HTTPServer"), "http_server");
+            assert_eq!(to_camel("HTTPServer"), "httpServer");
+            // Idempotent on input already in the target style
+            assert_eq!(to_pascal("AlreadyPascal"), "AlreadyPascal");
+            assert_eq!(to_snake("already_snake"), "already_snake");
+        }
+
+        #[test]
+        fn underscores_and_digits() {
+            // Leading/trailing underscores survive; runs of inner underscores collapse
+            assert_eq!(to_camel("_private_field"), "_privateField");
+            assert_eq!(to_pascal("__raw__name_"), "__RawName_");
+            assert_eq!(to_screaming_snake("_unused"), "_UNUSED");
+            assert_eq!(to_snake("_"), "_");
+            // Digits stay with the preceding word
+            assert_eq!(to_pascal("vec3_to_vec4"), "Vec3ToVec4");
+            assert_eq!(to_snake("uv0Scale"), "uv0_scale");
+            assert_eq!(to_camel("layer_2d"), "layer2d");
+            assert_eq!(to_screaming_snake("mip2Level"), "MIP2_LEVEL");
+        }
+
+        #[test]
+        fn style_parse_and_apply() {
+            assert_eq!(RenameStyle::parse("camelCase").map(|s| s.apply("max_lod")), Some("maxLod".to_string()));
+            assert_eq!(RenameStyle::parse("kebab-case"), None);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn field_rename_uses_style() {
+        let di: DeriveInput = parse_quote! { struct S { r#type: u8, max_lod: f32 } };
+        let spec = TypeSpec::from_derive_input(di).unwrap();
+        let renamed: Vec<String> = spec.named_fields().unwrap().iter().map(|f| f.rename(rename::RenameStyle::Pascal).to_string()).collect();
+        assert_eq!(renamed, vec!["Type", "MaxLod"]);
+        let di: DeriveInput = parse_quote! { struct T(u8); };
+        let spec = TypeSpec::from_derive_input(di).unwrap();
+        let TypeKind::Struct(st) = &spec.kind else { unreachable!() };
+        let FieldKind::Unnamed(fields) = &st.fields else { unreachable!() };
+        assert_eq!(fields[0].rename(rename::RenameStyle::ScreamingSnake).to_string(), "FIELD0");
+    }
+
+    #[test]
+    fn union_spec_from_derive_input() {
+        let di: DeriveInput = parse_quote! {
+            #[repr(C)]
+            union Value { i: i32, f: f32 }
+        };
+        let spec = TypeSpec::from_derive_input(di).unwrap();
+        assert!(spec.is_union());
+        let u = spec.as_union().unwrap();
+        assert_eq!(u.field_count(), 2);
+        let names: Vec<String> = u.fields().iter().map(|f| f.ident.as_ref().unwrap().to_string()).collect();
+        assert_eq!(names, vec!["i", "f"]);
+    }
+
+    #[test]
+    fn generic_bounds_from_params_and_where_clause() {
+        let di: DeriveInput = parse_quote! {
+            struct S<T: Clone + Send, U> where U: std::fmt::Debug, T: 'static { t: T, u: U }
+        };
+        let spec = TypeSpec::from_derive_input(di).unwrap();
+        let bounds = spec.generic_bounds();
+        let rendered: Vec<(String, Vec<String>)> = bounds
+            .iter()
+            .map(|(id, b)| (id.to_string(), b.iter().map(|x| quote::quote!(#x).to_string()).collect()))
+            .collect();
+        assert_eq!(rendered, vec![
+            ("T".to_string(), vec!["Clone".to_string(), "Send".to_string(), "'static".to_string()]),
+            ("U".to_string(), vec!["std :: fmt :: Debug".to_string()]),
+        ]);
+        assert!(spec.has_bound("T", "Clone"));
+        assert!(spec.has_bound("U", "Debug"));
+        assert!(!spec.has_bound("U", "Clone"));
+    }
+
+    #[test]
+    fn records_cfg_attrs_per_field() {
+        let di: DeriveInput = parse_quote! {
+            struct V { pos: [f32; 3], #[cfg(feature = "x")] #[doc = "extra"] extra: u32 }
+        };
+        let spec = TypeSpec::from_derive_input(di).unwrap();
+        let FieldKind::Named(fields) = spec.as_struct().unwrap().fields() else { panic!("named fields") };
+        assert!(!fields[0].is_cfg_gated());
+        let cfgs = fields[1].cfg_attrs();
+        assert_eq!(cfgs.len(), 1);
+        assert_eq!(quote::quote!(#(#cfgs)*).to_string(), "# [cfg (feature = \"x\")]");
+        assert_eq!(fields[1].attrs.len(), 2);
+    }
+
+    fn spec(di: DeriveInput) -> TypeSpec { TypeSpec::from_derive_input(di).unwrap() }
+
+    #[test]
+    fn fields_mention_only_used_type_params() {
+        let s = spec(parse_quote! { struct S<'a, T, U, const N: usize> { a: T, b: i32 } });
+        let params: Vec<String> = s.type_params().iter().map(|p| p.ident.to_string()).collect();
+        assert_eq!(params, vec!["T", "U"]);
+        let fields = s.named_fields().unwrap();
+        let (t, u) = (&s.type_params()[0].ident, &s.type_params()[1].ident);
+        assert!(fields[0].mentions_type_param(t));
+        assert!(!fields[0].mentions_type_param(u));
+        assert!(!fields.iter().any(|f| f.mentions_type_param(u)));
+
+        // Nested and projected uses count; same-named path segments and lifetimes don't
+        let s = spec(parse_quote! {
+            struct W<'T, T> { a: Vec<Option<[T; 2]>>, b: <T as Iterator>::Item, c: other::T, d: &'T str }
+        });
+        let t = &s.type_params()[0].ident;
+        let mentions: Vec<bool> = s.named_fields().unwrap().iter().map(|f| f.mentions_type_param(t)).collect();
+        assert_eq!(mentions, vec![true, true, false, false]);
+    }
+
+    #[test]
+    fn docs_collect_doc_lines() {
+        let s = spec(parse_quote! {
+            /// A light.
+            #[doc(hidden)]
+            struct Light {
+                /// Intensity in lux.
+                ///
+                #[doc = "Must be non-negative."]
+                #[cfg(feature = "x")]
+                lux: f32,
+                range: f32,
+            }
+        });
+        assert_eq!(s.docs(), vec!["A light."]);
+        let fields = s.named_fields().unwrap();
+        assert_eq!(fields[0].docs().join("\n"), "Intensity in lux.\n\nMust be non-negative.");
+        assert!(fields[1].docs().is_empty());
+        let e = spec(parse_quote! {
+            enum Mode {
+                /// Draw filled.
+                Fill,
+                Line,
+            }
+        });
+        let docs: Vec<Vec<String>> = e.as_enum().unwrap().variants().iter().map(|v| v.docs()).collect();
+        assert_eq!(docs, vec![vec!["Draw filled.".to_string()], vec![]]);
+    }
+
+    #[test]
+    fn require_struct_rejects_enum() {
+        let s = spec(parse_quote! { struct Ok { a: u8 } });
+        assert!(s.require_struct().is_ok());
+        let err = spec(parse_quote! { enum Mode { A, B } }).require_struct().unwrap_err();
+        assert_eq!(err.to_string(), "`Mode` must be a struct, found an enum");
+    }
+
+    #[test]
+    fn require_enum_rejects_struct() {
+        assert!(spec(parse_quote! { enum Mode { A } }).require_enum().is_ok());
+        let err = spec(parse_quote! { union U { a: u32 } }).require_enum().unwrap_err();
+        assert_eq!(err.to_string(), "`U` must be an enum, found a union");
+    }
+
+    #[test]
+    fn require_named_rejects_tuple_and_unit_structs() {
+        let named = spec(parse_quote! { struct P { x: f32, y: f32 } });
+        assert_eq!(named.require_named().unwrap().named_fields().unwrap().len(), 2);
+        let err = spec(parse_quote! { struct P(f32, f32); }).require_named().unwrap_err();
+        assert_eq!(err.to_string(), "`P` must be a struct with named fields, found a tuple struct");
+        let err = spec(parse_quote! { struct Marker; }).named_fields().unwrap_err();
+        assert_eq!(err.to_string(), "`Marker` must be a struct with named fields, found a unit struct");
+    }
+
+    #[test]
+    fn forbid_generics_rejects_type_and_lifetime_params() {
+        let s = spec(parse_quote! { struct Plain { a: u8 } });
+        assert!(s.require_struct().and_then(|s| s.require_named()).and_then(|s| s.forbid_generics()).is_ok());
+        let err = spec(parse_quote! { struct W<T> { t: T } }).forbid_generics().unwrap_err();
+        assert_eq!(err.to_string(), "`W` must not be generic");
+        assert!(spec(parse_quote! { struct R<'a> { s: &'a str } }).forbid_generics().is_err());
+    }
+}
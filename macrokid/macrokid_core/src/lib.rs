@@ -1,10 +1,14 @@
+#![cfg_attr(feature = "nightly", feature(proc_macro_diagnostic))]
+#[cfg(feature = "nightly")]
+extern crate proc_macro;
+
 pub mod ir;
 pub mod attr;
 pub mod function;
 pub mod common;
 pub mod derive;
 
-pub use ir::{FieldKind, TypeKind, TypeSpec, VariantSpec};
+pub use ir::{FieldKind, TypeKind, TypeSpec, UnionSpec, VariantSpec};
 pub use common::{attrs, builders, patterns, diag, type_utils, repr, attr_schema, collect, codegen};
 pub use derive::impl_for_trait;
 #[cfg(feature = "pattern_dsl")]
@@ -64,7 +64,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         vs = "macrokid_graphics/shaders/pbr.vert",
         fs = "macrokid_graphics/shaders/pbr.frag",
         topology = "TriangleList",
-        depth = true,
         polygon = "Fill",
         cull = "Back",
         front_face = "Ccw",
@@ -26,7 +26,6 @@ fn main() {
         vs = "macrokid_graphics/shaders/anim.vert",
         fs = "macrokid_graphics/shaders/anim.frag",
         topology = "TriangleList",
-        depth = true,
         polygon = "Fill",
         cull = "Back",
         front_face = "Cw",
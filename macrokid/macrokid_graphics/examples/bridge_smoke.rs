@@ -24,7 +24,7 @@ fn main() {
     }
 
     #[derive(GraphicsPipeline)]
-    #[pipeline(vs = "shaders/triangle.vert.spv", fs = "shaders/triangle.frag.spv", topology = "TriangleList", depth = true, depth_test = true, depth_write = true, depth_compare = "LEqual", polygon = "Fill", cull = "Back", front_face = "Cw", blend = false, samples = 1, dynamic = "viewport,scissor", push_constants_size = 64, push_constants_stages = "vs")]
+    #[pipeline(vs = "shaders/triangle.vert.spv", fs = "shaders/triangle.frag.spv", topology = "TriangleList", depth_test = true, depth_write = true, depth_compare = "LEqual", polygon = "Fill", cull = "Back", front_face = "Cw", blend = false, samples = 1, dynamic = "viewport,scissor", push_constants_size = 64, push_constants_stages = "vs")]
     struct P;
 
     // Map to Vulkan structs
@@ -30,7 +30,7 @@ fn main() {
     #[pipeline(
         vs = "shaders/triangle.vert",
         fs = "shaders/triangle.frag",
-        topology = "TriangleList", depth = true, samples = 1,
+        topology = "TriangleList", samples = 1,
         polygon = "Fill", cull = "Back", front_face = "Cw"
     )]
     #[color_target(format = "rgba16f")] // Albedo
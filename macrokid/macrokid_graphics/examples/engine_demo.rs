@@ -42,7 +42,8 @@ struct Vertex {
 
 // Optionally, describe a pipeline via derive (produces a PipelineDesc at type-level)
 #[derive(GraphicsPipeline)]
-#[pipeline(vs = "shaders/triangle.vert", fs = "shaders/triangle.frag", topology = "TriangleList", depth = true)]
+#[pipeline(vs = "shaders/triangle.vert", fs = "shaders/triangle.frag", topology = "TriangleList",
+           vertex = "Vertex", vertex_locations = "0, 1, 2")]
 struct TrianglePipeline;
 
 fn main() {
@@ -50,14 +51,17 @@ fn main() {
     fn touch_material_fields(m: &Material) { let _ = (&m.matrices, &m.albedo, &m.albedo_sampler); }
     fn touch_vertex_fields(v: &Vertex) { let _ = (&v.pos, &v.normal, &v.uv); }
     // Pipelines can be created by hand or collected from derives
+    #[allow(deprecated)] // `depth` is still a required field
     let tri = PipelineDesc {
         name: "triangle",
-        shaders: ShaderPaths { vs: "shaders/triangle.vert", fs: "shaders/triangle.frag" },
+        shaders: ShaderPaths::paths("shaders/triangle.vert", "shaders/triangle.frag"),
         topology: Topology::TriangleList,
+        primitive_restart: false,
         depth: true,
         raster: None,
         blend: None,
         samples: None,
+        multisample: None,
         depth_stencil: None,
         dynamic: None,
         push_constants: None,
@@ -78,6 +82,7 @@ fn main() {
     let engine = Engine::<VulkanBackend>::new_from_config(&cfg);
     engine.validate_pipelines_with::<Material, Vertex>(&cfg).expect("resources/layout validated");
     cfg.validate_with::<GraphicsValidator<Material, Vertex>>().expect("validator facade");
+    TrianglePipeline::validate_vertex_layout().expect("pipeline vertex inputs match Vertex layout");
 
     // Initialize pipelines and present a frame (logs for demo purposes)
     engine.init_pipelines(&cfg);
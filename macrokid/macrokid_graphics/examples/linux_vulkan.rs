@@ -8,14 +8,17 @@ fn main() {
     use macrokid_graphics::pipeline::{PipelineDesc, ShaderPaths, Topology};
     use macrokid_graphics::vk_linux::run_vulkan_linux_app_with;
     
+    #[allow(deprecated)] // `depth` is still a required field
     let tri = PipelineDesc {
         name: "triangle",
-        shaders: ShaderPaths { vs: concat!(env!("CARGO_MANIFEST_DIR"), "/shaders/triangle.vert"), fs: concat!(env!("CARGO_MANIFEST_DIR"), "/shaders/triangle.frag") },
+        shaders: ShaderPaths::paths(concat!(env!("CARGO_MANIFEST_DIR"), "/shaders/triangle.vert"), concat!(env!("CARGO_MANIFEST_DIR"), "/shaders/triangle.frag")),
         topology: Topology::TriangleList,
+        primitive_restart: false,
         depth: false,
         raster: None,
         blend: None,
         samples: None,
+        multisample: None,
         depth_stencil: None,
         dynamic: None,
         push_constants: None,
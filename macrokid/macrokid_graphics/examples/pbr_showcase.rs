@@ -60,7 +60,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         vs = "macrokid_graphics/shaders/pbr.vert",
         fs = "macrokid_graphics/shaders/pbr.frag",
         topology = "TriangleList",
-        depth = true,
         polygon = "Fill",
         cull = "Back",
         front_face = "Ccw",
@@ -85,7 +84,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         vs = "macrokid_graphics/shaders/shadow.vert",
         fs = "macrokid_graphics/shaders/shadow.frag",
         topology = "TriangleList",
-        depth = true,
         polygon = "Fill",
         cull = "Front", // Peter panning prevention
         front_face = "Ccw"
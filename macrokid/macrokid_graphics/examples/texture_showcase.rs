@@ -53,7 +53,6 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         vs = "macrokid_graphics/shaders/simple_lit.vert",
         fs = "macrokid_graphics/shaders/simple_lit.frag",
         topology = "TriangleList",
-        depth = true,
         polygon = "Fill",
         cull = "Back",
         front_face = "Ccw",
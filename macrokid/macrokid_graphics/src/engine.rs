@@ -70,6 +70,7 @@ pub struct EngineConfig {
 pub trait RenderBackend: Send + Sync + 'static {
     fn name() -> &'static str;
     fn create_device() { println!("[{}] create_device()", Self::name()); }
+    #[allow(deprecated)]
     fn create_pipeline(desc: &PipelineDesc) {
         println!(
             "[{}] create_pipeline: {} (vs={}, fs={}, topo={:?}, depth={})",
@@ -345,11 +346,12 @@ mod tests {
     use crate::pipeline::{PipelineDesc, ShaderPaths, Topology};
 
     #[test]
+    #[allow(deprecated)]
     fn builder_and_validation_work() {
         let cfg = EngineBuilder::new()
             .app("Demo")
             .window(800, 600, true)
-            .add_pipeline(PipelineDesc { name: "triangle", shaders: ShaderPaths { vs: "vs", fs: "fs" }, topology: Topology::TriangleList, depth: true, raster: None, blend: None, samples: None, depth_stencil: None, dynamic: None, push_constants: None, color_targets: None, depth_target: None })
+            .add_pipeline(PipelineDesc { name: "triangle", shaders: ShaderPaths::paths("vs", "fs"), topology: Topology::TriangleList, primitive_restart: false, depth: true, raster: None, blend: None, samples: None, multisample: None, depth_stencil: None, dynamic: None, push_constants: None, color_targets: None, depth_target: None })
             .build()
             .expect("valid");
         assert_eq!(cfg.window.width, 800);
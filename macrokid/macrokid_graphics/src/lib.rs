@@ -7,5 +7,8 @@ pub mod vk_linux;
 #[cfg(feature = "vulkan-linux")]
 pub mod vk_bridge;
 pub mod render_graph;
+pub mod pod;
+#[cfg(feature = "serde")]
+mod serde_static;
 #[cfg(feature = "proto")]
 pub mod proto;
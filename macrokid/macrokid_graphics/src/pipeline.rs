@@ -1,86 +1,529 @@
 #[derive(Clone, Debug)]
-pub enum Topology { TriangleList, LineList, PointList }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Topology { TriangleList, LineList, PointList, TriangleStrip, LineStrip, TriangleFan }
+
+impl Topology {
+    /// Strip and fan topologies are the only ones where an index restart value is meaningful.
+    pub fn supports_primitive_restart(&self) -> bool {
+        matches!(self, Topology::TriangleStrip | Topology::LineStrip | Topology::TriangleFan)
+    }
+}
+
+/// Where a shader stage's code comes from: a file path (`.spv` or GLSL by extension) or GLSL
+/// source embedded in the binary.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ShaderSource {
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::serde_static::leak_str"))]
+    Path(&'static str),
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::serde_static::leak_str"))]
+    Inline(&'static str),
+}
+
+impl ShaderSource {
+    pub fn path(&self) -> Option<&'static str> { match self { Self::Path(p) => Some(p), Self::Inline(_) => None } }
+    pub fn inline(&self) -> Option<&'static str> { match self { Self::Inline(s) => Some(s), Self::Path(_) => None } }
+    /// True for an empty path or empty inline source.
+    pub fn is_empty(&self) -> bool { match self { Self::Path(s) | Self::Inline(s) => s.is_empty() } }
+}
+
+impl core::fmt::Display for ShaderSource {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Path(p) => f.write_str(p),
+            Self::Inline(s) => write!(f, "<inline, {} bytes>", s.len()),
+        }
+    }
+}
 
 #[derive(Clone, Debug)]
-pub struct ShaderPaths { pub vs: &'static str, pub fs: &'static str }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(bound(deserialize = "'de: 'static")))]
+pub struct ShaderPaths { pub vs: ShaderSource, pub fs: ShaderSource }
+
+impl ShaderPaths {
+    /// Both stages loaded from files.
+    pub const fn paths(vs: &'static str, fs: &'static str) -> Self { Self { vs: ShaderSource::Path(vs), fs: ShaderSource::Path(fs) } }
+    /// Both stages embedded as GLSL source.
+    pub const fn inline(vs: &'static str, fs: &'static str) -> Self { Self { vs: ShaderSource::Inline(vs), fs: ShaderSource::Inline(fs) } }
+}
 
 // Render target descriptions for flexible attachment configuration
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ColorTargetDesc {
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::serde_static::leak_str"))]
     pub format: &'static str,
-    /// Optional per-target blend enable (falls back to pipeline-level blend if None)
-    pub blend: Option<bool>,
+    /// Optional per-target blend state (falls back to pipeline-level blend if None)
+    pub blend: Option<ColorBlendState>,
 }
 
 #[derive(Clone, Debug)]
-pub struct DepthTargetDesc { pub format: &'static str }
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DepthTargetDesc { #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::serde_static::leak_str"))] pub format: &'static str }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PipelineDesc {
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::serde_static::leak_str"))]
     pub name: &'static str,
     pub shaders: ShaderPaths,
     pub topology: Topology,
+    /// Treat the maximum index value as a strip restart (strip/fan topologies only).
+    pub primitive_restart: bool,
+    /// Legacy on/off depth switch, kept for old configs; backends read `depth_stencil`.
+    #[deprecated(note = "use depth_stencil: Some(DepthState { test, write, compare }) instead")]
     pub depth: bool,
     // Optional backend-agnostic pipeline state we can use for Vulkan or others
     pub raster: Option<RasterState>,
     pub blend: Option<ColorBlendState>,
     pub samples: Option<u32>,
+    /// Alpha-to-coverage / per-sample shading; only meaningful when `samples > 1`.
+    pub multisample: Option<MultisampleState>,
     pub depth_stencil: Option<DepthState>,
     pub dynamic: Option<DynamicStateDesc>,
     pub push_constants: Option<PushConstantRange>,
     /// Optional list of color targets (MRT). If None or empty, defaults to single swapchain target.
+    #[cfg_attr(feature = "serde", serde(default, deserialize_with = "crate::serde_static::leak_opt_slice"))]
     pub color_targets: Option<&'static [ColorTargetDesc]>,
     /// Optional depth target format (backend picks suitable default if None)
     pub depth_target: Option<DepthTargetDesc>,
 }
 
+#[cfg(feature = "serde")]
+impl PipelineDesc {
+    /// Pretty-printed JSON, for inspecting a derived pipeline or checking it against a golden file.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("PipelineDesc serializes infallibly")
+    }
+
+    /// Parse a pipeline from JSON.
+    ///
+    /// **Leaks memory:** every string and slice in the result is leaked to fill the `'static`
+    /// fields, and nothing reclaims it. Meant for one-off loads in tools and golden tests, not for
+    /// reloading pipelines in a loop.
+    ///
+    /// ```
+    /// # use macrokid_graphics::pipeline::PipelineDesc;
+    /// let json = r#"{"name":"quad","shaders":{"vs":{"Path":"quad.vert"},"fs":{"Path":"quad.frag"}},
+    ///     "topology":"TriangleList","primitive_restart":false,"depth":false,"raster":null,"blend":null,
+    ///     "samples":null,"multisample":null,"depth_stencil":null,"dynamic":null,"push_constants":null,
+    ///     "depth_target":null}"#;
+    /// assert_eq!(PipelineDesc::from_json(json).unwrap().name, "quad");
+    /// ```
+    ///
+    /// The derived `Deserialize` impls are only `Deserialize<'static>`, so deserializing straight
+    /// from a borrowed string is rejected at compile time; go through `from_json` instead:
+    ///
+    /// ```compile_fail
+    /// # use macrokid_graphics::pipeline::PipelineDesc;
+    /// let json = String::from("{}");
+    /// let desc: PipelineDesc = serde_json::from_str(&json).unwrap();
+    /// ```
+    pub fn from_json(json: &str) -> serde_json::Result<Self> {
+        // Via an owned `Value`: the derived impls only accept `'static` input (see `serde_static`)
+        let value: serde_json::Value = serde_json::from_str(json)?;
+        serde::Deserialize::deserialize(value)
+    }
+}
+
 pub trait PipelineInfo { fn pipeline_desc() -> &'static PipelineDesc; }
 
+/// Link-time collection of pipelines derived with `#[pipeline(register)]`.
+#[cfg(feature = "registry")]
+#[doc(hidden)]
+pub mod registry {
+    pub use linkme;
+    use linkme::distributed_slice;
+
+    #[distributed_slice]
+    pub static PIPELINES: [&'static super::PipelineDesc];
+}
+
+/// Every pipeline in the final binary derived with `#[pipeline(register)]`, in no particular order.
+/// Requires the `registry` feature.
+#[cfg(feature = "registry")]
+pub fn registered_pipelines() -> &'static [&'static PipelineDesc] { &registry::PIPELINES }
+
+/// Registration emitted by `#[pipeline(register)]`: adds `$desc` to `registered_pipelines()`.
+#[cfg(feature = "registry")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __register_pipeline {
+    ($desc:expr) => {
+        const _: () = {
+            #[$crate::pipeline::registry::linkme::distributed_slice($crate::pipeline::registry::PIPELINES)]
+            #[linkme(crate = $crate::pipeline::registry::linkme)]
+            static REGISTERED: &'static $crate::pipeline::PipelineDesc = $desc;
+        };
+    };
+}
+
+/// Without the `registry` feature there is nothing to register into; the derive spans this
+/// invocation at the `register` key so the error points there.
+#[cfg(not(feature = "registry"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __register_pipeline {
+    ($desc:expr) => {
+        ::core::compile_error!("#[pipeline(register)] needs the `registry` feature; enable it on macrokid_graphics");
+    };
+}
+
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ComputeDesc {
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::serde_static::leak_str"))]
     pub name: &'static str,
     /// Path to compute shader (`.comp`) or SPIR-V (`.spv`).
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::serde_static::leak_str"))]
     pub shader: &'static str,
     /// Dispatch group counts passed to `vkCmdDispatch`.
     pub dispatch: (u32, u32, u32),
+    /// Workgroup size the shader declares (`local_size_x/y/z`), when known; lets callers size
+    /// `dispatch` from an invocation count.
+    pub local_size: Option<(u32, u32, u32)>,
     /// Optional push constant range (size and stages) for compute.
     pub push_constants: Option<PushConstantRange>,
     /// Optional explicit descriptor layout for this compute pass (static at compile-time).
     /// Provide from a `ResourceBindings::bindings()` slice when layouts differ between passes.
+    #[cfg_attr(feature = "serde", serde(default, deserialize_with = "crate::serde_static::leak_opt_slice"))]
     pub bindings: Option<&'static [crate::resources::BindingDesc]>,
 }
 
+pub trait ComputePipelineInfo { fn compute_desc() -> &'static ComputeDesc; }
+
 // Backend-agnostic pipeline state (minimal set)
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PolygonMode { Fill, Line }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CullMode { None, Front, Back }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum FrontFace { Cw, Ccw }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RasterState {
     pub polygon: PolygonMode,
     pub cull: CullMode,
     pub front_face: FrontFace,
 }
 
-#[derive(Clone, Debug)]
-pub struct ColorBlendState { pub enable: bool }
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColorBlendState {
+    pub enable: bool,
+    pub equation: ColorBlendDesc,
+    /// Framebuffer logic op applied instead of blending (pipeline-wide; `enable` is then false).
+    pub logic_op: Option<LogicOp>,
+}
+
+impl ColorBlendState {
+    /// Logic-op state: blending disabled, `op` combines source and destination bitwise.
+    pub const fn logic(op: LogicOp) -> Self {
+        ColorBlendState { enable: false, equation: BlendPreset::Opaque.equation(), logic_op: Some(op) }
+    }
+
+    /// Blending enabled with the same `src`/`dst`/`op` on both the color and alpha channels.
+    pub const fn factors(src: BlendFactor, dst: BlendFactor, op: BlendOp) -> Self {
+        let equation = ColorBlendDesc { src_color: src, dst_color: dst, color_op: op, src_alpha: src, dst_alpha: dst, alpha_op: op };
+        ColorBlendState { enable: true, equation, logic_op: None }
+    }
+}
+
+/// Bitwise framebuffer operations for `logic_op = "..."` (mirrors `VkLogicOp`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LogicOp {
+    Clear, And, AndReverse, Copy, AndInverted, NoOp, Xor, Or,
+    Nor, Equivalent, Invert, OrReverse, CopyInverted, OrInverted, Nand, Set,
+}
+
+impl LogicOp {
+    /// Spellings listed in derive error messages.
+    pub const NAMES: &'static [&'static str] = &[
+        "clear", "and", "and_reverse", "copy", "and_inverted", "no_op", "xor", "or",
+        "nor", "equivalent", "invert", "or_reverse", "copy_inverted", "or_inverted", "nand", "set",
+    ];
+
+    /// Parse an op name; case, `_` and `-` are ignored.
+    pub fn parse(s: &str) -> Option<Self> {
+        let norm: String = s.trim().chars().filter(|c| *c != '_' && *c != '-').collect::<String>().to_ascii_lowercase();
+        Some(match norm.as_str() {
+            "clear" => Self::Clear,
+            "and" => Self::And,
+            "andreverse" => Self::AndReverse,
+            "copy" => Self::Copy,
+            "andinverted" => Self::AndInverted,
+            "noop" => Self::NoOp,
+            "xor" => Self::Xor,
+            "or" => Self::Or,
+            "nor" => Self::Nor,
+            "equivalent" | "xnor" => Self::Equivalent,
+            "invert" => Self::Invert,
+            "orreverse" => Self::OrReverse,
+            "copyinverted" => Self::CopyInverted,
+            "orinverted" => Self::OrInverted,
+            "nand" => Self::Nand,
+            "set" => Self::Set,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BlendFactor {
+    Zero,
+    One,
+    SrcColor,
+    OneMinusSrcColor,
+    DstColor,
+    OneMinusDstColor,
+    SrcAlpha,
+    OneMinusSrcAlpha,
+    DstAlpha,
+    OneMinusDstAlpha,
+}
+
+impl BlendFactor {
+    /// Spellings listed in derive error messages.
+    pub const NAMES: &'static [&'static str] = &[
+        "zero", "one", "src_color", "one_minus_src_color", "dst_color",
+        "one_minus_dst_color", "src_alpha", "one_minus_src_alpha", "dst_alpha", "one_minus_dst_alpha",
+    ];
+
+    /// Parse a factor name; case, `_` and `-` are ignored.
+    pub fn parse(s: &str) -> Option<Self> {
+        let norm: String = s.trim().chars().filter(|c| *c != '_' && *c != '-').collect::<String>().to_ascii_lowercase();
+        Some(match norm.as_str() {
+            "zero" => Self::Zero,
+            "one" => Self::One,
+            "srccolor" => Self::SrcColor,
+            "oneminussrccolor" => Self::OneMinusSrcColor,
+            "dstcolor" => Self::DstColor,
+            "oneminusdstcolor" => Self::OneMinusDstColor,
+            "srcalpha" => Self::SrcAlpha,
+            "oneminussrcalpha" => Self::OneMinusSrcAlpha,
+            "dstalpha" => Self::DstAlpha,
+            "oneminusdstalpha" => Self::OneMinusDstAlpha,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BlendOp { Add, Subtract, ReverseSubtract, Min, Max }
+
+impl BlendOp {
+    /// Spellings listed in derive error messages.
+    pub const NAMES: &'static [&'static str] = &["add", "subtract", "reverse_subtract", "min", "max"];
+
+    /// Parse an op name; case, `_` and `-` are ignored.
+    pub fn parse(s: &str) -> Option<Self> {
+        let norm: String = s.trim().chars().filter(|c| *c != '_' && *c != '-').collect::<String>().to_ascii_lowercase();
+        Some(match norm.as_str() {
+            "add" => Self::Add,
+            "subtract" | "sub" => Self::Subtract,
+            "reversesubtract" | "revsub" => Self::ReverseSubtract,
+            "min" => Self::Min,
+            "max" => Self::Max,
+            _ => return None,
+        })
+    }
+}
+
+/// Full blend equation for one color attachment (color and alpha channels).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ColorBlendDesc {
+    pub src_color: BlendFactor,
+    pub dst_color: BlendFactor,
+    pub color_op: BlendOp,
+    pub src_alpha: BlendFactor,
+    pub dst_alpha: BlendFactor,
+    pub alpha_op: BlendOp,
+}
+
+/// Named blend configurations accepted by `blend = "..."` in the pipeline and pass derives.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BlendPreset { AlphaBlend, Additive, PremultipliedAlpha, Opaque }
+
+impl BlendPreset {
+    /// Spellings listed in derive error messages.
+    pub const NAMES: &'static [&'static str] = &["alpha_blend", "additive", "premultiplied_alpha", "opaque"];
+
+    /// Parse a preset name; case, `_` and `-` are ignored. `true`/`false` alias `AlphaBlend`/`Opaque`.
+    pub fn parse(s: &str) -> Option<Self> {
+        let norm: String = s.trim().chars().filter(|c| *c != '_' && *c != '-').collect::<String>().to_ascii_lowercase();
+        match norm.as_str() {
+            "alphablend" | "alpha" | "true" | "1" | "yes" | "on" => Some(Self::AlphaBlend),
+            "additive" | "add" => Some(Self::Additive),
+            "premultipliedalpha" | "premultiplied" => Some(Self::PremultipliedAlpha),
+            "opaque" | "none" | "false" | "0" | "no" | "off" => Some(Self::Opaque),
+            _ => None,
+        }
+    }
+
+    /// Blend equation for this preset. `Opaque` yields the pass-through equation (One/Zero/Add).
+    pub const fn equation(self) -> ColorBlendDesc {
+        use BlendFactor::*;
+        let (src_color, dst_color, src_alpha, dst_alpha) = match self {
+            Self::AlphaBlend => (SrcAlpha, OneMinusSrcAlpha, One, OneMinusSrcAlpha),
+            Self::Additive => (One, One, One, One),
+            Self::PremultipliedAlpha => (One, OneMinusSrcAlpha, One, OneMinusSrcAlpha),
+            Self::Opaque => (One, Zero, One, Zero),
+        };
+        ColorBlendDesc { src_color, dst_color, color_op: BlendOp::Add, src_alpha, dst_alpha, alpha_op: BlendOp::Add }
+    }
+
+    /// Complete blend state; blending is disabled only for `Opaque`.
+    pub const fn state(self) -> ColorBlendState {
+        ColorBlendState { enable: !matches!(self, Self::Opaque), equation: self.equation(), logic_op: None }
+    }
+}
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CompareOp { Never, Less, Equal, LessOrEqual, Greater, NotEqual, GreaterOrEqual, Always }
 
+/// Extra multisample state for MSAA pipelines (e.g. alpha-tested foliage).
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MultisampleState {
+    /// Derive per-sample coverage from the fragment's alpha.
+    pub alpha_to_coverage: bool,
+    /// Minimum fraction of samples shaded individually, in `[0, 1]`; `None` disables sample shading.
+    pub min_sample_shading: Option<f32>,
+}
+
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DepthState { pub test: bool, pub write: bool, pub compare: CompareOp }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DynamicStateDesc { pub viewport: bool, pub scissor: bool }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct StageMask { pub vs: bool, pub fs: bool, pub cs: bool }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PushConstantRange { pub size: u32, pub stages: Option<StageMask> }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_preset_names_and_bool_aliases() {
+        assert_eq!(BlendPreset::parse("additive"), Some(BlendPreset::Additive));
+        assert_eq!(BlendPreset::parse("Premultiplied-Alpha"), Some(BlendPreset::PremultipliedAlpha));
+        assert_eq!(BlendPreset::parse("alpha_blend"), Some(BlendPreset::AlphaBlend));
+        assert_eq!(BlendPreset::parse("true"), Some(BlendPreset::AlphaBlend));
+        assert_eq!(BlendPreset::parse("false"), Some(BlendPreset::Opaque));
+        assert_eq!(BlendPreset::parse("multiply"), None);
+        for name in BlendPreset::NAMES { assert!(BlendPreset::parse(name).is_some(), "{}", name); }
+    }
+
+    #[test]
+    fn parses_factor_and_op_names() {
+        assert_eq!(BlendFactor::parse("one_minus_src_alpha"), Some(BlendFactor::OneMinusSrcAlpha));
+        assert_eq!(BlendFactor::parse("DstColor"), Some(BlendFactor::DstColor));
+        assert_eq!(BlendFactor::parse("src_alpha_saturate"), None);
+        assert_eq!(BlendOp::parse("reverse-subtract"), Some(BlendOp::ReverseSubtract));
+        assert_eq!(BlendOp::parse("mul"), None);
+        for name in BlendFactor::NAMES { assert!(BlendFactor::parse(name).is_some(), "{}", name); }
+        for name in BlendOp::NAMES { assert!(BlendOp::parse(name).is_some(), "{}", name); }
+        let s = ColorBlendState::factors(BlendFactor::One, BlendFactor::One, BlendOp::Max);
+        assert!(s.enable);
+        assert_eq!((s.equation.src_alpha, s.equation.alpha_op), (BlendFactor::One, BlendOp::Max));
+    }
+
+    #[test]
+    fn parses_logic_op_names() {
+        assert_eq!(LogicOp::parse("xor"), Some(LogicOp::Xor));
+        assert_eq!(LogicOp::parse("And-Reverse"), Some(LogicOp::AndReverse));
+        assert_eq!(LogicOp::parse("blend"), None);
+        for name in LogicOp::NAMES { assert!(LogicOp::parse(name).is_some(), "{}", name); }
+        let s = ColorBlendState::logic(LogicOp::Xor);
+        assert!(!s.enable);
+        assert_eq!(s.logic_op, Some(LogicOp::Xor));
+    }
+
+    #[test]
+    fn alpha_blend_preset() {
+        let s = BlendPreset::AlphaBlend.state();
+        assert!(s.enable);
+        assert_eq!((s.equation.src_color, s.equation.dst_color), (BlendFactor::SrcAlpha, BlendFactor::OneMinusSrcAlpha));
+        assert_eq!((s.equation.src_alpha, s.equation.dst_alpha), (BlendFactor::One, BlendFactor::OneMinusSrcAlpha));
+        assert_eq!((s.equation.color_op, s.equation.alpha_op), (BlendOp::Add, BlendOp::Add));
+    }
+
+    #[test]
+    fn additive_preset() {
+        let s = BlendPreset::Additive.state();
+        assert!(s.enable);
+        assert_eq!((s.equation.src_color, s.equation.dst_color), (BlendFactor::One, BlendFactor::One));
+        assert_eq!((s.equation.src_alpha, s.equation.dst_alpha), (BlendFactor::One, BlendFactor::One));
+    }
+
+    #[test]
+    fn premultiplied_alpha_preset() {
+        let s = BlendPreset::PremultipliedAlpha.state();
+        assert!(s.enable);
+        assert_eq!((s.equation.src_color, s.equation.dst_color), (BlendFactor::One, BlendFactor::OneMinusSrcAlpha));
+        assert_eq!((s.equation.src_alpha, s.equation.dst_alpha), (BlendFactor::One, BlendFactor::OneMinusSrcAlpha));
+    }
+
+    #[test]
+    fn opaque_preset_disables_blending() {
+        let s = BlendPreset::Opaque.state();
+        assert!(!s.enable);
+        assert_eq!((s.equation.src_color, s.equation.dst_color), (BlendFactor::One, BlendFactor::Zero));
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn pipeline_desc_json_round_trip() {
+        static TARGETS: [ColorTargetDesc; 2] = [
+            ColorTargetDesc { format: "RGBA16F", blend: Some(BlendPreset::Additive.state()) },
+            ColorTargetDesc { format: "R8", blend: Some(ColorBlendState::logic(LogicOp::Xor)) },
+        ];
+        #[allow(deprecated)]
+        let desc = PipelineDesc {
+            name: "toon \"outline\"",
+            shaders: ShaderPaths { vs: ShaderSource::Path("shaders/toon.vert"), fs: ShaderSource::Inline("void main() {}\n") },
+            topology: Topology::TriangleStrip,
+            primitive_restart: true,
+            depth: true,
+            raster: Some(RasterState { polygon: PolygonMode::Line, cull: CullMode::Front, front_face: FrontFace::Cw }),
+            blend: Some(BlendPreset::AlphaBlend.state()),
+            samples: Some(4),
+            multisample: Some(MultisampleState { alpha_to_coverage: true, min_sample_shading: Some(0.5) }),
+            depth_stencil: Some(DepthState { test: true, write: false, compare: CompareOp::GreaterOrEqual }),
+            dynamic: Some(DynamicStateDesc { viewport: true, scissor: false }),
+            push_constants: Some(PushConstantRange { size: 64, stages: Some(StageMask { vs: true, fs: true, cs: false }) }),
+            color_targets: Some(&TARGETS),
+            depth_target: Some(DepthTargetDesc { format: "D32" }),
+        };
+        let json = desc.to_json();
+        assert!(json.contains("\"RGBA16F\""), "{}", json);
+        let back = PipelineDesc::from_json(&json).unwrap();
+        assert_eq!(back.to_json(), json);
+        assert_eq!(back.name, desc.name);
+        assert_eq!(back.shaders.fs, desc.shaders.fs);
+        let targets = back.color_targets.unwrap();
+        assert_eq!((targets[1].format, targets[1].blend.as_ref().and_then(|b| b.logic_op)), ("R8", Some(LogicOp::Xor)));
+        assert_eq!(back.push_constants.map(|p| p.size), Some(64));
+    }
+}
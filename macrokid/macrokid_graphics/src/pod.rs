@@ -0,0 +1,54 @@
+//! Byte views of plain-old-data descriptors, without serde.
+//!
+//! `#[derive(Pod)]` (from `macrokid_graphics_derive`) implements [`Pod`] for `#[repr(C)]` structs
+//! whose fields are all `Pod` and that contain no padding, and adds inherent `to_bytes` /
+//! `from_bytes` methods backed by [`bytes_of`] and [`from_bytes`]. This lets descriptor tables be
+//! written to disk or memory-mapped and reinterpreted in place.
+
+/// Types that can be viewed as bytes and rebuilt from any byte pattern of the right size.
+///
+/// # Safety
+/// Implementors must be `#[repr(C)]` (or a primitive), contain no padding and no pointers, and
+/// accept every bit pattern as a valid value. Prefer `#[derive(Pod)]`, which checks this.
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! impl_pod {
+    ($($t:ty),* $(,)?) => { $( unsafe impl Pod for $t {} )* };
+}
+
+impl_pod!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+/// View `value` as its raw bytes.
+pub fn bytes_of<T: Pod>(value: &T) -> &[u8] {
+    // SAFETY: T: Pod has no padding, so every byte is initialized.
+    unsafe { core::slice::from_raw_parts(value as *const T as *const u8, core::mem::size_of::<T>()) }
+}
+
+/// Reinterpret `bytes` as a `T` in place. Returns `None` unless the length is exactly
+/// `size_of::<T>()` and the slice is suitably aligned for `T`.
+pub fn from_bytes<T: Pod>(bytes: &[u8]) -> Option<&T> {
+    if bytes.len() != core::mem::size_of::<T>() || (bytes.as_ptr() as usize) % core::mem::align_of::<T>() != 0 {
+        return None;
+    }
+    // SAFETY: size and alignment checked above; T: Pod accepts any bit pattern.
+    Some(unsafe { &*(bytes.as_ptr() as *const T) })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn primitive_round_trip_and_checks() {
+        let v: [u32; 2] = [7, 0xdead_beef];
+        let bytes = bytes_of(&v);
+        assert_eq!(bytes.len(), 8);
+        assert_eq!(from_bytes::<[u32; 2]>(bytes), Some(&v));
+        assert_eq!(from_bytes::<[u32; 2]>(&bytes[..4]), None);
+        // Misaligned view of a u32-aligned buffer
+        let wide = [0u32; 3];
+        assert_eq!(from_bytes::<u32>(&bytes_of(&wide)[1..5]), None);
+    }
+}
@@ -1,6 +1,6 @@
 #![cfg(feature = "proto")]
 use crate::engine::{EngineConfig, WindowCfg};
-use crate::pipeline::{PipelineDesc, ShaderPaths, Topology, RasterState, PolygonMode, CullMode, FrontFace, ColorBlendState};
+use crate::pipeline::{PipelineDesc, ShaderPaths, Topology, RasterState, PolygonMode, CullMode, FrontFace, BlendPreset};
 use macrokid_graphics_proto::proto as pb;
 
 #[derive(Debug)]
@@ -28,7 +28,7 @@ fn map_shader_paths(sp: &pb::ShaderPaths) -> Result<ShaderPaths, ConvertError> {
         Some(pb::shader_paths::Fs::FsSpirv(_)) => return Err(ConvertError::Invalid("fs_spirv not supported yet")),
         None => return Err(ConvertError::MissingField("fs")),
     };
-    Ok(ShaderPaths { vs, fs })
+    Ok(ShaderPaths::paths(vs, fs))
 }
 
 fn map_raster(r: &pb::RasterState) -> RasterState {
@@ -51,22 +51,26 @@ fn map_raster(r: &pb::RasterState) -> RasterState {
     RasterState { polygon, cull, front_face }
 }
 
+// `pb::PipelineDesc` still carries the legacy `depth` switch
+#[allow(deprecated)]
 impl TryFrom<pb::PipelineDesc> for PipelineDesc {
     type Error = ConvertError;
     fn try_from(v: pb::PipelineDesc) -> Result<Self, Self::Error> {
         let shaders = map_shader_paths(v.shaders.as_ref().ok_or(ConvertError::MissingField("shaders"))?)?;
         let topology: Topology = v.topology().into();
         let raster = v.raster.map(|r| map_raster(&r));
-        let blend = v.blend.map(|b| ColorBlendState { enable: b.enable });
+        let blend = v.blend.map(|b| if b.enable { BlendPreset::AlphaBlend } else { BlendPreset::Opaque }.state());
         let samples = if v.samples == 0 { None } else { Some(v.samples) };
         Ok(PipelineDesc {
             name: Box::leak(v.name.into_boxed_str()),
             shaders,
             topology,
+            primitive_restart: false,
             depth: v.depth,
             raster,
             blend,
             samples,
+            multisample: None,
             depth_stencil: None,
             dynamic: None,
             push_constants: None,
@@ -1,7 +1,9 @@
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PassKind { Graphics, Compute }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SizeSpec {
     Abs { width: u32, height: u32 },
     Rel { sx: f32, sy: f32 },
@@ -9,7 +11,8 @@ pub enum SizeSpec {
 }
 
 bitflags::bitflags! {
-    #[derive(Clone, Copy, Debug)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize), serde(transparent))]
     pub struct UsageMask: u32 {
         const COLOR = 1 << 0;
         const DEPTH = 1 << 1;
@@ -21,8 +24,11 @@ bitflags::bitflags! {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct TextureDesc {
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::serde_static::leak_str"))]
     pub name: &'static str,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::serde_static::leak_str"))]
     pub format: &'static str,
     pub size: SizeSpec,
     pub usage: UsageMask,
@@ -30,24 +36,38 @@ pub struct TextureDesc {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct OutputDesc {
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::serde_static::leak_str"))]
     pub name: &'static str,
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::serde_static::leak_str"))]
     pub format: &'static str,
     pub size: SizeSpec,
     pub usage: UsageMask,
     pub samples: u32,
     pub is_depth: bool,
+    /// Survives into the next frame (e.g. TAA history): the backend keeps two copies and
+    /// alternates them per frame instead of aliasing the memory within a frame.
+    pub persistent: bool,
+    /// Shown on screen: the backend renders this output into the swapchain image. Exactly one
+    /// output per graph may set it (see [`present_output_from_passes`]).
+    pub present: bool,
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PassDesc {
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::serde_static::leak_str"))]
     pub name: &'static str,
     pub kind: PassKind,
     // Legacy compatibility (may be None when outputs are used)
+    #[cfg_attr(feature = "serde", serde(default, deserialize_with = "crate::serde_static::leak_opt_slice"))]
     pub color: Option<&'static [crate::pipeline::ColorTargetDesc]>,
     pub depth: Option<crate::pipeline::DepthTargetDesc>,
+    #[cfg_attr(feature = "serde", serde(default, deserialize_with = "crate::serde_static::leak_opt_str_slice"))]
     pub inputs: Option<&'static [&'static str]>,
     // Preferred attachment description with names/sizes/usages
+    #[cfg_attr(feature = "serde", serde(default, deserialize_with = "crate::serde_static::leak_opt_slice"))]
     pub outputs: Option<&'static [OutputDesc]>,
 }
 
@@ -62,6 +82,34 @@ pub struct GraphPass {
 #[derive(Clone, Debug, Default)]
 pub struct RenderGraphDesc { pub passes: Vec<GraphPass> }
 
+impl RenderGraphDesc {
+    /// Group consecutive passes that can run as subpasses of one render pass (see [`merge_subpasses_from_passes`]).
+    pub fn merge_subpasses(&self) -> Vec<SubpassGroup> {
+        let passes: Vec<&PassDesc> = self.passes.iter().map(|gp| gp.pass).collect();
+        merge_subpasses_from_passes(&passes)
+    }
+
+    /// Names of outputs marked `persistent`, in first-producer order (see [`persistent_resources_from_passes`]).
+    pub fn persistent_resources(&self) -> Vec<&'static str> {
+        let passes: Vec<&PassDesc> = self.passes.iter().map(|gp| gp.pass).collect();
+        persistent_resources_from_passes(&passes)
+    }
+
+    /// The unique output marked `present`, if exactly one pass declares one.
+    pub fn present_output(&self) -> Option<&'static OutputDesc> {
+        let passes: Vec<&'static PassDesc> = self.passes.iter().map(|gp| gp.pass).collect();
+        present_output_from_passes(&passes).ok()
+    }
+
+    /// Check frame-to-frame resource rules (see [`validate_persistent_from_passes`]) and that
+    /// exactly one output is presented (see [`present_output_from_passes`]).
+    pub fn validate(&self) -> Result<(), RenderGraphError> {
+        let passes: Vec<&'static PassDesc> = self.passes.iter().map(|gp| gp.pass).collect();
+        validate_persistent_from_passes(&passes)?;
+        present_output_from_passes(&passes).map(|_| ())
+    }
+}
+
 pub struct RenderGraphBuilder { desc: RenderGraphDesc }
 
 impl RenderGraphBuilder {
@@ -79,6 +127,13 @@ pub struct ResourcePlan {
     pub size: SizeSpec,
     pub usage: UsageMask,
     pub samples: u32,
+    /// Double-buffered across frames; never aliased with other resources.
+    pub persistent: bool,
+}
+
+impl ResourcePlan {
+    /// Number of physical copies the backend should allocate (2 for persistent history resources).
+    pub fn copies(&self) -> u32 { if self.persistent { 2 } else { 1 } }
 }
 
 #[derive(Clone, Debug)]
@@ -101,7 +156,7 @@ pub fn compute_actual_size(size: &SizeSpec, swap_w: u32, swap_h: u32) -> (u32, u
 }
 
 /// Very simple planner: flattens all pass outputs into resources and creates per-pass bindings.
-/// Does not alias or validate overlaps yet — goal is to land resource planning structure first.
+/// Does not alias or validate overlaps; see [`plan_aliasing`] for memory sharing between transients.
 pub fn plan_resources(desc: &RenderGraphDesc) -> (Vec<ResourcePlan>, Vec<PassPlan>) {
     use std::collections::BTreeMap;
     let mut by_name: BTreeMap<&'static str, ResourcePlan> = BTreeMap::new();
@@ -113,18 +168,18 @@ pub fn plan_resources(desc: &RenderGraphDesc) -> (Vec<ResourcePlan>, Vec<PassPla
             for o in outs {
                 // Promote to static names; PassDesc holds &'static already
                 let name: &'static str = Box::leak(o.name.to_string().into_boxed_str());
-                let rp = ResourcePlan { name, format: o.format, size: o.size.clone(), usage: o.usage, samples: o.samples };
-                by_name.entry(name).or_insert(rp);
+                let rp = ResourcePlan { name, format: o.format, size: o.size.clone(), usage: o.usage, samples: o.samples, persistent: o.persistent };
+                by_name.entry(name).or_insert(rp).persistent |= o.persistent;
                 if o.is_depth { depth = Some(name); } else { colors.push(name); }
             }
         } else {
             // Legacy: synthesize names for color/depth
             if let Some(cols) = gp.pass.color { for (i, c) in cols.iter().enumerate() {
                 let name: &'static str = Box::leak(format!("{}_col{}", gp.pass.name, i).into_boxed_str());
-                let rp = ResourcePlan { name, format: c.format, size: SizeSpec::Swapchain, usage: UsageMask::COLOR, samples: 1 };
+                let rp = ResourcePlan { name, format: c.format, size: SizeSpec::Swapchain, usage: UsageMask::COLOR, samples: 1, persistent: false };
                 by_name.entry(name).or_insert(rp); colors.push(name);
             } }
-            if let Some(d) = &gp.pass.depth { let name: &'static str = Box::leak(format!("{}_depth", gp.pass.name).into_boxed_str()); let rp = ResourcePlan { name, format: d.format, size: SizeSpec::Swapchain, usage: UsageMask::DEPTH, samples: 1 }; by_name.entry(name).or_insert(rp); depth = Some(name); }
+            if let Some(d) = &gp.pass.depth { let name: &'static str = Box::leak(format!("{}_depth", gp.pass.name).into_boxed_str()); let rp = ResourcePlan { name, format: d.format, size: SizeSpec::Swapchain, usage: UsageMask::DEPTH, samples: 1, persistent: false }; by_name.entry(name).or_insert(rp); depth = Some(name); }
         }
         pass_plans.push(PassPlan { name: gp.pass.name, colors, depth });
     }
@@ -144,17 +199,17 @@ pub fn plan_resources_from_passes(passes: &[&PassDesc]) -> (Vec<ResourcePlan>, V
         if let Some(outs) = p.outputs {
             for o in outs {
                 let name: &'static str = o.name;
-                let rp = ResourcePlan { name, format: o.format, size: o.size.clone(), usage: o.usage, samples: o.samples };
-                by_name.entry(name).or_insert(rp);
+                let rp = ResourcePlan { name, format: o.format, size: o.size.clone(), usage: o.usage, samples: o.samples, persistent: o.persistent };
+                by_name.entry(name).or_insert(rp).persistent |= o.persistent;
                 if o.is_depth { depth = Some(name); } else { colors.push(name); }
             }
         } else {
             if let Some(cols) = p.color { for (i, c) in cols.iter().enumerate() {
                 let name: &'static str = Box::leak(format!("{}_col{}", p.name, i).into_boxed_str());
-                let rp = ResourcePlan { name, format: c.format, size: SizeSpec::Swapchain, usage: UsageMask::COLOR, samples: 1 };
+                let rp = ResourcePlan { name, format: c.format, size: SizeSpec::Swapchain, usage: UsageMask::COLOR, samples: 1, persistent: false };
                 by_name.entry(name).or_insert(rp); colors.push(name);
             } }
-            if let Some(d) = &p.depth { let name: &'static str = Box::leak(format!("{}_depth", p.name).into_boxed_str()); let rp = ResourcePlan { name, format: d.format, size: SizeSpec::Swapchain, usage: UsageMask::DEPTH, samples: 1 }; by_name.entry(name).or_insert(rp); depth = Some(name); }
+            if let Some(d) = &p.depth { let name: &'static str = Box::leak(format!("{}_depth", p.name).into_boxed_str()); let rp = ResourcePlan { name, format: d.format, size: SizeSpec::Swapchain, usage: UsageMask::DEPTH, samples: 1, persistent: false }; by_name.entry(name).or_insert(rp); depth = Some(name); }
         }
         pass_plans.push(PassPlan { name: p.name, colors, depth });
     }
@@ -162,3 +217,558 @@ pub fn plan_resources_from_passes(passes: &[&PassDesc]) -> (Vec<ResourcePlan>, V
     resources.sort_by_key(|r| r.name);
     (resources, pass_plans)
 }
+
+/// Render graph validation failure.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RenderGraphError {
+    /// A persistent resource is written by more than one pass, i.e. it would ping-pong within a
+    /// frame on top of the per-frame history swap.
+    PersistentPingPong { resource: &'static str, first: &'static str, second: &'static str },
+    /// No output is marked `present`, so nothing reaches the swapchain.
+    NoPresentOutput,
+    /// More than one output is marked `present`; each is given as `(pass, output)`.
+    MultiplePresentOutputs { first: (&'static str, &'static str), second: (&'static str, &'static str) },
+}
+
+/// Outputs marked `persistent`, deduplicated, in the order their producing passes appear.
+pub fn persistent_resources_from_passes(passes: &[&PassDesc]) -> Vec<&'static str> {
+    let mut out: Vec<&'static str> = Vec::new();
+    for o in passes.iter().flat_map(|p| p.outputs.unwrap_or(&[])) {
+        if o.persistent && !out.contains(&o.name) { out.push(o.name); }
+    }
+    out
+}
+
+/// A persistent resource must have exactly one writer per frame: that pass writes the current
+/// copy while anyone (including itself) may read last frame's copy through `inputs`.
+pub fn validate_persistent_from_passes(passes: &[&PassDesc]) -> Result<(), RenderGraphError> {
+    let persistent = persistent_resources_from_passes(passes);
+    for resource in persistent {
+        let mut writers = passes.iter().filter(|p| p.outputs.unwrap_or(&[]).iter().any(|o| o.name == resource));
+        if let (Some(first), Some(second)) = (writers.next(), writers.next()) {
+            return Err(RenderGraphError::PersistentPingPong { resource, first: first.name, second: second.name });
+        }
+    }
+    Ok(())
+}
+
+/// The single output marked `present` across all passes, which backends bind to the swapchain.
+pub fn present_output_from_passes(passes: &[&PassDesc]) -> Result<&'static OutputDesc, RenderGraphError> {
+    let mut presented = passes.iter().flat_map(|p| p.outputs.unwrap_or(&[]).iter().filter(|o| o.present).map(move |o| (p.name, o)));
+    match (presented.next(), presented.next()) {
+        (None, _) => Err(RenderGraphError::NoPresentOutput),
+        (Some((_, o)), None) => Ok(o),
+        (Some((p1, o1)), Some((p2, o2))) => Err(RenderGraphError::MultiplePresentOutputs { first: (p1, o1.name), second: (p2, o2.name) }),
+    }
+}
+
+/// Which pass the backend renders into the swapchain, and which of its outputs that image stands in for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SwapchainTarget {
+    /// Index into the pass list.
+    pub pass: usize,
+    /// The `present` output, or `None` for a graph that marks none (the first pass is used).
+    pub output: Option<&'static str>,
+}
+
+/// Pick the swapchain pass from the `present` output (see [`present_output_from_passes`]).
+/// Graphs without one keep the old behaviour of presenting the first pass; two `present`
+/// outputs are still an error.
+pub fn swapchain_target_from_passes(passes: &[&PassDesc]) -> Result<SwapchainTarget, RenderGraphError> {
+    match present_output_from_passes(passes) {
+        Ok(out) => {
+            let pass = passes.iter().position(|p| p.outputs.unwrap_or(&[]).iter().any(|o| core::ptr::eq(o, out))).unwrap_or(0);
+            Ok(SwapchainTarget { pass, output: Some(out.name) })
+        }
+        Err(RenderGraphError::NoPresentOutput) => Ok(SwapchainTarget { pass: 0, output: None }),
+        Err(e) => Err(e),
+    }
+}
+
+/// How the pass in a [`UsageError`] accesses the resource.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageAccess {
+    /// Listed in the pass's `inputs`.
+    Read,
+    /// A compute pass's own output, written through a storage image.
+    ComputeWrite,
+}
+
+/// A pass uses a resource in a way its `UsageMask` does not allow, found by [`validate_usages`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsageError {
+    pub resource: &'static str,
+    /// Pass whose access needs the missing bits.
+    pub pass: &'static str,
+    /// Pass that declared the output; a persistent input may be declared by the reading pass itself.
+    pub producer: &'static str,
+    pub access: UsageAccess,
+    /// Any one of these bits would satisfy the access.
+    pub required: UsageMask,
+}
+
+impl core::fmt::Display for UsageError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let bits: Vec<&str> = self.required.iter_names().map(|(n, _)| n).collect();
+        match self.access {
+            UsageAccess::ComputeWrite => {
+                write!(f, "pass '{}' writes '{}' from a compute shader but it lacks {} usage", self.pass, self.resource, bits.join(" or "))
+            }
+            UsageAccess::Read => {
+                write!(f, "pass '{}' reads '{}' but '{}' created it without {} usage", self.pass, self.resource, self.producer, bits.join(" or "))
+            }
+        }
+    }
+}
+
+/// Cross-check each pass's `inputs` against the `UsageMask` of the output they name.
+///
+/// A graphics pass samples its inputs, so the producer must set `SAMPLED`; a compute pass may
+/// sample or load them as storage images, so either `SAMPLED` or `STORAGE` will do. Compute
+/// passes write through storage images, so their own outputs need `STORAGE`. An input is matched
+/// to the nearest earlier writer, falling back to a later one (a persistent resource read before
+/// it is rewritten); inputs nobody produces are external and skipped. All problems are reported.
+pub fn validate_usages(passes: &[&PassDesc]) -> Result<(), Vec<UsageError>> {
+    let mut errors = Vec::new();
+    let writer = |name: &str, at: usize| {
+        let find = |p: &&PassDesc| p.outputs.unwrap_or(&[]).iter().find(|o| o.name == name).map(|o| (p.name, o));
+        passes[..at].iter().rev().find_map(find).or_else(|| passes[at..].iter().find_map(find))
+    };
+    for (i, p) in passes.iter().enumerate() {
+        let (read_bits, compute) = match p.kind {
+            PassKind::Graphics => (UsageMask::SAMPLED, false),
+            PassKind::Compute => (UsageMask::SAMPLED.union(UsageMask::STORAGE), true),
+        };
+        for input in p.inputs.unwrap_or(&[]) {
+            let Some((producer, o)) = writer(input, i) else { continue };
+            if !o.usage.intersects(read_bits) {
+                errors.push(UsageError { resource: o.name, pass: p.name, producer, access: UsageAccess::Read, required: read_bits });
+            }
+        }
+        if compute {
+            for o in p.outputs.unwrap_or(&[]).iter().filter(|o| !o.usage.contains(UsageMask::STORAGE)) {
+                errors.push(UsageError { resource: o.name, pass: p.name, producer: p.name, access: UsageAccess::ComputeWrite, required: UsageMask::STORAGE });
+            }
+        }
+    }
+    if errors.is_empty() { Ok(()) } else { Err(errors) }
+}
+
+/// A run of consecutive passes that can be merged into subpasses of a single render pass.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SubpassGroup {
+    /// Pass names in execution order; each becomes one subpass.
+    pub passes: Vec<&'static str>,
+    /// Outputs produced inside the group and read by a later subpass (candidate input attachments).
+    pub input_attachments: Vec<&'static str>,
+}
+
+/// Attachment extent of a pass: its first output's size, or the swapchain for legacy passes.
+fn pass_extent(p: &PassDesc) -> SizeSpec {
+    p.outputs.and_then(|o| o.first()).map(|o| o.size.clone()).unwrap_or(SizeSpec::Swapchain)
+}
+
+/// Subpass merging hint for tile-based GPUs.
+///
+/// A graphics pass joins the previous pass's group when it reads at least one of that pass's
+/// outputs, every such output is `SAMPLED`, and each matches the consumer's own extent (so the
+/// read can happen at the same pixel). Everything else starts a new group; every pass appears in
+/// exactly one group.
+pub fn merge_subpasses_from_passes(passes: &[&PassDesc]) -> Vec<SubpassGroup> {
+    let mut groups: Vec<SubpassGroup> = Vec::new();
+    let mut prev: Option<&PassDesc> = None;
+    for p in passes {
+        let mut reads: Vec<&'static str> = Vec::new();
+        let mut mergeable = false;
+        if let (Some(prod), PassKind::Graphics) = (prev, &p.kind) {
+            let extent = pass_extent(p);
+            let outs = prod.outputs.unwrap_or(&[]);
+            let read: Vec<&OutputDesc> = p.inputs.unwrap_or(&[]).iter()
+                .filter_map(|i| outs.iter().find(|o| o.name == *i))
+                .collect();
+            mergeable = matches!(prod.kind, PassKind::Graphics)
+                && !read.is_empty()
+                && read.iter().all(|o| o.usage.contains(UsageMask::SAMPLED) && !o.is_depth && o.size == extent);
+            if mergeable { reads = read.iter().map(|o| o.name).collect(); }
+        }
+        match groups.last_mut() {
+            Some(g) if mergeable => {
+                g.passes.push(p.name);
+                for r in reads { if !g.input_attachments.contains(&r) { g.input_attachments.push(r); } }
+            }
+            _ => groups.push(SubpassGroup { passes: vec![p.name], input_attachments: Vec::new() }),
+        }
+        prev = Some(p);
+    }
+    groups
+}
+
+/// Memory aliasing of transient attachments, computed by [`plan_aliasing`].
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct AliasPlan {
+    /// Resource name → alias bucket. Resources sharing a bucket have disjoint lifetimes and can
+    /// be bound to the same memory. Persistent and present outputs are never listed.
+    pub buckets: std::collections::BTreeMap<&'static str, usize>,
+}
+
+impl AliasPlan {
+    pub fn bucket(&self, name: &str) -> Option<usize> { self.buckets.get(name).copied() }
+    /// Number of distinct allocations the aliased resources need.
+    pub fn bucket_count(&self) -> usize { self.buckets.values().max().map_or(0, |b| b + 1) }
+    /// Resources assigned to `bucket`, by name.
+    pub fn members(&self, bucket: usize) -> Vec<&'static str> {
+        self.buckets.iter().filter(|(_, b)| **b == bucket).map(|(n, _)| *n).collect()
+    }
+}
+
+/// Group transient outputs that can share memory, given passes in execution order.
+///
+/// A resource lives from the first pass that writes it to the last pass that writes or reads it.
+/// Resources are placed greedily in order of first write: each joins the first bucket whose
+/// resources match its format, size, sample count and depth-ness and whose last use ends strictly
+/// before it is first written; otherwise it opens a new bucket. Persistent outputs (kept across
+/// frames) and the present output (rendered into the swapchain) are left out.
+pub fn plan_aliasing(passes: &[&PassDesc]) -> AliasPlan {
+    struct Life { out: &'static OutputDesc, first: usize, last: usize }
+    let mut lives: Vec<Life> = Vec::new();
+    for (i, p) in passes.iter().enumerate() {
+        for o in p.outputs.unwrap_or(&[]) {
+            match lives.iter_mut().find(|l| l.out.name == o.name) {
+                Some(l) => l.last = i,
+                None => lives.push(Life { out: o, first: i, last: i }),
+            }
+        }
+        for input in p.inputs.unwrap_or(&[]) {
+            if let Some(l) = lives.iter_mut().find(|l| l.out.name == *input) { l.last = i; }
+        }
+    }
+    let mut plan = AliasPlan::default();
+    let mut slots: Vec<(&'static OutputDesc, usize)> = Vec::new();
+    for l in lives.iter().filter(|l| !l.out.persistent && !l.out.present) {
+        let o = l.out;
+        let fits = |(rep, last): &(&OutputDesc, usize)| {
+            *last < l.first && rep.format == o.format && rep.size == o.size && rep.samples == o.samples && rep.is_depth == o.is_depth
+        };
+        let bucket = match slots.iter().position(fits) {
+            Some(b) => { slots[b].1 = l.last; b }
+            None => { slots.push((o, l.last)); slots.len() - 1 }
+        };
+        plan.buckets.insert(o.name, bucket);
+    }
+    plan
+}
+
+/// Resource names a pass reads and writes, emitted as a const by `#[derive(RenderPass)]` so
+/// `render_graph!` can order passes during const evaluation. `outputs` leaves out persistent
+/// outputs: reading one sees last frame's copy, so it orders nothing.
+#[derive(Clone, Copy, Debug)]
+pub struct PassIo {
+    pub name: &'static str,
+    pub inputs: &'static [&'static str],
+    pub outputs: &'static [&'static str],
+}
+
+const fn str_eq(a: &str, b: &str) -> bool {
+    let (a, b) = (a.as_bytes(), b.as_bytes());
+    if a.len() != b.len() { return false; }
+    let mut i = 0;
+    while i < a.len() {
+        if a[i] != b[i] { return false; }
+        i += 1;
+    }
+    true
+}
+
+/// `producer` writes something `consumer` reads (self-reads do not count).
+const fn pass_feeds(producer: &PassIo, consumer: &PassIo) -> bool {
+    let mut i = 0;
+    while i < consumer.inputs.len() {
+        let mut o = 0;
+        while o < producer.outputs.len() {
+            if str_eq(consumer.inputs[i], producer.outputs[o]) { return true; }
+            o += 1;
+        }
+        i += 1;
+    }
+    false
+}
+
+const CYCLE_MSG_CAP: usize = 256;
+
+const fn push_msg(mut buf: [u8; CYCLE_MSG_CAP], mut len: usize, s: &str) -> ([u8; CYCLE_MSG_CAP], usize) {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() && len < CYCLE_MSG_CAP {
+        buf[len] = bytes[i];
+        len += 1;
+        i += 1;
+    }
+    (buf, len)
+}
+
+/// Pass names in execution order: Kahn's algorithm over input/output name matches, keeping
+/// declaration order among ready passes. Inputs nobody produces are treated as external.
+///
+/// Panics with the offending cycle (`render graph cycle: a -> b -> a`) when passes depend on
+/// each other; in `render_graph!` this runs in a const and so becomes a compile error.
+pub const fn pass_order<const N: usize>(passes: &[PassIo; N]) -> [&'static str; N] {
+    let mut edge = [[false; N]; N];
+    let mut indeg = [0usize; N];
+    let mut u = 0;
+    while u < N {
+        let mut v = 0;
+        while v < N {
+            if u != v && pass_feeds(&passes[u], &passes[v]) {
+                edge[u][v] = true;
+                indeg[v] += 1;
+            }
+            v += 1;
+        }
+        u += 1;
+    }
+
+    let mut queue = [0usize; N];
+    let (mut head, mut tail) = (0, 0);
+    let mut i = 0;
+    while i < N {
+        if indeg[i] == 0 { queue[tail] = i; tail += 1; }
+        i += 1;
+    }
+    let mut order = [""; N];
+    let mut done = [false; N];
+    while head < tail {
+        let u = queue[head];
+        head += 1;
+        order[head - 1] = passes[u].name;
+        done[u] = true;
+        let mut v = 0;
+        while v < N {
+            if edge[u][v] {
+                indeg[v] -= 1;
+                if indeg[v] == 0 { queue[tail] = v; tail += 1; }
+            }
+            v += 1;
+        }
+    }
+    if head == N { return order; }
+
+    // Every pass left over has a leftover producer: walk producers until one repeats
+    let mut path = [0usize; N];
+    let mut len = 0;
+    let mut cur = 0;
+    while done[cur] { cur += 1; }
+    let start = loop {
+        let mut k = 0;
+        while k < len && path[k] != cur { k += 1; }
+        if k < len { break k; }
+        path[len] = cur;
+        len += 1;
+        let mut p = 0;
+        while done[p] || !edge[p][cur] { p += 1; }
+        cur = p;
+    };
+    // The walk went against the edges; print the cycle in execution direction
+    let (mut buf, mut n) = push_msg([0u8; CYCLE_MSG_CAP], 0, "render graph cycle: ");
+    let mut k = len;
+    while k > start {
+        k -= 1;
+        (buf, n) = push_msg(buf, n, passes[path[k]].name);
+        (buf, n) = push_msg(buf, n, " -> ");
+    }
+    (buf, n) = push_msg(buf, n, passes[path[len - 1]].name);
+    let mut msg: &[u8] = &buf;
+    while msg.len() > n {
+        if let [rest @ .., _] = msg { msg = rest; }
+    }
+    match core::str::from_utf8(msg) {
+        Ok(msg) => panic!("{}", msg),
+        Err(_) => panic!("render graph cycle"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const fn out(name: &'static str, size: SizeSpec, usage: UsageMask) -> OutputDesc {
+        OutputDesc { name, format: "RGBA8", size, usage, samples: 1, is_depth: false, persistent: false, present: false }
+    }
+
+    const fn pass(name: &'static str, inputs: Option<&'static [&'static str]>, outputs: &'static [OutputDesc]) -> PassDesc {
+        PassDesc { name, kind: PassKind::Graphics, color: None, depth: None, inputs, outputs: Some(outputs) }
+    }
+
+    static GBUF_OUT: [OutputDesc; 1] = [out("albedo", SizeSpec::Swapchain, UsageMask::COLOR.union(UsageMask::SAMPLED))];
+    static LIGHT_OUT: [OutputDesc; 1] = [out("lit", SizeSpec::Swapchain, UsageMask::COLOR.union(UsageMask::SAMPLED))];
+    static BLOOM_OUT: [OutputDesc; 1] = [out("bloom", SizeSpec::Rel { sx: 0.5, sy: 0.5 }, UsageMask::COLOR)];
+    static GBUF: PassDesc = pass("gbuffer", None, &GBUF_OUT);
+    static LIGHT: PassDesc = pass("lighting", Some(&["albedo"]), &LIGHT_OUT);
+    static BLOOM: PassDesc = pass("bloom", Some(&["lit"]), &BLOOM_OUT);
+
+    const fn io(name: &'static str, inputs: &'static [&'static str], outputs: &'static [&'static str]) -> PassIo {
+        PassIo { name, inputs, outputs }
+    }
+
+    #[test]
+    fn pass_order_follows_producers() {
+        // Evaluated as a const, like render_graph! does
+        const ORDER: [&str; 4] = pass_order(&[
+            io("post", &["lit", "bloom"], &["ldr"]),
+            io("bloom", &["lit"], &["bloom"]),
+            io("gbuffer", &["env_map"], &["albedo"]),
+            io("lighting", &["albedo", "lighting.scratch"], &["lit", "lighting.scratch"]),
+        ]);
+        assert_eq!(ORDER, ["gbuffer", "lighting", "bloom", "post"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "render graph cycle: c -> b -> c")]
+    fn pass_order_names_the_cycle() {
+        pass_order(&[io("a", &[], &["x"]), io("b", &["x", "z"], &["y"]), io("c", &["y"], &["z"])]);
+    }
+
+    #[test]
+    fn merges_same_extent_sampled_reads() {
+        let groups = merge_subpasses_from_passes(&[&GBUF, &LIGHT, &BLOOM]);
+        assert_eq!(groups, vec![
+            SubpassGroup { passes: vec!["gbuffer", "lighting"], input_attachments: vec!["albedo"] },
+            SubpassGroup { passes: vec!["bloom"], input_attachments: vec![] },
+        ]);
+    }
+
+    static TAA_OUT: [OutputDesc; 2] = [
+        out("resolved", SizeSpec::Swapchain, UsageMask::COLOR.union(UsageMask::SAMPLED)),
+        OutputDesc { persistent: true, ..out("history", SizeSpec::Swapchain, UsageMask::COLOR.union(UsageMask::SAMPLED)) },
+    ];
+    static TAA: PassDesc = pass("taa", Some(&["lit", "history"]), &TAA_OUT);
+    static HISTORY_COPY_OUT: [OutputDesc; 1] = [out("history", SizeSpec::Swapchain, UsageMask::COLOR)];
+    static HISTORY_COPY: PassDesc = pass("history_copy", Some(&["resolved"]), &HISTORY_COPY_OUT);
+
+    #[test]
+    fn history_buffer_is_double_buffered() {
+        let passes = [&GBUF, &LIGHT, &TAA];
+        assert_eq!(persistent_resources_from_passes(&passes), vec!["history"]);
+        assert_eq!(validate_persistent_from_passes(&passes), Ok(()));
+        let (resources, _) = plan_resources_from_passes(&passes);
+        let history = resources.iter().find(|r| r.name == "history").unwrap();
+        assert!(history.persistent);
+        assert_eq!(history.copies(), 2);
+        assert_eq!(resources.iter().find(|r| r.name == "resolved").unwrap().copies(), 1);
+    }
+
+    #[test]
+    fn persistent_resource_with_two_writers_errors() {
+        let err = validate_persistent_from_passes(&[&LIGHT, &TAA, &HISTORY_COPY]).unwrap_err();
+        assert_eq!(err, RenderGraphError::PersistentPingPong { resource: "history", first: "taa", second: "history_copy" });
+    }
+
+    static POST_OUT: [OutputDesc; 1] = [OutputDesc { present: true, ..out("final", SizeSpec::Swapchain, UsageMask::COLOR) }];
+    static POST: PassDesc = pass("post", Some(&["lit"]), &POST_OUT);
+    static UI_OUT: [OutputDesc; 1] = [OutputDesc { present: true, ..out("ui", SizeSpec::Swapchain, UsageMask::COLOR) }];
+    static UI: PassDesc = pass("ui", None, &UI_OUT);
+
+    #[test]
+    fn present_output_must_be_unique() {
+        assert_eq!(present_output_from_passes(&[&GBUF, &LIGHT]).unwrap_err(), RenderGraphError::NoPresentOutput);
+        assert_eq!(present_output_from_passes(&[&GBUF, &LIGHT, &POST]).unwrap().name, "final");
+        assert_eq!(
+            present_output_from_passes(&[&LIGHT, &POST, &UI]).unwrap_err(),
+            RenderGraphError::MultiplePresentOutputs { first: ("post", "final"), second: ("ui", "ui") },
+        );
+    }
+
+    #[test]
+    fn swapchain_target_follows_present_output() {
+        assert_eq!(swapchain_target_from_passes(&[&GBUF, &LIGHT, &POST]).unwrap(), SwapchainTarget { pass: 2, output: Some("final") });
+        // No present output: the first pass keeps the swapchain
+        assert_eq!(swapchain_target_from_passes(&[&GBUF, &LIGHT]).unwrap(), SwapchainTarget { pass: 0, output: None });
+        assert!(matches!(swapchain_target_from_passes(&[&POST, &UI]), Err(RenderGraphError::MultiplePresentOutputs { .. })));
+    }
+
+    // G-buffer → toon chain: `albedo` is dead once toon shading has read it, so the outline
+    // pass's scratch target (same shape) can reuse its memory.
+    static TOON_GBUF_OUT: [OutputDesc; 2] = [
+        out("albedo", SizeSpec::Swapchain, UsageMask::COLOR.union(UsageMask::SAMPLED)),
+        OutputDesc { format: "RGBA16F", ..out("normal", SizeSpec::Swapchain, UsageMask::COLOR.union(UsageMask::SAMPLED)) },
+    ];
+    static TOON_OUT: [OutputDesc; 1] = [out("toon", SizeSpec::Swapchain, UsageMask::COLOR.union(UsageMask::SAMPLED))];
+    static OUTLINE_OUT: [OutputDesc; 1] = [out("scratch", SizeSpec::Swapchain, UsageMask::COLOR.union(UsageMask::SAMPLED))];
+    static COMPOSITE_OUT: [OutputDesc; 2] = [
+        OutputDesc { present: true, ..out("final", SizeSpec::Swapchain, UsageMask::COLOR) },
+        out("half", SizeSpec::Rel { sx: 0.5, sy: 0.5 }, UsageMask::COLOR),
+    ];
+    static TOON_GBUF: PassDesc = pass("gbuffer", None, &TOON_GBUF_OUT);
+    static TOON: PassDesc = pass("toon", Some(&["albedo", "normal"]), &TOON_OUT);
+    static OUTLINE: PassDesc = pass("outline", Some(&["toon", "normal"]), &OUTLINE_OUT);
+    static COMPOSITE: PassDesc = pass("composite", Some(&["toon", "scratch"]), &COMPOSITE_OUT);
+
+    #[test]
+    fn albedo_aliases_later_scratch_target() {
+        let plan = plan_aliasing(&[&TOON_GBUF, &TOON, &OUTLINE, &COMPOSITE]);
+        // albedo ends at `toon`, scratch starts at `outline`
+        assert_eq!(plan.bucket("albedo"), plan.bucket("scratch"));
+        // toon overlaps both of them; normal differs in format; half differs in size
+        assert_eq!(plan.members(plan.bucket("albedo").unwrap()), vec!["albedo", "scratch"]);
+        for name in ["normal", "toon", "half"] {
+            assert_eq!(plan.members(plan.bucket(name).unwrap()), vec![name]);
+        }
+        assert_eq!(plan.bucket("final"), None);
+        assert_eq!(plan.bucket_count(), 4);
+    }
+
+    #[test]
+    fn persistent_outputs_are_not_aliased() {
+        let plan = plan_aliasing(&[&GBUF, &LIGHT, &TAA]);
+        assert_eq!(plan.bucket("history"), None);
+        // albedo is read by lighting, so its memory is free again before taa writes `resolved`
+        assert_eq!(plan.bucket("albedo"), plan.bucket("resolved"));
+        assert_ne!(plan.bucket("lit"), plan.bucket("albedo"));
+    }
+
+    static FLAT_OUT: [OutputDesc; 1] = [out("flat", SizeSpec::Swapchain, UsageMask::COLOR)];
+    static FLAT: PassDesc = pass("flat", None, &FLAT_OUT);
+    static SHARPEN: PassDesc = pass("sharpen", Some(&["flat", "env_map"]), &POST_OUT);
+    static CULL_OUT: [OutputDesc; 1] = [out("visibility", SizeSpec::Abs { width: 64, height: 64 }, UsageMask::SAMPLED)];
+    static CULL: PassDesc = PassDesc { kind: PassKind::Compute, ..pass("cull", Some(&["lit"]), &CULL_OUT) };
+
+    #[test]
+    fn sampling_requires_sampled_usage() {
+        assert_eq!(validate_usages(&[&GBUF, &LIGHT, &BLOOM, &TAA]), Ok(()));
+        // `env_map` has no producer in the graph and is not checked
+        let errs = validate_usages(&[&FLAT, &SHARPEN]).unwrap_err();
+        assert_eq!(errs, vec![UsageError { resource: "flat", pass: "sharpen", producer: "flat", access: UsageAccess::Read, required: UsageMask::SAMPLED }]);
+        assert_eq!(errs[0].to_string(), "pass 'sharpen' reads 'flat' but 'flat' created it without SAMPLED usage");
+    }
+
+    static TAA_UNSAMPLED_OUT: [OutputDesc; 2] = [
+        out("resolved", SizeSpec::Swapchain, UsageMask::COLOR.union(UsageMask::SAMPLED)),
+        OutputDesc { persistent: true, ..out("history", SizeSpec::Swapchain, UsageMask::COLOR) },
+    ];
+    static TAA_UNSAMPLED: PassDesc = pass("taa", Some(&["lit", "history"]), &TAA_UNSAMPLED_OUT);
+
+    #[test]
+    fn reading_own_persistent_output_is_reported_as_a_read() {
+        // `taa` reads last frame's `history` before rewriting it, so it is its own producer
+        let errs = validate_usages(&[&GBUF, &LIGHT, &TAA_UNSAMPLED]).unwrap_err();
+        assert_eq!(errs, vec![UsageError { resource: "history", pass: "taa", producer: "taa", access: UsageAccess::Read, required: UsageMask::SAMPLED }]);
+        assert_eq!(errs[0].to_string(), "pass 'taa' reads 'history' but 'taa' created it without SAMPLED usage");
+    }
+
+    #[test]
+    fn compute_outputs_require_storage() {
+        let errs = validate_usages(&[&GBUF, &LIGHT, &CULL]).unwrap_err();
+        assert_eq!(errs.len(), 1);
+        assert_eq!(errs[0].to_string(), "pass 'cull' writes 'visibility' from a compute shader but it lacks STORAGE usage");
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn pass_desc_json_round_trip() {
+        let json = serde_json::to_value(&TAA).unwrap();
+        assert_eq!(json["outputs"][0]["usage"], "COLOR | SAMPLED");
+        let back: PassDesc = serde::Deserialize::deserialize(json).unwrap();
+        assert_eq!(back.inputs, Some(&["lit", "history"][..]));
+        let history = &back.outputs.unwrap()[1];
+        assert_eq!((history.name, history.persistent, history.usage), ("history", true, UsageMask::COLOR | UsageMask::SAMPLED));
+    }
+}
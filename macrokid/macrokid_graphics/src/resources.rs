@@ -1,4 +1,5 @@
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ResourceKind {
     Uniform,
     Texture,
@@ -10,15 +11,33 @@ pub enum ResourceKind {
 }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BindingStages { pub vs: bool, pub fs: bool, pub cs: bool }
 
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BindingDesc {
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "crate::serde_static::leak_str"))]
     pub field: &'static str,
     pub set: u32,
     pub binding: u32,
     pub kind: ResourceKind,
     pub stages: Option<BindingStages>,
+    /// Updated via push descriptors (`vkCmdPushDescriptorSetKHR`) instead of pool-allocated sets.
+    pub push: bool,
+    /// Uniform bound as `UNIFORM_BUFFER_DYNAMIC`; its offset is supplied when binding the set.
+    pub dynamic: bool,
+    /// Array length of the binding (`descriptorCount`); 1 for a single resource.
+    pub count: u32,
+}
+
+/// Descriptors of one kind that pool-allocated sets need; dynamic uniforms get their own entry.
+#[derive(Clone, Debug)]
+pub struct DescriptorPoolSize {
+    pub kind: ResourceKind,
+    /// Sized as `UNIFORM_BUFFER_DYNAMIC` rather than `UNIFORM_BUFFER`.
+    pub dynamic: bool,
+    pub count: u32,
 }
 
 pub trait ResourceBindings { fn bindings() -> &'static [BindingDesc]; }
@@ -38,6 +57,63 @@ pub trait VertexLayout {
     fn vertex_buffers() -> &'static [VertexBufferDesc];
 }
 
+/// Mismatch between the vertex inputs a pipeline expects and a `VertexLayout`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VertexLayoutError {
+    /// No attribute in the layout feeds this shader input location.
+    MissingLocation { location: u32 },
+    /// An attribute references a vertex buffer binding the layout does not declare.
+    MissingBuffer { field: &'static str, binding: u32 },
+}
+
+/// Check that `V` provides every location in `expected` and that each of its attributes
+/// reads from a declared vertex buffer. Pipelines derived with `#[pipeline(vertex = "..")]`
+/// expose this as `validate_vertex_layout()` for use in tests or at startup.
+pub fn validate_vertex_layout<V: VertexLayout>(expected: &[u32]) -> Result<(), VertexLayoutError> {
+    let attrs = V::vertex_attrs();
+    for &location in expected {
+        if !attrs.iter().any(|a| a.location == location) {
+            return Err(VertexLayoutError::MissingLocation { location });
+        }
+    }
+    let buffers = V::vertex_buffers();
+    for a in attrs {
+        if !buffers.iter().any(|b| b.binding == a.binding) {
+            return Err(VertexLayoutError::MissingBuffer { field: a.field, binding: a.binding });
+        }
+    }
+    Ok(())
+}
+
+fn stage_mismatch(b: &BindingDesc, stage: &str, pipeline: &str, missing: &str) -> String {
+    format!("binding '{}' (set {}, binding {}) is visible to `{}` but pipeline '{}' has no {} shader", b.field, b.set, b.binding, stage, pipeline, missing)
+}
+
+/// Check binding stage masks against the shaders a graphics pipeline actually has: `cs` is
+/// always flagged, and `vs`/`fs` when the pipeline leaves that shader path empty. Bindings
+/// without a mask are accepted. Meant for startup or tests; every mismatch is reported.
+pub fn check_stages_against(bindings: &[BindingDesc], pipeline: &crate::pipeline::PipelineDesc) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+    for b in bindings {
+        let Some(st) = &b.stages else { continue };
+        if st.vs && pipeline.shaders.vs.is_empty() { errors.push(stage_mismatch(b, "vs", pipeline.name, "vertex")); }
+        if st.fs && pipeline.shaders.fs.is_empty() { errors.push(stage_mismatch(b, "fs", pipeline.name, "fragment")); }
+        if st.cs { errors.push(stage_mismatch(b, "cs", pipeline.name, "compute")); }
+    }
+    if errors.is_empty() { Ok(()) } else { Err(errors) }
+}
+
+/// Compute counterpart of [`check_stages_against`]: flags bindings visible to `vs` or `fs`.
+pub fn check_compute_stages_against(bindings: &[BindingDesc], pipeline: &crate::pipeline::ComputeDesc) -> Result<(), Vec<String>> {
+    let mut errors = Vec::new();
+    for b in bindings {
+        let Some(st) = &b.stages else { continue };
+        if st.vs { errors.push(stage_mismatch(b, "vs", pipeline.name, "vertex")); }
+        if st.fs { errors.push(stage_mismatch(b, "fs", pipeline.name, "fragment")); }
+    }
+    if errors.is_empty() { Ok(()) } else { Err(errors) }
+}
+
 // ============================================================================
 // GPU Resource Tracking for Barrier Generation
 // ============================================================================
@@ -67,6 +143,26 @@ pub struct GpuResourceMeta {
     pub write_access: vk::AccessFlags,
 }
 
+impl GpuResourceMeta {
+    /// Identity and kind of `T` only; with `vulkan-linux` the stage/access masks start empty.
+    /// `GpuResource::metadata` fills them in for the backend's own buffer and image types.
+    pub fn new<T: ?Sized + 'static>(resource_kind: GpuResourceKind) -> Self {
+        Self {
+            type_id: TypeId::of::<T>(),
+            type_name: core::any::type_name::<T>(),
+            resource_kind,
+            #[cfg(feature = "vulkan-linux")]
+            read_stage: vk::PipelineStageFlags::empty(),
+            #[cfg(feature = "vulkan-linux")]
+            write_stage: vk::PipelineStageFlags::empty(),
+            #[cfg(feature = "vulkan-linux")]
+            read_access: vk::AccessFlags::empty(),
+            #[cfg(feature = "vulkan-linux")]
+            write_access: vk::AccessFlags::empty(),
+        }
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum GpuResourceKind {
     Buffer,
@@ -76,22 +172,27 @@ pub enum GpuResourceKind {
 /// Trait for GPU-accessible resources that participate in barrier generation.
 ///
 /// Types implementing this trait provide metadata about their Vulkan synchronization
-/// requirements, enabling automatic barrier hint generation via derive macros.
-#[cfg(feature = "vulkan-linux")]
+/// requirements, enabling automatic barrier hint generation via derive macros. Without
+/// `vulkan-linux` only the resource kind is required, which is enough for descriptor layouts.
 pub trait GpuResource: Send + Sync + 'static {
     /// Get the Vulkan resource handle for barrier construction
+    #[cfg(feature = "vulkan-linux")]
     fn handle(&self) -> GpuHandle;
 
     /// Default pipeline stage for read operations
+    #[cfg(feature = "vulkan-linux")]
     fn read_stage() -> vk::PipelineStageFlags;
 
     /// Default pipeline stage for write operations
+    #[cfg(feature = "vulkan-linux")]
     fn write_stage() -> vk::PipelineStageFlags;
 
     /// Default access mask for read operations
+    #[cfg(feature = "vulkan-linux")]
     fn read_access() -> vk::AccessFlags;
 
     /// Default access mask for write operations
+    #[cfg(feature = "vulkan-linux")]
     fn write_access() -> vk::AccessFlags;
 
     /// Resource kind (Buffer or Image)
@@ -103,9 +204,13 @@ pub trait GpuResource: Send + Sync + 'static {
             type_id: TypeId::of::<Self>(),
             type_name: core::any::type_name::<Self>(),
             resource_kind: Self::resource_kind(),
+            #[cfg(feature = "vulkan-linux")]
             read_stage: Self::read_stage(),
+            #[cfg(feature = "vulkan-linux")]
             write_stage: Self::write_stage(),
+            #[cfg(feature = "vulkan-linux")]
             read_access: Self::read_access(),
+            #[cfg(feature = "vulkan-linux")]
             write_access: Self::write_access(),
         }
     }
@@ -353,6 +458,10 @@ pub trait GpuResourceAccess {
     /// GPU resources that this system writes to
     fn gpu_writes() -> &'static [GpuResourceMeta] { &[] }
 
+    /// Descriptor set layout for the accessed resources, as built by `gpu_access_bindings`.
+    /// `#[derive(System)]` implements it alongside `gpu_reads`/`gpu_writes`.
+    fn descriptor_bindings() -> &'static [BindingDesc] { &[] }
+
     /// Generate barrier requirement hints for this system
     fn barrier_requirements() -> String {
         let mut hints = String::from("GPU Barrier Requirements:\n\n");
@@ -411,3 +520,108 @@ pub trait GpuResourceAccess {
         hints
     }
 }
+
+/// Set 0 bindings for a system's GPU accesses: reads first, then writes, numbered in order.
+/// Buffers map to `Uniform` when read and `StorageBuffer` when written; images to `Texture`
+/// when read and `StorageImage` when written. A resource that is both read and written gets one
+/// storage binding, at its read position. `field` carries the resource's type name.
+pub fn gpu_access_bindings(reads: &[GpuResourceMeta], writes: &[GpuResourceMeta]) -> Vec<BindingDesc> {
+    let written = |meta: &GpuResourceMeta| writes.iter().any(|w| w.type_id == meta.type_id);
+    let read = |meta: &GpuResourceMeta| reads.iter().any(|r| r.type_id == meta.type_id);
+    let read_entries = reads.iter().map(|m| (m, written(m)));
+    let write_entries = writes.iter().filter(|m| !read(m)).map(|m| (m, true));
+    read_entries.chain(write_entries).enumerate().map(|(i, (meta, write))| {
+        let kind = match (&meta.resource_kind, write) {
+            (GpuResourceKind::Buffer, false) => ResourceKind::Uniform,
+            (GpuResourceKind::Buffer, true) => ResourceKind::StorageBuffer,
+            (GpuResourceKind::Image, false) => ResourceKind::Texture,
+            (GpuResourceKind::Image, true) => ResourceKind::StorageImage,
+        };
+        BindingDesc { field: meta.type_name, set: 0, binding: i as u32, kind, stages: None, push: false, dynamic: false, count: 1 }
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gpu_access_maps_to_layout_bindings() {
+        struct Camera; struct Albedo; struct Hdr; struct Particles;
+        let reads = [GpuResourceMeta::new::<Camera>(GpuResourceKind::Buffer), GpuResourceMeta::new::<Albedo>(GpuResourceKind::Image)];
+        let writes = [GpuResourceMeta::new::<Hdr>(GpuResourceKind::Image), GpuResourceMeta::new::<Particles>(GpuResourceKind::Buffer)];
+        let got: Vec<(&str, u32, String)> = gpu_access_bindings(&reads, &writes).iter()
+            .map(|b| (b.field.rsplit("::").next().unwrap(), b.binding, format!("{:?}", b.kind))).collect();
+        assert_eq!(got, vec![
+            ("Camera", 0, "Uniform".to_string()),
+            ("Albedo", 1, "Texture".to_string()),
+            ("Hdr", 2, "StorageImage".to_string()),
+            ("Particles", 3, "StorageBuffer".to_string()),
+        ]);
+
+        // Read and written: one storage binding at the read's slot, not a second entry
+        let writes = [GpuResourceMeta::new::<Hdr>(GpuResourceKind::Image), GpuResourceMeta::new::<Albedo>(GpuResourceKind::Image)];
+        let got: Vec<(&str, u32, String)> = gpu_access_bindings(&reads, &writes).iter()
+            .map(|b| (b.field.rsplit("::").next().unwrap(), b.binding, format!("{:?}", b.kind))).collect();
+        assert_eq!(got, vec![
+            ("Camera", 0, "Uniform".to_string()),
+            ("Albedo", 1, "StorageImage".to_string()),
+            ("Hdr", 2, "StorageImage".to_string()),
+        ]);
+    }
+
+    struct PosNormal;
+    impl VertexLayout for PosNormal {
+        fn vertex_attrs() -> &'static [VertexAttr] {
+            static ATTRS: [VertexAttr; 2] = [
+                VertexAttr { field: "pos", binding: 0, location: 0, format: "vec3", offset: 0, size: 12 },
+                VertexAttr { field: "normal", binding: 0, location: 1, format: "vec3", offset: 12, size: 12 },
+            ];
+            &ATTRS
+        }
+        fn vertex_buffers() -> &'static [VertexBufferDesc] {
+            static BUFS: [VertexBufferDesc; 1] = [VertexBufferDesc { binding: 0, stride: 24, step: StepMode::Vertex }];
+            &BUFS
+        }
+    }
+
+    #[test]
+    fn vertex_layout_matches_expected_locations() {
+        assert_eq!(validate_vertex_layout::<PosNormal>(&[0, 1]), Ok(()));
+        assert_eq!(validate_vertex_layout::<PosNormal>(&[0, 2]), Err(VertexLayoutError::MissingLocation { location: 2 }));
+    }
+
+    fn binding(field: &'static str, vs: bool, fs: bool, cs: bool) -> BindingDesc {
+        BindingDesc { field, set: 0, binding: 0, kind: ResourceKind::StorageBuffer, stages: Some(BindingStages { vs, fs, cs }), push: false, dynamic: false, count: 1 }
+    }
+
+    #[test]
+    fn compute_binding_on_graphics_pipeline_is_flagged() {
+        use crate::pipeline::{ComputeDesc, PipelineDesc, ShaderPaths, Topology};
+        #[allow(deprecated)]
+        let gfx = PipelineDesc {
+            name: "forward",
+            shaders: ShaderPaths::paths("shaders/forward.vert", "shaders/forward.frag"),
+            topology: Topology::TriangleList,
+            primitive_restart: false,
+            depth: true,
+            raster: None,
+            blend: None,
+            samples: None,
+            multisample: None,
+            depth_stencil: None,
+            dynamic: None,
+            push_constants: None,
+            color_targets: None,
+            depth_target: None,
+        };
+        let bindings = [binding("camera", true, true, false), binding("particles", false, false, true)];
+        let errs = check_stages_against(&bindings, &gfx).unwrap_err();
+        assert_eq!(errs.len(), 1);
+        assert!(errs[0].contains("'particles'") && errs[0].contains("`cs`"), "{}", errs[0]);
+        assert!(check_stages_against(&bindings[..1], &gfx).is_ok());
+
+        let cull = ComputeDesc { name: "cull", shader: "shaders/cull.comp", dispatch: (64, 1, 1), local_size: None, push_constants: None, bindings: None };
+        assert_eq!(check_compute_stages_against(&bindings, &cull).unwrap_err().len(), 2);
+    }
+}
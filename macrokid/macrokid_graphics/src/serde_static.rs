@@ -0,0 +1,31 @@
+//! `deserialize_with` helpers for the `&'static` borrows in descriptor types.
+//!
+//! Descriptors are normally `static`s emitted by the derives, so their strings and slices borrow
+//! for `'static`. A deserialized descriptor has no such backing storage; these helpers read owned
+//! values and leak them, which suits the one-off loads done by tools and golden tests. The leaked
+//! memory is never reclaimed, so repeated deserialization grows without bound.
+//!
+//! serde still counts `&'static str` fields as borrowed, so the derived impls are only
+//! `Deserialize<'static>`. Feed them an owned deserializer such as `serde_json::Value` (as
+//! `PipelineDesc::from_json` does) rather than `serde_json::from_str` on a short-lived string.
+use serde::{Deserialize, Deserializer};
+
+fn leak_string(s: String) -> &'static str { Box::leak(s.into_boxed_str()) }
+
+fn leak<T>(v: Vec<T>) -> &'static [T] { Box::leak(v.into_boxed_slice()) }
+
+pub(crate) fn leak_str<'de, D: Deserializer<'de>>(d: D) -> Result<&'static str, D::Error> {
+    String::deserialize(d).map(leak_string)
+}
+
+pub(crate) fn leak_opt_slice<'de, D, T>(d: D) -> Result<Option<&'static [T]>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de> + 'static,
+{
+    Option::<Vec<T>>::deserialize(d).map(|v| v.map(leak))
+}
+
+pub(crate) fn leak_opt_str_slice<'de, D: Deserializer<'de>>(d: D) -> Result<Option<&'static [&'static str]>, D::Error> {
+    Option::<Vec<String>>::deserialize(d).map(|v| v.map(|v| leak(v.into_iter().map(leak_string).collect())))
+}
@@ -1,7 +1,7 @@
 #![cfg(feature = "vulkan-linux")]
 use ash::vk;
 use crate::resources::{ResourceBindings, BindingStages, VertexLayout, StepMode};
-use crate::pipeline::{PipelineDesc, RasterState as Rs, PolygonMode as Pm, CullMode as Cm, FrontFace as Ff, CompareOp, PushConstantRange, StageMask};
+use crate::pipeline::{PipelineDesc, Topology, RasterState as Rs, PolygonMode as Pm, CullMode as Cm, FrontFace as Ff, CompareOp, PushConstantRange, StageMask, BlendFactor, BlendOp, BlendPreset, ColorBlendState, LogicOp};
 use std::collections::BTreeMap;
 
 pub fn stage_flags_from_binding_stages(st: &Option<BindingStages>) -> vk::ShaderStageFlags {
@@ -16,23 +16,35 @@ pub fn stage_flags_from_binding_stages(st: &Option<BindingStages>) -> vk::Shader
     }
 }
 
-pub fn descriptor_bindings_from<RB: ResourceBindings>() -> BTreeMap<u32, Vec<vk::DescriptorSetLayoutBinding>> {
+/// Descriptor type for a binding; `dynamic` uniforms map to `UNIFORM_BUFFER_DYNAMIC`.
+pub fn descriptor_type_of(b: &crate::resources::BindingDesc) -> vk::DescriptorType {
     use crate::resources::ResourceKind;
+    match b.kind {
+        ResourceKind::Uniform if b.dynamic => vk::DescriptorType::UNIFORM_BUFFER_DYNAMIC,
+        ResourceKind::Uniform => vk::DescriptorType::UNIFORM_BUFFER,
+        ResourceKind::Texture => vk::DescriptorType::SAMPLED_IMAGE,
+        ResourceKind::Sampler => vk::DescriptorType::SAMPLER,
+        ResourceKind::CombinedImageSampler => vk::DescriptorType::COMBINED_IMAGE_SAMPLER,
+        ResourceKind::StorageBuffer => vk::DescriptorType::STORAGE_BUFFER,
+        ResourceKind::StorageImage => vk::DescriptorType::STORAGE_IMAGE,
+    }
+}
+
+/// Zeroed dynamic offsets for binding all sets of `bindings` (one per dynamic descriptor).
+/// Backends with per-object data replace these with real offsets.
+pub fn dynamic_offsets_from(bindings: &[crate::resources::BindingDesc]) -> Vec<u32> {
+    vec![0; bindings.iter().filter(|b| b.dynamic).map(|b| b.count as usize).sum()]
+}
+
+pub fn descriptor_bindings_from<RB: ResourceBindings>() -> BTreeMap<u32, Vec<vk::DescriptorSetLayoutBinding>> {
     let mut by_set: BTreeMap<u32, Vec<vk::DescriptorSetLayoutBinding>> = BTreeMap::new();
     for b in RB::bindings() {
-        let dtype = match b.kind {
-            ResourceKind::Uniform => vk::DescriptorType::UNIFORM_BUFFER,
-            ResourceKind::Texture => vk::DescriptorType::SAMPLED_IMAGE,
-            ResourceKind::Sampler => vk::DescriptorType::SAMPLER,
-            ResourceKind::CombinedImageSampler => vk::DescriptorType::COMBINED_IMAGE_SAMPLER,
-            ResourceKind::StorageBuffer => vk::DescriptorType::STORAGE_BUFFER,
-            ResourceKind::StorageImage => vk::DescriptorType::STORAGE_IMAGE,
-        };
+        let dtype = descriptor_type_of(b);
         let stage_flags = stage_flags_from_binding_stages(&b.stages);
         let bind = vk::DescriptorSetLayoutBinding::builder()
             .binding(b.binding)
             .descriptor_type(dtype)
-            .descriptor_count(1)
+            .descriptor_count(b.count)
             .stage_flags(stage_flags)
             .build();
         by_set.entry(b.set).or_default().push(bind);
@@ -41,6 +53,31 @@ pub fn descriptor_bindings_from<RB: ResourceBindings>() -> BTreeMap<u32, Vec<vk:
     by_set
 }
 
+/// Layout create flags for `set`: push-descriptor layouts get `PUSH_DESCRIPTOR_KHR`.
+/// The derive rejects sets that mix push and non-push bindings.
+/// Zeroed dynamic offsets for the sets in `sets`, for binding a contiguous run of sets.
+pub fn dynamic_offsets_in(bindings: &[crate::resources::BindingDesc], sets: std::ops::Range<u32>) -> Vec<u32> {
+    vec![0; bindings.iter().filter(|b| b.dynamic && sets.contains(&b.set)).map(|b| b.count as usize).sum()]
+}
+
+/// True when `set` is recorded with `vkCmdPushDescriptorSetKHR` instead of being allocated.
+pub fn is_push_set(bindings: &[crate::resources::BindingDesc], set: u32) -> bool {
+    bindings.iter().any(|b| b.set == set && b.push)
+}
+
+/// True when any binding needs the `VK_KHR_push_descriptor` device extension.
+pub fn uses_push_descriptors(bindings: &[crate::resources::BindingDesc]) -> bool {
+    bindings.iter().any(|b| b.push)
+}
+
+pub fn set_layout_flags_from(bindings: &[crate::resources::BindingDesc], set: u32) -> vk::DescriptorSetLayoutCreateFlags {
+    if is_push_set(bindings, set) {
+        vk::DescriptorSetLayoutCreateFlags::PUSH_DESCRIPTOR_KHR
+    } else {
+        vk::DescriptorSetLayoutCreateFlags::empty()
+    }
+}
+
 fn map_format(fmt: &str) -> vk::Format {
     match fmt {
         "f32" => vk::Format::R32_SFLOAT,
@@ -49,19 +86,44 @@ fn map_format(fmt: &str) -> vk::Format {
         "vec2" => vk::Format::R32G32_SFLOAT,
         "vec3" => vk::Format::R32G32B32_SFLOAT,
         "vec4" => vk::Format::R32G32B32A32_SFLOAT,
+        "r8_unorm" => vk::Format::R8_UNORM,
+        "r8_snorm" => vk::Format::R8_SNORM,
+        "r8_uint" => vk::Format::R8_UINT,
+        "r8_sint" => vk::Format::R8_SINT,
+        "rg8_unorm" => vk::Format::R8G8_UNORM,
+        "rg8_snorm" => vk::Format::R8G8_SNORM,
+        "rg8_uint" => vk::Format::R8G8_UINT,
+        "rg8_sint" => vk::Format::R8G8_SINT,
         "rgba8_unorm" | "r8g8b8a8_unorm" | "u8x4_norm" => vk::Format::R8G8B8A8_UNORM,
+        "rgba8_snorm" => vk::Format::R8G8B8A8_SNORM,
+        "rgba8_uint" => vk::Format::R8G8B8A8_UINT,
+        "rgba8_sint" => vk::Format::R8G8B8A8_SINT,
         "rgba8_srgb" | "r8g8b8a8_srgb" => vk::Format::R8G8B8A8_SRGB,
         "bgra8_unorm" | "b8g8r8a8_unorm" => vk::Format::B8G8R8A8_UNORM,
         "bgra8_srgb" | "b8g8r8a8_srgb" => vk::Format::B8G8R8A8_SRGB,
+        "r16_unorm" => vk::Format::R16_UNORM,
+        "r16_snorm" => vk::Format::R16_SNORM,
+        "r16_uint" => vk::Format::R16_UINT,
+        "r16_sint" => vk::Format::R16_SINT,
+        "rg16_unorm" => vk::Format::R16G16_UNORM,
+        "rg16_snorm" => vk::Format::R16G16_SNORM,
+        "rg16_uint" => vk::Format::R16G16_UINT,
+        "rg16_sint" => vk::Format::R16G16_SINT,
         "rgba16_unorm" | "r16g16b16a16_unorm" => vk::Format::R16G16B16A16_UNORM,
-        "rgba16f" | "r16g16b16a16_sfloat" => vk::Format::R16G16B16A16_SFLOAT,
-        "r16f" | "r16_sfloat" => vk::Format::R16_SFLOAT,
-        "rg16f" | "r16g16_sfloat" => vk::Format::R16G16_SFLOAT,
+        "rgba16_snorm" => vk::Format::R16G16B16A16_SNORM,
+        "rgba16_uint" => vk::Format::R16G16B16A16_UINT,
+        "rgba16_sint" => vk::Format::R16G16B16A16_SINT,
+        "rgba16f" | "rgba16_float" | "r16g16b16a16_sfloat" => vk::Format::R16G16B16A16_SFLOAT,
+        "r16f" | "r16_float" | "r16_sfloat" => vk::Format::R16_SFLOAT,
+        "rg16f" | "rg16_float" | "r16g16_sfloat" => vk::Format::R16G16_SFLOAT,
         "r32f" | "r32_sfloat" => vk::Format::R32_SFLOAT,
         "rg32f" | "r32g32_sfloat" => vk::Format::R32G32_SFLOAT,
         "rgb32f" | "r32g32b32_sfloat" => vk::Format::R32G32B32_SFLOAT,
         "rgba32f" | "r32g32b32a32_sfloat" => vk::Format::R32G32B32A32_SFLOAT,
         "rgb10a2_unorm" | "a2b10g10r10_unorm" => vk::Format::A2B10G10R10_UNORM_PACK32,
+        "rgb10a2_snorm" => vk::Format::A2B10G10R10_SNORM_PACK32,
+        "rgb10a2_uint" => vk::Format::A2B10G10R10_UINT_PACK32,
+        "rg11b10_float" => vk::Format::B10G11R11_UFLOAT_PACK32,
         _ => vk::Format::R32G32B32A32_SFLOAT,
     }
 }
@@ -79,6 +141,17 @@ pub fn vertex_input_from<VL: VertexLayout>() -> (Vec<vk::VertexInputBindingDescr
     (binding_descs, attr_descs)
 }
 
+pub fn topology_from(t: &Topology) -> vk::PrimitiveTopology {
+    match t {
+        Topology::TriangleList => vk::PrimitiveTopology::TRIANGLE_LIST,
+        Topology::LineList => vk::PrimitiveTopology::LINE_LIST,
+        Topology::PointList => vk::PrimitiveTopology::POINT_LIST,
+        Topology::TriangleStrip => vk::PrimitiveTopology::TRIANGLE_STRIP,
+        Topology::LineStrip => vk::PrimitiveTopology::LINE_STRIP,
+        Topology::TriangleFan => vk::PrimitiveTopology::TRIANGLE_FAN,
+    }
+}
+
 pub fn raster_state_from(desc: &PipelineDesc) -> (vk::PolygonMode, vk::CullModeFlags, vk::FrontFace) {
     let rs = desc.raster.clone().unwrap_or(Rs { polygon: Pm::Fill, cull: Cm::Back, front_face: Ff::Cw });
     let poly = match rs.polygon { Pm::Fill => vk::PolygonMode::FILL, Pm::Line => vk::PolygonMode::LINE };
@@ -91,31 +164,106 @@ pub fn samples_from(desc: &PipelineDesc) -> vk::SampleCountFlags {
     match desc.samples.unwrap_or(1) { 1 => vk::SampleCountFlags::TYPE_1, 2 => vk::SampleCountFlags::TYPE_2, 4 => vk::SampleCountFlags::TYPE_4, 8 => vk::SampleCountFlags::TYPE_8, _ => vk::SampleCountFlags::TYPE_1 }
 }
 
-pub fn color_blend_attachment_from(desc: &PipelineDesc) -> vk::PipelineColorBlendAttachmentState {
-    let enable = desc.blend.as_ref().map(|b| b.enable).unwrap_or(false);
+/// `(alpha_to_coverage_enable, min_sample_shading)` for the multisample state; sample shading is
+/// enabled iff the second value is `Some`.
+pub fn multisample_from(desc: &PipelineDesc) -> (bool, Option<f32>) {
+    desc.multisample.as_ref().map_or((false, None), |m| (m.alpha_to_coverage, m.min_sample_shading))
+}
+
+/// Optional device features to enable for `pipelines`, limited to what the device offers.
+/// `sampleRateShading` is requested only when some pipeline sets `min_sample_shading`; when the
+/// device lacks it the feature stays off and pipelines must fall back to per-pixel shading.
+/// `logicOp` has no such fallback, so a pipeline with `logic_op` on a device without it errors.
+pub fn device_features_for(pipelines: &[PipelineDesc], available: &vk::PhysicalDeviceFeatures) -> Result<vk::PhysicalDeviceFeatures, String> {
+    let mut enabled = vk::PhysicalDeviceFeatures::default();
+    if let Some(p) = pipelines.iter().find(|p| color_blend_logic_op_from(p).is_some()) {
+        if available.logic_op == vk::FALSE {
+            return Err(format!("pipeline '{}' sets logic_op, but the device does not support the logicOp feature", p.name));
+        }
+        enabled.logic_op = vk::TRUE;
+    }
+    if pipelines.iter().any(|p| multisample_from(p).1.is_some()) {
+        enabled.sample_rate_shading = available.sample_rate_shading;
+    }
+    Ok(enabled)
+}
+
+pub fn blend_factor_from(f: BlendFactor) -> vk::BlendFactor {
+    match f {
+        BlendFactor::Zero => vk::BlendFactor::ZERO,
+        BlendFactor::One => vk::BlendFactor::ONE,
+        BlendFactor::SrcColor => vk::BlendFactor::SRC_COLOR,
+        BlendFactor::OneMinusSrcColor => vk::BlendFactor::ONE_MINUS_SRC_COLOR,
+        BlendFactor::DstColor => vk::BlendFactor::DST_COLOR,
+        BlendFactor::OneMinusDstColor => vk::BlendFactor::ONE_MINUS_DST_COLOR,
+        BlendFactor::SrcAlpha => vk::BlendFactor::SRC_ALPHA,
+        BlendFactor::OneMinusSrcAlpha => vk::BlendFactor::ONE_MINUS_SRC_ALPHA,
+        BlendFactor::DstAlpha => vk::BlendFactor::DST_ALPHA,
+        BlendFactor::OneMinusDstAlpha => vk::BlendFactor::ONE_MINUS_DST_ALPHA,
+    }
+}
+
+pub fn blend_op_from(op: BlendOp) -> vk::BlendOp {
+    match op {
+        BlendOp::Add => vk::BlendOp::ADD,
+        BlendOp::Subtract => vk::BlendOp::SUBTRACT,
+        BlendOp::ReverseSubtract => vk::BlendOp::REVERSE_SUBTRACT,
+        BlendOp::Min => vk::BlendOp::MIN,
+        BlendOp::Max => vk::BlendOp::MAX,
+    }
+}
+
+pub fn logic_op_from(op: LogicOp) -> vk::LogicOp {
+    match op {
+        LogicOp::Clear => vk::LogicOp::CLEAR,
+        LogicOp::And => vk::LogicOp::AND,
+        LogicOp::AndReverse => vk::LogicOp::AND_REVERSE,
+        LogicOp::Copy => vk::LogicOp::COPY,
+        LogicOp::AndInverted => vk::LogicOp::AND_INVERTED,
+        LogicOp::NoOp => vk::LogicOp::NO_OP,
+        LogicOp::Xor => vk::LogicOp::XOR,
+        LogicOp::Or => vk::LogicOp::OR,
+        LogicOp::Nor => vk::LogicOp::NOR,
+        LogicOp::Equivalent => vk::LogicOp::EQUIVALENT,
+        LogicOp::Invert => vk::LogicOp::INVERT,
+        LogicOp::OrReverse => vk::LogicOp::OR_REVERSE,
+        LogicOp::CopyInverted => vk::LogicOp::COPY_INVERTED,
+        LogicOp::OrInverted => vk::LogicOp::OR_INVERTED,
+        LogicOp::Nand => vk::LogicOp::NAND,
+        LogicOp::Set => vk::LogicOp::SET,
+    }
+}
+
+/// Pipeline-wide logic op for `VkPipelineColorBlendStateCreateInfo`, if the blend state sets one.
+pub fn color_blend_logic_op_from(desc: &PipelineDesc) -> Option<vk::LogicOp> {
+    desc.blend.as_ref().and_then(|b| b.logic_op).map(logic_op_from)
+}
+
+fn blend_attachment_state(state: Option<&ColorBlendState>) -> vk::PipelineColorBlendAttachmentState {
+    let state = state.cloned().unwrap_or(BlendPreset::Opaque.state());
+    let eq = state.equation;
     vk::PipelineColorBlendAttachmentState::builder()
         .color_write_mask(vk::ColorComponentFlags::R | vk::ColorComponentFlags::G | vk::ColorComponentFlags::B | vk::ColorComponentFlags::A)
-        .blend_enable(enable)
+        .blend_enable(state.enable)
+        .src_color_blend_factor(blend_factor_from(eq.src_color))
+        .dst_color_blend_factor(blend_factor_from(eq.dst_color))
+        .color_blend_op(blend_op_from(eq.color_op))
+        .src_alpha_blend_factor(blend_factor_from(eq.src_alpha))
+        .dst_alpha_blend_factor(blend_factor_from(eq.dst_alpha))
+        .alpha_blend_op(blend_op_from(eq.alpha_op))
         .build()
 }
 
+pub fn color_blend_attachment_from(desc: &PipelineDesc) -> vk::PipelineColorBlendAttachmentState {
+    blend_attachment_state(desc.blend.as_ref())
+}
+
 pub fn color_blend_attachments_from(desc: &PipelineDesc) -> Vec<vk::PipelineColorBlendAttachmentState> {
     if let Some(colors) = desc.color_targets {
         if !colors.is_empty() {
             return colors
                 .iter()
-                .map(|c| {
-                    let enable = c.blend.unwrap_or_else(|| desc.blend.as_ref().map(|b| b.enable).unwrap_or(false));
-                    vk::PipelineColorBlendAttachmentState::builder()
-                        .color_write_mask(
-                            vk::ColorComponentFlags::R
-                                | vk::ColorComponentFlags::G
-                                | vk::ColorComponentFlags::B
-                                | vk::ColorComponentFlags::A,
-                        )
-                        .blend_enable(enable)
-                        .build()
-                })
+                .map(|c| blend_attachment_state(c.blend.as_ref().or(desc.blend.as_ref())))
                 .collect();
         }
     }
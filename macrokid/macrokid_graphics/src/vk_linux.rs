@@ -122,6 +122,48 @@ impl ThreadLocalPools {
     }
 }
 
+/// Descriptor writes for one push-descriptor set, kept alive until command recording.
+struct PushSetWrites {
+    set: u32,
+    writes: Vec<vk::WriteDescriptorSet>,
+    // Backing storage for the raw pointers inside `writes`
+    _buf_infos: Vec<vk::DescriptorBufferInfo>,
+    _img_infos: Vec<vk::DescriptorImageInfo>,
+}
+
+/// Bind each contiguous run of allocated sets; push-set placeholders (null) split the runs.
+unsafe fn bind_descriptor_sets(
+    device: &ash::Device,
+    cb: vk::CommandBuffer,
+    bind_point: vk::PipelineBindPoint,
+    layout: vk::PipelineLayout,
+    sets: &[vk::DescriptorSet],
+    bindings: &[crate::resources::BindingDesc],
+) {
+    let mut start = 0;
+    while start < sets.len() {
+        if sets[start] == vk::DescriptorSet::null() { start += 1; continue; }
+        let end = sets[start..].iter().position(|&s| s == vk::DescriptorSet::null()).map_or(sets.len(), |n| start + n);
+        let dyn_offsets = crate::vk_bridge::dynamic_offsets_in(bindings, start as u32..end as u32);
+        device.cmd_bind_descriptor_sets(cb, bind_point, layout, start as u32, &sets[start..end], &dyn_offsets);
+        start = end;
+    }
+}
+
+/// Record push-descriptor sets with vkCmdPushDescriptorSetKHR.
+unsafe fn push_descriptor_sets(
+    push_descriptor: Option<&ash::extensions::khr::PushDescriptor>,
+    cb: vk::CommandBuffer,
+    bind_point: vk::PipelineBindPoint,
+    layout: vk::PipelineLayout,
+    pushes: &[PushSetWrites],
+) {
+    let Some(loader) = push_descriptor else { return };
+    for p in pushes.iter().filter(|p| !p.writes.is_empty()) {
+        loader.cmd_push_descriptor_set(cb, bind_point, layout, p.set, &p.writes);
+    }
+}
+
 struct VkCore {
     _entry: Entry,
     instance: Instance,
@@ -268,16 +310,38 @@ impl VkCore {
                 chosen.ok_or_else(|| VkError::General("no suitable queue family with graphics+present".into()))?
             };
 
-            // 4) Logical device + queue (+ swapchain extension)
+            // 4) Logical device + queue (+ swapchain extension, + push descriptors when any binding asks for them)
             let priorities = [1.0f32];
             let qci = [vk::DeviceQueueCreateInfo::builder().queue_family_index(qfi).queue_priorities(&priorities).build()];
-            let device_exts = [ash::extensions::khr::Swapchain::name().as_ptr()];
+            let mut device_exts = vec![ash::extensions::khr::Swapchain::name().as_ptr()];
+            let needs_push_descriptors = crate::vk_bridge::uses_push_descriptors(RB::bindings())
+                || cfg.compute_pipelines.iter().any(|cd| cd.bindings.is_some_and(crate::vk_bridge::uses_push_descriptors));
+            if needs_push_descriptors {
+                let push_name = ash::extensions::khr::PushDescriptor::name();
+                let supported = instance
+                    .enumerate_device_extension_properties(phys)
+                    .map_err(|e| VkError::General(format!("enumerate_device_extension_properties: {e}")))?
+                    .iter()
+                    .any(|ext| CStr::from_ptr(ext.extension_name.as_ptr()) == push_name);
+                if !supported {
+                    return Err(VkError::General(format!("bindings with push = true require {}, which the selected device does not support", push_name.to_string_lossy())));
+                }
+                device_exts.push(push_name.as_ptr());
+            }
+            let available_features = instance.get_physical_device_features(phys);
+            let device_features = crate::vk_bridge::device_features_for(&cfg.pipelines, &available_features)?;
+            let sample_shading_ok = device_features.sample_rate_shading == vk::TRUE;
+            if !sample_shading_ok && cfg.pipelines.iter().any(|p| crate::vk_bridge::multisample_from(p).1.is_some()) {
+                eprintln!("[vk-linux] Warning: min_sample_shading requested, but the device lacks the sampleRateShading feature; falling back to per-pixel shading.");
+            }
             let device_info = vk::DeviceCreateInfo::builder()
                 .queue_create_infos(&qci)
-                .enabled_extension_names(&device_exts);
+                .enabled_extension_names(&device_exts)
+                .enabled_features(&device_features);
             let device = instance.create_device(phys, &device_info, None)
                 .map_err(|e| VkError::General(format!("create_device: {e}")))?;
             let queue = device.get_device_queue(qfi, 0);
+            let push_descriptor = needs_push_descriptors.then(|| ash::extensions::khr::PushDescriptor::new(&instance, &device));
 
             // 5) Swapchain select + create
             let surface_caps = surface_loader
@@ -647,78 +711,67 @@ impl VkCore {
             // 9) Descriptor set layouts from ResourceBindings via bridge (graphics/global)
             let by_set = crate::vk_bridge::descriptor_bindings_from::<RB>();
             let mut set_layouts: Vec<vk::DescriptorSetLayout> = Vec::new();
-            for (_set, mut binds) in by_set.into_iter() {
+            // Push-descriptor sets are never allocated; they are recorded with vkCmdPushDescriptorSetKHR
+            let mut set_is_push: Vec<bool> = Vec::new();
+            for (set, mut binds) in by_set.into_iter() {
                 // Ensure deterministic order by binding index
                 binds.sort_by_key(|b| b.binding);
-                let info = vk::DescriptorSetLayoutCreateInfo::builder().bindings(&binds);
+                let flags = crate::vk_bridge::set_layout_flags_from(RB::bindings(), set);
+                let info = vk::DescriptorSetLayoutCreateInfo::builder().flags(flags).bindings(&binds);
                 let layout = device
                     .create_descriptor_set_layout(&info, None)
                     .map_err(|e| VkError::General(format!("create_descriptor_set_layout: {e}")))?;
                 set_layouts.push(layout);
+                set_is_push.push(crate::vk_bridge::is_push_set(RB::bindings(), set));
             }
 
             // 9a) Descriptor set layouts for each compute pass (if ComputeDesc.bindings provided)
             let mut compute_set_layouts: Vec<Vec<vk::DescriptorSetLayout>> = Vec::new();
+            let mut compute_set_is_push: Vec<Vec<bool>> = Vec::new();
             for cd in &cfg.compute_pipelines {
-                if let Some(binds) = cd.bindings { // group by set
+                if let Some(binds_desc) = cd.bindings { // group by set
                     use std::collections::BTreeMap;
                     let mut by_set: BTreeMap<u32, Vec<vk::DescriptorSetLayoutBinding>> = BTreeMap::new();
-                    for b in binds.iter() {
-                        let dtype = match b.kind {
-                            crate::resources::ResourceKind::Uniform => vk::DescriptorType::UNIFORM_BUFFER,
-                            crate::resources::ResourceKind::Texture => vk::DescriptorType::SAMPLED_IMAGE,
-                            crate::resources::ResourceKind::Sampler => vk::DescriptorType::SAMPLER,
-                            crate::resources::ResourceKind::CombinedImageSampler => vk::DescriptorType::COMBINED_IMAGE_SAMPLER,
-                            crate::resources::ResourceKind::StorageBuffer => vk::DescriptorType::STORAGE_BUFFER,
-                            crate::resources::ResourceKind::StorageImage => vk::DescriptorType::STORAGE_IMAGE,
-                        };
+                    for b in binds_desc.iter() {
+                        let dtype = crate::vk_bridge::descriptor_type_of(b);
                         let stage_flags = crate::vk_bridge::stage_flags_from_binding_stages(&b.stages);
                         let bind = vk::DescriptorSetLayoutBinding::builder()
                             .binding(b.binding)
                             .descriptor_type(dtype)
-                            .descriptor_count(1)
+                            .descriptor_count(b.count)
                             .stage_flags(stage_flags)
                             .build();
                         by_set.entry(b.set).or_default().push(bind);
                     }
                     let mut layouts: Vec<vk::DescriptorSetLayout> = Vec::new();
-                    for (_set, mut binds) in by_set.into_iter() {
+                    let mut is_push: Vec<bool> = Vec::new();
+                    for (set, mut binds) in by_set.into_iter() {
                         binds.sort_by_key(|b| b.binding);
-                        let info = vk::DescriptorSetLayoutCreateInfo::builder().bindings(&binds);
+                        let flags = crate::vk_bridge::set_layout_flags_from(binds_desc, set);
+                        let info = vk::DescriptorSetLayoutCreateInfo::builder().flags(flags).bindings(&binds);
                         let layout = device.create_descriptor_set_layout(&info, None)
                             .map_err(|e| VkError::General(format!("create_descriptor_set_layout(compute): {e}")))?;
                         layouts.push(layout);
+                        is_push.push(crate::vk_bridge::is_push_set(binds_desc, set));
                     }
                     compute_set_layouts.push(layouts);
-                } else { compute_set_layouts.push(Vec::new()); }
+                    compute_set_is_push.push(is_push);
+                } else { compute_set_layouts.push(Vec::new()); compute_set_is_push.push(Vec::new()); }
             }
 
             // 9.1) Descriptor pool + set allocation (no writes yet)
+            // Push-descriptor bindings take no pool space
             let mut pool_sizes: ::std::collections::BTreeMap<vk::DescriptorType, u32> = ::std::collections::BTreeMap::new();
-            for b in RB::bindings() {
-                let dtype = match b.kind {
-                    crate::resources::ResourceKind::Uniform => vk::DescriptorType::UNIFORM_BUFFER,
-                    crate::resources::ResourceKind::Texture => vk::DescriptorType::SAMPLED_IMAGE,
-                    crate::resources::ResourceKind::Sampler => vk::DescriptorType::SAMPLER,
-                    crate::resources::ResourceKind::CombinedImageSampler => vk::DescriptorType::COMBINED_IMAGE_SAMPLER,
-                    crate::resources::ResourceKind::StorageBuffer => vk::DescriptorType::STORAGE_BUFFER,
-                    crate::resources::ResourceKind::StorageImage => vk::DescriptorType::STORAGE_IMAGE,
-                };
-                *pool_sizes.entry(dtype).or_insert(0) += 1;
+            for b in RB::bindings().iter().filter(|b| !b.push) {
+                let dtype = crate::vk_bridge::descriptor_type_of(b);
+                *pool_sizes.entry(dtype).or_insert(0) += b.count;
             }
             // Include compute bindings in pool sizing
             for cd in &cfg.compute_pipelines {
                 if let Some(binds) = cd.bindings {
-                    for b in binds.iter() {
-                        let dtype = match b.kind {
-                            crate::resources::ResourceKind::Uniform => vk::DescriptorType::UNIFORM_BUFFER,
-                            crate::resources::ResourceKind::Texture => vk::DescriptorType::SAMPLED_IMAGE,
-                            crate::resources::ResourceKind::Sampler => vk::DescriptorType::SAMPLER,
-                            crate::resources::ResourceKind::CombinedImageSampler => vk::DescriptorType::COMBINED_IMAGE_SAMPLER,
-                            crate::resources::ResourceKind::StorageBuffer => vk::DescriptorType::STORAGE_BUFFER,
-                            crate::resources::ResourceKind::StorageImage => vk::DescriptorType::STORAGE_IMAGE,
-                        };
-                        *pool_sizes.entry(dtype).or_insert(0) += 1;
+                    for b in binds.iter().filter(|b| !b.push) {
+                        let dtype = crate::vk_bridge::descriptor_type_of(b);
+                        *pool_sizes.entry(dtype).or_insert(0) += b.count;
                     }
                 }
             }
@@ -732,38 +785,38 @@ impl VkCore {
                 // Oversize pool to reduce chance of exhaustion when adding compute sets
                 let mult = cfg.options.desc_pool_multiplier.unwrap_or(1).max(1);
                 let pool_sizes_vec: Vec<vk::DescriptorPoolSize> = pool_sizes_vec.iter().map(|p| vk::DescriptorPoolSize { ty: p.ty, descriptor_count: p.descriptor_count * mult }).collect();
-                let total_compute_sets: u32 = compute_set_layouts.iter().map(|v| v.len() as u32).sum();
-                let max_sets = ((set_layouts.len() as u32) + total_compute_sets) * frames * mult;
+                let allocated_sets = |is_push: &[bool]| is_push.iter().filter(|&&p| !p).count() as u32;
+                let total_compute_sets: u32 = compute_set_is_push.iter().map(|v| allocated_sets(v)).sum();
+                let max_sets = (allocated_sets(&set_is_push) + total_compute_sets) * frames * mult;
                 let info = vk::DescriptorPoolCreateInfo::builder()
                     .flags(vk::DescriptorPoolCreateFlags::FREE_DESCRIPTOR_SET)
                     .max_sets(max_sets)
                     .pool_sizes(&pool_sizes_vec);
                 device.create_descriptor_pool(&info, None).map_err(|e| VkError::General(format!("create_descriptor_pool: {e}")))?
             } else { vk::DescriptorPool::null() };
-            let descriptor_sets_per_frame: Vec<Vec<vk::DescriptorSet>> = if descriptor_pool != vk::DescriptorPool::null() && !set_layouts.is_empty() {
-                let mut v = Vec::with_capacity(views.len());
-                for _ in 0..views.len() {
-                    let alloc_info = vk::DescriptorSetAllocateInfo::builder().descriptor_pool(descriptor_pool).set_layouts(&set_layouts);
-                    let sets = device.allocate_descriptor_sets(&alloc_info).map_err(|e| VkError::General(format!("allocate_descriptor_sets: {e}")))?;
-                    v.push(sets);
-                }
-                v
-            } else { (0..views.len()).map(|_| Vec::new()).collect() };
+            // Sets are indexed by set number; push sets keep a null placeholder
+            let allocate_sets = |layouts: &[vk::DescriptorSetLayout], is_push: &[bool]| -> Result<Vec<vk::DescriptorSet>, vk::Result> {
+                let allocatable: Vec<vk::DescriptorSetLayout> = layouts.iter().zip(is_push).filter(|(_, &p)| !p).map(|(&l, _)| l).collect();
+                let mut allocated = if descriptor_pool != vk::DescriptorPool::null() && !allocatable.is_empty() {
+                    let alloc_info = vk::DescriptorSetAllocateInfo::builder().descriptor_pool(descriptor_pool).set_layouts(&allocatable);
+                    device.allocate_descriptor_sets(&alloc_info)?.into_iter()
+                } else { Vec::new().into_iter() };
+                Ok(is_push.iter().map(|&p| if p { vk::DescriptorSet::null() } else { allocated.next().unwrap_or(vk::DescriptorSet::null()) }).collect())
+            };
+            let mut descriptor_sets_per_frame: Vec<Vec<vk::DescriptorSet>> = Vec::with_capacity(views.len());
+            for _ in 0..views.len() {
+                let sets = allocate_sets(&set_layouts, &set_is_push).map_err(|e| VkError::General(format!("allocate_descriptor_sets: {e}")))?;
+                descriptor_sets_per_frame.push(sets);
+            }
             // Allocate compute descriptor sets per frame and compute pass
             let mut compute_descriptor_sets_per_frame: Vec<Vec<Vec<vk::DescriptorSet>>> = Vec::new();
-            if descriptor_pool != vk::DescriptorPool::null() {
-                for _ in 0..views.len() {
-                    let mut per_compute: Vec<Vec<vk::DescriptorSet>> = Vec::new();
-                    for layouts in &compute_set_layouts {
-                        if layouts.is_empty() { per_compute.push(Vec::new()); continue; }
-                        let alloc_info = vk::DescriptorSetAllocateInfo::builder().descriptor_pool(descriptor_pool).set_layouts(layouts);
-                        let sets = device.allocate_descriptor_sets(&alloc_info).map_err(|e| VkError::General(format!("allocate_descriptor_sets(compute): {e}")))?;
-                        per_compute.push(sets);
-                    }
-                    compute_descriptor_sets_per_frame.push(per_compute);
+            for _ in 0..views.len() {
+                let mut per_compute: Vec<Vec<vk::DescriptorSet>> = Vec::new();
+                for (layouts, is_push) in compute_set_layouts.iter().zip(&compute_set_is_push) {
+                    let sets = allocate_sets(layouts, is_push).map_err(|e| VkError::General(format!("allocate_descriptor_sets(compute): {e}")))?;
+                    per_compute.push(sets);
                 }
-            } else {
-                for _ in 0..views.len() { compute_descriptor_sets_per_frame.push(Vec::new()); }
+                compute_descriptor_sets_per_frame.push(per_compute);
             }
 
             // 9.2) Create demo resources for descriptors
@@ -1003,6 +1056,8 @@ impl VkCore {
             }
 
             // 9.3) Write descriptors for each set/binding kind we support (uniform, texture, sampler, combined)
+            // Push sets keep their writes (and the infos they point at) until command recording.
+            let mut push_writes_per_frame: Vec<Vec<PushSetWrites>> = (0..views.len()).map(|_| Vec::new()).collect();
             if !descriptor_sets_per_frame.is_empty() {
                 // Group writes per set
                 use std::collections::BTreeMap;
@@ -1010,10 +1065,13 @@ impl VkCore {
                 for b in RB::bindings() { by_set.entry(b.set).or_default().push(b); }
                 for (frame_idx, frame_sets) in descriptor_sets_per_frame.iter().enumerate() {
                     for (set_idx, binds) in by_set.iter() {
-                        if let Some(&dst_set) = frame_sets.get(*set_idx as usize) {
+                        let push = crate::vk_bridge::is_push_set(RB::bindings(), *set_idx);
+                        let dst_set = if push { Some(vk::DescriptorSet::null()) } else { frame_sets.get(*set_idx as usize).copied() };
+                        if let Some(dst_set) = dst_set {
                             let mut writes: Vec<vk::WriteDescriptorSet> = Vec::new();
-                            let mut buf_infos: Vec<vk::DescriptorBufferInfo> = Vec::new();
-                            let mut img_infos: Vec<vk::DescriptorImageInfo> = Vec::new();
+                            // Writes point into these; reserve so pushes never reallocate
+                            let mut buf_infos: Vec<vk::DescriptorBufferInfo> = Vec::with_capacity(binds.len());
+                            let mut img_infos: Vec<vk::DescriptorImageInfo> = Vec::with_capacity(binds.len());
                             for b in binds.iter() {
                                 match b.kind {
                                     crate::resources::ResourceKind::Uniform => {
@@ -1023,7 +1081,7 @@ impl VkCore {
                                             buf_infos.push(vk::DescriptorBufferInfo { buffer: ub, offset: 0, range });
                                             let info = buf_infos.last().unwrap();
                                             writes.push(vk::WriteDescriptorSet::builder()
-                                                .dst_set(dst_set).dst_binding(b.binding).descriptor_type(vk::DescriptorType::UNIFORM_BUFFER)
+                                                .dst_set(dst_set).dst_binding(b.binding).descriptor_type(crate::vk_bridge::descriptor_type_of(b))
                                                 .buffer_info(std::slice::from_ref(info)).build());
                                         }
                                     }
@@ -1062,14 +1120,18 @@ impl VkCore {
                                 }
                             }
                             }
-                            if !writes.is_empty() { device.update_descriptor_sets(&writes, &[]); }
+                            if push {
+                                push_writes_per_frame[frame_idx].push(PushSetWrites { set: *set_idx, writes, _buf_infos: buf_infos, _img_infos: img_infos });
+                            } else if !writes.is_empty() { device.update_descriptor_sets(&writes, &[]); }
                         }
                     }
-                    let _ = frame_idx; // reserved for per-frame updates
                 }
             }
 
             // 9.4) Write compute descriptor sets from provided ComputeDesc.bindings (if any)
+            let mut compute_push_writes_per_frame: Vec<Vec<Vec<PushSetWrites>>> = (0..views.len())
+                .map(|_| cfg.compute_pipelines.iter().map(|_| Vec::new()).collect())
+                .collect();
             if !compute_descriptor_sets_per_frame.is_empty() {
                 for (frame_idx, per_compute) in compute_descriptor_sets_per_frame.iter().enumerate() {
                     for (ci, sets) in per_compute.iter().enumerate() {
@@ -1082,11 +1144,13 @@ impl VkCore {
                         // Ensure orders by binding index within each set
                         for v in by_set.values_mut() { v.sort_by_key(|b| b.binding); }
                         // Iterate sets in ascending set index; map to ordinal in `sets`
-                        for (ordinal, (_set_idx, binds)) in by_set.into_iter().enumerate() {
+                        for (ordinal, (set_idx, binds)) in by_set.into_iter().enumerate() {
+                            let push = crate::vk_bridge::is_push_set(binds_slice, set_idx);
                             if let Some(&dst_set) = sets.get(ordinal) {
                                 let mut writes: Vec<vk::WriteDescriptorSet> = Vec::new();
-                                let mut buf_infos: Vec<vk::DescriptorBufferInfo> = Vec::new();
-                                let mut img_infos: Vec<vk::DescriptorImageInfo> = Vec::new();
+                                // Writes point into these; reserve so pushes never reallocate
+                                let mut buf_infos: Vec<vk::DescriptorBufferInfo> = Vec::with_capacity(binds.len());
+                                let mut img_infos: Vec<vk::DescriptorImageInfo> = Vec::with_capacity(binds.len());
                                 for b in binds {
                                     match b.kind {
                                         crate::resources::ResourceKind::Uniform => {
@@ -1096,7 +1160,7 @@ impl VkCore {
                                                 buf_infos.push(vk::DescriptorBufferInfo { buffer: ub, offset: 0, range });
                                                 let info = buf_infos.last().unwrap();
                                                 writes.push(vk::WriteDescriptorSet::builder()
-                                                    .dst_set(dst_set).dst_binding(b.binding).descriptor_type(vk::DescriptorType::UNIFORM_BUFFER)
+                                                    .dst_set(dst_set).dst_binding(b.binding).descriptor_type(crate::vk_bridge::descriptor_type_of(b))
                                                     .buffer_info(std::slice::from_ref(info)).build());
                                             }
                                         }
@@ -1148,7 +1212,9 @@ impl VkCore {
                                         }
                                     }
                                 }
-                                if !writes.is_empty() { device.update_descriptor_sets(&writes, &[]); }
+                                if push {
+                                    compute_push_writes_per_frame[frame_idx][ci].push(PushSetWrites { set: ordinal as u32, writes, _buf_infos: buf_infos, _img_infos: img_infos });
+                                } else if !writes.is_empty() { device.update_descriptor_sets(&writes, &[]); }
                             }
                         }
                     }
@@ -1192,8 +1258,9 @@ impl VkCore {
                 while i < bytes.len() { v.push(u32::from_le_bytes([bytes[i], bytes[i+1], bytes[i+2], bytes[i+3]])); i += 4; }
                 Ok(v)
             }
-            // Support inline/in-memory GLSL sources using prefixes: "source:" or "inline:"
-            // Example: desc.shaders.vs = "source:#version 450\n..."
+            // Compile GLSL tagged with a "source:"/"inline:" prefix (optionally ".vert"/".frag").
+            // `ShaderSource::Inline` reaches this through `load_stage`, which adds the stage prefix;
+            // a `ShaderSource::Path` still carrying a prefix is the older inline spelling.
             #[cfg(feature = "vk-shaderc-compile")]
             fn compile_inline_glsl(src_with_prefix: &str) -> Option<Vec<u32>> {
                 let lower = src_with_prefix.to_ascii_lowercase();
@@ -1232,7 +1299,7 @@ impl VkCore {
                 None
             }
             fn load_or_compile(path: &str) -> Result<Vec<u32>, VkError> {
-                // 1) Inline GLSL via prefixes (requires shaderc feature)
+                // 1) Legacy inline GLSL in a path string (requires shaderc feature)
                 if let Some(words) = compile_inline_glsl(path) {
                     return Ok(words);
                 }
@@ -1250,6 +1317,14 @@ impl VkCore {
                     { return Err(VkError::General("enable feature 'vk-shaderc-compile' or use .spv files".into())); }
                 }
             }
+            // Inline sources are compiled for the stage named by `stage_prefix`
+            fn load_stage(src: crate::pipeline::ShaderSource, stage_prefix: &str) -> Result<Vec<u32>, VkError> {
+                match src {
+                    crate::pipeline::ShaderSource::Path(path) => load_or_compile(path),
+                    crate::pipeline::ShaderSource::Inline(code) => compile_inline_glsl(&format!("{}{}", stage_prefix, code))
+                        .ok_or_else(|| VkError::General("inline shader source requires feature 'vk-shaderc-compile' and valid GLSL".into())),
+                }
+            }
 
             // Create compute pipelines (zero or more)
             let mut compute_pipelines: Vec<vk::Pipeline> = Vec::new();
@@ -1294,17 +1369,14 @@ impl VkCore {
             // Graphics pipeline common state
             let mut pipeline: vk::Pipeline = vk::Pipeline::null();
             let graphics_possible = {
-                let vs = active_desc.shaders.vs.to_ascii_lowercase();
-                let fs = active_desc.shaders.fs.to_ascii_lowercase();
-                !(vs.ends_with(".comp") || fs.ends_with(".comp"))
+                let is_comp = |src: crate::pipeline::ShaderSource| src.path().map_or(false, |p| p.to_ascii_lowercase().ends_with(".comp"));
+                !(is_comp(active_desc.shaders.vs) || is_comp(active_desc.shaders.fs))
             };
             if graphics_possible {
                 // Load shader modules from PipelineDesc (graphics path)
                 let (vert_module, frag_module) = {
-                    let vs_path = active_desc.shaders.vs;
-                    let fs_path = active_desc.shaders.fs;
-                    let vs = load_or_compile(vs_path)?;
-                    let fs = load_or_compile(fs_path)?;
+                    let vs = load_stage(active_desc.shaders.vs, "inline.vert:")?;
+                    let fs = load_stage(active_desc.shaders.fs, "inline.frag:")?;
                     let vm = device.create_shader_module(&vk::ShaderModuleCreateInfo::builder().code(&vs), None)
                         .map_err(|e| VkError::General(format!("create_shader_module: {e}")))?;
                     let fm = device.create_shader_module(&vk::ShaderModuleCreateInfo::builder().code(&fs), None)
@@ -1332,14 +1404,10 @@ impl VkCore {
                     .vertex_attribute_descriptions(&attr_descs);
 
                 // Map topology from PipelineDesc
-                let topo = match active_desc.topology {
-                    crate::pipeline::Topology::TriangleList => vk::PrimitiveTopology::TRIANGLE_LIST,
-                    crate::pipeline::Topology::LineList => vk::PrimitiveTopology::LINE_LIST,
-                    crate::pipeline::Topology::PointList => vk::PrimitiveTopology::POINT_LIST,
-                };
+                let topo = crate::vk_bridge::topology_from(&active_desc.topology);
                 let input_assembly = vk::PipelineInputAssemblyStateCreateInfo::builder()
                     .topology(topo)
-                    .primitive_restart_enable(false);
+                    .primitive_restart_enable(active_desc.primitive_restart);
 
             let viewport = vk::Viewport { x: 0.0, y: 0.0, width: extent.width as f32, height: extent.height as f32, min_depth: 0.0, max_depth: 1.0 };
             let scissor = vk::Rect2D { offset: vk::Offset2D { x: 0, y: 0 }, extent };
@@ -1365,11 +1433,20 @@ impl VkCore {
                 .front_face(ff)
                 .line_width(1.0);
             let samples_flag = cfg.options.msaa_samples.map(samples_from_opt).unwrap_or_else(|| crate::vk_bridge::samples_from(active_desc));
+            let (alpha_to_coverage, min_sample_shading) = crate::vk_bridge::multisample_from(active_desc);
+            let min_sample_shading = min_sample_shading.filter(|_| sample_shading_ok);
             let multisample = vk::PipelineMultisampleStateCreateInfo::builder()
-                .rasterization_samples(samples_flag);
+                .rasterization_samples(samples_flag)
+                .alpha_to_coverage_enable(alpha_to_coverage)
+                .sample_shading_enable(min_sample_shading.is_some())
+                .min_sample_shading(min_sample_shading.unwrap_or(0.0));
             let depth_stencil = crate::vk_bridge::depth_stencil_from(active_desc);
             let color_blend_atts = crate::vk_bridge::color_blend_attachments_from(active_desc);
-            let color_blend = vk::PipelineColorBlendStateCreateInfo::builder().attachments(&color_blend_atts);
+            let logic_op = crate::vk_bridge::color_blend_logic_op_from(active_desc);
+            let color_blend = vk::PipelineColorBlendStateCreateInfo::builder()
+                .attachments(&color_blend_atts)
+                .logic_op_enable(logic_op.is_some())
+                .logic_op(logic_op.unwrap_or(vk::LogicOp::COPY));
 
                 let mut pipeline_info = vk::GraphicsPipelineCreateInfo::builder()
                     .stages(&stages)
@@ -1444,7 +1521,9 @@ impl VkCore {
                             if let Some(sets) = per_compute.get(idx) {
                                 let layout = compute_pipeline_layouts.get(idx).copied().unwrap_or(vk::PipelineLayout::null());
                                 if layout != vk::PipelineLayout::null() && !sets.is_empty() {
-                                    device.cmd_bind_descriptor_sets(cb, vk::PipelineBindPoint::COMPUTE, layout, 0, sets, &[]);
+                                    let binds = cfg.compute_pipelines[idx].bindings.unwrap_or(&[]);
+                                    bind_descriptor_sets(&device, cb, vk::PipelineBindPoint::COMPUTE, layout, sets, binds);
+                                    push_descriptor_sets(push_descriptor.as_ref(), cb, vk::PipelineBindPoint::COMPUTE, layout, &compute_push_writes_per_frame[i][idx]);
                                 }
                             }
                         }
@@ -1474,10 +1553,8 @@ impl VkCore {
                         device.cmd_set_scissor(cb, 0, std::slice::from_ref(&vk::Rect2D { offset: vk::Offset2D { x: 0, y: 0 }, extent }));
                     }
                     // Bind descriptor sets for this frame if available
-                    let sets = &descriptor_sets_per_frame[i];
-                    if !sets.is_empty() {
-                        device.cmd_bind_descriptor_sets(cb, vk::PipelineBindPoint::GRAPHICS, pipeline_layout, 0, sets, &[]);
-                    }
+                    bind_descriptor_sets(&device, cb, vk::PipelineBindPoint::GRAPHICS, pipeline_layout, &descriptor_sets_per_frame[i], RB::bindings());
+                    push_descriptor_sets(push_descriptor.as_ref(), cb, vk::PipelineBindPoint::GRAPHICS, pipeline_layout, &push_writes_per_frame[i]);
                     // Bind dummy vertex buffer at binding 0 to match vertex input
                     device.cmd_bind_vertex_buffers(cb, 0, std::slice::from_ref(&vertex_buffer), &[0]);
                     device.cmd_draw(cb, 3, 1, 0, 0);
@@ -1708,7 +1785,11 @@ where
         MkTopology::TriangleList => (vk::PrimitiveTopology::TRIANGLE_LIST, "TRIANGLE_LIST"),
         MkTopology::LineList => (vk::PrimitiveTopology::LINE_LIST, "LINE_LIST"),
         MkTopology::PointList => (vk::PrimitiveTopology::POINT_LIST, "POINT_LIST"),
+        MkTopology::TriangleStrip => (vk::PrimitiveTopology::TRIANGLE_STRIP, "TRIANGLE_STRIP"),
+        MkTopology::LineStrip => (vk::PrimitiveTopology::LINE_STRIP, "LINE_STRIP"),
+        MkTopology::TriangleFan => (vk::PrimitiveTopology::TRIANGLE_FAN, "TRIANGLE_FAN"),
     };
+    #[allow(deprecated)]
     for p in &cfg.pipelines {
         let (topo, topo_name) = map_topology(&p.topology);
         println!(
@@ -1781,8 +1862,10 @@ pub fn run_vulkan_linux_app(cfg: &EngineConfig) -> Result<(), VkError> {
     run_vulkan_linux_app_with::<NoRB, NoVL>(cfg)
 }
 
-/// Run using a derived RenderGraph: maps the first pass's attachments to a synthetic
-/// PipelineDesc and delegates to `run_vulkan_linux_app_with`.
+/// Run using a derived RenderGraph: maps the attachments of the pass that owns the graph's
+/// `present` output (the first pass when none is marked) to a synthetic PipelineDesc and
+/// delegates to `run_vulkan_linux_app_with`. The present output itself becomes the swapchain
+/// attachment rather than an offscreen color target.
 pub fn run_vulkan_linux_app_with_graph<RB, VL>(cfg: &EngineConfig, passes: &[&crate::render_graph::PassDesc]) -> Result<(), VkError>
 where
     RB: ResourceBindings,
@@ -1791,31 +1874,37 @@ where
     use crate::pipeline::{PipelineDesc, ShaderPaths};
     if passes.is_empty() { return run_vulkan_linux_app_with::<RB, VL>(cfg); }
     let base = cfg.pipelines.first().ok_or_else(|| VkError::General("no base pipeline in EngineConfig".into()))?;
-    let pass = passes[0];
+    let target = crate::render_graph::swapchain_target_from_passes(passes)
+        .map_err(|e| VkError::General(format!("render graph: {e:?}")))?;
+    let pass = passes[target.pass];
     // Integrate resource planning based solely on pass descriptors
     let (resources, pass_plans) = plan_resources_from_passes(passes);
-    let plan0 = &pass_plans[0];
-    // Map planned resources to pipeline attachment descriptors for the first pass
+    let plan = &pass_plans[target.pass];
+    // Map planned resources to pipeline attachment descriptors for the presenting pass; with
+    // no other color targets left the backend renders straight into the swapchain image
     let mut ct_vec: Vec<crate::pipeline::ColorTargetDesc> = Vec::new();
-    for &name in &plan0.colors {
+    for &name in plan.colors.iter().filter(|&&n| Some(n) != target.output) {
         if let Some(r) = resources.iter().find(|r| r.name == name) {
             ct_vec.push(crate::pipeline::ColorTargetDesc { format: r.format, blend: None });
         }
     }
     let color_targets = if ct_vec.is_empty() { pass.color } else { let leaked: &'static [crate::pipeline::ColorTargetDesc] = Box::leak(ct_vec.into_boxed_slice()); Some(leaked) };
-    let depth_target = if let Some(dname) = plan0.depth {
+    let depth_target = if let Some(dname) = plan.depth {
         if let Some(r) = resources.iter().find(|r| r.name == dname) {
             Some(crate::pipeline::DepthTargetDesc { format: r.format })
         } else { pass.depth.clone() }
     } else { pass.depth.clone() };
+    #[allow(deprecated)]
     let synth = PipelineDesc {
         name: "graph_pass_0",
         shaders: ShaderPaths { vs: base.shaders.vs, fs: base.shaders.fs },
         topology: base.topology.clone(),
+        primitive_restart: base.primitive_restart,
         depth: base.depth,
         raster: base.raster.clone(),
         blend: base.blend.clone(),
         samples: base.samples.clone(),
+        multisample: base.multisample.clone(),
         depth_stencil: base.depth_stencil.clone(),
         dynamic: base.dynamic.clone(),
         push_constants: base.push_constants.clone(),
@@ -1847,7 +1936,7 @@ where
 
     use crate::pipeline::Topology as MkTopology;
     for p in &cfg.pipelines {
-        let topo_name = match p.topology { MkTopology::TriangleList => "TRIANGLE_LIST", MkTopology::LineList => "LINE_LIST", MkTopology::PointList => "POINT_LIST" };
+        let topo_name = match p.topology { MkTopology::TriangleList => "TRIANGLE_LIST", MkTopology::LineList => "LINE_LIST", MkTopology::PointList => "POINT_LIST", MkTopology::TriangleStrip => "TRIANGLE_STRIP", MkTopology::LineStrip => "LINE_STRIP", MkTopology::TriangleFan => "TRIANGLE_FAN" };
         println!("[vk-linux] pipeline: '{}' topo={}", p.name, topo_name);
     }
 
@@ -0,0 +1,70 @@
+//! Derives over structs with `#[cfg]`-gated fields. Test builds set `cfg(test)`, so
+//! `cfg(test)` / `cfg(not(test))` stand in for a feature that is on or off.
+#![allow(dead_code)]
+
+use macrokid_graphics::resources::{ResourceBindings, ResourceKind, VertexLayout};
+use macrokid_graphics_derive::{BufferLayout, Pod, ResourceBinding};
+
+#[derive(BufferLayout)]
+struct FeatureOn {
+    #[vertex(location = 0, format = "vec3")] pos: [f32; 3],
+    #[cfg(test)]
+    #[vertex(location = 1, format = "vec3")] normal: [f32; 3],
+    #[vertex(location = 2, format = "vec2")] uv: [f32; 2],
+}
+
+// Separate module: the generated slice modules are named per derive, not per type
+mod off {
+    use macrokid_graphics_derive::BufferLayout;
+
+    #[derive(BufferLayout)]
+    pub struct FeatureOff {
+        #[vertex(location = 0, format = "vec3")] pos: [f32; 3],
+        #[cfg(not(test))]
+        #[vertex(location = 1, format = "vec3")] normal: [f32; 3],
+        #[vertex(location = 2, format = "vec2")] uv: [f32; 2],
+    }
+}
+use off::FeatureOff;
+
+#[test]
+fn vertex_offsets_follow_compiled_fields() {
+    let on: Vec<(&str, u32)> = FeatureOn::vertex_attrs().iter().map(|a| (a.field, a.offset)).collect();
+    assert_eq!(on, [("pos", 0), ("normal", 12), ("uv", 24)]);
+    assert_eq!(FeatureOn::vertex_buffers()[0].stride, 32);
+
+    let off: Vec<(&str, u32)> = FeatureOff::vertex_attrs().iter().map(|a| (a.field, a.offset)).collect();
+    assert_eq!(off, [("pos", 0), ("uv", 12)]);
+    assert_eq!(FeatureOff::vertex_buffers()[0].stride, 20);
+}
+
+#[derive(ResourceBinding)]
+struct Material {
+    #[uniform(set = 0, binding = 0, stages = "vs|fs")] camera: (),
+    #[cfg(not(test))]
+    #[texture(set = 0, binding = 1, stages = "fs")] detail: (),
+    #[cfg(test)]
+    #[texture(set = 0, binding = 2, stages = "fs")] albedo: (),
+}
+
+#[test]
+fn bindings_and_pool_sizes_follow_compiled_fields() {
+    let fields: Vec<&str> = Material::bindings().iter().map(|b| b.field).collect();
+    assert_eq!(fields, ["camera", "albedo"]);
+    let textures = Material::descriptor_pool_sizes().iter().find(|p| matches!(p.kind, ResourceKind::Texture)).map(|p| p.count);
+    assert_eq!(textures, Some(1));
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod)]
+struct Packed {
+    a: u32,
+    #[cfg(not(test))]
+    b: u64,
+    c: u32,
+}
+
+#[test]
+fn pod_size_check_skips_disabled_fields() {
+    assert_eq!(Packed { a: 1, c: 2 }.to_bytes().len(), 8);
+}
@@ -0,0 +1,40 @@
+//! `#[derive(ComputePipeline)]` and `#[use_compute]` engine fields.
+#![allow(dead_code)]
+
+use macrokid_graphics::pipeline::ComputePipelineInfo;
+use macrokid_graphics_derive::{ComputePipeline, RenderEngine};
+
+#[derive(ComputePipeline)]
+#[compute(name = "cull", cs = "shaders/cull.comp", local_size = "8,8,1", dispatch = "16,16", push_constants_size = 8, push_constants_stages = "cs")]
+struct Cull;
+
+#[derive(ComputePipeline)]
+#[compute(cs = "shaders/tonemap.comp")]
+struct Tonemap;
+
+#[derive(RenderEngine)]
+#[app(name = "ComputeOnly")]
+struct Engine {
+    #[use_compute] cull: Cull,
+    #[use_compute] tonemap: Tonemap,
+}
+
+#[test]
+fn compute_desc_from_attributes() {
+    let d = Cull::compute_desc();
+    assert_eq!(d.name, "cull");
+    assert_eq!(d.local_size, Some((8, 8, 1)));
+    assert_eq!(d.dispatch, (16, 16, 1));
+    assert_eq!(d.push_constants.as_ref().map(|pc| pc.size), Some(8));
+
+    let t = Tonemap::describe_compute();
+    assert_eq!((t.name, t.local_size, t.dispatch), ("Tonemap", None, (1, 1, 1)));
+}
+
+#[test]
+fn engine_config_includes_compute_pipelines() {
+    let cfg = Engine::engine_config();
+    assert!(cfg.pipelines.is_empty());
+    let names: Vec<&str> = cfg.compute_pipelines.iter().map(|c| c.name).collect();
+    assert_eq!(names, ["cull", "Tonemap"]);
+}
@@ -0,0 +1,46 @@
+//! `#[derive(System)]` over GPU resources: the generated `descriptor_bindings()` layout.
+#![cfg(feature = "vulkan-linux")]
+#![allow(dead_code)]
+
+use macrokid_graphics::resources::{BindingDesc, GpuBuffer, GpuImage, GpuResourceAccess};
+use macrokid_threads_derive::System;
+
+struct Camera;
+struct Albedo;
+struct Hdr;
+
+type HdrTarget = GpuImage<Hdr>;
+
+#[derive(System)]
+#[reads(GpuBuffer<Camera>, GpuImage<Albedo>)]
+#[writes(GpuImage<Hdr>)]
+struct Tonemap;
+
+// Same resource through an alias on one side: the derive's token check can't see it
+#[derive(System)]
+#[system(gpu_types(GpuBuffer, GpuImage, HdrTarget))]
+#[reads(GpuBuffer<Camera>, HdrTarget)]
+#[writes(GpuImage<Hdr>)]
+struct Bloom;
+
+fn layout(bindings: &[BindingDesc]) -> Vec<(u32, String)> {
+    bindings.iter().map(|b| (b.binding, format!("{:?}", b.kind))).collect()
+}
+
+#[test]
+fn reads_then_writes_in_binding_order() {
+    assert_eq!(layout(Tonemap::descriptor_bindings()), vec![
+        (0, "Uniform".to_string()),
+        (1, "Texture".to_string()),
+        (2, "StorageImage".to_string()),
+    ]);
+    assert!(Tonemap::descriptor_bindings()[2].field.ends_with("GpuImage<gpu_system_bindings::Hdr>"));
+}
+
+#[test]
+fn read_and_written_resource_gets_one_binding() {
+    assert_eq!(layout(Bloom::descriptor_bindings()), vec![
+        (0, "Uniform".to_string()),
+        (1, "StorageImage".to_string()),
+    ]);
+}
@@ -0,0 +1,16 @@
+use macrokid_graphics::pipeline::ShaderSource;
+use macrokid_graphics_derive::GraphicsPipeline;
+
+const FULLSCREEN_VS: &str = "#version 450\nvoid main() { gl_Position = vec4(0.0); }";
+
+#[derive(GraphicsPipeline)]
+#[pipeline(vs_inline = "#version 450\nvoid main() { gl_Position = vec4(0.0); }", fs = "shaders/post.frag")]
+struct Post;
+
+#[test]
+fn inline_and_path_stages_mix() {
+    let shaders = &Post::describe_pipeline().shaders;
+    assert_eq!(shaders.vs, ShaderSource::Inline(FULLSCREEN_VS));
+    assert_eq!(shaders.fs.path(), Some("shaders/post.frag"));
+    assert_eq!(shaders.vs.to_string(), format!("<inline, {} bytes>", FULLSCREEN_VS.len()));
+}
@@ -0,0 +1,45 @@
+use macrokid_graphics::resources::ResourceKind;
+use macrokid_graphics_lighting::default_shaders::{FS_BLINN_MIN, FS_PBR_MIN, FS_PHONG_MIN};
+use macrokid_graphics_lighting_derive::LightingModel;
+
+#[derive(LightingModel)]
+struct Unspecified;
+
+#[derive(LightingModel)]
+#[model = "blinn"]
+struct Blinn;
+
+#[derive(LightingModel)]
+#[model = "pbr"]
+struct Pbr;
+
+#[test]
+fn model_selects_fragment_shader() {
+    assert_eq!(Unspecified::shader_sources().fs, FS_PHONG_MIN);
+    assert_eq!(Blinn::shader_sources().fs, FS_BLINN_MIN);
+    assert_eq!(Pbr::shader_sources().fs, FS_PBR_MIN);
+    assert_eq!(Pbr::shader_sources().vs, Unspecified::shader_sources().vs);
+}
+
+#[test]
+fn pbr_declares_metallic_roughness_binding() {
+    let fields: Vec<_> = Blinn::bindings().iter().map(|b| b.field).collect();
+    assert_eq!(fields, ["scene", "albedo"]);
+    let pbr = Pbr::bindings();
+    assert_eq!(pbr.len(), 3);
+    let mr = &pbr[2];
+    assert_eq!((mr.field, mr.set, mr.binding), ("metallic_roughness", 0, 2));
+    assert!(matches!(mr.kind, ResourceKind::CombinedImageSampler));
+    assert!(FS_PBR_MIN.contains("binding = 2"));
+}
+
+#[derive(macrokid_graphics_lighting_derive::LightSetup)]
+#[light_setup(shadow_size = "1024 x 512")]
+struct Sun;
+
+#[test]
+fn light_setup_sizes_shadow_pass() {
+    use macrokid_graphics::render_graph::SizeSpec;
+    let out = &Sun::shadow_pass().outputs.unwrap()[0];
+    assert!(matches!(out.size, SizeSpec::Abs { width: 1024, height: 512 }));
+}
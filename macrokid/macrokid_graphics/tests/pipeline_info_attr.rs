@@ -0,0 +1,29 @@
+use macrokid_graphics::pipeline::{PipelineDesc, PipelineInfo, ShaderPaths, Topology};
+use macrokid_graphics_derive::pipeline_info;
+
+#[allow(deprecated)]
+static SKY_DESC: PipelineDesc = PipelineDesc {
+    name: "sky",
+    shaders: ShaderPaths::paths("shaders/sky.vert", "shaders/sky.frag"),
+    topology: Topology::TriangleList,
+    primitive_restart: false,
+    depth: false,
+    raster: None,
+    blend: None,
+    samples: None,
+    multisample: None,
+    depth_stencil: None,
+    dynamic: None,
+    push_constants: None,
+    color_targets: None,
+    depth_target: None,
+};
+
+#[pipeline_info(desc = "SKY_DESC")]
+struct Sky;
+
+#[test]
+fn pipeline_info_uses_hand_written_desc() {
+    assert!(std::ptr::eq(Sky::pipeline_desc(), &SKY_DESC));
+    assert_eq!(Sky::describe_pipeline().name, "sky");
+}
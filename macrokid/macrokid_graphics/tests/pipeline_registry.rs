@@ -0,0 +1,28 @@
+//! `#[pipeline(register)]` collects pipelines for `registered_pipelines()`.
+//! Run with `cargo test -p macrokid_graphics --features registry`.
+#![cfg(feature = "registry")]
+#![allow(dead_code)]
+
+use macrokid_graphics::pipeline::registered_pipelines;
+use macrokid_graphics_derive::GraphicsPipeline;
+
+#[derive(GraphicsPipeline)]
+#[pipeline(vs = "shaders/sky.vert", fs = "shaders/sky.frag", register)]
+struct Sky;
+
+#[derive(GraphicsPipeline)]
+#[pipeline(vs = "shaders/ui.vert", fs = "shaders/ui.frag", topology = "TriangleStrip", register = true)]
+struct Ui;
+
+// Not registered
+#[derive(GraphicsPipeline)]
+#[pipeline(vs = "shaders/debug.vert", fs = "shaders/debug.frag")]
+struct Debug;
+
+#[test]
+fn registered_pipelines_lists_marked_derives() {
+    let mut names: Vec<&str> = registered_pipelines().iter().map(|d| d.name).collect();
+    names.sort_unstable();
+    assert_eq!(names, ["Sky", "Ui"]);
+    assert!(registered_pipelines().iter().any(|d| std::ptr::eq(*d, Sky::describe_pipeline())));
+}
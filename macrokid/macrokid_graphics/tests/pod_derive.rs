@@ -0,0 +1,24 @@
+use macrokid_graphics_derive::Pod;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Pod)]
+struct LightEntry {
+    position: [f32; 3],
+    intensity: f32,
+    flags: u32,
+    _pad: [u32; 3],
+}
+
+#[test]
+fn pod_round_trips_through_bytes() {
+    let light = LightEntry { position: [1.0, 2.0, 3.0], intensity: 0.5, flags: 0b101, _pad: [0; 3] };
+    let bytes = light.to_bytes();
+    assert_eq!(bytes.len(), std::mem::size_of::<LightEntry>());
+
+    // Copy into an aligned buffer, as a memory-mapped table would be
+    let mut table = [0u32; 8];
+    for (dst, src) in table.iter_mut().zip(bytes.chunks_exact(4)) { *dst = u32::from_ne_bytes(src.try_into().unwrap()); }
+    let view = macrokid_graphics::pod::bytes_of(&table);
+    assert_eq!(LightEntry::from_bytes(view), Some(&light));
+    assert_eq!(LightEntry::from_bytes(&view[..16]), None);
+}
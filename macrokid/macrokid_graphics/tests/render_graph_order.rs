@@ -0,0 +1,42 @@
+//! `render_graph!` orders `RenderPass` types at compile time from input/output names.
+#![allow(dead_code)]
+
+use macrokid_graphics_derive::{render_graph, RenderPass};
+
+#[derive(RenderPass)]
+#[pass(name = "post")]
+#[input(name = "hdr")]
+#[output(name = "ldr", format = "rgba8_unorm", present = true)]
+struct Post;
+
+#[derive(RenderPass)]
+#[pass(name = "gbuffer")]
+#[output(name = "gbuf.albedo", format = "rgba8_unorm", usage = "color|sampled")]
+#[output(name = "gbuf.normal", format = "rgba16f", usage = "sampled|color")]
+struct GBuffer;
+
+#[derive(RenderPass)]
+#[pass(name = "lighting")]
+#[input(name = "gbuf.albedo")]
+#[input(name = "gbuf.normal")]
+#[input(name = "history")]
+#[output(name = "hdr", format = "rgba16f")]
+#[output(name = "history", format = "rgba32f", usage = "color|sampled", persistent = true)]
+struct Lighting;
+
+#[derive(RenderPass)]
+#[pass(name = "shadow")]
+#[output(name = "shadow.depth", format = "d32", usage = "depth|sampled")]
+struct Shadow;
+
+// Declared out of order; reading its own persistent history does not make `lighting` a cycle
+render_graph! { Deferred: Post, Lighting, GBuffer }
+// A second graph in the same module gets its own static
+render_graph! { ShadowOnly: Shadow }
+
+#[test]
+fn passes_run_after_their_producers() {
+    assert_eq!(DEFERRED_ORDER, ["gbuffer", "lighting", "post"]);
+    assert_eq!(SHADOW_ONLY_ORDER, ["shadow"]);
+    assert_eq!(Lighting::PASS_IO.outputs, ["hdr"]);
+}
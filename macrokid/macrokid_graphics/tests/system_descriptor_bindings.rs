@@ -0,0 +1,55 @@
+//! `#[derive(System)]` descriptor layouts with resource types of our own, so this runs with or
+//! without `vulkan-linux` (the backend's `GpuBuffer`/`GpuImage` only exist with it).
+#![allow(dead_code)]
+
+use core::marker::PhantomData;
+use macrokid_graphics::resources::{GpuResource, GpuResourceAccess, GpuResourceKind};
+use macrokid_threads_derive::System;
+
+struct Params<T>(PhantomData<T>);
+struct Target<T>(PhantomData<T>);
+
+macro_rules! test_resource {
+    ($ty:ident, $kind:expr) => {
+        impl<T: Send + Sync + 'static> GpuResource for $ty<T> {
+            fn resource_kind() -> GpuResourceKind { $kind }
+            #[cfg(feature = "vulkan-linux")]
+            fn handle(&self) -> macrokid_graphics::resources::GpuHandle { unimplemented!("layout-only test resource") }
+            #[cfg(feature = "vulkan-linux")]
+            fn read_stage() -> ash::vk::PipelineStageFlags { ash::vk::PipelineStageFlags::FRAGMENT_SHADER }
+            #[cfg(feature = "vulkan-linux")]
+            fn write_stage() -> ash::vk::PipelineStageFlags { ash::vk::PipelineStageFlags::COMPUTE_SHADER }
+            #[cfg(feature = "vulkan-linux")]
+            fn read_access() -> ash::vk::AccessFlags { ash::vk::AccessFlags::SHADER_READ }
+            #[cfg(feature = "vulkan-linux")]
+            fn write_access() -> ash::vk::AccessFlags { ash::vk::AccessFlags::SHADER_WRITE }
+        }
+    };
+}
+test_resource!(Params, GpuResourceKind::Buffer);
+test_resource!(Target, GpuResourceKind::Image);
+
+struct Camera;
+struct Albedo;
+struct Hdr;
+struct Particles;
+
+#[derive(System)]
+#[system(gpu_types(Params, Target))]
+#[reads(Params<Camera>, Target<Albedo>)]
+#[writes(Target<Hdr>, Params<Particles>)]
+struct Shade;
+
+#[test]
+fn generated_descriptor_bindings_follow_access() {
+    let got: Vec<(u32, u32, String)> = Shade::descriptor_bindings().iter()
+        .map(|b| (b.set, b.binding, format!("{:?}", b.kind)))
+        .collect();
+    assert_eq!(got, vec![
+        (0, 0, "Uniform".to_string()),
+        (0, 1, "Texture".to_string()),
+        (0, 2, "StorageImage".to_string()),
+        (0, 3, "StorageBuffer".to_string()),
+    ]);
+    assert!(Shade::descriptor_bindings()[2].field.ends_with("Target<system_descriptor_bindings::Hdr>"));
+}
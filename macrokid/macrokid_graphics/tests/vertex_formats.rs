@@ -0,0 +1,41 @@
+//! Normalized and packed vertex formats in `#[vertex(format = "..")]`.
+#![allow(dead_code)]
+
+use macrokid_graphics::resources::VertexLayout;
+use macrokid_graphics_derive::BufferLayout;
+
+#[derive(BufferLayout)]
+struct PackedVertex {
+    #[vertex(location = 0, format = "vec3")] pos: [f32; 3],
+    #[vertex(location = 1, format = "rgb10a2_unorm")] normal: u32,
+    #[vertex(location = 2, format = "rg16_float")] uv: [u16; 2],
+    #[vertex(location = 3, format = "rgba8_snorm")] tangent: [i8; 4],
+    #[vertex(location = 4, format = "r16_uint")] material: u16,
+}
+
+#[test]
+fn packed_formats_size_offsets_and_stride() {
+    let attrs: Vec<(&str, u32, u32)> = PackedVertex::vertex_attrs().iter().map(|a| (a.field, a.offset, a.size)).collect();
+    assert_eq!(attrs, [("pos", 0, 12), ("normal", 12, 4), ("uv", 16, 4), ("tangent", 20, 4), ("material", 24, 2)]);
+    assert_eq!(PackedVertex::vertex_buffers()[0].stride, 26);
+    assert_eq!(PackedVertex::vertex_attrs()[1].format, "rgb10a2_unorm");
+}
+
+// Separate module: each `BufferLayout` derive emits its own helper modules
+mod half {
+    use super::*;
+
+    #[derive(BufferLayout)]
+    struct HalfVertex {
+        #[vertex(location = 0, format = "rgba16f")] color: [u16; 4],
+        #[vertex(location = 1, format = "rg16f")] uv: [u16; 2],
+        #[vertex(location = 2, format = "r16f")] weight: u16,
+    }
+
+    #[test]
+    fn half_float_shorthands() {
+        let attrs: Vec<(&str, u32, u32)> = HalfVertex::vertex_attrs().iter().map(|a| (a.field, a.offset, a.size)).collect();
+        assert_eq!(attrs, [("color", 0, 8), ("uv", 8, 4), ("weight", 12, 2)]);
+        assert_eq!(HalfVertex::vertex_buffers()[0].stride, 14);
+    }
+}
@@ -13,8 +13,9 @@
 
 use proc_macro2::Span;
 use macrokid_core::{
-    ir::{TypeSpec, FieldKind},
+    ir::TypeSpec,
     attr_schema::AttrSchema,
+    diag,
 };
 use quote::quote;
 use syn::DeriveInput;
@@ -83,7 +84,7 @@ pub fn expand_procedural_mesh(input: DeriveInput) -> syn::Result<proc_macro2::To
                 macrokid_graphics::assets::Primitives::cylinder::<macrokid_graphics::assets::SimpleVertex>(#radius, #height, #segments)
             }
         },
-        other => return Err(syn::Error::new(spec.span, format!("unknown primitive type '{}': expected sphere|cube|plane|cylinder", other))),
+        other => return Err(diag::expected_one_of(spec.span, "type", other, &["sphere", "cube", "plane", "cylinder"])),
     };
 
     // Optional transform attributes
@@ -267,7 +268,7 @@ pub fn expand_procedural_texture(input: DeriveInput) -> syn::Result<proc_macro2:
                 macrokid_graphics::assets::TextureGenerator::perlin_noise(#width, #height, #scale, #octaves)
             }
         },
-        other => return Err(syn::Error::new(spec.span, format!("unknown texture type '{}': expected solid|checkerboard|gradient|noise", other))),
+        other => return Err(diag::expected_one_of(spec.span, "type", other, &["solid", "checkerboard", "gradient", "noise"])),
     };
 
     let mod_ident = syn::Ident::new(&format!("__mk_ptex_{}", ident), Span::call_site());
@@ -312,10 +313,7 @@ pub fn expand_asset_bundle(input: DeriveInput) -> syn::Result<proc_macro2::Token
     let ident = spec.ident.clone();
 
     // Parse field references to other asset types
-    let st = match &spec.kind {
-        macrokid_core::TypeKind::Struct(st) => st,
-        _ => return Err(syn::Error::new(spec.span, "AssetBundle expects a struct")),
-    };
+    let fields = spec.named_fields()?;
 
     let mesh_schema = AttrSchema::new("mesh_ref");
     let texture_schema = AttrSchema::new("texture_ref");
@@ -329,28 +327,23 @@ pub fn expand_asset_bundle(input: DeriveInput) -> syn::Result<proc_macro2::Token
 
     let mut asset_refs = Vec::new();
 
-    match st.fields() {
-        FieldKind::Named(fields) => {
-            for field in fields {
-                let field_name = field.ident.as_ref().unwrap().to_string();
-                let field_type = &field.ty;
-                
-                if mesh_schema.parse(&field.attrs).is_ok() {
-                    asset_refs.push(AssetRef {
-                        field_name,
-                        field_type: quote! { #field_type },
-                        asset_kind: "mesh".to_string(),
-                    });
-                } else if texture_schema.parse(&field.attrs).is_ok() {
-                    asset_refs.push(AssetRef {
-                        field_name,
-                        field_type: quote! { #field_type },
-                        asset_kind: "texture".to_string(),
-                    });
-                }
-            }
-        },
-        _ => return Err(syn::Error::new(spec.span, "AssetBundle expects named fields")),
+    for field in fields {
+        let field_name = field.ident.as_ref().unwrap().to_string();
+        let field_type = &field.ty;
+        
+        if mesh_schema.parse(&field.attrs).is_ok() {
+            asset_refs.push(AssetRef {
+                field_name,
+                field_type: quote! { #field_type },
+                asset_kind: "mesh".to_string(),
+            });
+        } else if texture_schema.parse(&field.attrs).is_ok() {
+            asset_refs.push(AssetRef {
+                field_name,
+                field_type: quote! { #field_type },
+                asset_kind: "texture".to_string(),
+            });
+        }
     }
 
     // Generate bundle accessor methods
@@ -3,6 +3,8 @@ use macrokid_core::{
     ir::{TypeSpec, FieldKind},
     collect,
     codegen,
+    diag,
+    type_utils,
     derive_entry,
     common::derive_patterns::StaticSliceDerive,
 };
@@ -11,6 +13,7 @@ use quote::quote;
 use crate::gen::CodeGen;
 use syn::DeriveInput;
 use syn::spanned::Spanned;
+use macrokid_graphics::pipeline::{BlendFactor, BlendOp, BlendPreset, LogicOp};
 
 mod gen;
 mod assets;
@@ -34,7 +37,11 @@ struct BindingDescriptor {
     binding: u32,
     kind: proc_macro2::TokenStream,
     stages: Option<proc_macro2::TokenStream>,
+    push: bool,
+    dynamic: bool,
+    count: u32,
     span: proc_macro2::Span,
+    cfgs: Vec<syn::Attribute>,
 }
 
 impl quote::ToTokens for BindingDescriptor {
@@ -44,17 +51,25 @@ impl quote::ToTokens for BindingDescriptor {
         let binding = self.binding;
         let kind = &self.kind;
         let stages = &self.stages;
+        let push = self.push;
+        let dynamic = self.dynamic;
+        let count = self.count;
+        let cfgs = &self.cfgs;
         let stages_tokens = match stages {
             Some(s) => quote! { Some(#s) },
             None => quote! { None },
         };
         tokens.extend(quote! {
+            #( #cfgs )*
             macrokid_graphics::resources::BindingDesc {
                 field: #field,
                 set: #set,
                 binding: #binding,
                 kind: #kind,
-                stages: #stages_tokens
+                stages: #stages_tokens,
+                push: #push,
+                dynamic: #dynamic,
+                count: #count
             }
         });
     }
@@ -82,10 +97,10 @@ impl macrokid_core::common::derive_patterns::StaticSliceDerive for ResourceBindi
 
         // Define mutually exclusive resource kind schemas
         let kind_set = macrokid_core::exclusive_schemas![
-            uniform(set: int, binding: int, stages: str),
-            texture(set: int, binding: int, stages: str),
-            sampler(set: int, binding: int, stages: str),
-            combined(set: int, binding: int, stages: str),
+            uniform(set: int, binding: int, stages: str, push: opt_bool, dynamic: opt_bool, count: opt_int),
+            texture(set: int, binding: int, stages: str, push: opt_bool, dynamic: opt_bool, count: opt_int),
+            sampler(set: int, binding: int, stages: str, push: opt_bool, dynamic: opt_bool, count: opt_int),
+            combined(set: int, binding: int, stages: str, push: opt_bool, dynamic: opt_bool, count: opt_int),
         ];
 
         // Collect records from fields
@@ -95,6 +110,20 @@ impl macrokid_core::common::derive_patterns::StaticSliceDerive for ResourceBindi
                 let set = parsed.try_get_int("set")? as u32;
                 let binding = parsed.try_get_int("binding")? as u32;
                 let stages_str = parsed.get_str("stages");
+                let push = parsed.get_bool("push").unwrap_or(false);
+                let dynamic = parsed.get_bool("dynamic").unwrap_or(false);
+                // `count = N` declares an array of N descriptors at this binding
+                let count = parsed.get_int("count").unwrap_or(1);
+                if count < 1 {
+                    return Err(syn::Error::new(f.span, format!("binding '{}' has count = {}; array bindings need at least 1 descriptor", field, count)));
+                }
+                let count = count as u32;
+                if dynamic && kind_name != "uniform" {
+                    return Err(syn::Error::new(f.span, format!("`dynamic` is only valid on uniform bindings, not '{}'", kind_name)));
+                }
+                if dynamic && push {
+                    return Err(syn::Error::new(f.span, "push descriptor sets cannot contain dynamic uniform buffers"));
+                }
 
                 // Convert kind name to token stream
                 let kind = match kind_name.as_str() {
@@ -119,7 +148,7 @@ impl macrokid_core::common::derive_patterns::StaticSliceDerive for ResourceBindi
                     quote! { macrokid_graphics::resources::BindingStages { vs: #vs, fs: #fs, cs: #cs } }
                 });
 
-                Ok(Some(BindingDescriptor { field, set, binding, kind, stages, span: f.span }))
+                Ok(Some(BindingDescriptor { field, set, binding, kind, stages, push, dynamic, count, span: f.span, cfgs: f.cfg_attrs().to_vec() }))
             } else {
                 Ok(None)
             }
@@ -128,6 +157,20 @@ impl macrokid_core::common::derive_patterns::StaticSliceDerive for ResourceBindi
         // Enforce uniqueness of (set, binding) using validation helper
         let items = collect::unique_by(items, |r| ((r.set, r.binding), r.span), "duplicate (set,binding)")?;
 
+        // A set layout is either push-descriptor or pool-allocated; reject mixed sets
+        let mut first_in_set: std::collections::BTreeMap<u32, &BindingDescriptor> = std::collections::BTreeMap::new();
+        for r in &items {
+            let first = *first_in_set.entry(r.set).or_insert(r);
+            if first.push != r.push {
+                let mut err = syn::Error::new(r.span, format!(
+                    "set {} mixes push and non-push bindings: '{}' has push = {} but '{}' has push = {}",
+                    r.set, r.field, r.push, first.field, first.push
+                ));
+                err.combine(syn::Error::new(first.span, format!("set {} first declared here", r.set)));
+                return Err(err);
+            }
+        }
+
         Ok(items)
     }
 
@@ -150,13 +193,137 @@ impl macrokid_core::common::derive_patterns::StaticSliceDerive for ResourceBindi
 
 fn expand_resource_binding(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     let spec = TypeSpec::from_derive_input(input)?;
-    ResourceBindingDerive::generate(&spec)
+    let base = ResourceBindingDerive::generate(&spec)?;
+
+    // Push-descriptor subset for backends that record them with vkCmdPushDescriptorSetKHR
+    let ty = ResourceBindingDerive::descriptor_type();
+    let descriptors = ResourceBindingDerive::collect_descriptors(&spec)?;
+    let push_entries = descriptors.iter().filter(|d| d.push).map(|d| quote! { #d });
+    let (push_mod, push_module) = codegen::static_slice_mod("rb_push", ty.clone(), push_entries);
+
+    // Per-kind descriptor counts for pool creation, tallied here so backends need not count.
+    // Push bindings are recorded directly and never allocated from a pool.
+    // Dynamic uniforms are a distinct descriptor type, so they are tallied separately.
+    let mut pool_counts: Vec<((String, bool), proc_macro2::TokenStream, Vec<&BindingDescriptor>)> = Vec::new();
+    for d in descriptors.iter().filter(|d| !d.push) {
+        let key = (d.kind.to_string(), d.dynamic);
+        match pool_counts.iter_mut().find(|(k, _, _)| *k == key) {
+            Some((_, _, ds)) => ds.push(d),
+            None => pool_counts.push((key, d.kind.clone(), vec![d])),
+        }
+    }
+    let pool_ty = quote! { macrokid_graphics::resources::DescriptorPoolSize };
+    let (pool_mod, pool_module) = codegen::static_slice_mod(
+        "rb_pool",
+        pool_ty.clone(),
+        pool_counts.into_iter().map(|((_, dynamic), kind, ds)| {
+            // Count only the bindings whose fields are compiled in; arrays add their length
+            let n = if ds.iter().any(|d| !d.cfgs.is_empty()) {
+                cfg_gated_sum(quote!(0u32), ds.iter().map(|d| { let c = d.count; (d.cfgs.as_slice(), quote!(#c)) }))
+            } else { let n: u32 = ds.iter().map(|d| d.count).sum(); quote!(#n) };
+            quote! { macrokid_graphics::resources::DescriptorPoolSize { kind: #kind, dynamic: #dynamic, count: #n } }
+        }),
+    );
+    let inherent = codegen::impl_inherent_methods(&spec, &[
+        quote! {
+            pub fn push_descriptor_bindings() -> &'static [#ty] { #push_mod::DATA }
+        },
+        quote! {
+            /// Descriptor count per resource kind across all pool-allocated bindings; dynamic uniforms are a separate entry.
+            pub fn descriptor_pool_sizes() -> &'static [#pool_ty] { #pool_mod::DATA }
+        },
+    ]);
+
+    Ok(quote! { #base #push_module #pool_module #inherent })
 }
 
 // ================= BufferLayout derive =================
 
 derive_entry!(BufferLayout, attrs = [vertex, buffer], handler = expand_buffer_layout);
 
+// Byte views for #[repr(C)] plain-old-data descriptors
+derive_entry!(Pod, validate = pod_shape, spec_handler = expand_pod);
+
+/// Field types that are `Copy`-incompatible or have invalid bit patterns, rejected with a clear
+/// message before the `Pod` bound check would.
+const NON_POD_TYPES: &[&str] = &["String", "Vec", "Box", "Rc", "Arc", "Option", "HashMap", "BTreeMap", "Cow", "str", "bool", "char"];
+
+/// Pod is only derived for plain, non-generic structs.
+///
+/// ```compile_fail
+/// use macrokid_graphics_derive::Pod;
+/// #[derive(Clone, Copy, Pod)]
+/// #[repr(C)]
+/// enum Mode { Fill, Line }
+/// ```
+///
+/// ```compile_fail
+/// use macrokid_graphics_derive::Pod;
+/// #[derive(Clone, Copy, Pod)]
+/// #[repr(C)]
+/// struct Wrapper<T: Copy> { value: T }
+/// ```
+fn pod_shape(spec: &TypeSpec) -> syn::Result<()> {
+    spec.require_struct()?.forbid_generics().map(|_| ())
+}
+
+fn expand_pod(spec: TypeSpec) -> syn::Result<proc_macro2::TokenStream> {
+    let ident = &spec.ident;
+    let repr = macrokid_core::repr::parse_repr(&spec.attrs)?;
+    if !matches!(repr.and_then(|r| r.kind), Some(macrokid_core::repr::ReprKind::C | macrokid_core::repr::ReprKind::Transparent)) {
+        return Err(syn::Error::new(spec.span, "Pod requires #[repr(C)] or #[repr(transparent)] so the byte layout is defined"));
+    }
+
+    let fields: &[macrokid_core::ir::FieldSpec] = match spec.as_struct().map(|st| st.fields()) {
+        Some(FieldKind::Named(v)) | Some(FieldKind::Unnamed(v)) => v,
+        _ => &[],
+    };
+    for f in fields {
+        let name = f.ident.as_ref().map(|i| i.to_string()).unwrap_or_else(|| format!("_{}", f.index));
+        let ty = &f.ty;
+        let bad = if type_utils::is_reference(ty).is_some() || type_utils::type_has_lifetime(ty) {
+            Some("a reference".to_string())
+        } else if matches!(ty, syn::Type::Ptr(_)) {
+            Some("a raw pointer".to_string())
+        } else if let syn::Type::Path(tp) = ty {
+            tp.path.segments.last().map(|s| s.ident.to_string()).filter(|s| NON_POD_TYPES.contains(&s.as_str())).map(|s| format!("`{}`", s))
+        } else { None };
+        if let Some(what) = bad {
+            return Err(syn::Error::new(f.span, format!("Pod field '{}' is {}, which is not plain old data", name, what)));
+        }
+    }
+
+    let pod_checks = fields.iter().map(|f| { let (cfgs, ty) = (f.cfg_attrs(), &f.ty); quote! { #( #cfgs )* assert_pod::<#ty>(); } });
+    let field_bytes = cfg_gated_sum(quote!(0usize), fields.iter().map(|f| { let ty = &f.ty; (f.cfg_attrs(), quote!(::core::mem::size_of::<#ty>())) }));
+    let padding_msg = format!("Pod struct `{}` contains padding; add explicit padding fields", ident);
+    let methods = codegen::impl_inherent_methods(&spec, &[
+        quote! {
+            /// Raw bytes of this value.
+            pub fn to_bytes(&self) -> &[u8] { macrokid_graphics::pod::bytes_of(self) }
+        },
+        quote! {
+            /// Reinterpret `bytes` as `Self`; `None` on size or alignment mismatch.
+            pub fn from_bytes(bytes: &[u8]) -> ::core::option::Option<&Self> { macrokid_graphics::pod::from_bytes(bytes) }
+        },
+    ]);
+    Ok(quote! {
+        const _: fn() = || {
+            fn assert_pod<T: macrokid_graphics::pod::Pod>() {}
+            #( #pod_checks )*
+        };
+        const _: () = ::core::assert!(::core::mem::size_of::<#ident>() == #field_bytes, #padding_msg);
+        unsafe impl macrokid_graphics::pod::Pod for #ident {}
+        #methods
+    })
+}
+
+/// `{ let n = init; #[cfg(..)] let n = n + term; ...; n }`: a sum that only counts terms whose
+/// field is compiled in, for sizes/offsets/counts over structs with `#[cfg]`-gated fields.
+fn cfg_gated_sum<'a>(init: proc_macro2::TokenStream, terms: impl IntoIterator<Item = (&'a [syn::Attribute], proc_macro2::TokenStream)>) -> proc_macro2::TokenStream {
+    let steps = terms.into_iter().map(|(cfgs, term)| quote! { #( #cfgs )* let __mk_n = __mk_n + #term; });
+    quote! {{ let __mk_n = #init; #( #steps )* __mk_n }}
+}
+
 /// Vertex attribute record (collected from field attributes)
 #[derive(Clone, Debug)]
 struct VertexAttrRec {
@@ -167,19 +334,26 @@ struct VertexAttrRec {
     offset: u32,
     size: u32,
     span: proc_macro2::Span,
+    cfgs: Vec<syn::Attribute>,
 }
 
+/// Vertex formats accepted by `#[vertex(format = "..")]` with their byte sizes. Names mirror the
+/// Vulkan formats `vk_bridge` maps them to (`rg16_float` -> `R16G16_SFLOAT`, with the `r16f` /
+/// `rg16f` / `rgba16f` shorthands; packed 10/10/10/2 and 11/11/10 formats are one 32-bit word).
+const VERTEX_FORMATS: &[(&str, usize)] = &[
+    ("f32", 4), ("u32", 4), ("i32", 4), ("vec2", 8), ("vec3", 12), ("vec4", 16), ("mat4", 64),
+    ("r8_unorm", 1), ("r8_snorm", 1), ("r8_uint", 1), ("r8_sint", 1),
+    ("rg8_unorm", 2), ("rg8_snorm", 2), ("rg8_uint", 2), ("rg8_sint", 2),
+    ("rgba8_unorm", 4), ("u8x4_norm", 4), ("rgba8_snorm", 4), ("rgba8_uint", 4), ("rgba8_sint", 4), ("bgra8_unorm", 4),
+    ("r16_unorm", 2), ("r16_snorm", 2), ("r16_uint", 2), ("r16_sint", 2), ("r16_float", 2), ("r16f", 2),
+    ("rg16_unorm", 4), ("rg16_snorm", 4), ("rg16_uint", 4), ("rg16_sint", 4), ("rg16_float", 4), ("rg16f", 4),
+    ("rgba16_unorm", 8), ("rgba16_snorm", 8), ("rgba16_uint", 8), ("rgba16_sint", 8), ("rgba16_float", 8), ("rgba16f", 8),
+    ("rgb10a2_unorm", 4), ("rgb10a2_snorm", 4), ("rgb10a2_uint", 4), ("rg11b10_float", 4),
+];
+
 /// Helper: infer size from format string
 fn size_from_format(fmt: &str) -> Option<usize> {
-    match fmt {
-        "f32" | "u32" | "i32" => Some(4),
-        "vec2" => Some(8),
-        "vec3" => Some(12),
-        "vec4" => Some(16),
-        "rgba8_unorm" | "u8x4_norm" => Some(4),
-        "mat4" => Some(64),
-        _ => None,
-    }
+    VERTEX_FORMATS.iter().find(|(name, _)| *name == fmt).map(|(_, size)| *size)
 }
 
 /// Helper: infer size from syn::Type (supports paths and arrays)
@@ -224,8 +398,11 @@ fn collect_vertex_attrs(
 
                     // Determine size from format or type
                     let size = if let Some(ref fmt) = format_str {
-                        size_from_format(fmt).ok_or_else(||
-                            syn::Error::new(f.span, format!("unknown format '{}' for field '{}'", fmt, field_name)))?
+                        size_from_format(fmt).ok_or_else(|| {
+                            let names: Vec<&str> = VERTEX_FORMATS.iter().map(|(n, _)| *n).collect();
+                            let e = diag::expected_one_of(f.span, "format", fmt, &names);
+                            syn::Error::new(f.span, format!("field '{}': {}", field_name, e))
+                        })?
                     } else {
                         size_from_type(&f.ty).ok_or_else(||
                             syn::Error::new(f.span, format!("cannot infer size for field '{}'", field_name)))?
@@ -239,6 +416,7 @@ fn collect_vertex_attrs(
                         offset: 0, // Computed later
                         size,
                         span: f.span,
+                        cfgs: f.cfg_attrs().to_vec(),
                     }));
                 }
             }
@@ -311,6 +489,18 @@ fn expand_buffer_layout(input: DeriveInput) -> syn::Result<proc_macro2::TokenStr
         macrokid_core::TypeKind::Struct(st) => st,
         _ => return Err(syn::Error::new(spec.span, "BufferLayout expects a struct")),
     };
+    // Vertex data is uploaded as raw bytes, so borrowed fields can never be valid
+    if let FieldKind::Named(fields) | FieldKind::Unnamed(fields) = st.fields() {
+        for f in fields {
+            let name = f.ident.as_ref().map(|i| i.to_string()).unwrap_or_else(|| format!("_{}", f.index));
+            if type_utils::is_reference(&f.ty).is_some() {
+                return Err(syn::Error::new(f.span, format!("BufferLayout field '{}' is a reference; vertex data must be owned plain data", name)));
+            }
+            if type_utils::type_has_lifetime(&f.ty) {
+                return Err(syn::Error::new(f.span, format!("BufferLayout field '{}' borrows data through a lifetime; vertex data must be owned plain data", name)));
+            }
+        }
+    }
 
     // Define schemas
     let vertex_schema = macrokid_core::attr_schema::AttrSchema::new("vertex")
@@ -327,10 +517,7 @@ fn expand_buffer_layout(input: DeriveInput) -> syn::Result<proc_macro2::TokenStr
     let step_mode = match buf_attrs.get_str("step").unwrap_or("vertex") {
         "vertex" => quote! { macrokid_graphics::resources::StepMode::Vertex },
         "instance" => quote! { macrokid_graphics::resources::StepMode::Instance },
-        other => return Err(syn::Error::new(
-            spec.span,
-            format!("unknown step mode '{}': expected 'vertex' or 'instance'", other)
-        )),
+        other => return Err(diag::expected_one_of(spec.span, "step", other, &["vertex", "instance"])),
     };
 
     // Collect and process vertex attributes
@@ -338,6 +525,13 @@ fn expand_buffer_layout(input: DeriveInput) -> syn::Result<proc_macro2::TokenStr
     compute_offsets(&mut attrs);
     let strides = compute_strides(&attrs, buf_attrs.get_int("stride").map(|v| v as u32));
 
+    // With #[cfg]-gated attributes in a binding, offsets and strides are summed at compile time
+    // from the attributes that are actually present
+    let gated = |binding: u32| attrs.iter().any(|r| r.binding == binding && !r.cfgs.is_empty());
+    let sum_sizes = |binding: u32, below: Option<u32>| cfg_gated_sum(quote!(0u32), attrs.iter()
+        .filter(|p| p.binding == binding && below.map_or(true, |loc| p.location < loc))
+        .map(|p| { let size = p.size; (p.cfgs.as_slice(), quote!(#size)) }));
+
     // Generate vertex attribute descriptors
     let attr_ty = quote! { macrokid_graphics::resources::VertexAttr };
     let attr_entries = attrs.iter().map(|r| {
@@ -345,9 +539,11 @@ fn expand_buffer_layout(input: DeriveInput) -> syn::Result<proc_macro2::TokenStr
         let binding = r.binding;
         let location = r.location;
         let format = r.format.as_deref().unwrap_or("auto");
-        let offset = r.offset;
+        let offset = if gated(binding) { sum_sizes(binding, Some(location)) } else { let o = r.offset; quote!(#o) };
         let size = r.size;
+        let cfgs = &r.cfgs;
         quote! {
+            #( #cfgs )*
             macrokid_graphics::resources::VertexAttr {
                 field: #field,
                 binding: #binding,
@@ -362,7 +558,9 @@ fn expand_buffer_layout(input: DeriveInput) -> syn::Result<proc_macro2::TokenStr
 
     // Generate buffer descriptors
     let buf_ty = quote! { macrokid_graphics::resources::VertexBufferDesc };
+    let stride_override = buf_attrs.get_int("stride").is_some();
     let buf_entries = strides.iter().map(|(binding, stride)| {
+        let stride = if gated(*binding) && !stride_override { sum_sizes(*binding, None) } else { quote!(#stride) };
         quote! {
             macrokid_graphics::resources::VertexBufferDesc {
                 binding: #binding,
@@ -400,21 +598,34 @@ fn expand_buffer_layout(input: DeriveInput) -> syn::Result<proc_macro2::TokenStr
 
 derive_entry!(GraphicsPipeline, attrs = [pipeline, color_target, depth_target], handler = expand_graphics_pipeline);
 
+// Accepted names for the enum-valued `#[pipeline(..)]` keys; each is also the variant name in
+// `macrokid_graphics::pipeline`, so a value that passed the schema maps straight to a path.
+const TOPOLOGIES: &[&str] = &["TriangleList", "LineList", "PointList", "TriangleStrip", "LineStrip", "TriangleFan"];
+const POLYGON_MODES: &[&str] = &["Fill", "Line"];
+const CULL_MODES: &[&str] = &["None", "Front", "Back"];
+
 fn expand_graphics_pipeline(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     let spec = TypeSpec::from_derive_input(input)?;
     let ident = spec.ident.clone();
 
     // Parse type-level pipeline attributes
     let schema = macrokid_core::attr_schema::AttrSchema::new("pipeline")
-        .req_str("vs")
-        .req_str("fs")
-        .opt_str("topology")
+        // each stage takes a file path or inline GLSL source
+        .opt_str("vs")
+        .opt_str("fs")
+        .opt_str("vs_inline")
+        .opt_str("fs_inline")
+        .opt_enum("topology", TOPOLOGIES)
+        .opt_bool("primitive_restart")
         .opt_bool("depth")
-        .opt_str("polygon")
-        .opt_str("cull")
+        .opt_enum("polygon", POLYGON_MODES)
+        .opt_enum("cull", CULL_MODES)
         .opt_str("front_face")
-        .opt_bool("blend")
+        .opt_str_or_bool("blend")
+        .opt_str("logic_op")
         .opt_int("samples")
+        .opt_bool("alpha_to_coverage")
+        .opt_float("sample_shading")
         // depth/stencil extensions
         .opt_bool("depth_test")
         .opt_bool("depth_write")
@@ -422,25 +633,43 @@ fn expand_graphics_pipeline(input: DeriveInput) -> syn::Result<proc_macro2::Toke
         // dynamic states and push constants
         .opt_str("dynamic")
         .opt_int("push_constants_size")
-        .opt_str("push_constants_stages");
+        .opt_str("push_constants_stages")
+        // vertex input contract against a BufferLayout-deriving type
+        .opt_str("vertex")
+        .opt_str("vertex_locations")
+        // add to `pipeline::registered_pipelines()` (needs the `registry` feature)
+        .opt_flag("register")
+        .deprecate_key("depth", "use depth_test/depth_write instead");
     let attrs = macrokid_core::common::attr_schema::scope::on_type(&spec, &schema)?;
 
-    let vs = attrs.try_get_str("vs")?.to_string();
-    let fs = attrs.try_get_str("fs")?.to_string();
+    let (vs, vs_state) = shader_source_tokens(&attrs, "vs", spec.span)?;
+    let (fs, fs_state) = shader_source_tokens(&attrs, "fs", spec.span)?;
     let topology_s = attrs.get_str("topology").unwrap_or("TriangleList");
     let depth = attrs.get_bool("depth").unwrap_or(true);
     let polygon_s = attrs.get_str("polygon");
     let cull_s = attrs.get_str("cull");
     let front_s = attrs.get_str("front_face");
-    let blend_b = attrs.get_bool("blend");
+    let blend_preset = match (attrs.get_bool("blend"), attrs.get_str("blend")) {
+        (Some(b), _) => Some(if b { BlendPreset::AlphaBlend } else { BlendPreset::Opaque }),
+        (None, Some(s)) => Some(parse_blend_preset(s, spec.span)?),
+        (None, None) => None,
+    };
+    // Vulkan ignores blending while a logic op is enabled, so a logic op only pairs with no
+    // blending at all (`blend = false` / "opaque")
+    let logic_op = match attrs.get_str("logic_op") {
+        Some(s) => Some(LogicOp::parse(s).ok_or_else(|| diag::expected_one_of(spec.span, "logic_op", s, LogicOp::NAMES))?),
+        None => None,
+    };
+    if logic_op.is_some() && blend_preset.is_some_and(|p| p != BlendPreset::Opaque) {
+        return Err(syn::Error::new(spec.span, "logic_op cannot be combined with blend; Vulkan disables blending while a logic op is enabled"));
+    }
     let samples_i = attrs.get_int("samples");
 
-    let topology_tokens = match topology_s {
-        "TriangleList" => quote! { macrokid_graphics::pipeline::Topology::TriangleList },
-        "LineList" => quote! { macrokid_graphics::pipeline::Topology::LineList },
-        "PointList" => quote! { macrokid_graphics::pipeline::Topology::PointList },
-        other => return Err(syn::Error::new(spec.span, format!("unknown topology '{}': expected TriangleList|LineList|PointList", other))),
-    };
+    let topology_tokens = { let v = syn::Ident::new(topology_s, Span::call_site()); quote! { macrokid_graphics::pipeline::Topology::#v } };
+    let primitive_restart = attrs.get_bool("primitive_restart").unwrap_or(false);
+    if primitive_restart && !matches!(topology_s, "TriangleStrip" | "LineStrip" | "TriangleFan") {
+        return Err(syn::Error::new(spec.span, format!("primitive_restart requires a strip or fan topology, found '{}'", topology_s)));
+    }
 
     let name = ident.to_string();
     let mod_ident = syn::Ident::new(&format!("__mk_gp_{}", name), Span::call_site());
@@ -448,42 +677,60 @@ fn expand_graphics_pipeline(input: DeriveInput) -> syn::Result<proc_macro2::Toke
     struct GPInput {
         mod_ident: syn::Ident,
         name: String,
-        vs: String,
-        fs: String,
+        vs: proc_macro2::TokenStream,
+        fs: proc_macro2::TokenStream,
         topology: proc_macro2::TokenStream,
+        primitive_restart: bool,
         depth: bool,
         ident: syn::Ident,
         raster: proc_macro2::TokenStream,
         blend: proc_macro2::TokenStream,
         samples: proc_macro2::TokenStream,
+        multisample: proc_macro2::TokenStream,
         depth_stencil: proc_macro2::TokenStream,
         dynamic: proc_macro2::TokenStream,
         push_constants: proc_macro2::TokenStream,
         // attachment extensions
         color_entries: Option<Vec<proc_macro2::TokenStream>>,
         depth_target: proc_macro2::TokenStream,
+        state_hash: u64,
     }
     // Build optional state tokens
-    let polygon_tokens = match polygon_s.unwrap_or("Fill") {
-        "Fill" => quote! { macrokid_graphics::pipeline::PolygonMode::Fill },
-        "Line" => quote! { macrokid_graphics::pipeline::PolygonMode::Line },
-        other => return Err(syn::Error::new(spec.span, format!("unknown polygon mode '{}': expected Fill|Line", other))),
-    };
-    let cull_tokens = match cull_s.unwrap_or("Back") {
-        "None" => quote! { macrokid_graphics::pipeline::CullMode::None },
-        "Front" => quote! { macrokid_graphics::pipeline::CullMode::Front },
-        "Back" => quote! { macrokid_graphics::pipeline::CullMode::Back },
-        other => return Err(syn::Error::new(spec.span, format!("unknown cull mode '{}': expected None|Front|Back", other))),
-    };
+    let polygon_tokens = { let v = syn::Ident::new(polygon_s.unwrap_or("Fill"), Span::call_site()); quote! { macrokid_graphics::pipeline::PolygonMode::#v } };
+    let cull_tokens = { let v = syn::Ident::new(cull_s.unwrap_or("Back"), Span::call_site()); quote! { macrokid_graphics::pipeline::CullMode::#v } };
     let front_tokens = match front_s.unwrap_or("Ccw") {
         "Cw" | "CW" => quote! { macrokid_graphics::pipeline::FrontFace::Cw },
         "Ccw" | "CCW" => quote! { macrokid_graphics::pipeline::FrontFace::Ccw },
-        other => return Err(syn::Error::new(spec.span, format!("unknown front_face '{}': expected Cw|Ccw", other))),
+        other => return Err(diag::expected_one_of(spec.span, "front_face", other, &["Cw", "Ccw"])),
     };
     let raster_tokens = quote! { Some(macrokid_graphics::pipeline::RasterState { polygon: #polygon_tokens, cull: #cull_tokens, front_face: #front_tokens }) };
-    let blend_tokens = if blend_b.unwrap_or(false) { quote! { Some(macrokid_graphics::pipeline::ColorBlendState { enable: true }) } } else { quote! { None } };
+    let blend_tokens = match (blend_preset, logic_op) {
+        (_, Some(op)) => {
+            let variant = syn::Ident::new(&format!("{:?}", op), Span::call_site());
+            quote! { Some(macrokid_graphics::pipeline::ColorBlendState::logic(macrokid_graphics::pipeline::LogicOp::#variant)) }
+        }
+        (Some(p), None) if p != BlendPreset::Opaque => { let state = blend_state_tokens(p); quote! { Some(#state) } }
+        _ => quote! { None },
+    };
     let samples_tokens = if let Some(s) = samples_i { let s = s as u32; quote! { Some(#s) } } else { quote! { None } };
 
+    // MSAA extras: both only make sense with more than one sample per pixel
+    let alpha_to_coverage = attrs.get_bool("alpha_to_coverage").unwrap_or(false);
+    let sample_shading = attrs.get_float("sample_shading");
+    if let Some(f) = sample_shading {
+        if !(0.0..=1.0).contains(&f) {
+            return Err(syn::Error::new(spec.span, format!("sample_shading must be in [0, 1], found {}", f)));
+        }
+    }
+    if (alpha_to_coverage || sample_shading.is_some()) && samples_i.unwrap_or(1) <= 1 {
+        let key = if alpha_to_coverage { "alpha_to_coverage" } else { "sample_shading" };
+        return Err(syn::Error::new(spec.span, format!("{} requires samples > 1", key)));
+    }
+    let multisample_tokens = if alpha_to_coverage || sample_shading.is_some() {
+        let min = match sample_shading { Some(f) => { let f = f as f32; quote! { Some(#f) } } None => quote! { None } };
+        quote! { Some(macrokid_graphics::pipeline::MultisampleState { alpha_to_coverage: #alpha_to_coverage, min_sample_shading: #min }) }
+    } else { quote! { None } };
+
     // Depth state tokens
     let compare_tokens = match attrs.get_str("depth_compare").unwrap_or("Less") {
         "Never" => quote! { macrokid_graphics::pipeline::CompareOp::Never },
@@ -494,7 +741,7 @@ fn expand_graphics_pipeline(input: DeriveInput) -> syn::Result<proc_macro2::Toke
         "NotEqual" => quote! { macrokid_graphics::pipeline::CompareOp::NotEqual },
         "GEqual" | "GreaterOrEqual" => quote! { macrokid_graphics::pipeline::CompareOp::GreaterOrEqual },
         "Always" => quote! { macrokid_graphics::pipeline::CompareOp::Always },
-        other => return Err(syn::Error::new(spec.span, format!("unknown depth_compare '{}': expected Never|Less|Equal|LessOrEqual|Greater|NotEqual|GreaterOrEqual|Always", other))),
+        other => return Err(diag::expected_one_of(spec.span, "depth_compare", other, &["Never", "Less", "Equal", "LessOrEqual", "Greater", "NotEqual", "GreaterOrEqual", "Always"])),
     };
     let dt = attrs.get_bool("depth_test").unwrap_or(false);
     let dw = attrs.get_bool("depth_write").unwrap_or(false);
@@ -509,16 +756,7 @@ fn expand_graphics_pipeline(input: DeriveInput) -> syn::Result<proc_macro2::Toke
     } else { quote! { None } };
 
     // Push constants tokens
-    let pc_tokens = if let Some(sz) = attrs.get_int("push_constants_size") { 
-        let stages = if let Some(s) = attrs.get_str("push_constants_stages") { 
-            let mut vs = false; let mut fs = false; let mut cs = false;
-            for part in s.split(|c| c=='|'||c==','||c==' ') { match part.trim().to_lowercase().as_str() { "vs"|"vert"|"vertex"=>vs=true, "fs"|"frag"|"fragment"=>fs=true, "cs"|"comp"|"compute"=>cs=true, _=>{} } }
-            let vsb=vs; let fsb=fs; let csb=cs;
-            quote! { Some(macrokid_graphics::pipeline::StageMask { vs: #vsb, fs: #fsb, cs: #csb }) }
-        } else { quote! { None } };
-        let sz = sz as u32;
-        quote! { Some(macrokid_graphics::pipeline::PushConstantRange { size: #sz, stages: #stages }) }
-    } else { quote! { None } };
+    let pc_tokens = push_constants_tokens(&attrs);
 
     // Attachment extension parsing
     // Collect repeated #[color_target(format = "..", blend = true|false)] attributes
@@ -527,25 +765,15 @@ fn expand_graphics_pipeline(input: DeriveInput) -> syn::Result<proc_macro2::Toke
         if a.path().is_ident("color_target") {
             // Parse nested kv pairs for this single attribute occurrence
             let parsed = macrokid_core::common::attrs::parse_nested_attrs(&[a.clone()], "color_target")?;
-            let mut fmt: Option<String> = None;
-            let mut blend: Option<bool> = None;
-            for (k, v) in parsed {
-                match k.as_str() {
-                    "format" => fmt = Some(v),
-                    "blend" => {
-                        let vl = v.trim().to_ascii_lowercase();
-                        blend = match vl.as_str() {
-                            "true" | "1" | "yes" | "on" => Some(true),
-                            "false" | "0" | "no" | "off" => Some(false),
-                            _ => None,
-                        };
-                    }
-                    _ => {}
-                }
+            let blends = |(k, v): &(String, String)| match k.as_str() {
+                "blend" => BlendPreset::parse(v) != Some(BlendPreset::Opaque),
+                "src_factor" | "dst_factor" | "blend_op" => true,
+                _ => false,
+            };
+            if logic_op.is_some() && parsed.iter().any(blends) {
+                return Err(syn::Error::new(a.span(), "color_target blend cannot be combined with the pipeline's logic_op"));
             }
-            let fmt = fmt.ok_or_else(|| syn::Error::new(a.span(), "color_target requires format=..."))?;
-            let blend_ts = if let Some(b) = blend { quote! { Some(#b) } } else { quote! { None } };
-            color_entries.push(quote! { macrokid_graphics::pipeline::ColorTargetDesc { format: #fmt, blend: #blend_ts } });
+            color_entries.push(color_target_tokens(a, parsed)?);
         }
     }
     let _ct_entries_tokens: Option<Vec<proc_macro2::TokenStream>> = if color_entries.is_empty() { None } else { Some(color_entries.clone()) };
@@ -563,44 +791,92 @@ fn expand_graphics_pipeline(input: DeriveInput) -> syn::Result<proc_macro2::Toke
         }
     }
 
+    // Optional #[pipeline(vertex = "MyVertex", vertex_locations = "0, 1")]: emit a runtime check
+    // that the referenced VertexLayout provides every location the shaders consume
+    let vertex_locations: Vec<u32> = match attrs.get_str("vertex_locations") {
+        Some(s) => s
+            .split([',', ' '])
+            .filter(|p| !p.is_empty())
+            .map(|p| p.parse::<u32>().map_err(|_| syn::Error::new(spec.span, format!("invalid vertex location '{}': expected an integer", p))))
+            .collect::<syn::Result<_>>()?,
+        None => Vec::new(),
+    };
+    let vertex_check = match attrs.get_str("vertex") {
+        Some(path) => {
+            let vty: syn::Type = syn::parse_str(path)
+                .map_err(|_| syn::Error::new(spec.span, format!("invalid vertex type '{}'", path)))?;
+            quote! {
+                impl #ident {
+                    /// Shader input locations this pipeline expects its vertex layout to provide.
+                    pub const VERTEX_LOCATIONS: &'static [u32] = &[ #( #vertex_locations ),* ];
+                    /// Check the referenced vertex layout against `VERTEX_LOCATIONS`.
+                    pub fn validate_vertex_layout() -> ::core::result::Result<(), macrokid_graphics::resources::VertexLayoutError> {
+                        macrokid_graphics::resources::validate_vertex_layout::<#vty>(Self::VERTEX_LOCATIONS)
+                    }
+                }
+            }
+        }
+        None if !vertex_locations.is_empty() => {
+            return Err(syn::Error::new(spec.span, "vertex_locations requires vertex = \"<BufferLayout type>\""));
+        }
+        None => quote! {},
+    };
+
+    // Hash the stringified state (everything except the name) so runtimes can key pipeline caches
+    let state_hash = {
+        let mut state = format!("{};{};depth={};restart={};", vs_state, fs_state, depth, primitive_restart);
+        if let Some(v) = attrs.get_str("vertex") { state.push_str(&format!("vertex={}{:?};", v, vertex_locations)); }
+        for part in [&topology_tokens, &raster_tokens, &blend_tokens, &samples_tokens, &multisample_tokens, &depth_tokens, &dynamic_tokens, &pc_tokens, &depth_target_tokens] {
+            state.push_str(&part.to_string());
+            state.push(';');
+        }
+        for ct in &color_entries { state.push_str(&ct.to_string()); state.push(';'); }
+        fnv1a_64(state.as_bytes())
+    };
+
     let gp_input = GPInput {
         mod_ident: mod_ident.clone(),
         name: name.to_string(),
         vs,
         fs,
         topology: topology_tokens.clone(),
+        primitive_restart,
         depth,
         ident: ident.clone(),
         raster: raster_tokens,
         blend: blend_tokens,
         samples: samples_tokens,
+        multisample: multisample_tokens,
         depth_stencil: depth_tokens,
         dynamic: dynamic_tokens,
         push_constants: pc_tokens,
         color_entries: if color_entries.is_empty() { None } else { Some(color_entries) },
         depth_target: depth_target_tokens,
+        state_hash,
     };
 
     struct ModGen;
     impl crate::gen::CodeGen<GPInput> for ModGen {
         type Output = proc_macro2::TokenStream;
         fn generate(i: &GPInput) -> Self::Output {
-            let GPInput { mod_ident, name, vs, fs, topology, depth, raster, blend, samples, depth_stencil, dynamic, push_constants, color_entries, depth_target, .. } = i;
+            let GPInput { mod_ident, name, vs, fs, topology, primitive_restart, depth, raster, blend, samples, multisample, depth_stencil, dynamic, push_constants, color_entries, depth_target, .. } = i;
             let (ct_slice, ct_field) = if let Some(entries) = color_entries {
                 (quote! { pub static __COLOR: &[macrokid_graphics::pipeline::ColorTargetDesc] = &[ #( #entries ),* ]; }, quote! { Some(__COLOR) })
             } else { (quote! {}, quote! { None }) };
             quote! {
-                #[allow(non_snake_case)]
+                #[allow(non_snake_case, deprecated)]
                 mod #mod_ident {
                     #ct_slice
                     pub static DESC: macrokid_graphics::pipeline::PipelineDesc = macrokid_graphics::pipeline::PipelineDesc {
                         name: #name,
                         shaders: macrokid_graphics::pipeline::ShaderPaths { vs: #vs, fs: #fs },
                         topology: #topology,
+                        primitive_restart: #primitive_restart,
                         depth: #depth,
                         raster: #raster,
                         blend: #blend,
                         samples: #samples,
+                        multisample: #multisample,
                         depth_stencil: #depth_stencil,
                         dynamic: #dynamic,
                         push_constants: #push_constants,
@@ -617,13 +893,24 @@ fn expand_graphics_pipeline(input: DeriveInput) -> syn::Result<proc_macro2::Toke
             fn pipeline_desc() -> &'static macrokid_graphics::pipeline::PipelineDesc { &#mod_ident::DESC }
         }
     };
+    // The runtime crate's `__register_pipeline!` knows whether its `registry` feature is on;
+    // spanning the call at the `register` key puts a missing-feature error there.
+    let registration = if attrs.get_bool("register").unwrap_or(false) {
+        let span = spec.attrs.iter().filter(|a| a.path().is_ident("pipeline"))
+            .find_map(|a| macrokid_core::attrs::attr_value_spans(a, "register"))
+            .unwrap_or(spec.span);
+        quote::quote_spanned! {span=> macrokid_graphics::__register_pipeline!(&#mod_ident::DESC); }
+    } else { quote! {} };
     struct InherentGen;
     impl crate::gen::CodeGen<GPInput> for InherentGen {
         type Output = proc_macro2::TokenStream;
         fn generate(i: &GPInput) -> Self::Output {
             let ident = &i.ident;
+            let state_hash = i.state_hash;
             quote! {
                 impl #ident {
+                    /// Deterministic hash of the pipeline state, usable as a pipeline-cache key.
+                    pub const STATE_HASH: u64 = #state_hash;
                     pub fn describe_pipeline() -> &'static macrokid_graphics::pipeline::PipelineDesc { <Self as macrokid_graphics::pipeline::PipelineInfo>::pipeline_desc() }
                 }
             }
@@ -632,12 +919,202 @@ fn expand_graphics_pipeline(input: DeriveInput) -> syn::Result<proc_macro2::Toke
 
     type Both = crate::gen::Chain<ModGen, InherentGen>;
     let chained = Both::generate(&gp_input);
-    Ok(quote! { #chained #trait_impl })
+    let deprecations = attrs.deprecation_tokens();
+    Ok(quote! { #chained #trait_impl #registration #vertex_check #deprecations })
+}
+
+/// `Option<PushConstantRange>` from `push_constants_size` / `push_constants_stages`.
+fn push_constants_tokens(attrs: &macrokid_core::attr_schema::ParsedAttrs) -> proc_macro2::TokenStream {
+    if let Some(sz) = attrs.get_int("push_constants_size") { 
+        let stages = if let Some(s) = attrs.get_str("push_constants_stages") { 
+            let mut vs = false; let mut fs = false; let mut cs = false;
+            for part in s.split(|c| c=='|'||c==','||c==' ') { match part.trim().to_lowercase().as_str() { "vs"|"vert"|"vertex"=>vs=true, "fs"|"frag"|"fragment"=>fs=true, "cs"|"comp"|"compute"=>cs=true, _=>{} } }
+            let vsb=vs; let fsb=fs; let csb=cs;
+            quote! { Some(macrokid_graphics::pipeline::StageMask { vs: #vsb, fs: #fsb, cs: #csb }) }
+        } else { quote! { None } };
+        let sz = sz as u32;
+        quote! { Some(macrokid_graphics::pipeline::PushConstantRange { size: #sz, stages: #stages }) }
+    } else { quote! { None } }
+}
+
+/// `ShaderSource` expression for one stage from either `<stage> = "path"` or
+/// `<stage>_inline = "glsl"`, plus the `key=value` text folded into the state hash.
+fn shader_source_tokens(attrs: &macrokid_core::attr_schema::ParsedAttrs, stage: &str, span: Span) -> syn::Result<(proc_macro2::TokenStream, String)> {
+    let inline_key = format!("{}_inline", stage);
+    match (attrs.get_str(stage), attrs.get_str(&inline_key)) {
+        (Some(_), Some(_)) => Err(syn::Error::new(span, format!("set either {} or {} for the same stage, not both", stage, inline_key))),
+        (Some(path), None) => Ok((quote! { macrokid_graphics::pipeline::ShaderSource::Path(#path) }, format!("{}={}", stage, path))),
+        (None, Some(src)) => Ok((quote! { macrokid_graphics::pipeline::ShaderSource::Inline(#src) }, format!("{}={}", inline_key, src))),
+        (None, None) => Err(syn::Error::new(span, format!("missing required key '{}' (or '{}')", stage, inline_key))),
+    }
+}
+
+/// Resolve a `blend = "..."` value through the runtime `BlendPreset` parser so both the
+/// pipeline and pass derives accept exactly the same names.
+fn parse_blend_preset(value: &str, span: Span) -> syn::Result<BlendPreset> {
+    BlendPreset::parse(value).ok_or_else(|| diag::expected_one_of(span, "blend", value, BlendPreset::NAMES))
+}
+
+/// `BlendPreset::X.state()` expression; the preset→factor mapping stays in `macrokid_graphics`.
+fn blend_state_tokens(preset: BlendPreset) -> proc_macro2::TokenStream {
+    let variant = syn::Ident::new(&format!("{:?}", preset), Span::call_site());
+    quote! { macrokid_graphics::pipeline::BlendPreset::#variant.state() }
+}
+
+/// One `ColorTargetDesc` literal from a `#[color_target(format = "..", blend = "..")]` occurrence.
+/// `src_factor`/`dst_factor`/`blend_op` give the target its own equation (both channels); missing
+/// ones default to `src_alpha`/`one_minus_src_alpha`/`add`.
+fn color_target_tokens(attr: &syn::Attribute, parsed: Vec<(String, String)>) -> syn::Result<proc_macro2::TokenStream> {
+    let mut fmt: Option<String> = None;
+    let mut blend: Option<BlendPreset> = None;
+    let (mut src, mut dst, mut op): (Option<BlendFactor>, Option<BlendFactor>, Option<BlendOp>) = (None, None, None);
+    let factor = |key: &str, v: &str| BlendFactor::parse(v).ok_or_else(|| diag::expected_one_of(attr.span(), key, v, BlendFactor::NAMES));
+    for (k, v) in parsed {
+        match k.as_str() {
+            "format" => fmt = Some(v),
+            "blend" => blend = Some(parse_blend_preset(&v, attr.span())?),
+            "src_factor" => src = Some(factor("src_factor", &v)?),
+            "dst_factor" => dst = Some(factor("dst_factor", &v)?),
+            "blend_op" => op = Some(BlendOp::parse(&v).ok_or_else(|| diag::expected_one_of(attr.span(), "blend_op", &v, BlendOp::NAMES))?),
+            _ => {}
+        }
+    }
+    let fmt = fmt.ok_or_else(|| syn::Error::new(attr.span(), "color_target requires format=..."))?;
+    let explicit = src.is_some() || dst.is_some() || op.is_some();
+    if explicit && !matches!(blend, None | Some(BlendPreset::AlphaBlend)) {
+        return Err(syn::Error::new(attr.span(), "src_factor/dst_factor/blend_op need blend = true (or no blend key); they cannot be combined with another blend preset"));
+    }
+    let blend_ts = if explicit {
+        let src = syn::Ident::new(&format!("{:?}", src.unwrap_or(BlendFactor::SrcAlpha)), Span::call_site());
+        let dst = syn::Ident::new(&format!("{:?}", dst.unwrap_or(BlendFactor::OneMinusSrcAlpha)), Span::call_site());
+        let op = syn::Ident::new(&format!("{:?}", op.unwrap_or(BlendOp::Add)), Span::call_site());
+        quote! { Some(macrokid_graphics::pipeline::ColorBlendState::factors(
+            macrokid_graphics::pipeline::BlendFactor::#src,
+            macrokid_graphics::pipeline::BlendFactor::#dst,
+            macrokid_graphics::pipeline::BlendOp::#op,
+        )) }
+    } else {
+        match blend { Some(p) => { let state = blend_state_tokens(p); quote! { Some(#state) } } None => quote! { None } }
+    };
+    Ok(quote! { macrokid_graphics::pipeline::ColorTargetDesc { format: #fmt, blend: #blend_ts } })
+}
+
+/// FNV-1a (64-bit): stable across compilers and runs, unlike `DefaultHasher`.
+fn fnv1a_64(bytes: &[u8]) -> u64 {
+    let mut h: u64 = 0xcbf2_9ce4_8422_2325;
+    for b in bytes {
+        h ^= *b as u64;
+        h = h.wrapping_mul(0x0000_0100_0000_01b3);
+    }
+    h
+}
+
+// ================= pipeline_info attribute =================
+
+/// `#[pipeline_info(desc = "MY_DESC")]` on a struct: implement `PipelineInfo` (plus the inherent
+/// `describe_pipeline`) from a hand-written `static MY_DESC: PipelineDesc` in scope, for pipelines
+/// built without the `GraphicsPipeline` derive.
+#[proc_macro_attribute]
+pub fn pipeline_info(attr: proc_macro::TokenStream, item: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    expand_pipeline_info(attr.into(), item.into()).unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+fn expand_pipeline_info(attr: proc_macro2::TokenStream, item: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    let st: syn::ItemStruct = syn::parse2(item)?;
+    if !st.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(&st.generics, "#[pipeline_info] cannot be used on generic structs: the desc is a single static"));
+    }
+    let mut desc: Option<syn::LitStr> = None;
+    let parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("desc") {
+            desc = Some(meta.value()?.parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("unknown key in #[pipeline_info(...)]; expected `desc`"))
+        }
+    });
+    syn::parse::Parser::parse2(parser, attr)?;
+    let desc = desc.ok_or_else(|| syn::Error::new(Span::call_site(), "#[pipeline_info] requires desc = \"<static PipelineDesc>\""))?;
+    let desc_ident: syn::Ident = desc.parse()
+        .map_err(|_| syn::Error::new(desc.span(), format!("desc must name a static in scope (a plain identifier), found '{}'", desc.value())))?;
+
+    let ident = &st.ident;
+    Ok(quote! {
+        #st
+        impl macrokid_graphics::pipeline::PipelineInfo for #ident {
+            fn pipeline_desc() -> &'static macrokid_graphics::pipeline::PipelineDesc { &#desc_ident }
+        }
+        impl #ident {
+            pub fn describe_pipeline() -> &'static macrokid_graphics::pipeline::PipelineDesc { <Self as macrokid_graphics::pipeline::PipelineInfo>::pipeline_desc() }
+        }
+    })
+}
+
+// ================= ComputePipeline derive =================
+
+derive_entry!(ComputePipeline, attrs = [compute], handler = expand_compute_pipeline);
+
+fn expand_compute_pipeline(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let spec = TypeSpec::from_derive_input(input)?;
+    let ident = spec.ident.clone();
+    let schema = macrokid_core::attr_schema::AttrSchema::new("compute")
+        .opt_str("name")
+        .req_str("cs")
+        .opt_str("local_size")
+        .opt_str("dispatch")
+        .opt_int("push_constants_size")
+        .opt_str("push_constants_stages");
+    let attrs = macrokid_core::common::attr_schema::scope::on_type(&spec, &schema)?;
+
+    let name = attrs.get_str("name").map(|s| s.to_string()).unwrap_or_else(|| ident.to_string());
+    let cs = attrs.try_get_str("cs")?;
+    let local_size = match attrs.get_str("local_size") {
+        Some(s) => { let (x, y, z) = parse_xyz(s, "local_size", spec.span)?; quote! { Some((#x, #y, #z)) } }
+        None => quote! { None },
+    };
+    let (dx, dy, dz) = match attrs.get_str("dispatch") {
+        Some(s) => parse_xyz(s, "dispatch", spec.span)?,
+        None => (1, 1, 1),
+    };
+    let pc_tokens = push_constants_tokens(&attrs);
+
+    let mod_ident = syn::Ident::new(&format!("__mk_compute_{}", ident), Span::call_site());
+    Ok(quote! {
+        #[allow(non_snake_case)]
+        mod #mod_ident {
+            pub static DESC: macrokid_graphics::pipeline::ComputeDesc = macrokid_graphics::pipeline::ComputeDesc {
+                name: #name,
+                shader: #cs,
+                dispatch: (#dx, #dy, #dz),
+                local_size: #local_size,
+                push_constants: #pc_tokens,
+                bindings: None,
+            };
+        }
+        impl macrokid_graphics::pipeline::ComputePipelineInfo for #ident {
+            fn compute_desc() -> &'static macrokid_graphics::pipeline::ComputeDesc { &#mod_ident::DESC }
+        }
+        impl #ident {
+            pub fn describe_compute() -> &'static macrokid_graphics::pipeline::ComputeDesc { <Self as macrokid_graphics::pipeline::ComputePipelineInfo>::compute_desc() }
+        }
+    })
+}
+
+/// `"x,y,z"` with one to three positive components; missing trailing ones default to 1.
+fn parse_xyz(s: &str, key: &str, span: Span) -> syn::Result<(u32, u32, u32)> {
+    let parts: Vec<&str> = s.split(',').map(|p| p.trim()).collect();
+    let invalid = || syn::Error::new(span, format!("{} = \"{}\": expected 1 to 3 comma-separated positive integers, e.g. \"8,8,1\"", key, s));
+    if parts.is_empty() || parts.len() > 3 { return Err(invalid()); }
+    let mut out = [1u32; 3];
+    for (slot, p) in out.iter_mut().zip(&parts) {
+        *slot = p.parse::<u32>().ok().filter(|v| *v > 0).ok_or_else(invalid)?;
+    }
+    Ok((out[0], out[1], out[2]))
 }
 
 // ================= RenderEngine derive =================
 
-derive_entry!(RenderEngine, attrs = [app, window, use_pipeline], handler = expand_render_engine);
+derive_entry!(RenderEngine, attrs = [app, window, use_pipeline, use_compute], handler = expand_render_engine);
 
 fn expand_render_engine(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     use macrokid_core::ir::TypeKind;
@@ -645,36 +1122,46 @@ fn expand_render_engine(input: DeriveInput) -> syn::Result<proc_macro2::TokenStr
     let ident = spec.ident.clone();
 
     // Type-level attributes: app(name), window(width,height,vsync)
-    let app_schema = macrokid_core::attr_schema::AttrSchema::new("app").opt_str("name");
+    let app_schema = macrokid_core::attr_schema::AttrSchema::new("app").opt_str_default("name", "Untitled");
     let win_schema = macrokid_core::attr_schema::AttrSchema::new("window")
-        .opt_int("width")
-        .opt_int("height")
-        .opt_bool("vsync");
+        .opt_int_default("width", 1280)
+        .opt_int_default("height", 720)
+        .opt_bool_default("vsync", true);
     let app_attrs = macrokid_core::common::attr_schema::scope::on_type(&spec, &app_schema)?;
     let win_attrs = macrokid_core::common::attr_schema::scope::on_type(&spec, &win_schema)?;
 
-    let app_name = app_attrs.get_str("name").unwrap_or("Untitled");
-    let width = win_attrs.get_int("width").unwrap_or(1280) as u32;
-    let height = win_attrs.get_int("height").unwrap_or(720) as u32;
-    let vsync = win_attrs.get_bool("vsync").unwrap_or(true);
+    let app_name = app_attrs.try_get_str("name")?;
+    let width = win_attrs.try_get_int("width")? as u32;
+    let height = win_attrs.try_get_int("height")? as u32;
+    let vsync = win_attrs.try_get_bool("vsync")?;
 
     // Fields: any field marked with #[use_pipeline] will be treated as a pipeline type
     // that implements macrokid_graphics::pipeline::PipelineInfo. We collect their descs.
-    let use_schema = macrokid_core::attr_schema::AttrSchema::new("use_pipeline");
+    // Likewise #[use_compute] fields implement ComputePipelineInfo and feed compute_pipelines.
 
     let mut pipeline_ty_tokens: Vec<proc_macro2::TokenStream> = Vec::new();
+    let mut compute_ty_tokens: Vec<proc_macro2::TokenStream> = Vec::new();
+    // Per-field trait assertions spanned at the field type, so a missing impl is reported there
+    // rather than inside the generated engine_config body
+    let mut guards: Vec<proc_macro2::TokenStream> = Vec::new();
     match &spec.kind {
         TypeKind::Struct(st) => {
             match st.fields() {
                 FieldKind::Named(fields) | FieldKind::Unnamed(fields) => {
                     for f in fields {
-                        if let Ok(v) = use_schema.parse(&f.attrs) {
-                            if v.map.is_empty() { continue; }
-                            // Use the field type from syn metadata
-                            let ty = &f.ty;
+                        // Both markers are bare (`#[use_pipeline]`), so detect them by presence
+                        let marked = |name: &str| f.attrs.iter().any(|a| a.path().is_ident(name));
+                        // Use the field type from syn metadata
+                        let ty = &f.ty;
+                        if marked("use_pipeline") {
+                            guards.push(engine_field_guard(ty, "use_pipeline", "PipelineInfo", "GraphicsPipeline")?);
                             let ts = quote! { <#ty as macrokid_graphics::pipeline::PipelineInfo>::pipeline_desc() };
                             pipeline_ty_tokens.push(ts);
                         }
+                        if marked("use_compute") {
+                            guards.push(engine_field_guard(ty, "use_compute", "ComputePipelineInfo", "ComputePipeline")?);
+                            compute_ty_tokens.push(quote! { <#ty as macrokid_graphics::pipeline::ComputePipelineInfo>::compute_desc() });
+                        }
                     }
                 }
                 FieldKind::Unit => {}
@@ -687,15 +1174,18 @@ fn expand_render_engine(input: DeriveInput) -> syn::Result<proc_macro2::TokenStr
     // Build EngineConfig at call-site by cloning PipelineDesc values from PipelineInfo types.
     let app_s = app_name.to_string();
     let gen = quote! {
+        #( #guards )*
         impl macrokid_graphics::engine::RenderEngineInfo for #ident {
             fn engine_config() -> macrokid_graphics::engine::EngineConfig {
                 let mut pipelines: ::std::vec::Vec<macrokid_graphics::pipeline::PipelineDesc> = ::std::vec::Vec::new();
                 #( pipelines.push((#pipeline_ty_tokens).clone()); )*
+                let mut compute_pipelines: ::std::vec::Vec<macrokid_graphics::pipeline::ComputeDesc> = ::std::vec::Vec::new();
+                #( compute_pipelines.push((#compute_ty_tokens).clone()); )*
                 macrokid_graphics::engine::EngineConfig {
                     app: #app_s,
                     window: macrokid_graphics::engine::WindowCfg { width: #width, height: #height, vsync: #vsync },
                     pipelines,
-                    compute_pipelines: ::std::vec::Vec::new(),
+                    compute_pipelines,
                     options: macrokid_graphics::engine::BackendOptions::default(),
                 }
             }
@@ -707,6 +1197,48 @@ fn expand_render_engine(input: DeriveInput) -> syn::Result<proc_macro2::TokenStr
     Ok(gen)
 }
 
+/// Reject `#[use_pipeline]`/`#[use_compute]` field types that can never implement `trait_name`
+/// (references, tuples, primitives, ...), then emit a `const` assertion spanned at the type so an
+/// ordinary struct without the derive fails with the error on the field.
+fn engine_field_guard(ty: &syn::Type, marker: &str, trait_name: &str, derive: &str) -> syn::Result<proc_macro2::TokenStream> {
+    let not_a_target = match ty {
+        syn::Type::Path(tp) if tp.qself.is_none() => {
+            const PRIMITIVES: &[&str] = &[
+                "bool", "char", "str", "String", "u8", "u16", "u32", "u64", "u128", "usize",
+                "i8", "i16", "i32", "i64", "i128", "isize", "f32", "f64",
+            ];
+            tp.path.get_ident().is_some_and(|id| PRIMITIVES.iter().any(|p| id == p))
+        }
+        syn::Type::Path(_) | syn::Type::Paren(_) | syn::Type::Group(_) | syn::Type::Macro(_) => false,
+        _ => true,
+    };
+    if not_a_target {
+        return Err(syn::Error::new_spanned(ty, format!(
+            "#[{}] field type `{}` cannot implement {}; use a type with #[derive({})]",
+            marker, type_display(ty), trait_name, derive
+        )));
+    }
+    let trait_ident = syn::Ident::new(trait_name, Span::call_site());
+    Ok(quote::quote_spanned! {ty.span()=>
+        const _: fn() = || {
+            fn assert_impl<T: macrokid_graphics::pipeline::#trait_ident>() {}
+            assert_impl::<#ty>();
+        };
+    })
+}
+
+/// A type as written in source: `quote!` spacing without the spaces between punctuation
+/// (`&'static str`, not `& 'static str`).
+fn type_display(ty: &syn::Type) -> String {
+    let spaced = quote!(#ty).to_string();
+    let chars: Vec<char> = spaced.chars().collect();
+    let word = |c: Option<&char>| c.is_some_and(|c| c.is_alphanumeric() || *c == '_');
+    chars.iter().enumerate()
+        .filter(|&(i, c)| *c != ' ' || (i > 0 && word(chars.get(i - 1)) && word(chars.get(i + 1))))
+        .map(|(_, c)| *c)
+        .collect()
+}
+
 // ================= RenderPass derive (minimal graph node) =================
 
 derive_entry!(RenderPass, attrs = [pass, color_target, depth_target, input, output], handler = expand_render_pass);
@@ -722,7 +1254,7 @@ fn expand_render_pass(input: DeriveInput) -> syn::Result<proc_macro2::TokenStrea
     let kind_tokens = match attrs.get_str("kind").unwrap_or("graphics").to_ascii_lowercase().as_str() {
         "graphics" => quote! { macrokid_graphics::render_graph::PassKind::Graphics },
         "compute" => quote! { macrokid_graphics::render_graph::PassKind::Compute },
-        other => return Err(syn::Error::new(spec.span, format!("unknown pass kind '{}': expected graphics|compute", other))),
+        other => return Err(diag::expected_one_of(spec.span, "kind", other, &["graphics", "compute"])),
     };
 
     // Collect color targets (reuse same grammar as GraphicsPipeline)
@@ -730,12 +1262,7 @@ fn expand_render_pass(input: DeriveInput) -> syn::Result<proc_macro2::TokenStrea
     for a in &spec.attrs {
         if a.path().is_ident("color_target") {
             let parsed = macrokid_core::common::attrs::parse_nested_attrs(&[a.clone()], "color_target")?;
-            let mut fmt: Option<String> = None;
-            let mut blend: Option<bool> = None;
-            for (k, v) in parsed { match k.as_str() { "format" => fmt = Some(v), "blend" => { let vl = v.to_ascii_lowercase(); blend = match vl.as_str() { "true"|"1"|"yes"|"on" => Some(true), "false"|"0"|"no"|"off" => Some(false), _ => None }; }, _ => {} } }
-            let fmt = fmt.ok_or_else(|| syn::Error::new(a.span(), "color_target requires format=..."))?;
-            let blend_ts = if let Some(b) = blend { quote! { Some(#b) } } else { quote! { None } };
-            color_entries.push(quote! { macrokid_graphics::pipeline::ColorTargetDesc { format: #fmt, blend: #blend_ts } });
+            color_entries.push(color_target_tokens(a, parsed)?);
         }
     }
     let ct_entries_tokens: Option<Vec<proc_macro2::TokenStream>> = if color_entries.is_empty() { None } else { Some(color_entries.clone()) };
@@ -768,11 +1295,13 @@ fn expand_render_pass(input: DeriveInput) -> syn::Result<proc_macro2::TokenStrea
 
     // Rich outputs (preferred). Users can specify named outputs with sizes/usages.
     // #[output(name = "gbuf.albedo", format = "rgba16f", size = "rel(1.0,1.0)", usage = "color|sampled", samples = 1)]
+    // `persistent = true` marks a history resource kept alive into the next frame;
+    // `present = true` marks the output shown on screen (swapchain).
     let out_schema = macrokid_core::attr_schema::AttrSchema::new("output")
         .req_str("name").req_str("format")
-        .opt_str("size").opt_str("usage").opt_int("samples");
+        .opt_str("size").opt_str("usage").opt_int("samples").opt_bool("persistent").opt_bool("present");
     #[derive(Clone, Debug)]
-    struct OutRec { name: String, format: String, size: String, usage: String, samples: u32, is_depth: bool }
+    struct OutRec { name: String, format: String, size: String, usage: String, samples: u32, is_depth: bool, persistent: bool, present: bool }
     let mut outs: Vec<OutRec> = Vec::new();
     for a in &spec.attrs {
         if a.path().is_ident("output") {
@@ -783,12 +1312,20 @@ fn expand_render_pass(input: DeriveInput) -> syn::Result<proc_macro2::TokenStrea
             let usage = parsed.get_str("usage").unwrap_or("color").to_string();
             let samples = parsed.get_int("samples").unwrap_or(1) as u32;
             let is_depth = usage.to_ascii_lowercase().split(|c| c=='|' || c==',' || c==' ').any(|t| t.trim()=="depth");
-            outs.push(OutRec { name, format, size, usage, samples, is_depth });
+            let persistent = parsed.get_bool("persistent").unwrap_or(false);
+            let present = parsed.get_bool("present").unwrap_or(false);
+            if present && is_depth {
+                return Err(syn::Error::new(a.span(), format!("depth output '{}' cannot be presented", name)));
+            }
+            if present && outs.iter().any(|o| o.present) {
+                return Err(syn::Error::new(a.span(), format!("output '{}': only one output per pass may set present", name)));
+            }
+            outs.push(OutRec { name, format, size, usage, samples, is_depth, persistent, present });
         }
     }
     // If a depth_target(format=..) exists but not declared as output, synthesize an output named "depth"
     if depth_target_tokens.to_string().starts_with("Some(") && !outs.iter().any(|o| o.is_depth) {
-        outs.push(OutRec { name: "depth".into(), format: "D32_SFLOAT".into(), size: "rel(1.0,1.0)".into(), usage: "depth".into(), samples: 1, is_depth: true });
+        outs.push(OutRec { name: "depth".into(), format: "D32_SFLOAT".into(), size: "rel(1.0,1.0)".into(), usage: "depth".into(), samples: 1, is_depth: true, persistent: false, present: false });
     }
 
     let mod_ident = syn::Ident::new(&format!("__mk_pass_{}", name), Span::call_site());
@@ -832,7 +1369,7 @@ fn expand_render_pass(input: DeriveInput) -> syn::Result<proc_macro2::TokenStrea
                     "transfer_dst" | "xfer_dst" => quote! { macrokid_graphics::render_graph::UsageMask::TRANSFER_DST },
                     _ => quote! { macrokid_graphics::render_graph::UsageMask::empty() },
                 };
-                expr = quote! { (#expr) | (#flag) };
+                expr = quote! { #expr.union(#flag) };
             }
             expr
         }
@@ -844,7 +1381,9 @@ fn expand_render_pass(input: DeriveInput) -> syn::Result<proc_macro2::TokenStrea
             let usage_tokens = parse_usage_tokens(&o.usage);
             let samples = o.samples;
             let is_depth = o.is_depth;
-            quote! { macrokid_graphics::render_graph::OutputDesc { name: #name, format: #format, size: #size_tokens, usage: #usage_tokens, samples: #samples, is_depth: #is_depth } }
+            let persistent = o.persistent;
+            let present = o.present;
+            quote! { macrokid_graphics::render_graph::OutputDesc { name: #name, format: #format, size: #size_tokens, usage: #usage_tokens, samples: #samples, is_depth: #is_depth, persistent: #persistent, present: #present } }
         }).collect();
         let outs_slice = if outs.is_empty() { quote! {} } else { quote! { pub static __OUTS: &[macrokid_graphics::render_graph::OutputDesc] = &[ #( #out_items ),* ]; } };
         let inputs_slice = if let Some(items) = &input_items_tokens {
@@ -871,11 +1410,552 @@ fn expand_render_pass(input: DeriveInput) -> syn::Result<proc_macro2::TokenStrea
         }
     };
 
+    // Const view of the pass's resource names for render_graph!; persistent outputs order nothing
+    let io_outputs: Vec<&str> = outs.iter().filter(|o| !o.persistent).map(|o| o.name.as_str()).collect();
     let impls = quote! {
         impl macrokid_graphics::render_graph::PassInfo for #ident {
             fn pass_desc() -> &'static macrokid_graphics::render_graph::PassDesc { &#mod_ident::DESC }
         }
-        impl #ident { pub fn describe_pass() -> &'static macrokid_graphics::render_graph::PassDesc { <Self as macrokid_graphics::render_graph::PassInfo>::pass_desc() } }
+        impl #ident {
+            pub fn describe_pass() -> &'static macrokid_graphics::render_graph::PassDesc { <Self as macrokid_graphics::render_graph::PassInfo>::pass_desc() }
+            pub const PASS_IO: macrokid_graphics::render_graph::PassIo = macrokid_graphics::render_graph::PassIo {
+                name: #name,
+                inputs: &[ #( #inputs ),* ],
+                outputs: &[ #( #io_outputs ),* ],
+            };
+        }
     };
     Ok(quote! { #module #impls })
 }
+
+// ================= render_graph! (compile-time pass ordering) =================
+
+/// `render_graph!{ Deferred: GBuffer, Lighting, Post }`: order `RenderPass`-deriving types by
+/// matching each pass's `#[input]` names against the others' `#[output]` names, and emit
+/// `pub static DEFERRED_ORDER: &[&str]` (the graph name in SCREAMING_SNAKE_CASE plus `_ORDER`)
+/// with the pass names in execution order, so several graphs can share a module. The ordering
+/// runs in a const (`render_graph::pass_order`), so a dependency cycle is a compile error naming it.
+///
+/// ```compile_fail
+/// use macrokid_graphics_derive::{render_graph, RenderPass};
+/// #[derive(RenderPass)]
+/// #[pass(name = "a")]
+/// #[input(name = "b.out")]
+/// #[output(name = "a.out", format = "rgba8_unorm")]
+/// struct A;
+/// #[derive(RenderPass)]
+/// #[pass(name = "b")]
+/// #[input(name = "a.out")]
+/// #[output(name = "b.out", format = "rgba8_unorm")]
+/// struct B;
+/// render_graph! { Cyclic: A, B }
+/// ```
+#[proc_macro]
+pub fn render_graph(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    expand_render_graph(input.into()).unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+fn expand_render_graph(input: proc_macro2::TokenStream) -> syn::Result<proc_macro2::TokenStream> {
+    use syn::parse::Parser;
+    let usage = "render_graph! expects `GraphName: PassA, PassB, ...` with RenderPass types";
+    let (graph, passes) = (|input: syn::parse::ParseStream| {
+        let graph: syn::Ident = input.parse().map_err(|e| syn::Error::new(e.span(), usage))?;
+        let _colon: syn::Token![:] = input.parse().map_err(|e| syn::Error::new(e.span(), usage))?;
+        let passes = syn::punctuated::Punctuated::<syn::Path, syn::Token![,]>::parse_terminated(input)?;
+        Ok((graph, passes))
+    }).parse2(input)?;
+    if passes.is_empty() {
+        return Err(syn::Error::new_spanned(&graph, usage));
+    }
+    let mut seen: Vec<String> = Vec::new();
+    for p in &passes {
+        let key = quote!(#p).to_string();
+        if seen.contains(&key) {
+            return Err(syn::Error::new_spanned(p, format!("pass `{}` is listed more than once", key.replace(' ', ""))));
+        }
+        seen.push(key);
+    }
+    let n = passes.len();
+    let passes = passes.iter();
+    let order_ident = syn::Ident::new(&format!("{}_ORDER", macrokid_core::ir::rename::to_screaming_snake(&graph.to_string())), graph.span());
+    Ok(quote! {
+        pub static #order_ident: &[&str] = &{
+            const PASSES: [macrokid_graphics::render_graph::PassIo; #n] = [ #( <#passes>::PASS_IO ),* ];
+            const ORDER: [&str; #n] = macrokid_graphics::render_graph::pass_order(&PASSES);
+            ORDER
+        };
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn push_bindings_are_collected() {
+        let di: DeriveInput = parse_quote! {
+            struct R {
+                #[uniform(set = 0, binding = 0, stages = "vs")] camera: (),
+                #[uniform(set = 1, binding = 0, stages = "fs", push = true)] object: (),
+            }
+        };
+        let spec = TypeSpec::from_derive_input(di.clone()).unwrap();
+        let descs = ResourceBindingDerive::collect_descriptors(&spec).unwrap();
+        let pushed: Vec<&str> = descs.iter().filter(|d| d.push).map(|d| d.field.as_str()).collect();
+        assert_eq!(pushed, vec!["object"]);
+        let out = expand_resource_binding(di).unwrap().to_string();
+        assert!(out.contains("push_descriptor_bindings"));
+    }
+
+    #[test]
+    fn push_and_pool_bindings_cannot_share_a_set() {
+        let di: DeriveInput = parse_quote! {
+            struct R {
+                #[uniform(set = 0, binding = 0, stages = "vs")] camera: (),
+                #[uniform(set = 0, binding = 1, stages = "fs", push = true)] object: (),
+            }
+        };
+        let err = expand_resource_binding(di).unwrap_err();
+        assert!(err.to_string().contains("mixes push and non-push"));
+    }
+
+    #[test]
+    fn array_bindings_carry_count() {
+        let di: DeriveInput = parse_quote! {
+            struct Bindless {
+                #[uniform(set = 0, binding = 0, stages = "vs")] camera: (),
+                #[texture(set = 0, binding = 3, stages = "fs", count = 4)] textures: (),
+            }
+        };
+        let spec = TypeSpec::from_derive_input(di.clone()).unwrap();
+        let counts: Vec<(String, u32)> = ResourceBindingDerive::collect_descriptors(&spec).unwrap().into_iter().map(|d| (d.field, d.count)).collect();
+        assert_eq!(counts, vec![("camera".to_string(), 1), ("textures".to_string(), 4)]);
+        let out = expand_resource_binding(di).unwrap().to_string();
+        assert!(out.contains("field : \"textures\" , set : 0u32 , binding : 3u32 , kind : macrokid_graphics :: resources :: ResourceKind :: Texture , stages : Some"), "{}", out);
+        assert!(out.contains("dynamic : false , count : 4u32 }"), "{}", out);
+        assert!(out.contains("DescriptorPoolSize { kind : macrokid_graphics :: resources :: ResourceKind :: Texture , dynamic : false , count : 4u32 }"), "{}", out);
+
+        let zero: DeriveInput = parse_quote! {
+            struct Bad { #[texture(set = 0, binding = 0, stages = "fs", count = 0)] textures: () }
+        };
+        assert!(expand_resource_binding(zero).unwrap_err().to_string().contains("binding 'textures' has count = 0"));
+        // Arrays still occupy a single (set, binding) slot
+        let dup: DeriveInput = parse_quote! {
+            struct Dup {
+                #[texture(set = 0, binding = 1, stages = "fs", count = 8)] a: (),
+                #[sampler(set = 0, binding = 1, stages = "fs")] b: (),
+            }
+        };
+        assert!(expand_resource_binding(dup).unwrap_err().to_string().contains("duplicate (set,binding)"));
+    }
+
+    #[test]
+    fn descriptor_pool_sizes_count_kinds() {
+        let di: DeriveInput = parse_quote! {
+            struct R {
+                #[uniform(set = 0, binding = 0, stages = "vs")] camera: (),
+                #[texture(set = 0, binding = 1, stages = "fs")] albedo: (),
+                #[uniform(set = 0, binding = 2, stages = "fs")] light: (),
+                #[texture(set = 1, binding = 0, stages = "fs")] normal: (),
+                #[texture(set = 1, binding = 1, stages = "fs")] roughness: (),
+            }
+        };
+        let out = expand_resource_binding(di).unwrap().to_string();
+        assert!(out.contains("pub static DATA : & [macrokid_graphics :: resources :: DescriptorPoolSize] = & [\
+macrokid_graphics :: resources :: DescriptorPoolSize { kind : macrokid_graphics :: resources :: ResourceKind :: Uniform , dynamic : false , count : 2u32 } , \
+macrokid_graphics :: resources :: DescriptorPoolSize { kind : macrokid_graphics :: resources :: ResourceKind :: Texture , dynamic : false , count : 3u32 }]"), "{}", out);
+        assert!(out.contains("pub fn descriptor_pool_sizes"));
+    }
+
+    #[test]
+    fn dynamic_uniform_binding() {
+        let di: DeriveInput = parse_quote! {
+            struct R {
+                #[uniform(set = 0, binding = 0, stages = "vs")] camera: (),
+                #[uniform(set = 0, binding = 1, stages = "vs", dynamic = true)] object: (),
+            }
+        };
+        let spec = TypeSpec::from_derive_input(di.clone()).unwrap();
+        let descs = ResourceBindingDerive::collect_descriptors(&spec).unwrap();
+        let dynamic: Vec<&str> = descs.iter().filter(|d| d.dynamic).map(|d| d.field.as_str()).collect();
+        assert_eq!(dynamic, vec!["object"]);
+        assert!(expand_resource_binding(di).unwrap().to_string().contains("dynamic : true"));
+    }
+
+    #[test]
+    fn descriptor_pool_sizes_separate_dynamic_uniforms() {
+        let di: DeriveInput = parse_quote! {
+            struct R {
+                #[uniform(set = 0, binding = 0, stages = "vs")] camera: (),
+                #[uniform(set = 0, binding = 1, stages = "vs", dynamic = true)] object: (),
+                #[uniform(set = 0, binding = 2, stages = "fs", dynamic = true)] material: (),
+            }
+        };
+        let out = expand_resource_binding(di).unwrap().to_string();
+        assert!(out.contains("DescriptorPoolSize { kind : macrokid_graphics :: resources :: ResourceKind :: Uniform , dynamic : false , count : 1u32 } , \
+macrokid_graphics :: resources :: DescriptorPoolSize { kind : macrokid_graphics :: resources :: ResourceKind :: Uniform , dynamic : true , count : 2u32 }"), "{}", out);
+    }
+
+    #[test]
+    fn dynamic_rejected_on_textures() {
+        let di: DeriveInput = parse_quote! {
+            struct R {
+                #[texture(set = 0, binding = 0, stages = "fs", dynamic = true)] albedo: (),
+            }
+        };
+        let err = expand_resource_binding(di).unwrap_err();
+        assert!(err.to_string().contains("only valid on uniform bindings"));
+    }
+
+    #[test]
+    fn conflicting_resource_kinds_name_both_attributes() {
+        let di: DeriveInput = parse_quote! {
+            struct R {
+                #[uniform(set = 0, binding = 0, stages = "vs")]
+                #[texture(set = 0, binding = 1, stages = "fs")]
+                both: (),
+            }
+        };
+        let err = expand_resource_binding(di).unwrap_err();
+        let msg = err.to_string();
+        assert!(msg.contains("'uniform'") && msg.contains("'texture'"), "{}", msg);
+        assert_eq!(err.into_iter().count(), 2);
+    }
+
+    #[test]
+    fn buffer_layout_rejects_borrowed_fields() {
+        let r: DeriveInput = parse_quote! { struct V<'a> { #[vertex(location = 0)] pos: &'a [f32; 3] } };
+        assert!(expand_buffer_layout(r).unwrap_err().to_string().contains("'pos' is a reference"));
+        let c: DeriveInput = parse_quote! { struct V<'a> { #[vertex(location = 0)] pos: [f32; 3], name: Cow<'a, str> } };
+        assert!(expand_buffer_layout(c).unwrap_err().to_string().contains("'name' borrows data"));
+    }
+
+    #[test]
+    fn buffer_layout_unknown_format_lists_supported_set() {
+        let di: DeriveInput = parse_quote! { struct V { #[vertex(location = 0, format = "rgb10a2_unrom")] n: u32 } };
+        let msg = expand_buffer_layout(di).unwrap_err().to_string();
+        assert!(msg.starts_with("field 'n': unknown value 'rgb10a2_unrom' for `format`"), "{}", msg);
+        assert!(msg.contains("|r16_uint|"), "{}", msg);
+        assert!(msg.contains("did you mean 'rgb10a2_unorm'"), "{}", msg);
+    }
+
+    #[test]
+    fn blend_presets_in_pipeline_and_color_targets() {
+        let di: DeriveInput = parse_quote! {
+            #[pipeline(vs = "a.vert", fs = "a.frag", blend = "additive")]
+            #[color_target(format = "rgba16f", blend = "premultiplied_alpha")]
+            #[color_target(format = "rgba8_unorm", blend = "false")]
+            struct Glow;
+        };
+        let out = expand_graphics_pipeline(di).unwrap().to_string();
+        assert!(out.contains("blend : Some (macrokid_graphics :: pipeline :: BlendPreset :: Additive . state ())"));
+        assert!(out.contains("BlendPreset :: PremultipliedAlpha . state ()"));
+        assert!(out.contains("BlendPreset :: Opaque . state ()"));
+
+        let alpha: DeriveInput = parse_quote! { #[pipeline(vs = "a.vert", fs = "a.frag", blend = true)] struct A; };
+        assert!(expand_graphics_pipeline(alpha).unwrap().to_string().contains("BlendPreset :: AlphaBlend . state ()"));
+
+        let pass: DeriveInput = parse_quote! { #[pass(name = "hud")] #[color_target(format = "rgba8_unorm", blend = "alpha_blend")] struct Hud; };
+        assert!(expand_render_pass(pass).unwrap().to_string().contains("BlendPreset :: AlphaBlend . state ()"));
+    }
+
+    #[test]
+    fn pipeline_info_attribute_points_at_static() {
+        let out = expand_pipeline_info(quote! { desc = "SKY_DESC" }, quote! { pub struct Sky; }).unwrap().to_string();
+        assert!(out.contains("pub struct Sky ;"));
+        assert!(out.contains("fn pipeline_desc () -> & 'static macrokid_graphics :: pipeline :: PipelineDesc { & SKY_DESC }"), "{}", out);
+        assert!(out.contains("pub fn describe_pipeline ()"));
+
+        let path = expand_pipeline_info(quote! { desc = "descs::SKY" }, quote! { struct Sky; }).unwrap_err();
+        assert!(path.to_string().contains("plain identifier"));
+        assert!(expand_pipeline_info(quote! {}, quote! { struct Sky; }).unwrap_err().to_string().contains("requires desc"));
+        assert!(expand_pipeline_info(quote! { desc = "D" }, quote! { enum Sky { A } }).is_err());
+    }
+
+    #[test]
+    fn compute_pipeline_parses_local_size() {
+        let di: DeriveInput = parse_quote! {
+            #[compute(cs = "shaders/blur.comp", local_size = "8,8,1", dispatch = "120, 68", push_constants_size = 16)]
+            struct Blur;
+        };
+        let out = expand_compute_pipeline(di).unwrap().to_string();
+        assert!(out.contains("local_size : Some ((8u32 , 8u32 , 1u32))"), "{}", out);
+        assert!(out.contains("dispatch : (120u32 , 68u32 , 1u32)"), "{}", out);
+        assert!(out.contains("PushConstantRange { size : 16u32 , stages : None }"), "{}", out);
+        assert_eq!(parse_xyz("64", "local_size", Span::call_site()).unwrap(), (64, 1, 1));
+
+        let bad: DeriveInput = parse_quote! { #[compute(cs = "a.comp", local_size = "8,0,1")] struct Bad; };
+        assert!(expand_compute_pipeline(bad).unwrap_err().to_string().contains("expected 1 to 3 comma-separated positive integers"));
+        let missing: DeriveInput = parse_quote! { #[compute(local_size = "8")] struct Missing; };
+        assert!(expand_compute_pipeline(missing).is_err());
+    }
+
+    #[test]
+    fn render_engine_collects_marked_fields() {
+        let di: DeriveInput = parse_quote! {
+            struct Engine { #[use_pipeline] tri: Triangle, #[use_compute] blur: Blur, other: u32 }
+        };
+        let out = expand_render_engine(di).unwrap().to_string();
+        assert!(out.contains("pipelines . push ((< Triangle as macrokid_graphics :: pipeline :: PipelineInfo > :: pipeline_desc ()) . clone ())"), "{}", out);
+        assert!(out.contains("compute_pipelines . push ((< Blur as macrokid_graphics :: pipeline :: ComputePipelineInfo > :: compute_desc ()) . clone ())"), "{}", out);
+        assert!(!out.contains("u32 as"));
+    }
+
+    #[test]
+    fn render_graph_lists_passes_once() {
+        let out = expand_render_graph(quote! { Deferred: GBuffer, passes::Lighting }).unwrap().to_string();
+        assert!(out.contains("pub static DEFERRED_ORDER"), "{}", out);
+        assert!(out.contains("< passes :: Lighting > :: PASS_IO"), "{}", out);
+        let out = expand_render_graph(quote! { ShadowPasses: Shadow }).unwrap().to_string();
+        assert!(out.contains("pub static SHADOW_PASSES_ORDER"), "{}", out);
+        assert!(expand_render_graph(quote! { G: A, B, A }).unwrap_err().to_string().contains("`A` is listed more than once"));
+        let usage = "render_graph! expects `GraphName: PassA, PassB, ...` with RenderPass types";
+        assert_eq!(expand_render_graph(quote! { A, B }).unwrap_err().to_string(), usage);
+        assert_eq!(expand_render_graph(quote! { G: }).unwrap_err().to_string(), usage);
+        assert!(expand_render_graph(quote! {}).is_err());
+    }
+
+    #[test]
+    fn color_targets_carry_independent_blend_factors() {
+        let di: DeriveInput = parse_quote! {
+            #[pipeline(vs = "g.vert", fs = "g.frag")]
+            #[color_target(format = "rgba16f", blend = "false")]
+            #[color_target(format = "rgba8_unorm", src_factor = "one", dst_factor = "one_minus_src_alpha")]
+            #[color_target(format = "rgba8_unorm", blend = "true", blend_op = "max")]
+            struct Deferred;
+        };
+        let out = expand_graphics_pipeline(di).unwrap().to_string();
+        assert!(out.contains("format : \"rgba16f\" , blend : Some (macrokid_graphics :: pipeline :: BlendPreset :: Opaque . state ())"), "{}", out);
+        assert!(out.contains("ColorBlendState :: factors (macrokid_graphics :: pipeline :: BlendFactor :: One , macrokid_graphics :: pipeline :: BlendFactor :: OneMinusSrcAlpha , macrokid_graphics :: pipeline :: BlendOp :: Add ,)"), "{}", out);
+        assert!(out.contains("ColorBlendState :: factors (macrokid_graphics :: pipeline :: BlendFactor :: SrcAlpha , macrokid_graphics :: pipeline :: BlendFactor :: OneMinusSrcAlpha , macrokid_graphics :: pipeline :: BlendOp :: Max ,)"), "{}", out);
+
+        let bad: DeriveInput = parse_quote! {
+            #[pipeline(vs = "g.vert", fs = "g.frag")]
+            #[color_target(format = "rgba8_unorm", src_factor = "src_alpha_saturate")]
+            struct Bad;
+        };
+        assert!(expand_graphics_pipeline(bad).unwrap_err().to_string().contains("one_minus_src_alpha"));
+        let mixed: DeriveInput = parse_quote! {
+            #[pipeline(vs = "g.vert", fs = "g.frag")]
+            #[color_target(format = "rgba8_unorm", blend = "additive", dst_factor = "zero")]
+            struct Mixed;
+        };
+        assert!(expand_graphics_pipeline(mixed).unwrap_err().to_string().contains("another blend preset"));
+    }
+
+    #[test]
+    fn logic_op_sets_blend_state() {
+        let di: DeriveInput = parse_quote! { #[pipeline(vs = "a.vert", fs = "a.frag", logic_op = "xor")] struct Mask; };
+        let out = expand_graphics_pipeline(di).unwrap().to_string();
+        assert!(out.contains("blend : Some (macrokid_graphics :: pipeline :: ColorBlendState :: logic (macrokid_graphics :: pipeline :: LogicOp :: Xor))"), "{}", out);
+
+        let bad: DeriveInput = parse_quote! { #[pipeline(vs = "a.vert", fs = "a.frag", logic_op = "mix")] struct Bad; };
+        assert!(expand_graphics_pipeline(bad).unwrap_err().to_string().contains("xor"));
+    }
+
+    #[test]
+    fn legacy_depth_key_warns_but_applies() {
+        let di: DeriveInput = parse_quote! { #[pipeline(vs = "a.vert", fs = "a.frag", depth = false)] struct Legacy; };
+        let out = expand_graphics_pipeline(di).unwrap().to_string();
+        assert!(out.contains("depth : false"), "{}", out);
+        assert!(out.contains("`depth` in #[pipeline] is deprecated: use depth_test/depth_write instead"), "{}", out);
+
+        let di: DeriveInput = parse_quote! { #[pipeline(vs = "a.vert", fs = "a.frag", depth_test = true)] struct Current; };
+        assert!(!expand_graphics_pipeline(di).unwrap().to_string().contains("__mk_deprecated"));
+    }
+
+    #[test]
+    fn shader_stages_accept_paths_or_inline_source() {
+        let di: DeriveInput = parse_quote! { #[pipeline(vs = "a.vert", fs_inline = "#version 450\nvoid main() {}")] struct Mixed; };
+        let out = expand_graphics_pipeline(di).unwrap().to_string();
+        assert!(out.contains("vs : macrokid_graphics :: pipeline :: ShaderSource :: Path (\"a.vert\")"), "{}", out);
+        assert!(out.contains("fs : macrokid_graphics :: pipeline :: ShaderSource :: Inline (\"#version 450\\nvoid main() {}\")"), "{}", out);
+
+        let both: DeriveInput = parse_quote! { #[pipeline(vs = "a.vert", vs_inline = "void main() {}", fs = "a.frag")] struct Both; };
+        assert!(expand_graphics_pipeline(both).unwrap_err().to_string().contains("either vs or vs_inline"));
+        let missing: DeriveInput = parse_quote! { #[pipeline(vs = "a.vert")] struct Missing; };
+        assert!(expand_graphics_pipeline(missing).unwrap_err().to_string().contains("missing required key 'fs' (or 'fs_inline')"));
+    }
+
+    #[test]
+    fn logic_op_excludes_blend() {
+        let both: DeriveInput = parse_quote! { #[pipeline(vs = "a.vert", fs = "a.frag", blend = true, logic_op = "xor")] struct Both; };
+        assert!(expand_graphics_pipeline(both).unwrap_err().to_string().contains("logic_op cannot be combined with blend"));
+
+        let target: DeriveInput = parse_quote! {
+            #[pipeline(vs = "a.vert", fs = "a.frag", logic_op = "and")]
+            #[color_target(format = "rgba8_unorm", blend = "additive")]
+            struct Target;
+        };
+        assert!(expand_graphics_pipeline(target).unwrap_err().to_string().contains("color_target blend"));
+
+        // Turning blending off is what a logic op needs anyway
+        let off: DeriveInput = parse_quote! {
+            #[pipeline(vs = "a.vert", fs = "a.frag", blend = false, logic_op = "xor")]
+            #[color_target(format = "rgba8_unorm", blend = "opaque")]
+            struct Off;
+        };
+        let out = expand_graphics_pipeline(off).unwrap().to_string();
+        assert!(out.contains("ColorBlendState :: logic (macrokid_graphics :: pipeline :: LogicOp :: Xor)"), "{}", out);
+    }
+
+    #[test]
+    fn unknown_blend_preset_errors() {
+        let di: DeriveInput = parse_quote! { #[pipeline(vs = "a.vert", fs = "a.frag", blend = "multiply")] struct M; };
+        let err = expand_graphics_pipeline(di).unwrap_err().to_string();
+        assert!(err.contains("multiply") && err.contains("additive"), "{}", err);
+        let pass: DeriveInput = parse_quote! { #[color_target(format = "rgba8_unorm", blend = "multiply")] struct P; };
+        assert!(expand_render_pass(pass).is_err());
+    }
+
+    #[test]
+    fn strip_topologies_and_primitive_restart() {
+        for topo in ["TriangleStrip", "LineStrip", "TriangleFan"] {
+            let lit = syn::LitStr::new(topo, Span::call_site());
+            let di: DeriveInput = parse_quote! { #[pipeline(vs = "a.vert", fs = "a.frag", topology = #lit, primitive_restart = true)] struct S; };
+            let out = expand_graphics_pipeline(di).unwrap().to_string();
+            assert!(out.contains(&format!("topology : macrokid_graphics :: pipeline :: Topology :: {}", topo)), "{}", out);
+            assert!(out.contains("primitive_restart : true"));
+        }
+        let plain: DeriveInput = parse_quote! { #[pipeline(vs = "a.vert", fs = "a.frag", topology = "LineStrip")] struct P; };
+        assert!(expand_graphics_pipeline(plain).unwrap().to_string().contains("primitive_restart : false"));
+    }
+
+    #[test]
+    fn topology_validated_by_schema() {
+        for topo in TOPOLOGIES {
+            let lit = syn::LitStr::new(topo, Span::call_site());
+            let di: DeriveInput = parse_quote! { #[pipeline(vs = "a.vert", fs = "a.frag", topology = #lit)] struct S; };
+            assert!(expand_graphics_pipeline(di).is_ok(), "{}", topo);
+        }
+        for (polygon, cull) in POLYGON_MODES.iter().zip(CULL_MODES) {
+            let (p, c) = (syn::LitStr::new(polygon, Span::call_site()), syn::LitStr::new(cull, Span::call_site()));
+            let di: DeriveInput = parse_quote! { #[pipeline(vs = "a.vert", fs = "a.frag", polygon = #p, cull = #c)] struct S; };
+            let out = expand_graphics_pipeline(di).unwrap().to_string();
+            assert!(out.contains(&format!("PolygonMode :: {}", polygon)) && out.contains(&format!("CullMode :: {}", cull)), "{}", out);
+        }
+        let src = r#"#[pipeline(vs = "a.vert", fs = "a.frag", topology = "Quads")] struct Q;"#;
+        let err = expand_graphics_pipeline(syn::parse_str(src).unwrap()).unwrap_err();
+        assert_eq!(err.to_string(), "unknown topology 'Quads': expected TriangleList|LineList|PointList|TriangleStrip|LineStrip|TriangleFan");
+        let cull: DeriveInput = parse_quote! { #[pipeline(vs = "a.vert", fs = "a.frag", cull = "back")] struct C; };
+        assert!(expand_graphics_pipeline(cull).unwrap_err().to_string().contains("(did you mean 'Back'?)"));
+    }
+
+    #[test]
+    fn primitive_restart_requires_strip_topology() {
+        let di: DeriveInput = parse_quote! { #[pipeline(vs = "a.vert", fs = "a.frag", topology = "TriangleList", primitive_restart = true)] struct S; };
+        let err = expand_graphics_pipeline(di).unwrap_err().to_string();
+        assert!(err.contains("primitive_restart requires a strip or fan topology"), "{}", err);
+        let default_topo: DeriveInput = parse_quote! { #[pipeline(vs = "a.vert", fs = "a.frag", primitive_restart = true)] struct D; };
+        assert!(expand_graphics_pipeline(default_topo).is_err());
+    }
+
+    #[test]
+    fn pipeline_vertex_layout_check() {
+        let di: DeriveInput = parse_quote! {
+            #[pipeline(vs = "a.vert", fs = "a.frag", vertex = "MyVertex", vertex_locations = "0, 1")]
+            struct P;
+        };
+        let out = expand_graphics_pipeline(di).unwrap().to_string();
+        assert!(out.contains("VERTEX_LOCATIONS : & 'static [u32] = & [0u32 , 1u32]"));
+        assert!(out.contains("validate_vertex_layout :: < MyVertex >"));
+
+        let di: DeriveInput = parse_quote! {
+            #[pipeline(vs = "a.vert", fs = "a.frag", vertex_locations = "0")]
+            struct Q;
+        };
+        assert!(expand_graphics_pipeline(di).unwrap_err().to_string().contains("requires vertex"));
+    }
+
+    fn state_hash_of(di: DeriveInput) -> u64 {
+        let out = expand_graphics_pipeline(di).unwrap().to_string();
+        let rest = out.split("STATE_HASH : u64 =").nth(1).expect("STATE_HASH emitted");
+        rest.trim_start().split("u64").next().unwrap().trim().parse().unwrap()
+    }
+
+    #[test]
+    fn state_hash_tracks_pipeline_state() {
+        let opaque: DeriveInput = parse_quote! { #[pipeline(vs = "a.vert", fs = "a.frag", blend = false)] struct Opaque; };
+        let opaque_twin: DeriveInput = parse_quote! { #[pipeline(vs = "a.vert", fs = "a.frag", blend = false)] struct OpaqueTwin; };
+        let blended: DeriveInput = parse_quote! { #[pipeline(vs = "a.vert", fs = "a.frag", blend = true)] struct Blended; };
+        assert_eq!(state_hash_of(opaque.clone()), state_hash_of(opaque_twin));
+        assert_ne!(state_hash_of(opaque), state_hash_of(blended));
+    }
+
+    #[test]
+    fn persistent_output_flag() {
+        let di: DeriveInput = parse_quote! {
+            #[pass(name = "taa")]
+            #[output(name = "history", format = "rgba16f", usage = "color|sampled", persistent = true)]
+            #[output(name = "resolved", format = "rgba8_unorm")]
+            struct Taa;
+        };
+        let out = expand_render_pass(di).unwrap().to_string();
+        assert!(out.contains("name : \"history\""));
+        assert!(out.contains("is_depth : false , persistent : true"));
+        assert!(out.contains("is_depth : false , persistent : false"));
+    }
+
+    #[test]
+    fn present_output_flag() {
+        let di: DeriveInput = parse_quote! {
+            #[pass(name = "post")]
+            #[output(name = "final", format = "bgra8_srgb", size = "swapchain", present = true)]
+            struct Post;
+        };
+        assert!(expand_render_pass(di).unwrap().to_string().contains("persistent : false , present : true"));
+
+        let depth: DeriveInput = parse_quote! { #[pass(name = "z")] #[output(name = "z", format = "d32", usage = "depth", present = true)] struct Z; };
+        assert!(expand_render_pass(depth).unwrap_err().to_string().contains("cannot be presented"));
+        let twice: DeriveInput = parse_quote! {
+            #[pass(name = "p")]
+            #[output(name = "a", format = "rgba8", present = true)]
+            #[output(name = "b", format = "rgba8", present = true)]
+            struct Twice;
+        };
+        assert!(expand_render_pass(twice).unwrap_err().to_string().contains("only one output per pass"));
+    }
+
+    #[test]
+    fn alpha_to_coverage_and_sample_shading() {
+        let di: DeriveInput = parse_quote! { #[pipeline(vs = "a.vert", fs = "a.frag", samples = 4, alpha_to_coverage = true, sample_shading = 0.5)] struct Foliage; };
+        let out = expand_graphics_pipeline(di).unwrap().to_string();
+        assert!(out.contains("MultisampleState { alpha_to_coverage : true , min_sample_shading : Some (0.5f32) }"), "{}", out);
+
+        let plain: DeriveInput = parse_quote! { #[pipeline(vs = "a.vert", fs = "a.frag", samples = 4)] struct Plain; };
+        assert!(expand_graphics_pipeline(plain).unwrap().to_string().contains("multisample : None"));
+    }
+
+    #[test]
+    fn multisample_options_are_validated() {
+        let no_msaa: DeriveInput = parse_quote! { #[pipeline(vs = "a.vert", fs = "a.frag", alpha_to_coverage = true)] struct A; };
+        assert!(expand_graphics_pipeline(no_msaa).unwrap_err().to_string().contains("alpha_to_coverage requires samples > 1"));
+        let single: DeriveInput = parse_quote! { #[pipeline(vs = "a.vert", fs = "a.frag", samples = 1, sample_shading = 0.25)] struct B; };
+        assert!(expand_graphics_pipeline(single).unwrap_err().to_string().contains("sample_shading requires samples > 1"));
+        let range: DeriveInput = parse_quote! { #[pipeline(vs = "a.vert", fs = "a.frag", samples = 4, sample_shading = 1.5)] struct C; };
+        assert!(expand_graphics_pipeline(range).unwrap_err().to_string().contains("sample_shading must be in [0, 1]"));
+    }
+
+    #[test]
+    fn pod_emits_checks_and_byte_views() {
+        let di: DeriveInput = parse_quote! { #[repr(C)] #[derive(Clone, Copy)] struct Light { pos: [f32; 3], id: u32 } };
+        let out = macrokid_core::derive::with_type_spec(di, expand_pod).unwrap().to_string();
+        assert!(out.contains("unsafe impl macrokid_graphics :: pod :: Pod for Light"));
+        assert!(out.contains("assert_pod :: < [f32 ; 3] > ()"));
+        assert!(out.contains("pub fn from_bytes"));
+    }
+
+    #[test]
+    fn pod_rejects_non_pod_fields_and_repr() {
+        let s: DeriveInput = parse_quote! { #[repr(C)] struct Named { name: String } };
+        assert!(macrokid_core::derive::with_type_spec(s, expand_pod).unwrap_err().to_string().contains("Pod field 'name' is `String`"));
+        let r: DeriveInput = parse_quote! { #[repr(C)] struct Borrowed<'a> { data: &'a [u8] } };
+        let err = macrokid_core::derive::validate_then(r, pod_shape, expand_pod).unwrap_err();
+        assert_eq!(err.to_string(), "`Borrowed` must not be generic");
+        let e: DeriveInput = parse_quote! { #[repr(C)] enum Kind { A } };
+        assert!(macrokid_core::derive::validate_then(e, pod_shape, expand_pod).unwrap_err().to_string().contains("must be a struct"));
+        let no_repr: DeriveInput = parse_quote! { struct Plain { x: u32 } };
+        assert!(macrokid_core::derive::with_type_spec(no_repr, expand_pod).unwrap_err().to_string().contains("repr(C)"));
+    }
+}
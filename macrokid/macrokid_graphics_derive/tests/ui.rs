@@ -0,0 +1,5 @@
+//! Compile-fail tests: each `tests/ui/*.rs` must fail with the diagnostic in its `.stderr`.
+#[test]
+fn ui() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,16 @@
+use macrokid_graphics_derive::{GraphicsPipeline, RenderEngine};
+
+#[derive(GraphicsPipeline)]
+#[pipeline(vs = "tri.vert", fs = "tri.frag")]
+struct Triangle;
+
+struct Settings;
+
+#[derive(RenderEngine)]
+#[app(name = "Ui")]
+struct Engine {
+    #[use_pipeline] tri: Triangle,
+    #[use_pipeline] settings: Settings,
+}
+
+fn main() {}
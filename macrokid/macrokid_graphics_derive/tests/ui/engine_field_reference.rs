@@ -0,0 +1,9 @@
+use macrokid_graphics_derive::RenderEngine;
+
+#[derive(RenderEngine)]
+#[app(name = "Ui")]
+struct Engine {
+    #[use_compute] shader: &'static str,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+// macrokid_graphics is built without its `registry` feature here
+use macrokid_graphics_derive::GraphicsPipeline;
+
+#[derive(GraphicsPipeline)]
+#[pipeline(vs = "sky.vert", fs = "sky.frag", register)]
+struct Sky;
+
+fn main() {}
@@ -0,0 +1,7 @@
+use macrokid_graphics_derive::GraphicsPipeline;
+
+#[derive(GraphicsPipeline)]
+#[pipeline(vs = "a.vert", fs = "a.frag", topology = "Quads")]
+struct Quads;
+
+fn main() {}
@@ -19,6 +19,7 @@ pub mod default_shaders {
     // Set/binding conventions expected by generated ResourceBindings:
     // set=0, binding=0: uniform buffer with MVP + light params
     // set=0, binding=1: combined image sampler for albedo
+    // set=0, binding=2: combined image sampler for metallic (B) / roughness (G), PBR only
     pub const VS_POS_UV: &str = r#"#version 450
 layout(location=0) in vec3 a_pos;
 layout(location=1) in vec3 a_normal;
@@ -38,21 +39,20 @@ void main() {
 "#;
 
     // Convenience: build a PipelineDesc for a forward lighting pass from a LightingModel
+    #[allow(deprecated)]
     pub fn forward_pipeline_desc_for<M: crate::LightingModel>(name: &str) -> macrokid_graphics::pipeline::PipelineDesc {
         use macrokid_graphics::pipeline::*;
         let ss = M::shader_sources();
-        let vs_prefixed = format!("inline.vert:{}", ss.vs);
-        let fs_prefixed = format!("inline.frag:{}", ss.fs);
-        let vs_static: &'static str = Box::leak(vs_prefixed.into_boxed_str());
-        let fs_static: &'static str = Box::leak(fs_prefixed.into_boxed_str());
         PipelineDesc {
             name: Box::leak(name.to_string().into_boxed_str()),
-            shaders: ShaderPaths { vs: vs_static, fs: fs_static },
+            shaders: ShaderPaths::inline(ss.vs, ss.fs),
             topology: Topology::TriangleList,
+            primitive_restart: false,
             depth: true,
             raster: Some(RasterState { polygon: PolygonMode::Fill, cull: CullMode::Back, front_face: FrontFace::Cw }),
-            blend: Some(ColorBlendState { enable: false }),
+            blend: Some(BlendPreset::Opaque.state()),
             samples: Some(1),
+            multisample: None,
             depth_stencil: Some(DepthState { test: true, write: true, compare: CompareOp::LessOrEqual }),
             dynamic: Some(DynamicStateDesc { viewport: true, scissor: true }),
             push_constants: None,
@@ -93,4 +93,68 @@ layout(location=0) out vec4 o_color;
         o_color = vec4(ambient + diffuse + specular, 1.0);
     }
 "#;
+
+    pub const FS_BLINN_MIN: &str = r#"#version 450
+layout(location=0) in vec3 v_normal;
+layout(location=1) in vec2 v_uv;
+layout(location=0) out vec4 o_color;
+    layout(set = 0, binding = 0) uniform Scene {
+        mat4 mvp;
+        vec3 light_dir; float _pad0;
+        vec3 light_color; float _pad1;
+    } uScene;
+    layout(set = 0, binding = 1) uniform sampler2D uAlbedo;
+    void main() {
+        vec3 N = normalize(v_normal);
+        vec3 L = normalize(uScene.light_dir);
+        vec3 V = normalize(vec3(0.0, 0.0, 1.0));
+        vec3 H = normalize(L + V);
+        float NdotL = max(dot(N,L), 0.0);
+        vec3 albedo = texture(uAlbedo, v_uv).rgb;
+        vec3 diffuse = albedo * uScene.light_color * NdotL;
+        float spec = pow(max(dot(N, H), 0.0), 64.0);
+        vec3 specular = vec3(0.5) * spec;
+        vec3 ambient = 0.05 * albedo;
+        o_color = vec4(ambient + diffuse + specular, 1.0);
+    }
+"#;
+
+    pub const FS_PBR_MIN: &str = r#"#version 450
+layout(location=0) in vec3 v_normal;
+layout(location=1) in vec2 v_uv;
+layout(location=0) out vec4 o_color;
+    layout(set = 0, binding = 0) uniform Scene {
+        mat4 mvp;
+        vec3 light_dir; float _pad0;
+        vec3 light_color; float _pad1;
+    } uScene;
+    layout(set = 0, binding = 1) uniform sampler2D uAlbedo;
+    layout(set = 0, binding = 2) uniform sampler2D uMetallicRoughness;
+    const float PI = 3.14159265;
+    void main() {
+        vec3 N = normalize(v_normal);
+        vec3 L = normalize(uScene.light_dir);
+        vec3 V = normalize(vec3(0.0, 0.0, 1.0));
+        vec3 H = normalize(L + V);
+        vec3 albedo = texture(uAlbedo, v_uv).rgb;
+        vec2 mr = texture(uMetallicRoughness, v_uv).bg;
+        float metallic = mr.x;
+        float rough = max(mr.y, 0.04);
+        float NdotL = max(dot(N,L), 0.0);
+        float NdotV = max(dot(N,V), 1e-4);
+        float NdotH = max(dot(N,H), 0.0);
+        // GGX distribution, Schlick-GGX geometry, Schlick fresnel
+        float a2 = rough * rough * rough * rough;
+        float d = NdotH * NdotH * (a2 - 1.0) + 1.0;
+        float D = a2 / (PI * d * d);
+        float k = (rough + 1.0) * (rough + 1.0) / 8.0;
+        float G = (NdotL / (NdotL * (1.0 - k) + k)) * (NdotV / (NdotV * (1.0 - k) + k));
+        vec3 F0 = mix(vec3(0.04), albedo, metallic);
+        vec3 F = F0 + (1.0 - F0) * pow(1.0 - max(dot(H,V), 0.0), 5.0);
+        vec3 specular = D * G * F / max(4.0 * NdotL * NdotV, 1e-4);
+        vec3 diffuse = (1.0 - F) * (1.0 - metallic) * albedo / PI;
+        vec3 ambient = 0.03 * albedo;
+        o_color = vec4(ambient + (diffuse + specular) * uScene.light_color * NdotL, 1.0);
+    }
+"#;
 }
@@ -1,10 +1,20 @@
 use proc_macro::TokenStream;
 use quote::quote;
+use macrokid_core::{attr_schema::AttrSchema, attrs::attr_value_spans, diag};
 use syn::{parse_macro_input, DeriveInput};
 
 #[proc_macro_derive(LightingModel, attributes(model))]
 pub fn derive_lighting_model(input: TokenStream) -> TokenStream {
     let ast: DeriveInput = parse_macro_input!(input as DeriveInput);
+    match expand_lighting_model(&ast) {
+        Ok(ts) => ts.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+const MODELS: &[&str] = &["phong", "pbr", "blinn"];
+
+fn expand_lighting_model(ast: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
     let ident = &ast.ident;
     // Parse #[model = "phong" | "pbr" | "blinn"] (defaults to phong)
     let mut model = String::from("phong");
@@ -12,29 +22,37 @@ pub fn derive_lighting_model(input: TokenStream) -> TokenStream {
         if attr.path().is_ident("model") {
             if let Ok(meta) = attr.meta.clone().require_name_value() {
                 if let syn::Expr::Lit(syn::ExprLit { lit: syn::Lit::Str(s), .. }) = &meta.value {
-                    model = s.value();
+                    model = s.value().to_ascii_lowercase();
+                    if !MODELS.contains(&model.as_str()) {
+                        return Err(diag::expected_one_of(s.span(), "model", &s.value(), MODELS));
+                    }
                 }
             }
         }
     }
-    let (vs_src, fs_src) = match model.to_ascii_lowercase().as_str() {
-        // For MVP, all models share the same minimalist shaders
-        _ => (
-            quote! { macrokid_graphics_lighting::default_shaders::VS_POS_UV },
-            quote! { macrokid_graphics_lighting::default_shaders::FS_PHONG_MIN },
-        ),
+    let fs_src = match model.as_str() {
+        "pbr" => quote! { macrokid_graphics_lighting::default_shaders::FS_PBR_MIN },
+        "blinn" => quote! { macrokid_graphics_lighting::default_shaders::FS_BLINN_MIN },
+        _ => quote! { macrokid_graphics_lighting::default_shaders::FS_PHONG_MIN },
     };
+    let vs_src = quote! { macrokid_graphics_lighting::default_shaders::VS_POS_UV };
+    // All models share the scene UBO + albedo; PBR also samples a metallic-roughness map
+    let mut bindings = vec![
+        quote! { BindingDesc { field: "scene", set: 0, binding: 0, kind: ResourceKind::Uniform, stages: Some(BindingStages { vs: true, fs: true, cs: false }), push: false, dynamic: false, count: 1 } },
+        quote! { BindingDesc { field: "albedo", set: 0, binding: 1, kind: ResourceKind::CombinedImageSampler, stages: Some(BindingStages { vs: false, fs: true, cs: false }), push: false, dynamic: false, count: 1 } },
+    ];
+    if model == "pbr" {
+        bindings.push(quote! { BindingDesc { field: "metallic_roughness", set: 0, binding: 2, kind: ResourceKind::CombinedImageSampler, stages: Some(BindingStages { vs: false, fs: true, cs: false }), push: false, dynamic: false, count: 1 } });
+    }
+    let n_bindings = bindings.len();
     let rb_ident = syn::Ident::new(&format!("{}Bindings", ident), ident.span());
-    let gen = quote! {
+    Ok(quote! {
         // Generated ResourceBindings type for this lighting model
         pub struct #rb_ident;
         impl macrokid_graphics::resources::ResourceBindings for #rb_ident {
             fn bindings() -> &'static [macrokid_graphics::resources::BindingDesc] {
                 use macrokid_graphics::resources::{BindingDesc, ResourceKind, BindingStages};
-                static B: [BindingDesc; 2] = [
-                    BindingDesc { field: "scene", set: 0, binding: 0, kind: ResourceKind::Uniform, stages: Some(BindingStages { vs: true, fs: true, cs: false }) },
-                    BindingDesc { field: "albedo", set: 0, binding: 1, kind: ResourceKind::CombinedImageSampler, stages: Some(BindingStages { vs: false, fs: true, cs: false }) },
-                ];
+                static B: [BindingDesc; #n_bindings] = [ #( #bindings ),* ];
                 &B
             }
         }
@@ -49,41 +67,68 @@ pub fn derive_lighting_model(input: TokenStream) -> TokenStream {
             pub fn shader_sources() -> macrokid_graphics_lighting::ShaderSources { <Self as macrokid_graphics_lighting::LightingModel>::shader_sources() }
             pub fn bindings() -> &'static [macrokid_graphics::resources::BindingDesc] { <#rb_ident as macrokid_graphics::resources::ResourceBindings>::bindings() }
         }
-    };
-    gen.into()
+    })
 }
 
 #[proc_macro_derive(LightSetup, attributes(light_setup))]
 pub fn derive_light_setup(input: TokenStream) -> TokenStream {
     let ast: DeriveInput = parse_macro_input!(input as DeriveInput);
-    let ident = &ast.ident;
-    // Parse optional #[light_setup(shadow_size = "WxH")]
-    let mut shadow_w: u32 = 2048;
-    let mut shadow_h: u32 = 2048;
-    for attr in ast.attrs.iter() {
-        if attr.path().is_ident("light_setup") {
-            if let Ok(list) = attr.meta.clone().require_list() {
-                let tokens = list.tokens.to_string();
-                // extremely simple parse: look for shadow_size = "WxH"
-                if let Some(eq) = tokens.find("shadow_size") {
-                    if let Some(start) = tokens[eq..].find('"') {
-                        let a = eq + start + 1;
-                        if let Some(end_rel) = tokens[a..].find('"') {
-                            let b = a + end_rel;
-                            let val = &tokens[a..b];
-                            if let Some(xpos) = val.find('x') { if let (Ok(w), Ok(h)) = (val[..xpos].parse::<u32>(), val[xpos+1..].parse::<u32>()) { shadow_w = w; shadow_h = h; } }
-                        }
-                    }
-                }
+    match expand_light_setup(&ast) {
+        Ok(ts) => ts.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// Shadow map extent from `#[light_setup(shadow_size = "WxH")]` or `shadow_w`/`shadow_h`
+/// (each defaulting to 2048), plus warnings for non-power-of-two sides.
+fn parse_shadow_size(attrs: &[syn::Attribute]) -> syn::Result<(u32, u32, proc_macro2::TokenStream)> {
+    let parsed = AttrSchema::new("light_setup")
+        .opt_str("shadow_size")
+        .opt_int("shadow_w")
+        .opt_int("shadow_h")
+        .parse(attrs)?;
+    let span_of = |key: &str| {
+        attrs.iter()
+            .filter(|a| a.path().is_ident("light_setup"))
+            .find_map(|a| attr_value_spans(a, key))
+            .unwrap_or_else(proc_macro2::Span::call_site)
+    };
+    let side = |key: &str, v: i64| -> syn::Result<u32> {
+        u32::try_from(v).ok().filter(|&v| v > 0)
+            .ok_or_else(|| diag::err_at_span(span_of(key), &format!("{} must be a positive integer, got {}", key, v)))
+    };
+    let (w, h) = match parsed.get_str("shadow_size") {
+        Some(size) => {
+            if parsed.get_int("shadow_w").is_some() || parsed.get_int("shadow_h").is_some() {
+                return Err(diag::err_at_span(span_of("shadow_size"), "shadow_size cannot be combined with shadow_w/shadow_h"));
             }
+            let dims = size.split_once(['x', 'X'])
+                .and_then(|(w, h)| Some((w.trim().parse::<u32>().ok()?, h.trim().parse::<u32>().ok()?)))
+                .filter(|&(w, h)| w > 0 && h > 0);
+            dims.ok_or_else(|| diag::err_at_span(span_of("shadow_size"), &format!("invalid shadow_size '{}': expected \"WxH\", e.g. \"2048x2048\"", size)))?
         }
+        None => (
+            parsed.get_int("shadow_w").map(|v| side("shadow_w", v)).transpose()?.unwrap_or(2048),
+            parsed.get_int("shadow_h").map(|v| side("shadow_h", v)).transpose()?.unwrap_or(2048),
+        ),
+    };
+    let mut warnings = proc_macro2::TokenStream::new();
+    if !w.is_power_of_two() || !h.is_power_of_two() {
+        let key = if parsed.get_str("shadow_size").is_some() { "shadow_size" } else if !w.is_power_of_two() { "shadow_w" } else { "shadow_h" };
+        warnings.extend(diag::warn_at_span(span_of(key), &format!("shadow map size {}x{} is not a power of two", w, h)));
     }
+    Ok((w, h, warnings))
+}
+
+fn expand_light_setup(ast: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let ident = &ast.ident;
+    let (shadow_w, shadow_h, warnings) = parse_shadow_size(&ast.attrs)?;
 
     // Generate a SceneBindings type (set=1, binding=0 uniform)
     let scene_bind_ident = syn::Ident::new(&format!("{}SceneBindings", ident), ident.span());
     let mod_ident = syn::Ident::new(&format!("__mk_ls_{}_shadow", ident), ident.span());
-    let name_str = "shadow_depth".to_string();
-    let gen = quote! {
+    Ok(quote! {
+        #warnings
         impl macrokid_graphics_lighting::LightSetup for #ident {}
 
         pub struct #scene_bind_ident;
@@ -91,7 +136,7 @@ pub fn derive_light_setup(input: TokenStream) -> TokenStream {
             fn bindings() -> &'static [macrokid_graphics::resources::BindingDesc] {
                 use macrokid_graphics::resources::{BindingDesc, ResourceKind, BindingStages};
                 static B: [BindingDesc; 1] = [
-                    BindingDesc { field: "scene_lights", set: 1, binding: 0, kind: ResourceKind::Uniform, stages: Some(BindingStages { vs: true, fs: true, cs: false }) },
+                    BindingDesc { field: "scene_lights", set: 1, binding: 0, kind: ResourceKind::Uniform, stages: Some(BindingStages { vs: true, fs: true, cs: false }), push: false, dynamic: false, count: 1 },
                 ];
                 &B
             }
@@ -100,7 +145,7 @@ pub fn derive_light_setup(input: TokenStream) -> TokenStream {
         #[allow(non_snake_case)]
         mod #mod_ident {
             pub static __OUTS: &[macrokid_graphics::render_graph::OutputDesc] = &[
-                macrokid_graphics::render_graph::OutputDesc { name: "shadow_depth", format: "D32_SFLOAT", size: macrokid_graphics::render_graph::SizeSpec::Abs { width: #shadow_w, height: #shadow_h }, usage: macrokid_graphics::render_graph::UsageMask::DEPTH | macrokid_graphics::render_graph::UsageMask::SAMPLED, samples: 1, is_depth: true },
+                macrokid_graphics::render_graph::OutputDesc { name: "shadow_depth", format: "D32_SFLOAT", size: macrokid_graphics::render_graph::SizeSpec::Abs { width: #shadow_w, height: #shadow_h }, usage: macrokid_graphics::render_graph::UsageMask::DEPTH.union(macrokid_graphics::render_graph::UsageMask::SAMPLED), samples: 1, is_depth: true, persistent: false, present: false },
             ];
             pub static DESC: macrokid_graphics::render_graph::PassDesc = macrokid_graphics::render_graph::PassDesc {
                 name: "shadow_depth",
@@ -115,6 +160,67 @@ pub fn derive_light_setup(input: TokenStream) -> TokenStream {
             pub fn shadow_pass() -> &'static macrokid_graphics::render_graph::PassDesc { &#mod_ident::DESC }
             pub fn scene_bindings() -> &'static [macrokid_graphics::resources::BindingDesc] { <#scene_bind_ident as macrokid_graphics::resources::ResourceBindings>::bindings() }
         }
-    };
-    gen.into()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn unknown_model_is_rejected() {
+        let di: DeriveInput = parse_quote! {
+            #[model = "pbrr"]
+            struct M;
+        };
+        let err = expand_lighting_model(&di).unwrap_err().to_string();
+        assert_eq!(err, "unknown value 'pbrr' for `model`: expected one of phong|pbr|blinn (did you mean 'pbr'?)");
+    }
+
+    #[test]
+    fn model_name_is_case_insensitive() {
+        let di: DeriveInput = parse_quote! {
+            #[model = "Blinn"]
+            struct M;
+        };
+        let out = expand_lighting_model(&di).unwrap().to_string();
+        assert!(out.contains("FS_BLINN_MIN"), "{}", out);
+    }
+
+    fn shadow(di: DeriveInput) -> syn::Result<(u32, u32, bool)> {
+        parse_shadow_size(&di.attrs).map(|(w, h, warn)| (w, h, !warn.is_empty()))
+    }
+
+    #[test]
+    fn shadow_size_tolerates_whitespace() {
+        let compact: DeriveInput = parse_quote! { #[light_setup(shadow_size = "1024x512")] struct L; };
+        let spaced: DeriveInput = parse_quote! { #[light_setup( shadow_size="1024 x 512" )] struct L; };
+        let padded: DeriveInput = parse_quote! { #[light_setup(shadow_size = " 1024X512 ")] struct L; };
+        for di in [compact, spaced, padded] {
+            assert_eq!(shadow(di).unwrap(), (1024, 512, false));
+        }
+        let none: DeriveInput = parse_quote! { struct L; };
+        assert_eq!(shadow(none).unwrap(), (2048, 2048, false));
+    }
+
+    #[test]
+    fn shadow_w_and_h_coexist() {
+        let di: DeriveInput = parse_quote! { #[light_setup(shadow_h = 256, shadow_w = 4096)] struct L; };
+        assert_eq!(shadow(di).unwrap(), (4096, 256, false));
+        let only_w: DeriveInput = parse_quote! { #[light_setup(shadow_w = 512)] struct L; };
+        assert_eq!(shadow(only_w).unwrap(), (512, 2048, false));
+        let both: DeriveInput = parse_quote! { #[light_setup(shadow_size = "512x512", shadow_w = 512)] struct L; };
+        assert_eq!(shadow(both).unwrap_err().to_string(), "shadow_size cannot be combined with shadow_w/shadow_h");
+    }
+
+    #[test]
+    fn shadow_size_rejects_malformed_and_warns_on_npot() {
+        let bad: DeriveInput = parse_quote! { #[light_setup(shadow_size = "1024*1024")] struct L; };
+        assert!(shadow(bad).unwrap_err().to_string().starts_with("invalid shadow_size '1024*1024'"));
+        let zero: DeriveInput = parse_quote! { #[light_setup(shadow_w = 0)] struct L; };
+        assert!(shadow(zero).is_err());
+        let npot: DeriveInput = parse_quote! { #[light_setup(shadow_size = "1000x1024")] struct L; };
+        assert_eq!(shadow(npot).unwrap(), (1000, 1024, true));
+    }
 }
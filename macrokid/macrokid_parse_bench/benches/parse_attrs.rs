@@ -2,7 +2,7 @@ use criterion::{black_box, criterion_group, criterion_main, Criterion};
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::{Attribute, DeriveInput};
-use macrokid_parse_bench::parse_resource_attr;
+use macrokid_parse_bench::{parse_resource_attr, parse_resource_attr_or_syn};
 
 // Sample input: a struct with typical macrokid attributes
 fn sample_input() -> TokenStream {
@@ -99,11 +99,30 @@ fn bench_custom_parse(c: &mut Criterion) {
     });
 }
 
+// Fast path with syn fallback: the common case should cost about the same as `custom_parse`,
+// and the fallback case shows what an uncovered form costs
+fn bench_custom_parse_or_syn(c: &mut Criterion) {
+    let common = quote! {
+        #[uniform(set = 0, binding = 1, stages = "vs|fs")]
+    };
+    let fallback = quote! {
+        #[uniform(set = 0, binding = 1, stages(vs, fs))]
+    };
+
+    c.bench_function("custom_parse_or_syn", |b| {
+        b.iter(|| black_box(parse_resource_attr_or_syn(black_box(common.clone())).unwrap()))
+    });
+    c.bench_function("custom_parse_or_syn_fallback", |b| {
+        b.iter(|| black_box(parse_resource_attr_or_syn(black_box(fallback.clone())).unwrap()))
+    });
+}
+
 criterion_group!(
     benches,
     bench_syn_full_parse,
     bench_syn_attrs_only,
     bench_syn_parse_nested_meta,
-    bench_custom_parse
+    bench_custom_parse,
+    bench_custom_parse_or_syn
 );
 criterion_main!(benches);
@@ -21,6 +21,37 @@ pub enum ResourceAttr {
     Combined { set: u32, binding: u32, stages: Option<String> },
 }
 
+/// Why a resource attribute failed to parse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParamError {
+    /// A token other than the expected one (the message names what was expected).
+    Syntax(String),
+    /// Attribute name outside `uniform`/`texture`/`sampler`/`combined`.
+    UnknownAttr(String),
+    UnknownParam(String),
+    /// A required key (`set` or `binding`) was not given.
+    Missing(&'static str),
+    /// `key` was given something other than a `u32` literal.
+    InvalidInt { key: &'static str, found: String },
+    /// `stages` was given something other than a string literal.
+    InvalidString(String),
+}
+
+impl std::fmt::Display for ParamError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParamError::Syntax(expected) => write!(f, "Expected {}", expected),
+            ParamError::UnknownAttr(name) => write!(f, "Unknown attribute: {}", name),
+            ParamError::UnknownParam(key) => write!(f, "Unknown parameter: {}", key),
+            ParamError::Missing(key) => write!(f, "Missing '{}' parameter", key),
+            ParamError::InvalidInt { key, found } => write!(f, "Invalid integer for '{}': {}", key, found),
+            ParamError::InvalidString(found) => write!(f, "Expected string literal, got: {}", found),
+        }
+    }
+}
+
+impl std::error::Error for ParamError {}
+
 /// Fast parser for macrokid resource attributes
 ///
 /// Parses attributes like:
@@ -33,51 +64,115 @@ pub enum ResourceAttr {
 /// 2. No need to build a full AST
 /// 3. Direct pattern matching on token trees
 /// 4. Fail-fast on unexpected patterns
-pub fn parse_resource_attr(tokens: TokenStream) -> Result<ResourceAttr, String> {
+pub fn parse_resource_attr(tokens: TokenStream) -> Result<ResourceAttr, ParamError> {
+    parse_attr_tokens(tokens, None)
+}
+
+/// Relaxed [`parse_resource_attr`]: a missing `set` defaults to 0 (`binding` is still required).
+pub fn parse_resource_attr_with_defaults(tokens: TokenStream) -> Result<ResourceAttr, ParamError> {
+    parse_attr_tokens(tokens, Some(0))
+}
+
+/// Fast path with a syn fallback: tries [`parse_resource_attr`] first and, if it rejects the input,
+/// reparses with syn. The fallback accepts forms the fast path does not, such as suffixed or hex
+/// integers (`binding = 1u32`) and grouped stages (`stages(vs, fs)`, joined as `"vs|fs"`), and
+/// produces the same [`ResourceAttr`]. Errors come from the syn parser.
+pub fn parse_resource_attr_or_syn(tokens: TokenStream) -> syn::Result<ResourceAttr> {
+    match parse_resource_attr(tokens.clone()) {
+        Ok(attr) => Ok(attr),
+        Err(_) => parse_resource_attr_syn(tokens),
+    }
+}
+
+fn parse_resource_attr_syn(tokens: TokenStream) -> syn::Result<ResourceAttr> {
+    let attrs = syn::parse::Parser::parse2(syn::Attribute::parse_outer, tokens)?;
+    let [attr] = attrs.as_slice() else {
+        return Err(syn::Error::new(proc_macro2::Span::call_site(), "Expected exactly one attribute"));
+    };
+    let kind = attr.path().get_ident().map(|i| i.to_string()).unwrap_or_default();
+    if !matches!(kind.as_str(), "uniform" | "texture" | "sampler" | "combined") {
+        return Err(syn::Error::new_spanned(attr.path(), format!("Unknown attribute: {}", kind)));
+    }
+
+    let (mut set, mut binding, mut stages) = (None, None, None);
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("set") {
+            set = Some(meta.value()?.parse::<syn::LitInt>()?.base10_parse::<u32>()?);
+        } else if meta.path.is_ident("binding") {
+            binding = Some(meta.value()?.parse::<syn::LitInt>()?.base10_parse::<u32>()?);
+        } else if meta.path.is_ident("stages") {
+            if meta.input.peek(syn::Token![=]) {
+                stages = Some(meta.value()?.parse::<syn::LitStr>()?.value());
+            } else {
+                let mut list = Vec::new();
+                meta.parse_nested_meta(|stage| {
+                    list.push(stage.path.get_ident().map(|i| i.to_string()).ok_or_else(|| stage.error("Expected stage name"))?);
+                    Ok(())
+                })?;
+                stages = Some(list.join("|"));
+            }
+        } else {
+            let key = meta.path.get_ident().map(|i| i.to_string()).unwrap_or_default();
+            return Err(meta.error(format!("Unknown parameter: {}", key)));
+        }
+        Ok(())
+    })?;
+    let set = set.ok_or_else(|| syn::Error::new_spanned(attr, "Missing 'set' parameter"))?;
+    let binding = binding.ok_or_else(|| syn::Error::new_spanned(attr, "Missing 'binding' parameter"))?;
+
+    Ok(match kind.as_str() {
+        "uniform" => ResourceAttr::Uniform(UniformAttr { set, binding, stages }),
+        "texture" => ResourceAttr::Texture { set, binding, stages },
+        "sampler" => ResourceAttr::Sampler { set, binding, stages },
+        _ => ResourceAttr::Combined { set, binding, stages },
+    })
+}
+
+fn parse_attr_tokens(tokens: TokenStream, default_set: Option<u32>) -> Result<ResourceAttr, ParamError> {
     let mut iter = tokens.into_iter().peekable();
 
     // Skip leading '#'
     match iter.next() {
         Some(TokenTree::Punct(p)) if p.as_char() == '#' => {}
-        _ => return Err("Expected '#'".to_string()),
+        _ => return Err(ParamError::Syntax("'#'".to_string())),
     }
 
     // Parse the attribute group [...]
     let group = match iter.next() {
         Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Bracket => g,
-        _ => return Err("Expected '[...]'".to_string()),
+        _ => return Err(ParamError::Syntax("'[...]'".to_string())),
     };
 
-    parse_attr_content(group.stream())
+    parse_attr_content(group.stream(), default_set)
 }
 
-fn parse_attr_content(tokens: TokenStream) -> Result<ResourceAttr, String> {
+fn parse_attr_content(tokens: TokenStream, default_set: Option<u32>) -> Result<ResourceAttr, ParamError> {
     let mut iter = tokens.into_iter().peekable();
 
     // Get attribute name (uniform, texture, sampler, combined)
     let attr_name = match iter.next() {
         Some(TokenTree::Ident(ident)) => ident.to_string(),
-        _ => return Err("Expected attribute name".to_string()),
+        _ => return Err(ParamError::Syntax("attribute name".to_string())),
     };
 
     // Parse the parameter group (...)
     let params = match iter.next() {
         Some(TokenTree::Group(g)) if g.delimiter() == Delimiter::Parenthesis => g,
-        _ => return Err("Expected '(...)'".to_string()),
+        _ => return Err(ParamError::Syntax("'(...)'".to_string())),
     };
 
-    let (set, binding, stages) = parse_params(params.stream())?;
+    let (set, binding, stages) = parse_params(params.stream(), default_set)?;
 
     match attr_name.as_str() {
         "uniform" => Ok(ResourceAttr::Uniform(UniformAttr { set, binding, stages })),
         "texture" => Ok(ResourceAttr::Texture { set, binding, stages }),
         "sampler" => Ok(ResourceAttr::Sampler { set, binding, stages }),
         "combined" => Ok(ResourceAttr::Combined { set, binding, stages }),
-        _ => Err(format!("Unknown attribute: {}", attr_name)),
+        _ => Err(ParamError::UnknownAttr(attr_name)),
     }
 }
 
-fn parse_params(tokens: TokenStream) -> Result<(u32, u32, Option<String>), String> {
+fn parse_params(tokens: TokenStream, default_set: Option<u32>) -> Result<(u32, u32, Option<String>), ParamError> {
     let mut iter = tokens.into_iter().peekable();
     let mut set = None;
     let mut binding = None;
@@ -88,27 +183,27 @@ fn parse_params(tokens: TokenStream) -> Result<(u32, u32, Option<String>), Strin
         let key = match iter.next() {
             Some(TokenTree::Ident(ident)) => ident.to_string(),
             None => break,
-            _ => return Err("Expected parameter name".to_string()),
+            _ => return Err(ParamError::Syntax("parameter name".to_string())),
         };
 
         // Expect '='
         match iter.next() {
             Some(TokenTree::Punct(p)) if p.as_char() == '=' => {}
-            _ => return Err("Expected '='".to_string()),
+            _ => return Err(ParamError::Syntax("'='".to_string())),
         }
 
         // Parse value based on key
         match key.as_str() {
             "set" => {
-                set = Some(parse_int(&mut iter)?);
+                set = Some(parse_int(&mut iter, "set")?);
             }
             "binding" => {
-                binding = Some(parse_int(&mut iter)?);
+                binding = Some(parse_int(&mut iter, "binding")?);
             }
             "stages" => {
                 stages = Some(parse_string(&mut iter)?);
             }
-            _ => return Err(format!("Unknown parameter: {}", key)),
+            _ => return Err(ParamError::UnknownParam(key)),
         }
 
         // Check for comma or end
@@ -121,26 +216,26 @@ fn parse_params(tokens: TokenStream) -> Result<(u32, u32, Option<String>), Strin
         }
     }
 
-    let set = set.ok_or("Missing 'set' parameter")?;
-    let binding = binding.ok_or("Missing 'binding' parameter")?;
+    let set = set.or(default_set).ok_or(ParamError::Missing("set"))?;
+    let binding = binding.ok_or(ParamError::Missing("binding"))?;
 
     Ok((set, binding, stages))
 }
 
-fn parse_int<I>(iter: &mut Peekable<I>) -> Result<u32, String>
+fn parse_int<I>(iter: &mut Peekable<I>, key: &'static str) -> Result<u32, ParamError>
 where
     I: Iterator<Item = TokenTree>,
 {
     match iter.next() {
         Some(TokenTree::Literal(lit)) => {
             let s = lit.to_string();
-            s.parse().map_err(|_| format!("Invalid integer: {}", s))
+            s.parse().map_err(|_| ParamError::InvalidInt { key, found: s })
         }
-        _ => Err("Expected integer literal".to_string()),
+        other => Err(ParamError::InvalidInt { key, found: other.map_or_else(|| "nothing".to_string(), |t| t.to_string()) }),
     }
 }
 
-fn parse_string<I>(iter: &mut Peekable<I>) -> Result<String, String>
+fn parse_string<I>(iter: &mut Peekable<I>) -> Result<String, ParamError>
 where
     I: Iterator<Item = TokenTree>,
 {
@@ -151,10 +246,10 @@ where
             if s.starts_with('"') && s.ends_with('"') {
                 Ok(s[1..s.len() - 1].to_string())
             } else {
-                Err(format!("Expected string literal, got: {}", s))
+                Err(ParamError::InvalidString(s))
             }
         }
-        _ => Err("Expected string literal".to_string()),
+        other => Err(ParamError::InvalidString(other.map_or_else(|| "nothing".to_string(), |t| t.to_string()))),
     }
 }
 
@@ -196,4 +291,44 @@ mod tests {
             _ => panic!("Expected Texture variant"),
         }
     }
+
+    #[test]
+    fn missing_set_defaults_in_relaxed_mode() {
+        let tokens = quote! { #[combined(binding = 3, stages = "fs")] };
+        assert_eq!(parse_resource_attr(tokens.clone()), Err(ParamError::Missing("set")));
+        assert_eq!(
+            parse_resource_attr_with_defaults(tokens).unwrap(),
+            ResourceAttr::Combined { set: 0, binding: 3, stages: Some("fs".to_string()) }
+        );
+        // An explicit set still wins, and binding stays required
+        let explicit = parse_resource_attr_with_defaults(quote! { #[sampler(set = 2, binding = 0)] }).unwrap();
+        assert_eq!(explicit, ResourceAttr::Sampler { set: 2, binding: 0, stages: None });
+        assert_eq!(parse_resource_attr_with_defaults(quote! { #[texture(set = 1)] }), Err(ParamError::Missing("binding")));
+    }
+
+    #[test]
+    fn non_integer_binding_is_typed_error() {
+        let err = parse_resource_attr(quote! { #[uniform(set = 0, binding = "one")] }).unwrap_err();
+        assert_eq!(err, ParamError::InvalidInt { key: "binding", found: "\"one\"".to_string() });
+        assert_eq!(err.to_string(), "Invalid integer for 'binding': \"one\"");
+        let err = parse_resource_attr(quote! { #[uniform(set = 0, binding = one)] }).unwrap_err();
+        assert!(matches!(err, ParamError::InvalidInt { key: "binding", .. }));
+    }
+
+    #[test]
+    fn syn_fallback_matches_fast_path() {
+        let fast = parse_resource_attr(quote! { #[uniform(set = 0, binding = 1, stages = "vs|fs")] }).unwrap();
+
+        let grouped = quote! { #[uniform(set = 0u32, binding = 0x1, stages(vs, fs),)] };
+        assert!(parse_resource_attr(grouped.clone()).is_err(), "fast path rejects suffixed ints and grouped stages");
+        assert_eq!(parse_resource_attr_or_syn(grouped).unwrap(), fast);
+
+        // Inputs the fast path handles come out the same either way
+        let plain = quote! { #[combined(set = 1, binding = 2, stages = "fs")] };
+        assert_eq!(parse_resource_attr_syn(plain.clone()).unwrap(), parse_resource_attr(plain.clone()).unwrap());
+        assert_eq!(parse_resource_attr_or_syn(plain.clone()).unwrap(), parse_resource_attr(plain).unwrap());
+
+        let err = parse_resource_attr_or_syn(quote! { #[uniform(set = 0, bindng = 1)] }).unwrap_err();
+        assert_eq!(err.to_string(), "Unknown parameter: bindng");
+    }
 }
@@ -3,8 +3,30 @@
 //! Minimal `#[derive(Job)]` prototype:
 //! - Expects an inherent method `fn run(self)` on the target type by default.
 //! - Optional attribute: `#[job(method = "run_impl")]` to call a different method name.
+//! - `#[job(fallible)]`: the method returns `Result<(), E>` with `E: Error + Send + 'static`;
+//!   `JobRun::run_checked` returns the boxed error and `run` reports it through
+//!   `threads::report_job_error` (`log::warn!` with macrokid_core's `log` feature, else stderr).
 //! - Implements `macrokid_core::threads::JobRun` for the type, enabling `SpawnExt`.
 //!
+//! `#[derive(Schedule)]` fields are `#[stage(...)]` tuples of systems, or `#[stage(sub)]`
+//! fields holding another Schedule-deriving type that runs as one stage.
+//! `#[stage(dynamic)]` fields hold `Vec<Box<dyn macrokid_core::threads::System>>` filled at
+//! runtime. Their conflict layers are computed on every run from the `System` vtable, where tuple
+//! stages index `ResourceAccess` arrays fixed at compile time, and `explain()` can only show
+//! them as one `"<dynamic>"` layer. `#[stage(max_threads = N)]`
+//! submits each conflict-free layer of a stage in sub-batches of at most `N` jobs.
+//! Layers go through `Scheduler::submit_prioritized`: each job's priority is the stage's
+//! `#[stage(priority = N)]` plus its system's `#[system(priority = N)]` (both default to 0), so
+//! latency-critical systems start first on a priority-aware pool.
+//! `#[derive(System)]` treats `#[reads]`/`#[writes]` types whose last path segment is
+//! `GpuBuffer` or `GpuImage` as GPU resources. `#[system(gpu_types(VertexBuf, GpuImage))]`
+//! replaces that set of idents; matching is always by last segment, so list type aliases by
+//! their alias name.
+//!
+//! `#[schedule(profile)]` on the struct times each stage of `run` (or of a `MergedSchedule` run)
+//! into the struct's `StageTimings` field and exposes the durations of the instance's most recent
+//! run through `last_run_timings(&self)`.
+//!
 //! Example:
 //! ```ignore
 //! use std::sync::Arc;
@@ -29,14 +51,20 @@ pub fn derive_job(input: TokenStream) -> TokenStream {
     let di: DeriveInput = syn::parse(input).expect("parse derive input");
     let ident = di.ident.clone();
 
-    // Parse optional #[job(method = "...")]
+    // Parse optional #[job(method = "...", fallible)]
     let mut method_name: Option<syn::Ident> = None;
+    let mut fallible = false;
     for a in &di.attrs {
         if a.path().is_ident("job") {
             let parsed = a.parse_args_with(|stream: syn::parse::ParseStream| {
                 let mut out: Option<syn::Ident> = None;
                 while !stream.is_empty() {
                     let key: syn::Ident = stream.parse()?;
+                    if key == "fallible" {
+                        fallible = true;
+                        let _ = stream.parse::<syn::Token![,]>();
+                        continue;
+                    }
                     stream.parse::<syn::Token![=]>()?;
                     match key.to_string().as_str() {
                         "method" => {
@@ -58,20 +86,53 @@ pub fn derive_job(input: TokenStream) -> TokenStream {
     }
     let method_ident = method_name.unwrap_or_else(|| syn::Ident::new("run", Span::call_site()));
 
-    let expanded = quote! {
-        impl macrokid_core::threads::JobRun for #ident {
-            fn run(self) { self.#method_ident() }
+    let expanded = if fallible {
+        let name = ident.to_string();
+        quote! {
+            impl macrokid_core::threads::JobRun for #ident {
+                fn run(self) {
+                    if let ::std::result::Result::Err(e) = macrokid_core::threads::JobRun::run_checked(self) {
+                        macrokid_core::threads::report_job_error(#name, &*e);
+                    }
+                }
+                fn run_checked(self) -> ::std::result::Result<(), ::std::boxed::Box<dyn ::std::error::Error + ::std::marker::Send>> {
+                    self.#method_ident().map_err(|e| ::std::boxed::Box::new(e) as ::std::boxed::Box<dyn ::std::error::Error + ::std::marker::Send>)
+                }
+            }
+        }
+    } else {
+        quote! {
+            impl macrokid_core::threads::JobRun for #ident {
+                fn run(self) { self.#method_ident() }
+            }
         }
     };
     expanded.into()
 }
 
-#[proc_macro_derive(System, attributes(reads, writes))]
+/// A type may appear in `#[reads]` or `#[writes]` but not both; write access implies read.
+///
+/// ```compile_fail
+/// use macrokid_threads_derive::System;
+/// struct Transform;
+/// #[derive(System)]
+/// #[reads(Transform)]
+/// #[writes(Transform)]
+/// struct Move;
+/// ```
+#[proc_macro_derive(System, attributes(reads, writes, system))]
 pub fn derive_system(input: TokenStream) -> TokenStream {
     let di: DeriveInput = match syn::parse(input) {
         Ok(v) => v,
         Err(e) => return e.to_compile_error().into(),
     };
+    match expand_system(di) {
+        Ok(ts) => ts.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand_system(di: DeriveInput) -> syn::Result<TokenStream2> {
     let ident = di.ident.clone();
 
     // Collect types from #[reads(T, U,...)] and #[writes(X,...)]
@@ -88,19 +149,55 @@ pub fn derive_system(input: TokenStream) -> TokenStream {
         Ok(out)
     }
 
-    // Check if a type is a GPU resource (GpuBuffer<T> or GpuImage<T>)
-    fn is_gpu_type(ty: &syn::Type) -> bool {
+    // Parse optional #[system(gpu_types(A, B, ...), priority = N)]; gpu_types defaults to GpuBuffer/GpuImage
+    let mut gpu_types: Option<Vec<syn::Ident>> = None;
+    let mut priority: Option<i32> = None;
+    for a in &di.attrs {
+        if a.path().is_ident("system") {
+            a.parse_nested_meta(|meta| {
+                if meta.path.is_ident("priority") {
+                    priority = Some(parse_priority(meta.value()?)?);
+                    Ok(())
+                } else if meta.path.is_ident("gpu_types") {
+                    let content;
+                    syn::parenthesized!(content in meta.input);
+                    let list = content.parse_terminated(<syn::Ident as syn::parse::Parse>::parse, syn::Token![,])?;
+                    gpu_types.get_or_insert_with(Vec::new).extend(list);
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown key in #[system(...)]"))
+                }
+            })?;
+        }
+    }
+    let gpu_types: Vec<String> = match gpu_types {
+        Some(ids) => ids.iter().map(|i| i.to_string()).collect(),
+        None => vec!["GpuBuffer".to_string(), "GpuImage".to_string()],
+    };
+
+    // Check if a type is a GPU resource by its last path segment
+    let is_gpu_type = |ty: &syn::Type| -> bool {
         if let syn::Type::Path(type_path) = ty {
             if let Some(segment) = type_path.path.segments.last() {
-                let ident_str = segment.ident.to_string();
-                return ident_str == "GpuBuffer" || ident_str == "GpuImage";
+                return gpu_types.iter().any(|g| segment.ident == g);
             }
         }
         false
-    }
+    };
 
-    let reads = match types_from_attr(&di, "reads") { Ok(v) => v, Err(e) => return e.to_compile_error().into() };
-    let writes = match types_from_attr(&di, "writes") { Ok(v) => v, Err(e) => return e.to_compile_error().into() };
+    let reads = types_from_attr(&di, "reads")?;
+    let writes = types_from_attr(&di, "writes")?;
+
+    // Write access already implies read access; listing a type twice skews conflict batching
+    for r in &reads {
+        let key = quote!(#r).to_string();
+        if writes.iter().any(|w| quote!(#w).to_string() == key) {
+            return Err(syn::Error::new_spanned(r, format!(
+                "`{}` is listed in both #[reads] and #[writes]; keep it only in #[writes] (write access implies read)",
+                key.replace(' ', "")
+            )));
+        }
+    }
 
     // Separate CPU and GPU resources
     let (cpu_reads, gpu_reads): (Vec<_>, Vec<_>) = reads.iter().partition(|t| !is_gpu_type(t));
@@ -125,6 +222,8 @@ pub fn derive_system(input: TokenStream) -> TokenStream {
 
     let has_gpu_resources = !gpu_reads.is_empty() || !gpu_writes.is_empty();
 
+    let priority_fn = priority.map(|p| quote! { fn priority() -> i32 { #p } });
+
     // Generate CPU ResourceAccess impl
     let resource_access_impl = quote! {
         impl macrokid_core::threads::ResourceAccess for #ident {
@@ -136,6 +235,7 @@ pub fn derive_system(input: TokenStream) -> TokenStream {
                 static WRITES: ::std::sync::OnceLock<::std::vec::Vec<::std::any::TypeId>> = ::std::sync::OnceLock::new();
                 WRITES.get_or_init(|| vec![ #( #writes_ids ),* ]).as_slice()
             }
+            #priority_fn
         }
     };
 
@@ -152,27 +252,52 @@ pub fn derive_system(input: TokenStream) -> TokenStream {
                     static GPU_WRITES: ::std::sync::OnceLock<::std::vec::Vec<macrokid_graphics::resources::GpuResourceMeta>> = ::std::sync::OnceLock::new();
                     GPU_WRITES.get_or_init(|| vec![ #( #gpu_writes_meta ),* ]).as_slice()
                 }
+
+                fn descriptor_bindings() -> &'static [macrokid_graphics::resources::BindingDesc] {
+                    static BINDINGS: ::std::sync::OnceLock<::std::vec::Vec<macrokid_graphics::resources::BindingDesc>> = ::std::sync::OnceLock::new();
+                    BINDINGS.get_or_init(|| macrokid_graphics::resources::gpu_access_bindings(
+                        <Self as macrokid_graphics::resources::GpuResourceAccess>::gpu_reads(),
+                        <Self as macrokid_graphics::resources::GpuResourceAccess>::gpu_writes(),
+                    )).as_slice()
+                }
             }
         }
     } else {
         quote! {}
     };
 
-    let expanded = quote! {
+    Ok(quote! {
         #resource_access_impl
         #gpu_resource_access_impl
-    };
+    })
+}
 
-    expanded.into()
+/// Parse a possibly negative integer priority, e.g. `10` or `-5`.
+fn parse_priority(input: syn::parse::ParseStream) -> syn::Result<i32> {
+    let neg = input.parse::<Option<syn::Token![-]>>()?.is_some();
+    let lit: syn::LitInt = input.parse()?;
+    let v = lit.base10_parse::<i32>()?;
+    Ok(if neg { -v } else { v })
 }
 
-#[proc_macro_derive(Schedule, attributes(stage))]
+#[proc_macro_derive(Schedule, attributes(stage, schedule))]
 pub fn derive_schedule(input: TokenStream) -> TokenStream {
     let di: DeriveInput = match syn::parse(input) {
         Ok(v) => v,
         Err(e) => return e.to_compile_error().into(),
     };
     let ident = di.ident.clone();
+
+    // Parse optional type-level #[schedule(profile)]
+    let mut profile = false;
+    for a in &di.attrs {
+        if a.path().is_ident("schedule") {
+            let parsed = a.parse_nested_meta(|meta| {
+                if meta.path.is_ident("profile") { profile = true; Ok(()) } else { Err(meta.error("unknown key in #[schedule(...)]")) }
+            });
+            if let Err(e) = parsed { return e.to_compile_error().into(); }
+        }
+    }
     let data = match di.data { syn::Data::Struct(s) => s, _ => {
         return syn::Error::new(Span::call_site(), "Schedule derive expects a struct").to_compile_error().into()
     } };
@@ -181,14 +306,39 @@ pub fn derive_schedule(input: TokenStream) -> TokenStream {
     } };
 
     // Collect stage metadata: name, after, and job terms for each field.
+    // `sub` holds the field access for a `#[stage(sub)]` field, whose type is itself a Schedule
+    // and is then the only entry in `tys`; `dynamic` holds it for a `#[stage(dynamic)]` field.
     struct StageMeta {
         name: String,
         after: Vec<String>,
         before: Vec<String>,
         jobs: Vec<TokenStream2>,
         tys: Vec<syn::Type>,
+        sub: Option<TokenStream2>,
+        max_threads: Option<usize>,
+        priority: i32,
+        dynamic: Option<TokenStream2>,
     }
 
+    // With `profile`, timings live in the instance's (non-stage) `StageTimings` field
+    let timings_field: Option<TokenStream2> = if profile {
+        let found = fields.iter().enumerate().find(|(_, f)| {
+            !f.attrs.iter().any(|a| a.path().is_ident("stage"))
+                && matches!(&f.ty, syn::Type::Path(tp) if tp.path.segments.last().is_some_and(|seg| seg.ident == "StageTimings"))
+        });
+        match found {
+            Some((idx, f)) => Some(match &f.ident {
+                Some(id) => quote! { self.#id },
+                None => { let i = syn::Index::from(idx); quote! { self.#i } },
+            }),
+            None => {
+                return syn::Error::new(Span::call_site(), "#[schedule(profile)] requires a `macrokid_core::threads::StageTimings` field to record into").to_compile_error().into()
+            }
+        }
+    } else {
+        None
+    };
+
     let mut metas: Vec<StageMeta> = Vec::new();
 
     for (idx, f) in fields.iter().enumerate() {
@@ -197,13 +347,35 @@ pub fn derive_schedule(input: TokenStream) -> TokenStream {
         let mut name_opt: Option<String> = None;
         let mut after_list: Vec<String> = Vec::new();
         let mut before_list: Vec<String> = Vec::new();
+        let mut is_sub = false;
+        let mut is_dynamic = false;
+        let mut max_threads: Option<usize> = None;
+        let mut priority: Option<i32> = None;
         for a in &f.attrs {
             if a.path().is_ident("stage") {
                 has_stage = true;
                 let parsed = a.parse_args_with(|input: syn::parse::ParseStream| {
                     while !input.is_empty() {
                         let key: syn::Ident = input.parse()?;
+                        if (key == "sub" || key == "dynamic") && !input.peek(syn::Token![=]) {
+                            if key == "sub" { is_sub = true } else { is_dynamic = true }
+                            let _ = input.parse::<syn::Token![,]>();
+                            continue;
+                        }
                         input.parse::<syn::Token![=]>()?;
+                        if key == "max_threads" {
+                            let n: syn::LitInt = input.parse()?;
+                            let v = n.base10_parse::<usize>()?;
+                            if v == 0 { return Err(syn::Error::new(n.span(), "max_threads must be at least 1")); }
+                            max_threads = Some(v);
+                            let _ = input.parse::<syn::Token![,]>();
+                            continue;
+                        }
+                        if key == "priority" {
+                            priority = Some(parse_priority(input)?);
+                            let _ = input.parse::<syn::Token![,]>();
+                            continue;
+                        }
                         let lit: syn::LitStr = input.parse()?;
                         match key.to_string().as_str() {
                             "name" => name_opt = Some(lit.value()),
@@ -243,10 +415,36 @@ pub fn derive_schedule(input: TokenStream) -> TokenStream {
             None => { let i = syn::Index::from(idx); quote! { self.#i } },
         };
 
+        if is_sub && is_dynamic {
+            return syn::Error::new(f.span(), "#[stage] cannot be both `sub` and `dynamic`").to_compile_error().into();
+        }
+
+        // Dynamic stage: systems come from a runtime collection of `dyn System`
+        if is_dynamic {
+            if let syn::Type::Tuple(_) = &f.ty {
+                return syn::Error::new(f.ty.span(), "#[stage(dynamic)] field must be a Vec<Box<dyn System>>, not a tuple of systems").to_compile_error().into();
+            }
+            metas.push(StageMeta { name, after: after_list, before: before_list, jobs: Vec::new(), tys: Vec::new(), sub: None, max_threads, priority: priority.unwrap_or(0), dynamic: Some(field_access) });
+            continue;
+        }
+
+        // Sub-schedule: the field's type derives Schedule and runs as a whole within this stage
+        if is_sub {
+            if let syn::Type::Tuple(_) = &f.ty {
+                return syn::Error::new(f.ty.span(), "#[stage(sub)] field must be a Schedule type, not a tuple of systems").to_compile_error().into();
+            }
+            if max_threads.is_some() || priority.is_some() {
+                let key = if max_threads.is_some() { "max_threads" } else { "priority" };
+                return syn::Error::new(f.span(), format!("{} is not supported on #[stage(sub)]; set it on the nested schedule's stages", key)).to_compile_error().into();
+            }
+            metas.push(StageMeta { name, after: after_list, before: before_list, jobs: Vec::new(), tys: vec![f.ty.clone()], sub: Some(field_access), max_threads: None, priority: 0, dynamic: None });
+            continue;
+        }
+
         // Expect tuple type
         let tys: Vec<syn::Type> = match &f.ty {
             syn::Type::Tuple(tt) => tt.elems.iter().cloned().collect(),
-            _ => { return syn::Error::new(f.ty.span(), "#[stage] field must be a tuple of systems").to_compile_error().into() }
+            _ => { return syn::Error::new(f.ty.span(), "#[stage] field must be a tuple of systems (use #[stage(sub)] for a nested Schedule)").to_compile_error().into() }
         };
 
         // Build jobs for this stage
@@ -259,10 +457,12 @@ pub fn derive_schedule(input: TokenStream) -> TokenStream {
             }});
         }
 
-        metas.push(StageMeta { name, after: after_list, before: before_list, jobs, tys });
+        metas.push(StageMeta { name, after: after_list, before: before_list, jobs, tys, sub: None, max_threads, priority: priority.unwrap_or(0), dynamic: None });
     }
 
-    // Topologically sort stages by `after` dependencies.
+    // Topologically sort stages by `after` dependencies. Qualified labels ("Other::stage") refer
+    // to another schedule and are only resolved when schedules are merged at runtime.
+    let is_external = |dep: &str| dep.contains("::");
     let n = metas.len();
     let mut name_to_idx = std::collections::HashMap::<String, usize>::new();
     for (i, m) in metas.iter().enumerate() { name_to_idx.insert(m.name.clone(), i); }
@@ -270,14 +470,14 @@ pub fn derive_schedule(input: TokenStream) -> TokenStream {
     let mut adj: Vec<Vec<usize>> = vec![Vec::new(); n];
     for (i, m) in metas.iter().enumerate() {
         // after: edge dep -> i
-        for dep in &m.after {
+        for dep in m.after.iter().filter(|d| !is_external(d)) {
             let Some(&j) = name_to_idx.get(dep) else {
                 return syn::Error::new(Span::call_site(), format!("unknown stage in 'after': '{}'", dep)).to_compile_error().into();
             };
             adj[j].push(i); indeg[i] += 1;
         }
         // before: edge i -> dep
-        for dep in &m.before {
+        for dep in m.before.iter().filter(|d| !is_external(d)) {
             let Some(&j) = name_to_idx.get(dep) else {
                 return syn::Error::new(Span::call_site(), format!("unknown stage in 'before': '{}'", dep)).to_compile_error().into();
             };
@@ -295,43 +495,176 @@ pub fn derive_schedule(input: TokenStream) -> TokenStream {
         return syn::Error::new(Span::call_site(), "cycle detected in #[stage(after = ...)] graph").to_compile_error().into();
     }
 
-    // Emit blocks in sorted order
-    let stage_blocks: Vec<TokenStream2> = order.into_iter().map(|i| {
+    // Conflict-aware layering shared by `run` and `explain`: expects `reads`/`writes` arrays in
+    // scope and runs `on_layer` with each conflict-free `layer` of system indices.
+    let layer_loop = |n_jobs: TokenStream2, on_layer: TokenStream2| quote! {
+        let mut remaining: ::std::vec::Vec<usize> = (0..#n_jobs).collect();
+        while !remaining.is_empty() {
+            let mut layer: ::std::vec::Vec<usize> = ::std::vec::Vec::new();
+            let snapshot = remaining.clone();
+            for i in snapshot {
+                let mut ok = true;
+                for &j in &layer {
+                    // check conflicts between i and j
+                    // conflict if writes[i]∩writes[j] or writes[i]∩reads[j] or writes[j]∩reads[i]
+                    let wr_i = writes[i]; let wr_j = writes[j]; let rd_i = reads[i]; let rd_j = reads[j];
+                    let mut conflict = false;
+                    'a: {
+                        for a in wr_i { for b in wr_j { if a == b { conflict = true; break 'a; } } }
+                        for a in wr_i { for b in rd_j { if a == b { conflict = true; break 'a; } } }
+                        for a in wr_j { for b in rd_i { if a == b { conflict = true; break 'a; } } }
+                    }
+                    if conflict { ok = false; break; }
+                }
+                if ok { layer.push(i); }
+            }
+            remaining.retain(|x| !layer.contains(x));
+            #on_layer
+        }
+    };
+    let access_arrays = |tys: &[syn::Type]| {
+        let n_jobs = tys.len();
+        quote! {
+            let reads: [&[::std::any::TypeId]; #n_jobs] = [ #( <#tys as macrokid_core::threads::ResourceAccess>::reads() ),* ];
+            let writes: [&[::std::any::TypeId]; #n_jobs] = [ #( <#tys as macrokid_core::threads::ResourceAccess>::writes() ),* ];
+        }
+    };
+    // Display name for a system or sub-schedule type: the last path segment when there is one
+    let type_name = |ty: &syn::Type| match ty {
+        syn::Type::Path(tp) if tp.qself.is_none() => tp.path.segments.last().map(|s| s.ident.to_string()).unwrap_or_default(),
+        other => quote!(#other).to_string(),
+    };
+
+    // Dry-run plan in the same stage order: one layer per sub-schedule, otherwise conflict layers
+    let explain_blocks: Vec<TokenStream2> = order.iter().map(|&i| {
+        if metas[i].dynamic.is_some() {
+            return quote! { plan.push(::std::vec![::std::vec!["<dynamic>"]]); };
+        }
+        if metas[i].sub.is_some() {
+            let name = type_name(&metas[i].tys[0]);
+            return quote! { plan.push(::std::vec![::std::vec![#name]]); };
+        }
+        let tys = &metas[i].tys;
+        let n_jobs = tys.len();
+        let names: Vec<String> = tys.iter().map(type_name).collect();
+        let arrays = access_arrays(tys);
+        // With max_threads, each sub-batch `run` submits is its own layer, highest priority first
+        let (priorities, on_layer) = match metas[i].max_threads {
+            Some(max) => {
+                let stage_priority = metas[i].priority;
+                (
+                    quote! { let priorities: [i32; #n_jobs] = [ #( (#stage_priority).saturating_add(<#tys as macrokid_core::threads::ResourceAccess>::priority()) ),* ]; },
+                    quote! {
+                        let mut batch: ::std::vec::Vec<(i32, &'static str)> = layer.into_iter().map(|k| (priorities[k], names[k])).collect();
+                        batch.sort_by_key(|(p, _)| ::std::cmp::Reverse(*p));
+                        for chunk in batch.chunks(#max) { layers.push(chunk.iter().map(|(_, n)| *n).collect()); }
+                    },
+                )
+            }
+            None => (quote! {}, quote! { layers.push(layer.into_iter().map(|k| names[k]).collect()); }),
+        };
+        let layering = layer_loop(quote!(#n_jobs), on_layer);
+        quote! {{
+            let names: [&'static str; #n_jobs] = [ #( #names ),* ];
+            #arrays
+            #priorities
+            let mut layers: ::std::vec::Vec<::std::vec::Vec<&'static str>> = ::std::vec::Vec::new();
+            #layering
+            plan.push(layers);
+        }}
+    }).collect();
+
+    // Per-stage bodies in declaration order; a sub-schedule runs its own stages in local order.
+    // Bodies call `Scheduler::submit_prioritized` directly so they work for both `&S` and `&dyn Scheduler`.
+    let stage_bodies: Vec<TokenStream2> = (0..n).map(|i| {
+        if let Some(field_access) = &metas[i].sub {
+            return quote! {
+                let inner: &dyn macrokid_core::threads::StageGraph = &#field_access;
+                for &k in inner.local_order() { inner.run_stage(k, sched); }
+            };
+        }
+        // With max_threads, split each layer into sub-batches joined one after another, taking
+        // the highest priorities first
+        let submit = match metas[i].max_threads {
+            Some(max) => quote! {
+                let mut batch = batch;
+                batch.sort_by_key(|(p, _)| ::std::cmp::Reverse(*p));
+                while !batch.is_empty() {
+                    let rest = batch.split_off(batch.len().min(#max));
+                    macrokid_core::threads::Scheduler::submit_prioritized(sched, batch);
+                    batch = rest;
+                }
+            },
+            None => quote! { macrokid_core::threads::Scheduler::submit_prioritized(sched, batch); },
+        };
+        let stage_priority = metas[i].priority;
+        let on_layer = quote! {
+            let batch: ::std::vec::Vec<(i32, macrokid_core::threads::Job)> = layer.into_iter().map(|k| (priorities[k], jobs[k].take().unwrap())).collect();
+            #submit
+        };
+        if let Some(field_access) = &metas[i].dynamic {
+            let layering = layer_loop(quote!(systems.len()), on_layer);
+            return quote! {
+                // Same batching, with access sets read through the `System` vtable
+                let systems: ::std::vec::Vec<&dyn macrokid_core::threads::System> = #field_access.iter().map(|s| &**s as &dyn macrokid_core::threads::System).collect();
+                let reads: ::std::vec::Vec<&[::std::any::TypeId]> = systems.iter().map(|s| s.reads()).collect();
+                let writes: ::std::vec::Vec<&[::std::any::TypeId]> = systems.iter().map(|s| s.writes()).collect();
+                let priorities: ::std::vec::Vec<i32> = systems.iter().map(|s| (#stage_priority).saturating_add(s.priority())).collect();
+                let mut jobs: ::std::vec::Vec<::std::option::Option<macrokid_core::threads::Job>> = systems.iter().map(|s| Some(s.job())).collect();
+                #layering
+            };
+        }
         let jobs = &metas[i].jobs;
         let tys = &metas[i].tys;
         let n_jobs = jobs.len();
+        let arrays = access_arrays(tys);
+        let layering = layer_loop(quote!(#n_jobs), on_layer);
         quote! {
             // Conflict-aware batching within stage using ResourceAccess
-            let reads: [&[::std::any::TypeId]; #n_jobs] = [ #( <#tys as macrokid_core::threads::ResourceAccess>::reads() ),* ];
-            let writes: [&[::std::any::TypeId]; #n_jobs] = [ #( <#tys as macrokid_core::threads::ResourceAccess>::writes() ),* ];
-            let mut remaining: ::std::vec::Vec<usize> = (0..#n_jobs).collect();
+            #arrays
+            let priorities: [i32; #n_jobs] = [ #( (#stage_priority).saturating_add(<#tys as macrokid_core::threads::ResourceAccess>::priority()) ),* ];
             let mut jobs: ::std::vec::Vec<::std::option::Option<macrokid_core::threads::Job>> = ::std::vec::Vec::with_capacity(#n_jobs);
             #( jobs.push(Some(#jobs)); )*
-            while !remaining.is_empty() {
-                let mut layer: ::std::vec::Vec<usize> = ::std::vec::Vec::new();
-                let snapshot = remaining.clone();
-                for i in snapshot {
-                    let mut ok = true;
-                    for &j in &layer {
-                        // check conflicts between i and j
-                        // conflict if writes[i]∩writes[j] or writes[i]∩reads[j] or writes[j]∩reads[i]
-                        let wr_i = writes[i]; let wr_j = writes[j]; let rd_i = reads[i]; let rd_j = reads[j];
-                        let mut conflict = false;
-                        'a: {
-                            for a in wr_i { for b in wr_j { if a == b { conflict = true; break 'a; } } }
-                            for a in wr_i { for b in rd_j { if a == b { conflict = true; break 'a; } } }
-                            for a in wr_j { for b in rd_i { if a == b { conflict = true; break 'a; } } }
-                        }
-                        if conflict { ok = false; break; }
-                    }
-                    if ok { layer.push(i); }
-                }
-                remaining.retain(|x| !layer.contains(x));
-                let batch: ::std::vec::Vec<_> = layer.into_iter().map(|k| jobs[k].take().unwrap()).collect();
-                macrokid_core::threads::join_all(sched, batch);
-            }
+            #layering
+        }
+    }).collect();
+    // Emit blocks in sorted order; with profiling each block records its wall time
+    let stage_blocks: Vec<TokenStream2> = order.iter().map(|&i| {
+        let block = match &metas[i].sub {
+            Some(field_access) => quote! { #field_access.run(sched); },
+            None => { let body = &stage_bodies[i]; quote! {{ #body }} }
+        };
+        let Some(timings) = &timings_field else { return block; };
+        let name = &metas[i].name;
+        quote! {
+            let start = ::std::time::Instant::now();
+            #block
+            #timings.record(#name, start.elapsed());
         }
     }).collect();
+    let (profile_reset, profile_methods, timings_accessor) = match &timings_field {
+        Some(timings) => (
+            quote! { #timings.clear(); },
+            quote! {
+                /// Wall time of each stage during this instance's most recent run (its own `run`
+                /// or a `MergedSchedule` it belongs to), in execution order. A `#[stage(sub)]`
+                /// stage is reported as one entry covering the nested schedule.
+                pub fn last_run_timings(&self) -> ::std::vec::Vec<(&'static str, ::std::time::Duration)> {
+                    #timings.snapshot()
+                }
+            },
+            quote! {
+                fn stage_timings(&self) -> ::std::option::Option<&macrokid_core::threads::StageTimings> { ::std::option::Option::Some(&#timings) }
+            },
+        ),
+        None => (quote! {}, quote! {}, quote! {}),
+    };
+    let stage_indices: Vec<usize> = (0..n).collect();
+    let decl_terms: Vec<TokenStream2> = metas.iter().map(|m| {
+        let (name, after, before) = (&m.name, &m.after, &m.before);
+        quote! { macrokid_core::threads::StageDecl { name: #name, after: &[ #( #after ),* ], before: &[ #( #before ),* ] } }
+    }).collect();
+    let label = ident.to_string();
 
     // Prepare constants for a debug grouping method
     let name_literals: Vec<TokenStream2> = metas.iter().map(|m| {
@@ -340,8 +673,8 @@ pub fn derive_schedule(input: TokenStream) -> TokenStream {
     }).collect();
     let mut edge_pairs: Vec<(usize, usize)> = Vec::new();
     for (i, m) in metas.iter().enumerate() {
-        for dep in &m.after { let &j = name_to_idx.get(dep).unwrap(); edge_pairs.push((j, i)); }
-        for dep in &m.before { let &j = name_to_idx.get(dep).unwrap(); edge_pairs.push((i, j)); }
+        for dep in m.after.iter().filter(|d| !is_external(d)) { let &j = name_to_idx.get(dep).unwrap(); edge_pairs.push((j, i)); }
+        for dep in m.before.iter().filter(|d| !is_external(d)) { let &j = name_to_idx.get(dep).unwrap(); edge_pairs.push((i, j)); }
     }
     let edge_terms: Vec<TokenStream2> = edge_pairs.iter().map(|(u, v)| {
         let uu = syn::Index::from(*u); let vv = syn::Index::from(*v);
@@ -349,10 +682,40 @@ pub fn derive_schedule(input: TokenStream) -> TokenStream {
     }).collect();
 
     let expanded = quote! {
+        impl macrokid_core::threads::StageGraph for #ident {
+            fn schedule_label(&self) -> &'static str { #label }
+            fn stages(&self) -> &'static [macrokid_core::threads::StageDecl] {
+                const STAGES: &[macrokid_core::threads::StageDecl] = &[ #( #decl_terms ),* ];
+                STAGES
+            }
+            fn local_order(&self) -> &'static [usize] { &[ #( #order ),* ] }
+            fn run_stage(&self, index: usize, sched: &dyn macrokid_core::threads::Scheduler) {
+                match index {
+                    #( #stage_indices => { #stage_bodies } )*
+                    _ => panic!("stage index {} out of range for {}", index, #label),
+                }
+            }
+            #timings_accessor
+        }
+
         impl #ident {
+            /// Run all stages in local order. Cross-schedule (`"Other::stage"`) constraints are
+            /// ignored here; merge schedules with `MergedSchedule` to honor them.
             pub fn run<S: macrokid_core::threads::Scheduler>(&self, sched: &S) {
+                #profile_reset
                 #( #stage_blocks )*
             }
+            #profile_methods
+
+            /// Dry-run of `run`: for each stage in execution order, the conflict-free layers of
+            /// system type names that would be submitted together, split into `max_threads`
+            /// sub-batches where the stage sets it. Nothing is spawned or run; a `#[stage(sub)]`
+            /// stage appears as a single layer holding the nested schedule.
+            pub fn explain() -> ::std::vec::Vec<::std::vec::Vec<::std::vec::Vec<&'static str>>> {
+                let mut plan: ::std::vec::Vec<::std::vec::Vec<::std::vec::Vec<&'static str>>> = ::std::vec::Vec::new();
+                #( #explain_blocks )*
+                plan
+            }
 
             /// Return topological groups (layers) of stages for debugging.
             pub fn topo_groups() -> ::std::vec::Vec<::std::vec::Vec<&'static str>> {
@@ -383,3 +746,79 @@ pub fn derive_schedule(input: TokenStream) -> TokenStream {
     };
     expanded.into()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn system_rejects_type_in_reads_and_writes() {
+        let di: DeriveInput = parse_quote! {
+            #[reads(Transform, physics::State)]
+            #[writes(physics :: State)]
+            struct Integrate;
+        };
+        let err = expand_system(di).unwrap_err();
+        assert!(err.to_string().contains("`physics::State` is listed in both #[reads] and #[writes]"));
+
+        let ok: DeriveInput = parse_quote! { #[reads(Transform)] #[writes(physics::State)] struct Integrate; };
+        assert!(expand_system(ok).is_ok());
+    }
+
+    fn has_gpu_impl(di: DeriveInput) -> bool {
+        expand_system(di).unwrap().to_string().contains("GpuResourceAccess for")
+    }
+
+    #[test]
+    fn gpu_types_default_matches_last_segment() {
+        assert!(has_gpu_impl(parse_quote! { #[reads(macrokid_graphics::resources::GpuBuffer<Vertex>)] struct Draw; }));
+        // An alias is not recognized unless listed
+        assert!(!has_gpu_impl(parse_quote! { #[reads(VertexBuf)] struct Draw; }));
+        assert!(has_gpu_impl(parse_quote! {
+            #[system(gpu_types(VertexBuf, GpuImage))]
+            #[reads(VertexBuf)]
+            struct Draw;
+        }));
+    }
+
+    #[test]
+    fn gpu_system_emits_descriptor_bindings() {
+        let di: DeriveInput = parse_quote! {
+            #[reads(GpuBuffer<Camera>)]
+            #[writes(GpuImage<Hdr>)]
+            struct Tonemap;
+        };
+        let out = expand_system(di).unwrap().to_string();
+        assert!(out.contains("fn descriptor_bindings () -> & 'static [macrokid_graphics :: resources :: BindingDesc]"), "{}", out);
+        assert!(out.contains("gpu_access_bindings"));
+        // Reads and writes both feed the layout, in that order
+        let reads = out.find("fn gpu_reads").unwrap();
+        assert!(out[reads..].contains("< GpuBuffer < Camera > as macrokid_graphics :: resources :: GpuResource > :: metadata ()"));
+        assert!(out.contains("< GpuImage < Hdr > as macrokid_graphics :: resources :: GpuResource > :: metadata ()"));
+    }
+
+    #[test]
+    fn gpu_types_can_exclude_user_types() {
+        // A user's own `GpuBuffer` wrapper stays a CPU resource once the set is narrowed
+        let di: DeriveInput = parse_quote! {
+            #[system(gpu_types(GpuImage))]
+            #[writes(mycrate::GpuBuffer)]
+            struct Upload;
+        };
+        let out = expand_system(di).unwrap().to_string();
+        assert!(!out.contains("GpuResourceAccess for"));
+        assert!(out.contains("TypeId :: of :: < mycrate :: GpuBuffer > ()"));
+
+        let bad: DeriveInput = parse_quote! { #[system(gpu_type(GpuImage))] struct Upload; };
+        assert!(expand_system(bad).unwrap_err().to_string().contains("unknown key in #[system(...)]"));
+    }
+
+    #[test]
+    fn system_priority_overrides_default() {
+        let di: DeriveInput = parse_quote! { #[system(priority = -3, gpu_types(GpuImage))] #[reads(Transform)] struct Upload; };
+        assert!(expand_system(di).unwrap().to_string().contains("fn priority () -> i32 { - 3i32 }"));
+        let plain: DeriveInput = parse_quote! { #[reads(Transform)] struct Upload; };
+        assert!(!expand_system(plain).unwrap().to_string().contains("fn priority"));
+    }
+}